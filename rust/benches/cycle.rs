@@ -0,0 +1,99 @@
+//! Cycle throughput of the async VM and the SDK engine
+//!
+//! Run with `cargo bench --features bench`.
+
+use charta::bench::{self, BenchSize};
+use charta::embedded;
+use charta::engine::Engine;
+use charta::ChartaVM;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tokio::runtime::Runtime;
+
+const SIZES: [usize; 3] = [10, 100, 1_000];
+
+fn execute_cycle(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("execute_cycle");
+    for rungs in SIZES {
+        let size = BenchSize::rungs(rungs);
+        let program = bench::program(&size);
+        let inputs = bench::inputs(&program, 256, 0.05, 7);
+        let mut vm = ChartaVM::new();
+        runtime
+            .block_on(vm.load_program(&bench::program_json(&size)))
+            .unwrap();
+
+        group.throughput(Throughput::Elements(rungs as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(rungs), &inputs, |b, inputs| {
+            let mut cycle = inputs.iter().cycle();
+            b.iter(|| {
+                let inputs = cycle.next().unwrap().clone();
+                runtime.block_on(vm.execute_cycle_with_inputs(inputs)).unwrap();
+            });
+        });
+    }
+    group.finish();
+}
+
+fn read_coils(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("get_all_coils");
+    for rungs in SIZES {
+        let size = BenchSize::rungs(rungs);
+        let mut vm = ChartaVM::new();
+        runtime
+            .block_on(vm.load_program(&bench::program_json(&size)))
+            .unwrap();
+
+        group.bench_function(BenchmarkId::from_parameter(rungs), |b| {
+            b.iter(|| runtime.block_on(vm.get_all_coils()).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn engine_scan(c: &mut Criterion) {
+    let mut group = c.benchmark_group("engine_scan");
+    for rungs in SIZES {
+        let program = bench::program(&BenchSize::rungs(rungs));
+        group.throughput(Throughput::Elements(rungs as u64));
+        for (label, engine) in [
+            ("tree", Engine::new(&program)),
+            ("compiled", Engine::compiled(&program)),
+        ] {
+            let mut state = engine.state();
+            group.bench_function(BenchmarkId::new(label, rungs), |b| {
+                b.iter(|| engine.scan(&mut state));
+            });
+        }
+    }
+    group.finish();
+}
+
+fn incremental_scan(c: &mut Criterion) {
+    let mut group = c.benchmark_group("embedded_incremental");
+    for rungs in SIZES {
+        let size = BenchSize::rungs(rungs);
+        let program = bench::program(&size);
+        let inputs = bench::inputs(&program, 256, 0.01, 7);
+        for incremental in [false, true] {
+            let mut vm = embedded::ChartaVM::new();
+            vm.set_incremental(incremental);
+            vm.load(program.clone());
+            let mut cycle = inputs.iter().cycle();
+            let label = if incremental { "incremental" } else { "full" };
+            group.bench_function(BenchmarkId::new(label, rungs), |b| {
+                b.iter(|| {
+                    for (name, value) in cycle.next().unwrap() {
+                        vm.set_signal(name, *value).unwrap();
+                    }
+                    vm.execute_cycle().unwrap();
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, execute_cycle, read_coils, engine_scan, incremental_scan);
+criterion_main!(benches);
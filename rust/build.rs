@@ -0,0 +1,18 @@
+fn main() {
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("proto/charta.proto").expect("failed to compile charta.proto");
+
+    #[cfg(feature = "capi")]
+    {
+        // The header goes to OUT_DIR so builds never write to the source tree
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set");
+        let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR not set");
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+            .expect("failed to read cbindgen.toml");
+        cbindgen::generate_with_config(&crate_dir, config)
+            .expect("failed to generate charta.h")
+            .write_to_file(format!("{}/charta.h", out_dir));
+        println!("cargo:rerun-if-changed=src/capi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+    }
+}
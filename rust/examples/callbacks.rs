@@ -80,7 +80,7 @@ async fn main() -> Result<(), Error> {
 
     // Register callback for specific coil
     let output_coil_count = output_coil_changes.clone();
-    vm.on_coil_change("output_coil", move |name, old_val, new_val| {
+    vm.on_coil_change("output_coil", move |name, old_val, new_val, _| {
         println!("  → Coil '{}' changed: {} → {}", name, old_val, new_val);
         output_coil_count.fetch_add(1, Ordering::Relaxed);
     })
@@ -88,7 +88,7 @@ async fn main() -> Result<(), Error> {
 
     // Register callback for any coil change
     let any_coil_count = coil_change_count.clone();
-    vm.on_any_coil_change(move |name, old_val, new_val| {
+    vm.on_any_coil_change(move |name, old_val, new_val, _| {
         println!("  → Any coil changed: '{}' {} → {}", name, old_val, new_val);
         any_coil_count.fetch_add(1, Ordering::Relaxed);
     })
@@ -96,7 +96,7 @@ async fn main() -> Result<(), Error> {
 
     // Register callback for cycle completion
     let cycle_count = cycle_complete_count.clone();
-    vm.on_cycle_complete(move |outputs| {
+    vm.on_cycle_complete(move |outputs, _| {
         println!("  → Cycle complete. Outputs: {:?}", outputs);
         cycle_count.fetch_add(1, Ordering::Relaxed);
     })
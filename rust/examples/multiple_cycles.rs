@@ -86,7 +86,7 @@ async fn main() -> Result<(), Error> {
     println!("\n--- Cycle {}: Start signal ---", cycle);
     vm.set_signal("start", true).await?;
     let outputs = vm.execute_cycle().await?;
-    println!("  Outputs: {:?}", outputs);
+    println!("  Outputs: {:?}", outputs.coils());
     let coils = vm.get_all_coils().await?;
     println!("  All coils: {:?}", coils);
     cycle += 1;
@@ -94,7 +94,7 @@ async fn main() -> Result<(), Error> {
     println!("\n--- Cycle {}: Start signal cleared (latching should maintain) ---", cycle);
     vm.set_signal("start", false).await?;
     let outputs = vm.execute_cycle().await?;
-    println!("  Outputs: {:?}", outputs);
+    println!("  Outputs: {:?}", outputs.coils());
     let coils = vm.get_all_coils().await?;
     println!("  All coils: {:?}", coils);
     println!("  → 'running' should still be true (latching)");
@@ -103,7 +103,7 @@ async fn main() -> Result<(), Error> {
     println!("\n--- Cycle {}: Stop signal ---", cycle);
     vm.set_signal("stop", true).await?;
     let outputs = vm.execute_cycle().await?;
-    println!("  Outputs: {:?}", outputs);
+    println!("  Outputs: {:?}", outputs.coils());
     let coils = vm.get_all_coils().await?;
     println!("  All coils: {:?}", coils);
     cycle += 1;
@@ -111,7 +111,7 @@ async fn main() -> Result<(), Error> {
     println!("\n--- Cycle {}: Stop signal cleared ---", cycle);
     vm.set_signal("stop", false).await?;
     let outputs = vm.execute_cycle().await?;
-    println!("  Outputs: {:?}", outputs);
+    println!("  Outputs: {:?}", outputs.coils());
     let coils = vm.get_all_coils().await?;
     println!("  All coils: {:?}", coils);
 
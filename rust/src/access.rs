@@ -0,0 +1,90 @@
+//! Capability-scoped write access for Charta VM
+//!
+//! A [`SignalWriter`] may only set the signals it was granted, letting
+//! separate subsystems share a VM without being able to spoof each other's
+//! inputs.
+
+use crate::error::{Error, Result};
+use crate::observer::SharedState;
+use crate::pattern::Pattern;
+use crate::quality::Quality;
+use crate::value;
+use charta_vm::VM;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Handle that can set a fixed set of signals
+///
+/// Created with [`ChartaVM::writer_for`](crate::ChartaVM::writer_for).
+#[derive(Clone)]
+pub struct SignalWriter {
+    vm: Arc<RwLock<VM>>,
+    state: Arc<SharedState>,
+    allowed: Arc<Vec<Pattern>>,
+}
+
+impl SignalWriter {
+    pub(crate) fn new(vm: Arc<RwLock<VM>>, state: Arc<SharedState>, signals: &[&str]) -> Self {
+        Self {
+            vm,
+            state,
+            allowed: Arc::new(signals.iter().map(|s| Pattern::new(s)).collect()),
+        }
+    }
+
+    /// Check whether this writer may set a signal
+    pub fn can_write(&self, name: &str) -> bool {
+        self.allowed.iter().any(|pattern| pattern.matches(name))
+    }
+
+    /// Set a signal value
+    pub async fn set_signal(&self, name: &str, value: bool) -> Result<()> {
+        self.set_signal_with_quality(name, value, Quality::Good)
+            .await
+    }
+
+    /// Set a signal value along with its quality
+    pub async fn set_signal_with_quality(
+        &self,
+        name: &str,
+        value: bool,
+        quality: Quality,
+    ) -> Result<()> {
+        self.check(name)?;
+        let mut vm = self.vm.write().await;
+        vm.set_signal(name.to_string(), value);
+        self.state.quality().update(name, quality);
+        self.state.publish_signals(&vm);
+        Ok(())
+    }
+
+    /// Set several signals at once
+    ///
+    /// Either every signal is in scope and all are written, or nothing is
+    /// written.
+    pub async fn set_signals(&self, values: &HashMap<String, bool>) -> Result<()> {
+        for name in values.keys() {
+            self.check(name)?;
+        }
+        let mut vm = self.vm.write().await;
+        for (name, value) in values {
+            vm.set_signal(name.clone(), *value);
+            self.state.quality().update(name, Quality::Good);
+        }
+        self.state.publish_signals(&vm);
+        Ok(())
+    }
+
+    fn check(&self, name: &str) -> Result<()> {
+        value::check_writable(name)?;
+        if self.can_write(name) {
+            Ok(())
+        } else {
+            Err(Error::AccessDenied(format!(
+                "writer is not permitted to set signal '{}'",
+                name
+            )))
+        }
+    }
+}
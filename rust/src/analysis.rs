@@ -0,0 +1,334 @@
+//! Static analysis of programs for safety reviews
+//!
+//! [`interlocks`] inventories everything gating a coil: the signals,
+//! intermediate coils, and comparisons that must hold, or not hold, for it
+//! to energise. The gating condition is resolved through every coil it
+//! reads, with negations pushed down to the leaves, so each leaf states
+//! the value it requires:
+//!
+//! ```no_run
+//! use charta::analysis;
+//! use charta::ir::Program;
+//!
+//! # fn example(ir_json: &str) -> charta::Result<()> {
+//! let program = Program::from_json(ir_json)?;
+//! let report = analysis::interlocks(&program, "allow_operation")?;
+//! print!("{}", report);
+//! for interlock in &report.inventory {
+//!     println!("{} {:?}: {:?}", interlock.name, interlock.kind, interlock.required);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A coil's state after a scan is decided by the last rung driving it in
+//! scan order (see [`crate::order`]), so that rung's guard is its gating
+//! condition. Coils no rung drives, and coils reached again through their
+//! own condition (seal-in circuits), are leaves.
+
+use crate::error::{Error, Result};
+use crate::ir::{ContactType, Guard, Program, Rung, SourceLocation};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Everything gating one coil
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interlocks {
+    /// Coil analysed
+    pub coil: String,
+    /// Rung deciding the coil, if any drives it
+    pub rung: Option<String>,
+    /// Where that rung was written, if known
+    pub source: Option<SourceLocation>,
+    /// What must hold for the coil to energise, if a rung drives it
+    pub condition: Option<Condition>,
+    /// Every signal, coil, and comparison in the condition, by kind and name
+    pub inventory: Vec<Interlock>,
+}
+
+/// Gating condition, with negations pushed down to the leaves
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Condition {
+    /// A signal must have `state`
+    Signal {
+        /// Signal name
+        name: String,
+        /// Required state
+        state: bool,
+    },
+    /// An intermediate coil must have `state`
+    Coil {
+        /// Coil name
+        name: String,
+        /// Required state
+        state: bool,
+        /// Rung deciding the coil, if any drives it
+        rung: Option<String>,
+        /// What gives the coil that state; `None` for coils no rung drives
+        /// and coils already being resolved
+        condition: Option<Box<Condition>>,
+    },
+    /// A comparison must hold, or not hold
+    Compare {
+        /// Condition tested, e.g. `amount > 10000`
+        condition: String,
+        /// Whether it must hold
+        holds: bool,
+    },
+    /// Every condition must be met
+    All {
+        /// Conditions
+        conditions: Vec<Condition>,
+    },
+    /// At least one condition must be met
+    Any {
+        /// Alternative conditions
+        conditions: Vec<Condition>,
+    },
+}
+
+/// Kind of an inventoried interlock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterlockKind {
+    /// Input signal
+    Signal,
+    /// Intermediate coil
+    Coil,
+    /// Comparison of a typed value
+    Comparison,
+}
+
+/// State an interlock is required to have
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Requirement {
+    /// Must be true (or, for comparisons, hold)
+    True,
+    /// Must be false
+    False,
+    /// Required true on some paths and false on others
+    Either,
+}
+
+/// One signal, coil, or comparison a coil depends on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interlock {
+    /// Signal or coil name, or comparison condition
+    pub name: String,
+    /// What it is
+    pub kind: InterlockKind,
+    /// State the gating condition requires
+    pub required: Requirement,
+}
+
+/// Get everything gating `coil` in `program`
+///
+/// Fails with [`Error::NotFound`] if the program does not declare the coil.
+pub fn interlocks(program: &Program, coil: &str) -> Result<Interlocks> {
+    if !program
+        .module
+        .coils
+        .iter()
+        .any(|declared| declared.name == coil)
+    {
+        return Err(Error::NotFound(format!("coil '{}'", coil)));
+    }
+    let resolver = Resolver { program };
+    let rung = resolver.deciding_rung(coil);
+    let mut resolving = vec![coil.to_string()];
+    let condition = rung.map(|rung| resolver.condition(&rung.guard, true, &mut resolving));
+
+    let mut required = BTreeMap::new();
+    if let Some(condition) = &condition {
+        collect(condition, &mut required);
+    }
+    Ok(Interlocks {
+        coil: coil.to_string(),
+        rung: rung.map(|rung| rung.name.clone()),
+        source: rung.and_then(|rung| rung.source.clone()),
+        condition,
+        inventory: required
+            .into_iter()
+            .map(|((kind, name), required)| Interlock {
+                name,
+                kind,
+                required,
+            })
+            .collect(),
+    })
+}
+
+struct Resolver<'a> {
+    program: &'a Program,
+}
+
+impl<'a> Resolver<'a> {
+    /// Last rung driving `coil` in scan order: lowest priority, then last in
+    /// document order
+    fn deciding_rung(&self, coil: &str) -> Option<&'a Rung> {
+        self.program
+            .module
+            .rungs
+            .iter()
+            .enumerate()
+            .filter(|(_, rung)| rung.target_coils().any(|target| target == coil))
+            .max_by_key(|(index, rung)| (std::cmp::Reverse(rung.priority), *index))
+            .map(|(_, rung)| rung)
+    }
+
+    /// Condition for `guard` to evaluate to `state`
+    fn condition(&self, guard: &Guard, state: bool, resolving: &mut Vec<String>) -> Condition {
+        match guard {
+            Guard::Contact { name, contact_type } => {
+                let state = state != (*contact_type == ContactType::NormallyClosed);
+                self.contact(name, state, resolving)
+            }
+            Guard::Not { operand } => self.condition(operand, !state, resolving),
+            Guard::And { .. } | Guard::Or { .. } => {
+                let conditions = guard
+                    .operands()
+                    .into_iter()
+                    .map(|operand| self.condition(operand, state, resolving))
+                    .collect();
+                // De Morgan: a conjunction is false when any operand is
+                if matches!(guard, Guard::And { .. }) == state {
+                    Condition::All { conditions }
+                } else {
+                    Condition::Any { conditions }
+                }
+            }
+            Guard::Compare { .. } | Guard::WithinRange { .. } => Condition::Compare {
+                condition: guard.condition().unwrap_or_default(),
+                holds: state,
+            },
+        }
+    }
+
+    fn contact(&self, name: &str, state: bool, resolving: &mut Vec<String>) -> Condition {
+        if !self
+            .program
+            .module
+            .coils
+            .iter()
+            .any(|coil| coil.name == name)
+        {
+            return Condition::Signal {
+                name: name.to_string(),
+                state,
+            };
+        }
+        let rung = self.deciding_rung(name);
+        let condition = match rung {
+            Some(rung) if !resolving.iter().any(|coil| coil == name) => {
+                resolving.push(name.to_string());
+                let condition = self.condition(&rung.guard, state, resolving);
+                resolving.pop();
+                Some(Box::new(condition))
+            }
+            _ => None,
+        };
+        Condition::Coil {
+            name: name.to_string(),
+            state,
+            rung: rung.map(|rung| rung.name.clone()),
+            condition,
+        }
+    }
+}
+
+fn collect(condition: &Condition, out: &mut BTreeMap<(InterlockKind, String), Requirement>) {
+    let mut require = |kind, name: &str, state: bool| {
+        let state = if state {
+            Requirement::True
+        } else {
+            Requirement::False
+        };
+        out.entry((kind, name.to_string()))
+            .and_modify(|required| {
+                if *required != state {
+                    *required = Requirement::Either;
+                }
+            })
+            .or_insert(state);
+    };
+    match condition {
+        Condition::Signal { name, state } => require(InterlockKind::Signal, name, *state),
+        Condition::Coil {
+            name,
+            state,
+            condition,
+            ..
+        } => {
+            require(InterlockKind::Coil, name, *state);
+            if let Some(condition) = condition {
+                collect(condition, out);
+            }
+        }
+        Condition::Compare { condition, holds } => {
+            require(InterlockKind::Comparison, condition, *holds)
+        }
+        Condition::All { conditions } | Condition::Any { conditions } => {
+            for condition in conditions {
+                collect(condition, out);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Interlocks {
+    /// Render the condition as an indented tree
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.rung, &self.condition) {
+            (Some(rung), Some(condition)) => {
+                let rung = crate::ir::describe_rung(rung, self.source.as_ref());
+                writeln!(f, "{} <- rung {}", self.coil, rung)?;
+                write_condition(f, condition, 1)
+            }
+            _ => writeln!(f, "{} <- no rung drives it", self.coil),
+        }
+    }
+}
+
+fn write_condition(f: &mut fmt::Formatter<'_>, condition: &Condition, depth: usize) -> fmt::Result {
+    let indent = "  ".repeat(depth);
+    match condition {
+        Condition::Signal { name, state } => writeln!(f, "{}{} = {}", indent, name, state),
+        Condition::Coil {
+            name,
+            state,
+            rung,
+            condition,
+        } => {
+            match (rung, condition) {
+                (Some(rung), Some(_)) => {
+                    writeln!(f, "{}{} = {} <- rung '{}'", indent, name, state, rung)?
+                }
+                (Some(_), None) => writeln!(f, "{}{} = {} (seal-in)", indent, name, state)?,
+                (None, _) => writeln!(f, "{}{} = {} (not driven)", indent, name, state)?,
+            }
+            match condition {
+                Some(condition) => write_condition(f, condition, depth + 1),
+                None => Ok(()),
+            }
+        }
+        Condition::Compare { condition, holds } => {
+            let holds = if *holds { "holds" } else { "does not hold" };
+            writeln!(f, "{}{} {}", indent, condition, holds)
+        }
+        Condition::All { conditions } | Condition::Any { conditions } => {
+            let label = if matches!(condition, Condition::All { .. }) {
+                "all of:"
+            } else {
+                "any of:"
+            };
+            writeln!(f, "{}{}", indent, label)?;
+            for condition in conditions {
+                write_condition(f, condition, depth + 1)?;
+            }
+            Ok(())
+        }
+    }
+}
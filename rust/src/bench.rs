@@ -0,0 +1,276 @@
+//! Benchmark fixtures and scan budget sizing
+//!
+//! With the `bench` feature, deterministic generators build programs of a
+//! chosen size and input sequences over them, for the crate's criterion
+//! benches and for sizing scan budgets against your own hardware:
+//!
+//! ```no_run
+//! use charta::bench::{self, BenchSize};
+//! use charta::ChartaVM;
+//!
+//! # async fn example() -> charta::Result<()> {
+//! let size = BenchSize::rungs(5_000);
+//! let mut vm = ChartaVM::new();
+//! vm.load_program(&bench::program_json(&size)).await?;
+//!
+//! let inputs = bench::inputs(&bench::program(&size), 1_000, 0.05, 7);
+//! let timings = bench::time_cycles(&mut vm, inputs).await?;
+//! println!("p99 cycle: {:?}", timings.p99);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Generated programs are reproducible: the same size and seed always give
+//! the same program and inputs.
+
+use crate::error::Result;
+use crate::ir::{Action, CoilDecl, ContactType, Guard, Metadata, Module, Program, Rung, SignalDecl};
+use crate::value::ValueType;
+use crate::vm::ChartaVM;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Shape of a generated benchmark program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchSize {
+    /// Declared signals
+    pub signals: usize,
+    /// Declared coils
+    pub coils: usize,
+    /// Rungs, each driving one coil
+    pub rungs: usize,
+    /// Nesting depth of every guard (1 is a single contact)
+    pub guard_depth: u32,
+    /// Operands of each AND/OR node
+    pub fan_in: usize,
+    /// Seed for the program generator
+    pub seed: u64,
+}
+
+impl BenchSize {
+    /// A program with `rungs` rungs and proportionate signals and coils
+    pub fn rungs(rungs: usize) -> Self {
+        Self {
+            signals: (rungs / 2).max(1),
+            coils: rungs.max(1),
+            rungs,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for BenchSize {
+    fn default() -> Self {
+        Self {
+            signals: 50,
+            coils: 100,
+            rungs: 100,
+            guard_depth: 3,
+            fan_in: 3,
+            seed: 1,
+        }
+    }
+}
+
+/// Small deterministic generator (xorshift64*)
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    fn chance(&mut self, p: f64) -> bool {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64 < p
+    }
+}
+
+fn signal_name(i: usize) -> String {
+    format!("s{}", i)
+}
+
+fn coil_name(i: usize) -> String {
+    format!("c{}", i)
+}
+
+/// Build a guard; rungs only read coils driven by earlier rungs
+fn guard(rng: &mut Rng, size: &BenchSize, rung: usize, depth: u32) -> Guard {
+    if depth <= 1 {
+        let name = if rung > 0 && rng.chance(0.25) {
+            coil_name(rng.below(rung.min(size.coils)))
+        } else {
+            signal_name(rng.below(size.signals))
+        };
+        let contact_type = if rng.chance(0.2) {
+            ContactType::NormallyClosed
+        } else {
+            ContactType::NormallyOpen
+        };
+        return Guard::Contact { name, contact_type };
+    }
+    let operands = (0..size.fan_in.max(1))
+        .map(|_| guard(rng, size, rung, depth - 1))
+        .collect();
+    if rng.chance(0.5) {
+        Guard::And {
+            left: None,
+            right: None,
+            operands,
+        }
+    } else {
+        Guard::Or {
+            left: None,
+            right: None,
+            operands,
+        }
+    }
+}
+
+/// Generate a program of the given size
+pub fn program(size: &BenchSize) -> Program {
+    let mut rng = Rng::new(size.seed);
+    let coils = size.coils.max(1);
+    Program {
+        version: "0.1.0".to_string(),
+        imports: Vec::new(),
+        module: Module {
+            name: "bench".to_string(),
+            signals: (0..size.signals.max(1))
+                .map(|i| SignalDecl {
+                    name: signal_name(i),
+                    value_type: ValueType::Bool,
+                    filter: None,
+                    initial: None,
+                    meta: Metadata::default(),
+                })
+                .collect(),
+            coils: (0..coils)
+                .map(|i| CoilDecl {
+                    name: coil_name(i),
+                    initial: None,
+                    retained: false,
+                    meta: Metadata::default(),
+                })
+                .collect(),
+            imports: Vec::new(),
+            exports: Vec::new(),
+            registers: Vec::new(),
+            rungs: (0..size.rungs)
+                .map(|i| Rung {
+                    name: format!("r{}", i),
+                    guard: guard(&mut rng, size, i, size.guard_depth.max(1)),
+                    actions: vec![Action::Energise {
+                        coil: coil_name(i % coils),
+                    }],
+                    priority: 0,
+                    group: None,
+                    source: None,
+                })
+                .collect(),
+        },
+    }
+}
+
+/// Generate a program of the given size as IR JSON
+pub fn program_json(size: &BenchSize) -> String {
+    serde_json::to_string(&program(size)).expect("programs serialize")
+}
+
+/// Generate `cycles` input sets over the signals of `program`
+///
+/// The first set assigns every signal; each later set flips each signal
+/// with probability `change_rate`, like inputs that change a few at a time.
+pub fn inputs(
+    program: &Program,
+    cycles: usize,
+    change_rate: f64,
+    seed: u64,
+) -> Vec<HashMap<String, bool>> {
+    let mut rng = Rng::new(seed);
+    let names: Vec<&str> = program
+        .module
+        .signals
+        .iter()
+        .map(|signal| signal.name.as_str())
+        .collect();
+    let mut values: Vec<bool> = names.iter().map(|_| rng.chance(0.5)).collect();
+
+    (0..cycles)
+        .map(|cycle| {
+            names
+                .iter()
+                .zip(values.iter_mut())
+                .filter_map(|(name, value)| {
+                    if cycle == 0 {
+                        return Some((name.to_string(), *value));
+                    }
+                    rng.chance(change_rate).then(|| {
+                        *value = !*value;
+                        (name.to_string(), *value)
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Cycle latencies measured by [`time_cycles`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleTimings {
+    /// Cycles measured
+    pub cycles: usize,
+    /// Mean cycle time
+    pub mean: Duration,
+    /// Median cycle time
+    pub p50: Duration,
+    /// 99th percentile cycle time
+    pub p99: Duration,
+    /// Slowest cycle
+    pub max: Duration,
+}
+
+/// Execute one cycle per input set, timing each including callbacks
+///
+/// Use the percentiles to choose a
+/// [`cycle_deadline`](crate::ChartaVMBuilder::cycle_deadline).
+pub async fn time_cycles(
+    vm: &mut ChartaVM,
+    inputs: Vec<HashMap<String, bool>>,
+) -> Result<CycleTimings> {
+    let mut samples = Vec::with_capacity(inputs.len());
+    for inputs in inputs {
+        let started = Instant::now();
+        vm.execute_cycle_with_inputs(inputs).await?;
+        samples.push(started.elapsed());
+    }
+    samples.sort_unstable();
+
+    let percentile = |p: usize| {
+        samples
+            .get((samples.len() * p / 100).min(samples.len().saturating_sub(1)))
+            .copied()
+            .unwrap_or_default()
+    };
+    let total: Duration = samples.iter().sum();
+    Ok(CycleTimings {
+        cycles: samples.len(),
+        mean: total
+            .checked_div(samples.len() as u32)
+            .unwrap_or_default(),
+        p50: percentile(50),
+        p99: percentile(99),
+        max: samples.last().copied().unwrap_or_default(),
+    })
+}
@@ -0,0 +1,291 @@
+/// Interactive shell for loading a Charta IR program and driving it by hand
+///
+/// ```text
+/// $ charta-repl program.ir.json
+/// charta> set user_submitted true
+/// charta> cycle
+/// charta> explain allow_review
+/// charta> force allow_review false
+/// charta> trace on
+/// ```
+
+use charta::ir::{ContactType, Guard, Program};
+use charta::{ChartaVM, Error};
+use clap::Parser;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+#[derive(Parser)]
+#[command(
+    name = "charta-repl",
+    version,
+    about = "Interactively drive a Charta IR program"
+)]
+struct Cli {
+    /// IR JSON file
+    program: PathBuf,
+}
+
+const HELP: &str = "\
+commands:
+  set <signal> <true|false>    set a signal
+  cycle [n]                    execute n cycles (default 1) and print coil changes
+  coils                        print every coil
+  signals                      print every signal
+  explain <coil>               show the rungs driving a coil and why they hold
+  force <coil> [true|false]    hold a coil at a value after every cycle; safety
+                               coils print a token to repeat the command with
+                               and are set once, not held
+  unforce <coil>               release a forced coil
+  trace <on|off>               print every rung evaluation during cycles
+  help                         show this message
+  quit                         exit";
+
+/// REPL state around the VM
+struct Repl {
+    vm: ChartaVM,
+    program: Program,
+    forced: BTreeMap<String, bool>,
+    trace: bool,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.program).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(path: PathBuf) -> Result<(), Error> {
+    let source = tokio::fs::read_to_string(&path).await?;
+    let mut vm = ChartaVM::new();
+    vm.load_program(&source).await?;
+    let mut repl = Repl {
+        vm,
+        program: Program::from_json(&source)?,
+        forced: BTreeMap::new(),
+        trace: false,
+    };
+    println!(
+        "loaded {} ({} rungs); type 'help' for commands",
+        path.display(),
+        repl.program.module.rungs.len()
+    );
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        print!("charta> ");
+        std::io::stdout().flush()?;
+        let Some(line) = lines.next_line().await? else {
+            println!();
+            return Ok(());
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => {}
+            ["quit" | "exit"] => return Ok(()),
+            words => {
+                if let Err(e) = repl.command(words).await {
+                    println!("error: {}", e);
+                }
+            }
+        }
+    }
+}
+
+impl Repl {
+    async fn command(&mut self, words: &[&str]) -> Result<(), Error> {
+        match words {
+            ["help"] => println!("{}", HELP),
+            ["set", name, value] => self.vm.set_signal(name, parse_bool(value)?).await?,
+            ["cycle"] => self.cycles(1).await?,
+            ["cycle", count] => {
+                let count = count
+                    .parse()
+                    .map_err(|_| usage(&format!("invalid cycle count '{}'", count)))?;
+                self.cycles(count).await?;
+            }
+            ["coils"] => print_states(&self.vm.get_all_coils().await?, &self.forced),
+            ["signals"] => print_states(&self.vm.get_all_signals().await?, &BTreeMap::new()),
+            ["explain", coil] => self.explain(coil).await?,
+            ["force", coil] => self.force(coil, true, None).await?,
+            ["force", coil, value] => self.force(coil, parse_bool(value)?, None).await?,
+            ["force", coil, value, token] => {
+                self.force(coil, parse_bool(value)?, Some(*token)).await?
+            }
+            ["unforce", coil] => {
+                if self.forced.remove(*coil).is_none() {
+                    println!("'{}' is not forced", coil);
+                }
+            }
+            ["trace", "on"] => self.trace = true,
+            ["trace", "off"] => self.trace = false,
+            _ => return Err(usage(&format!("unknown command '{}'", words.join(" ")))),
+        }
+        Ok(())
+    }
+
+    async fn cycles(&mut self, count: u64) -> Result<(), Error> {
+        for _ in 0..count {
+            println!("cycle {}", self.vm.cycle_count() + 1);
+            if self.trace {
+                for evaluation in self.program.evaluate_rungs(&self.state().await?) {
+                    let result = if evaluation.energised {
+                        "energised"
+                    } else {
+                        "-"
+                    };
+                    println!("  rung {:<24} {}", evaluation.rung, result);
+                }
+            }
+
+            let outputs = self.vm.execute_cycle().await?;
+            for (coil, value) in &self.forced {
+                self.vm.set_coil(coil, *value).await?;
+            }
+            let changes: BTreeMap<&str, (bool, bool)> = outputs
+                .changes()
+                .filter(|(coil, _, _)| !self.forced.contains_key(*coil))
+                .map(|(coil, old, new)| (coil, (old, new)))
+                .collect();
+            for (coil, (old, new)) in changes {
+                println!("  {:<28} {} -> {}", coil, old, new);
+            }
+        }
+        Ok(())
+    }
+
+    async fn explain(&self, coil: &str) -> Result<(), Error> {
+        if self.vm.get_coil(coil).await?.is_none() {
+            return Err(Error::NotFound(format!("coil '{}'", coil)));
+        }
+        if let Some(value) = self.forced.get(coil) {
+            println!("'{}' is forced {}", coil, value);
+        }
+
+        // Replay the scan so each rung sees the coils earlier rungs drove
+        let mut state = self.state().await?;
+        let mut driven = false;
+        for rung in &self.program.module.rungs {
+            let lookup = |name: &str| state.get(name).copied().unwrap_or(false);
+            let energised = rung.guard.evaluate(&lookup);
+            if rung.target_coils().any(|target| target == coil) {
+                driven = true;
+                let result = if energised {
+                    "energised"
+                } else {
+                    "not energised"
+                };
+                println!("rung {}: {}", rung.describe(), result);
+                print_guard(&rung.guard, &lookup, 1);
+            }
+            for target in rung.target_coils() {
+                state.insert(target.to_string(), energised);
+            }
+        }
+        if !driven {
+            println!("no rung drives '{}'", coil);
+        }
+        Ok(())
+    }
+
+    async fn force(&mut self, coil: &str, value: bool, token: Option<&str>) -> Result<(), Error> {
+        if self.vm.get_coil(coil).await?.is_none() {
+            return Err(Error::NotFound(format!("coil '{}'", coil)));
+        }
+        if self.vm.is_safety_coil(coil) {
+            let Some(token) = token else {
+                let token = self.vm.request_safety_override(coil, value)?;
+                println!(
+                    "'{}' is a safety coil; confirm with: force {} {} {}",
+                    coil, coil, value, token
+                );
+                return Ok(());
+            };
+            // Each token confirms one override, so safety coils are not held
+            self.vm.set_safety_coil(coil, value, token).await?;
+            println!(
+                "'{}' set {} once; scans drive it from the next cycle",
+                coil, value
+            );
+            return Ok(());
+        }
+        self.vm.set_coil(coil, value).await?;
+        self.forced.insert(coil.to_string(), value);
+        Ok(())
+    }
+
+    /// Current signal and coil states, as a scan starts from
+    async fn state(&self) -> Result<HashMap<String, bool>, Error> {
+        let mut state = self.vm.get_all_signals().await?;
+        state.extend(self.vm.get_all_coils().await?);
+        Ok(state)
+    }
+}
+
+/// Print a guard as an indented tree with the value of each node
+///
+/// Comparisons read typed values the REPL does not track, so they print
+/// without a value.
+fn print_guard<F>(guard: &Guard, lookup: &F, depth: usize)
+where
+    F: Fn(&str) -> bool,
+{
+    let indent = "  ".repeat(depth);
+    let value = guard.evaluate(lookup);
+    match guard {
+        Guard::Contact { name, contact_type } => {
+            let contact = match contact_type {
+                ContactType::NormallyOpen => format!("[ {} ]", name),
+                ContactType::NormallyClosed => format!("[/{} ]", name),
+            };
+            println!("{}{} {} (signal {})", indent, contact, value, lookup(name));
+        }
+        Guard::And { .. } | Guard::Or { .. } | Guard::Not { .. } => {
+            let node = match guard {
+                Guard::And { .. } => "and",
+                Guard::Or { .. } => "or",
+                _ => "not",
+            };
+            println!("{}{} {}", indent, node, value);
+            for operand in guard.operands() {
+                print_guard(operand, lookup, depth + 1);
+            }
+        }
+        Guard::Compare { .. } | Guard::WithinRange { .. } => {
+            println!("{}{} ?", indent, guard.condition().unwrap_or_default());
+        }
+    }
+}
+
+fn print_states(states: &HashMap<String, bool>, forced: &BTreeMap<String, bool>) {
+    let states: BTreeMap<&String, &bool> = states.iter().collect();
+    for (name, value) in states {
+        let marker = if forced.contains_key(name) {
+            " (forced)"
+        } else {
+            ""
+        };
+        println!("  {:<28} {}{}", name, value, marker);
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, Error> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "on" => Ok(true),
+        "false" | "0" | "off" => Ok(false),
+        _ => Err(usage(&format!("invalid value '{}'", value))),
+    }
+}
+
+fn usage(message: &str) -> Error {
+    Error::InvalidOperation(format!("{}; type 'help' for commands", message))
+}
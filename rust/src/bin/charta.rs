@@ -0,0 +1,186 @@
+/// Command-line tool for exercising Charta IR programs
+///
+/// ```text
+/// charta validate program.ir.json
+/// charta run program.ir.json --set user_submitted=true --cycles 5
+/// charta trace program.ir.json --set user_submitted=true
+/// charta fmt --write program.ir.json
+/// ```
+
+use charta::ir::Program;
+use charta::format::{format_ir, format_ir_minified};
+use charta::{ChartaVM, Error, UnknownNodePolicy};
+use clap::{Args, Parser, Subcommand};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "charta", version, about = "Validate, run, and trace Charta IR programs")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check that a program loads and summarise it
+    Validate {
+        /// IR JSON file
+        program: PathBuf,
+        /// Load rungs with unknown node types as disabled instead of failing
+        #[arg(long)]
+        permissive: bool,
+    },
+    /// Execute cycles and print coil changes
+    Run(RunArgs),
+    /// Execute cycles and print every rung evaluation
+    Trace(RunArgs),
+    /// Rewrite a program in canonical form
+    Fmt {
+        /// IR JSON file
+        program: PathBuf,
+        /// Use the minified form
+        #[arg(long)]
+        minify: bool,
+        /// Fail if the file is not already formatted
+        #[arg(long, conflicts_with = "write")]
+        check: bool,
+        /// Format the file in place instead of printing it
+        #[arg(long)]
+        write: bool,
+    },
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// IR JSON file
+    program: PathBuf,
+    /// Set a signal before the first cycle (`name=true|false`); repeatable
+    #[arg(long = "set", value_name = "SIGNAL=VALUE", value_parser = parse_assignment)]
+    signals: Vec<(String, bool)>,
+    /// Number of cycles to execute
+    #[arg(long, default_value_t = 1)]
+    cycles: u64,
+}
+
+fn parse_assignment(arg: &str) -> Result<(String, bool), String> {
+    let (name, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected SIGNAL=VALUE, got '{}'", arg))?;
+    let value = match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "on" => true,
+        "false" | "0" | "off" => false,
+        _ => return Err(format!("invalid value '{}' for signal '{}'", value, name)),
+    };
+    Ok((name.to_string(), value))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Validate {
+            program,
+            permissive,
+        } => validate(program, permissive).await,
+        Command::Run(args) => run(args, false).await,
+        Command::Trace(args) => run(args, true).await,
+        Command::Fmt {
+            program,
+            minify,
+            check,
+            write,
+        } => fmt(program, minify, check, write).await,
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn validate(path: PathBuf, permissive: bool) -> Result<(), Error> {
+    let policy = if permissive {
+        UnknownNodePolicy::Permissive
+    } else {
+        UnknownNodePolicy::Strict
+    };
+    let mut vm = ChartaVM::builder().unknown_nodes(policy).build();
+    let report = vm.load_program_from_file(&path).await?;
+
+    println!("{}: ok", path.display());
+    println!("  program id: {}", report.program_id);
+    println!("  signals:    {}", report.signals);
+    println!("  coils:      {}", report.coils);
+    println!("  rungs:      {}", report.rungs);
+    for warning in &report.warnings {
+        println!("  warning: {}", warning);
+    }
+    Ok(())
+}
+
+async fn fmt(path: PathBuf, minify: bool, check: bool, write: bool) -> Result<(), Error> {
+    let source = tokio::fs::read_to_string(&path).await?;
+    let formatted = if minify {
+        format_ir_minified(&source)?
+    } else {
+        format_ir(&source)?
+    };
+
+    if check {
+        if formatted != source {
+            return Err(Error::InvalidOperation(format!(
+                "{} is not formatted",
+                path.display()
+            )));
+        }
+    } else if write {
+        if formatted != source {
+            tokio::fs::write(&path, formatted).await?;
+        }
+    } else {
+        print!("{}", formatted);
+    }
+    Ok(())
+}
+
+async fn run(args: RunArgs, trace: bool) -> Result<(), Error> {
+    let source = tokio::fs::read_to_string(&args.program).await?;
+    let mut vm = ChartaVM::new();
+    vm.load_program(&source).await?;
+    let program = if trace {
+        Some(Program::from_json(&source)?)
+    } else {
+        None
+    };
+
+    for (name, value) in &args.signals {
+        vm.set_signal(name, *value).await?;
+    }
+
+    for _ in 0..args.cycles {
+        let cycle = vm.cycle_count() + 1;
+        println!("cycle {}", cycle);
+
+        if let Some(program) = &program {
+            let mut state: HashMap<String, bool> = vm.get_all_signals().await?;
+            state.extend(vm.get_all_coils().await?);
+            for evaluation in program.evaluate_rungs(&state) {
+                let result = if evaluation.energised { "energised" } else { "-" };
+                println!("  rung {:<24} {}", evaluation.rung, result);
+            }
+        }
+
+        let outputs = vm.execute_cycle().await?;
+        let coils: BTreeMap<&String, &bool> = outputs.iter().collect();
+        for (coil, state) in coils {
+            let marker = if outputs.changed(coil) { "*" } else { " " };
+            println!("  {} {:<26} {}", marker, coil, state);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,646 @@
+//! Synchronous Charta VM
+//!
+//! [`ChartaVM`] mirrors the async [`crate::ChartaVM`] API without locks or a
+//! runtime, for CLI tools, embedded targets, and tests where the async
+//! wrapper is pure overhead:
+//!
+//! ```no_run
+//! use charta::blocking::ChartaVM;
+//!
+//! # fn main() -> charta::Result<()> {
+//! let mut vm = ChartaVM::new();
+//! vm.load_program_from_file("policy.json")?;
+//! vm.set_signal("user_submitted", true)?;
+//! let outputs = vm.execute_cycle()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Callbacks, the fault hook, panic policy, load limits, and the unknown-node
+//! policy behave as in the async VM. Events, statistics, history, drivers,
+//! and shadow programs are only available on the async VM.
+
+use crate::builder::VmConfig;
+use crate::bypass::{self, Bypasses, RungBypass};
+use crate::callbacks::{
+    CallbackError, CallbackManager, CycleContext, ErrorContext, ErrorPhase, PanicPolicy,
+};
+use crate::collections::Map;
+use crate::error::{Error, Result};
+use crate::filter::InputFilters;
+use crate::ir::{Metadata, Program, Rung, RungInfo};
+use crate::load::{self, LoadOptions, LoadReport};
+use crate::mode::VmMode;
+use crate::quality::{Quality, QualityTracker};
+use crate::registry::program_hash;
+use crate::safety::{self, SafetyOverrides};
+use crate::scan::{self, Scanner};
+use crate::value::{self, is_derived_signal, Assignment, Value};
+use charta_vm::{ir::load_ir, VM};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// Synchronous Charta VM instance
+pub struct ChartaVM {
+    vm: VM,
+    callbacks: CallbackManager,
+    program: Option<Program>,
+    program_id: Option<String>,
+    report: LoadReport,
+    scanner: Scanner,
+    values: HashMap<String, Value>,
+    registers: HashMap<String, Value>,
+    quality: QualityTracker,
+    filters: InputFilters,
+    bypasses: Bypasses,
+    safety_overrides: SafetyOverrides,
+    cycle_count: u64,
+    config: VmConfig,
+}
+
+impl ChartaVM {
+    /// Create a new VM instance
+    pub fn new() -> Self {
+        Self::with_config(VmConfig::default())
+    }
+
+    pub(crate) fn with_config(config: VmConfig) -> Self {
+        Self {
+            vm: VM::new(),
+            callbacks: CallbackManager::new(),
+            program: None,
+            program_id: None,
+            report: LoadReport::default(),
+            scanner: Scanner::default(),
+            values: HashMap::new(),
+            registers: HashMap::new(),
+            quality: QualityTracker::default(),
+            filters: InputFilters::default(),
+            bypasses: Bypasses::default(),
+            safety_overrides: SafetyOverrides::default(),
+            cycle_count: 0,
+            config,
+        }
+    }
+
+    /// Load a program from IR JSON string
+    ///
+    /// Returns a [`LoadReport`] of what was loaded.
+    pub fn load_program(&mut self, ir_json: &str) -> Result<LoadReport> {
+        self.load_program_with_options(ir_json, &LoadOptions::default())
+    }
+
+    /// Load a program from IR JSON string, starting signals and coils at the
+    /// values in `options`
+    pub fn load_program_with_options(
+        &mut self,
+        ir_json: &str,
+        options: &LoadOptions,
+    ) -> Result<LoadReport> {
+        let result = self.load_program_inner(ir_json, options);
+        if let Err(e) = &result {
+            self.report_error(e, ErrorPhase::Load);
+        }
+        result
+    }
+
+    fn load_program_inner(&mut self, ir_json: &str, options: &LoadOptions) -> Result<LoadReport> {
+        let program_id = program_hash(ir_json);
+        let validated = load::validate(
+            ir_json,
+            self.config.unknown_nodes,
+            &self.config.limits,
+            self.config.quality,
+        )?;
+        let filters = InputFilters::new(validated.program.as_ref(), &self.config.filters)?;
+        let initial = load::initial_state(validated.program.as_ref(), options)?;
+        let ir = load_ir(&validated.ir_json).map_err(|e| Error::IRLoad(e.to_string()))?;
+        self.vm.load_program(ir).map_err(Error::VM)?;
+        initial.apply(&mut self.vm);
+        self.filters = filters;
+        self.bypasses.clear();
+
+        value::declare(&mut self.values, validated.program.as_ref());
+        self.values.extend(initial.values);
+        value::declare_registers(&mut self.registers, validated.program.as_ref());
+        self.report = LoadReport::new(
+            program_id.clone(),
+            validated.program.as_ref(),
+            validated.ignored,
+            validated.order_issues,
+        );
+        self.program = validated.program;
+        self.program_id = Some(program_id);
+        self.scanner = validated.scanner;
+        Ok(self.report.clone())
+    }
+
+    /// Load a program from a file
+    pub fn load_program_from_file<P: AsRef<Path>>(&mut self, path: P) -> Result<LoadReport> {
+        let contents = std::fs::read_to_string(path)?;
+        self.load_program(&contents)
+    }
+
+    /// Unload the running program
+    ///
+    /// Clears signals, coils, typed values, registers, and bypasses, keeping
+    /// the cycle count and callbacks. Returns whether a program was loaded.
+    pub fn unload_program(&mut self) -> bool {
+        if self.program_id.take().is_none() {
+            return false;
+        }
+        self.vm = VM::new();
+        self.program = None;
+        self.report = LoadReport::default();
+        self.scanner = Scanner::default();
+        self.values.clear();
+        self.registers.clear();
+        self.filters = InputFilters::default();
+        self.bypasses.clear();
+        self.safety_overrides = SafetyOverrides::default();
+        true
+    }
+
+    /// Return the loaded program to its state just after loading
+    ///
+    /// Signals and coils go back to their initial values, typed values and
+    /// registers to their defaults, and the cycle count to zero; input
+    /// filters and signal quality timers restart. The program, bypasses, and
+    /// callbacks are kept. Fails if no program is loaded.
+    pub fn reset_state(&mut self) -> Result<()> {
+        if self.program_id.is_none() {
+            return Err(Error::InvalidOperation("no program loaded".to_string()));
+        }
+        let initial = load::initial_state(self.program.as_ref(), &LoadOptions::default())?;
+        let filters = InputFilters::new(self.program.as_ref(), &self.config.filters)?;
+        // Derived signals are set every cycle or mirror bypasses
+        for (name, _) in self.vm.get_all_signals() {
+            if !is_derived_signal(&name) {
+                self.vm.set_signal(name, false);
+            }
+        }
+        for (name, _) in self.vm.get_all_coils() {
+            self.vm.set_coil(name, false);
+        }
+        initial.apply(&mut self.vm);
+
+        self.filters = filters;
+        self.values.clear();
+        value::declare(&mut self.values, self.program.as_ref());
+        self.values.extend(initial.values);
+        self.registers.clear();
+        value::declare_registers(&mut self.registers, self.program.as_ref());
+        self.quality.restart();
+        self.safety_overrides = SafetyOverrides::default();
+        self.cycle_count = 0;
+        Ok(())
+    }
+
+    /// Execute one scan cycle
+    ///
+    /// Returns a map of coil names to their new states (true if energised).
+    pub fn execute_cycle(&mut self) -> Result<HashMap<String, bool>> {
+        self.execute_cycle_with_inputs(HashMap::new())
+    }
+
+    /// Execute one scan cycle with input signals
+    pub fn execute_cycle_with_inputs(
+        &mut self,
+        inputs: HashMap<String, bool>,
+    ) -> Result<HashMap<String, bool>> {
+        self.scan(inputs, None)
+    }
+
+    /// Execute one cycle for each map of input signals, in order
+    ///
+    /// Returns the coil states after every cycle. Stops at the first cycle
+    /// that fails and returns its error; the cycles before it stay
+    /// committed.
+    pub fn execute_cycles(
+        &mut self,
+        inputs_per_cycle: Vec<HashMap<String, bool>>,
+    ) -> Result<Vec<HashMap<String, bool>>> {
+        inputs_per_cycle
+            .into_iter()
+            .map(|inputs| self.execute_cycle_with_inputs(inputs))
+            .collect()
+    }
+
+    /// Compute the outputs of a cycle with input signals without committing
+    /// any state
+    ///
+    /// Signals, coils, registers, and the cycle count are left as they were,
+    /// and no callbacks run.
+    pub fn execute_cycle_dry_run(
+        &mut self,
+        mut inputs: HashMap<String, bool>,
+    ) -> Result<HashMap<String, bool>> {
+        scan::check_inputs(&inputs)?;
+        self.scanner.prepare(
+            &mut inputs,
+            None,
+            &self.values,
+            &self.registers,
+            &self.quality,
+        )?;
+        let (outputs, _) = scan::dry_step(&mut self.vm, &self.filters, inputs, false)?;
+        Ok(outputs)
+    }
+
+    /// Execute one scan cycle of the rungs in scan group `group`
+    ///
+    /// Rungs of other groups hold their coils and move nothing. Fails if no
+    /// rung of the loaded program is in `group`.
+    pub fn execute_group(&mut self, group: &str) -> Result<HashMap<String, bool>> {
+        self.scanner.check_group(group)?;
+        self.scan(HashMap::new(), Some(group))
+    }
+
+    fn scan(
+        &mut self,
+        mut inputs: HashMap<String, bool>,
+        group: Option<&str>,
+    ) -> Result<HashMap<String, bool>> {
+        if let Err(e) = scan::check_inputs(&inputs) {
+            self.report_error(&e, ErrorPhase::Cycle);
+            return Err(e);
+        }
+        for (name, fallback) in scan::refresh_quality(&mut self.quality, &inputs) {
+            self.vm.set_signal(name, fallback);
+        }
+        if let Err(e) = self.scanner.prepare(
+            &mut inputs,
+            group,
+            &self.values,
+            &self.registers,
+            &self.quality,
+        ) {
+            self.report_error(&e, ErrorPhase::Cycle);
+            return Err(e);
+        }
+        let held = if self.filters.is_empty() {
+            Vec::new()
+        } else {
+            self.filters.condition(&self.vm.get_all_signals(), &mut inputs)
+        };
+        let old_coils = self.vm.get_all_coils();
+        let scan_state = match &self.program {
+            Some(_) if self.scanner.has_moves() => {
+                let signals = self.vm.get_all_signals();
+                Some(scan::scan_state(signals, &old_coils, &inputs))
+            }
+            _ => None,
+        };
+        let stepped = self.vm.step(inputs);
+        for (name, raw) in held {
+            self.vm.set_signal(name, raw);
+        }
+        let outputs = match stepped {
+            Ok(outputs) => outputs,
+            Err(e) => {
+                let e = Error::VM(e);
+                self.report_error(&e, ErrorPhase::Cycle);
+                return Err(e);
+            }
+        };
+        self.cycle_count += 1;
+        if let (Some(program), Some(state)) = (&self.program, &scan_state) {
+            let evaluations = self.scanner.evaluate(program, state, &self.bypasses, group);
+            self.scanner
+                .apply_moves(&evaluations, &self.values, &mut self.registers);
+        }
+
+        let changes: Map<String, (bool, bool)> = outputs
+            .iter()
+            .filter_map(|(name, &new_value)| {
+                let old_value = old_coils.get(name).copied().unwrap_or(false);
+                (old_value != new_value).then(|| (name.clone(), (old_value, new_value)))
+            })
+            .collect();
+
+        let cycle = CycleContext::now(self.cycle_count, self.program_id.as_deref());
+        let mut callback_errors = Vec::new();
+        if !changes.is_empty() {
+            callback_errors.extend(self.callbacks.trigger_coil_changes(&changes, &cycle));
+        }
+        callback_errors.extend(self.callbacks.trigger_cycle_complete(&outputs, &cycle));
+
+        let context = ErrorContext {
+            cycle: self.cycle_count,
+            phase: ErrorPhase::Callback,
+        };
+        for error in &callback_errors {
+            let error = Error::CallbackPanicked(format!("{}: {}", error.callback, error.message));
+            self.callbacks.trigger_error(&error, &context);
+        }
+        self.callbacks.check(&callback_errors)?;
+        Ok(outputs)
+    }
+
+    fn report_error(&self, error: &Error, phase: ErrorPhase) {
+        let cycle = match phase {
+            ErrorPhase::Cycle => self.cycle_count + 1,
+            _ => self.cycle_count,
+        };
+        self.callbacks.trigger_error(error, &ErrorContext { cycle, phase });
+    }
+
+    /// Get the hash identifying the loaded program
+    pub fn program_id(&self) -> Option<String> {
+        self.program_id.clone()
+    }
+
+    /// Get the report of the last successful program load
+    pub fn load_report(&self) -> Option<LoadReport> {
+        self.program_id.as_ref().map(|_| self.report.clone())
+    }
+
+    /// Get the loaded rungs' names in scan order
+    pub fn rung_order(&self) -> Vec<String> {
+        self.program
+            .iter()
+            .flat_map(|program| program.module.rungs.iter().map(|rung| rung.name.clone()))
+            .collect()
+    }
+
+    /// Get the structure of the loaded rungs in scan order
+    pub fn rungs(&self) -> Vec<RungInfo> {
+        self.program
+            .iter()
+            .flat_map(|program| program.module.rungs.iter().map(Rung::info))
+            .collect()
+    }
+
+    /// Get the number of cycles executed so far
+    pub fn cycle_count(&self) -> u64 {
+        self.cycle_count
+    }
+
+    /// Get the current state of a coil
+    pub fn get_coil(&self, name: &str) -> Result<Option<bool>> {
+        Ok(self.vm.get_coil_state(name))
+    }
+
+    /// Get the current state of a signal
+    pub fn get_signal(&self, name: &str) -> Result<Option<bool>> {
+        Ok(self.vm.get_signal_state(name))
+    }
+
+    /// Get all coil states
+    pub fn get_all_coils(&self) -> Result<HashMap<String, bool>> {
+        Ok(self.vm.get_all_coils())
+    }
+
+    /// Get all signal states
+    pub fn get_all_signals(&self) -> Result<HashMap<String, bool>> {
+        let mut signals = self.vm.get_all_signals();
+        signals.retain(|name, _| !is_derived_signal(name));
+        Ok(signals)
+    }
+
+    /// Get the value of a signal, typed or boolean
+    pub fn get_value(&self, name: &str) -> Result<Option<Value>> {
+        if let Some(value) = self.values.get(name) {
+            return Ok(Some(value.clone()));
+        }
+        Ok(self.vm.get_signal_state(name).map(Value::Bool))
+    }
+
+    /// Get the values of all typed signals
+    pub fn get_all_values(&self) -> Result<HashMap<String, Value>> {
+        Ok(self.values.clone())
+    }
+
+    /// Get the value of a register written by `move` actions
+    pub fn get_register(&self, name: &str) -> Result<Option<Value>> {
+        Ok(self.registers.get(name).cloned())
+    }
+
+    /// Get the values of all registers
+    pub fn get_all_registers(&self) -> Result<HashMap<String, Value>> {
+        Ok(self.registers.clone())
+    }
+
+    /// Get the metadata declared for a signal
+    pub fn signal_meta(&self, name: &str) -> Option<Metadata> {
+        let program = self.program.as_ref()?;
+        program
+            .module
+            .signals
+            .iter()
+            .find(|signal| signal.name == name)
+            .map(|signal| signal.meta.clone())
+    }
+
+    /// Get the metadata declared for a coil
+    pub fn coil_meta(&self, name: &str) -> Option<Metadata> {
+        let program = self.program.as_ref()?;
+        program
+            .module
+            .coils
+            .iter()
+            .find(|coil| coil.name == name)
+            .map(|coil| coil.meta.clone())
+    }
+
+    /// Set a signal value
+    ///
+    /// Fails with [`Error::AccessDenied`] for derived signals (see
+    /// [`is_derived_signal`](crate::value::is_derived_signal)), which the
+    /// SDK sets itself; so do cycle inputs naming one.
+    pub fn set_signal(&mut self, name: &str, value: bool) -> Result<()> {
+        self.set_signal_with_quality(name, value, Quality::Good)
+    }
+
+    /// Set a signal value along with its quality
+    pub fn set_signal_with_quality(
+        &mut self,
+        name: &str,
+        value: bool,
+        quality: Quality,
+    ) -> Result<()> {
+        value::check_writable(name)?;
+        self.vm.set_signal(name.to_string(), value);
+        self.quality.update(name, quality);
+        Ok(())
+    }
+
+    /// Mark a signal [`Quality::Stale`] when it is not written for `timeout`
+    pub fn set_stale_timeout(&mut self, name: &str, timeout: Duration) {
+        self.quality.set_timeout(name, timeout);
+    }
+
+    /// Revert a signal to `fallback` when it is not written for `ttl`
+    ///
+    /// The first cycle after the TTL runs out sets the signal to `fallback`
+    /// and marks it [`Quality::Stale`].
+    pub fn set_signal_ttl(&mut self, name: &str, ttl: Duration, fallback: bool) {
+        self.quality.set_ttl(name, ttl, fallback);
+    }
+
+    /// Take a rung out of the scan until it is enabled again
+    ///
+    /// From the next cycle the rung's guard evaluates false. Disabling a
+    /// rung that already is does nothing. See [`crate::bypass`].
+    pub fn disable_rung(&mut self, name: &str) -> Result<()> {
+        if self
+            .bypasses
+            .disable(self.program.as_ref(), name, self.cycle_count)?
+        {
+            self.vm.set_signal(bypass::bypass_signal(name), true);
+        }
+        Ok(())
+    }
+
+    /// Return a disabled rung to the scan
+    pub fn enable_rung(&mut self, name: &str) -> Result<()> {
+        if self.bypasses.enable(name) {
+            self.vm.set_signal(bypass::bypass_signal(name), false);
+        }
+        Ok(())
+    }
+
+    /// Get the rungs currently disabled, in the order they were disabled
+    pub fn disabled_rungs(&self) -> Vec<RungBypass> {
+        self.bypasses.list().to_vec()
+    }
+
+    /// Get the quality of a signal's value
+    pub fn get_signal_quality(&self, name: &str) -> Result<Quality> {
+        Ok(self.quality.quality(name))
+    }
+
+    /// Set the value of a signal, checked against its declared type
+    pub fn set_value(&mut self, name: &str, value: impl Into<Value>) -> Result<()> {
+        match value::assign(self.program.as_ref(), name, value.into())? {
+            Assignment::Typed(value) => {
+                self.values.insert(name.to_string(), value);
+                self.quality.update(name, Quality::Good);
+                Ok(())
+            }
+            Assignment::Bool(value) => self.set_signal(name, value),
+        }
+    }
+
+    /// Set a coil value (for testing/debugging)
+    ///
+    /// Fails with [`Error::AccessDenied`] for safety coils (see
+    /// [`set_safety_coil`](Self::set_safety_coil)), and with
+    /// [`Error::InvalidOperation`] in [production mode](crate::mode).
+    pub fn set_coil(&mut self, name: &str, value: bool) -> Result<()> {
+        self.config.mode.permit("set_coil")?;
+        if self.is_safety_coil(name) {
+            return Err(safety::refused(name));
+        }
+        self.vm.set_coil(name.to_string(), value);
+        Ok(())
+    }
+
+    /// Get whether test-oriented mutators are permitted; see [`crate::mode`]
+    pub fn mode(&self) -> VmMode {
+        self.config.mode
+    }
+
+    /// Check whether a coil is safety-classified
+    pub fn is_safety_coil(&self, name: &str) -> bool {
+        safety::is_safety_coil(self.program.as_ref(), &self.config.safety_coils, name)
+    }
+
+    /// Issue a one-time token confirming a write of `value` to a safety coil
+    pub fn request_safety_override(&mut self, name: &str, value: bool) -> Result<String> {
+        self.config.mode.permit("request_safety_override")?;
+        if !self.is_safety_coil(name) {
+            return Err(safety::not_safety(name));
+        }
+        #[cfg(feature = "tracing")]
+        tracing::warn!(coil = %name, value, "safety override requested");
+        Ok(self.safety_overrides.issue(name, value))
+    }
+
+    /// Set a safety coil with a token from
+    /// [`request_safety_override`](Self::request_safety_override)
+    pub fn set_safety_coil(&mut self, name: &str, value: bool, token: &str) -> Result<()> {
+        self.config.mode.permit("set_safety_coil")?;
+        if !self.is_safety_coil(name) {
+            return Err(safety::not_safety(name));
+        }
+        self.safety_overrides.redeem(name, value, token)?;
+        self.vm.set_coil(name.to_string(), value);
+        #[cfg(feature = "tracing")]
+        tracing::error!(coil = %name, value, "safety coil overridden");
+        Ok(())
+    }
+
+    /// Get signal names
+    pub fn signal_names(&self) -> Result<Vec<String>> {
+        let mut names = self.vm.signal_names().to_vec();
+        names.retain(|name| !is_derived_signal(name));
+        Ok(names)
+    }
+
+    /// Get coil names
+    pub fn coil_names(&self) -> Result<Vec<String>> {
+        Ok(self.vm.coil_names().to_vec())
+    }
+
+    /// Register a callback for when a specific coil changes state
+    ///
+    /// `coil_name` may also be a pattern like `"allow_*"` or `"safety/**"`.
+    ///
+    /// The callback receives: (coil_name, old_value, new_value, context)
+    pub fn on_coil_change<F>(&mut self, coil_name: &str, callback: F)
+    where
+        F: Fn(&str, bool, bool, &CycleContext) + Send + Sync + 'static,
+    {
+        self.callbacks.on_coil_change(coil_name, callback);
+    }
+
+    /// Register a callback for when any coil changes state
+    pub fn on_any_coil_change<F>(&mut self, callback: F)
+    where
+        F: Fn(&str, bool, bool, &CycleContext) + Send + Sync + 'static,
+    {
+        self.callbacks.on_any_coil_change(callback);
+    }
+
+    /// Register a callback for cycle completion
+    pub fn on_cycle_complete<F>(&mut self, callback: F)
+    where
+        F: Fn(&HashMap<String, bool>, &CycleContext) + Send + Sync + 'static,
+    {
+        self.callbacks.on_cycle_complete(callback);
+    }
+
+    /// Register a callback for panicking callbacks
+    pub fn on_callback_error<F>(&mut self, callback: F)
+    where
+        F: Fn(&CallbackError) + Send + Sync + 'static,
+    {
+        self.callbacks.on_callback_error(callback);
+    }
+
+    /// Register a callback for VM faults
+    pub fn on_error<F>(&mut self, callback: F)
+    where
+        F: Fn(&Error, &ErrorContext) + Send + Sync + 'static,
+    {
+        self.callbacks.on_error(callback);
+    }
+
+    /// Set how panicking callbacks are handled
+    pub fn set_panic_policy(&mut self, policy: PanicPolicy) {
+        self.callbacks.set_panic_policy(policy);
+    }
+
+    /// Clear all callbacks
+    pub fn clear_callbacks(&mut self) {
+        self.callbacks.clear();
+    }
+}
+
+impl Default for ChartaVM {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,253 @@
+//! Configurable construction of Charta VMs
+//!
+//! ```no_run
+//! use charta::ChartaVM;
+//! use std::time::Duration;
+//!
+//! let vm = ChartaVM::builder()
+//!     .cycle_deadline(Duration::from_millis(10))
+//!     .abort_on_deadline(true)
+//!     .build();
+//! ```
+
+use crate::dispatch::DispatchMode;
+use crate::events::DEFAULT_EVENT_CAPACITY;
+use crate::filter::InputFilter;
+use crate::limits::LoadLimits;
+use crate::load::UnknownNodePolicy;
+use crate::mode::VmMode;
+use crate::persistence::{Persistence, StateStore};
+use crate::quality::QualityPolicy;
+use crate::vm::ChartaVM;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Settings fixed when a VM is built
+#[derive(Debug, Clone)]
+pub(crate) struct VmConfig {
+    /// Capacity of the event broadcast channel
+    pub(crate) event_capacity: usize,
+    /// Time budget for evaluation plus callbacks
+    pub(crate) cycle_deadline: Option<Duration>,
+    /// Skip remaining callback dispatch once the deadline has passed
+    pub(crate) abort_on_deadline: bool,
+    /// Size limits applied to loaded programs
+    pub(crate) limits: LoadLimits,
+    /// Handling of unrecognised IR nodes
+    pub(crate) unknown_nodes: UnknownNodePolicy,
+    /// Where callbacks run
+    pub(crate) dispatch: DispatchMode,
+    /// Store receiving periodic checkpoints
+    pub(crate) persist: Option<Persistence>,
+    /// Handling of rungs reading Bad or Stale signals
+    pub(crate) quality: QualityPolicy,
+    /// Input filters, overriding those declared in the IR
+    pub(crate) filters: HashMap<String, InputFilter>,
+    /// Scan group intervals driven by the scheduler
+    pub(crate) scan_groups: HashMap<String, Duration>,
+    /// How long event-driven execution waits for more signal changes
+    pub(crate) coalesce_window: Duration,
+    /// Coils protected from manual writes, besides those marked in the IR
+    pub(crate) safety_coils: HashSet<String>,
+    /// Whether test-oriented mutators are permitted
+    pub(crate) mode: VmMode,
+}
+
+impl Default for VmConfig {
+    fn default() -> Self {
+        Self {
+            event_capacity: DEFAULT_EVENT_CAPACITY,
+            cycle_deadline: None,
+            abort_on_deadline: false,
+            limits: LoadLimits::default(),
+            unknown_nodes: UnknownNodePolicy::default(),
+            dispatch: DispatchMode::Inline,
+            persist: None,
+            quality: QualityPolicy::default(),
+            filters: HashMap::new(),
+            scan_groups: HashMap::new(),
+            coalesce_window: Duration::ZERO,
+            safety_coils: HashSet::new(),
+            mode: VmMode::default(),
+        }
+    }
+}
+
+/// Builder for [`ChartaVM`]
+#[derive(Debug, Clone, Default)]
+pub struct ChartaVMBuilder {
+    config: VmConfig,
+}
+
+impl ChartaVMBuilder {
+    /// Create a builder with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the capacity of the event broadcast channel
+    pub fn event_capacity(mut self, capacity: usize) -> Self {
+        self.config.event_capacity = capacity;
+        self
+    }
+
+    /// Set the time budget for one cycle, including callbacks
+    ///
+    /// Cycles that overrun it emit a
+    /// [`VmEvent::DeadlineExceeded`](crate::VmEvent::DeadlineExceeded) event.
+    pub fn cycle_deadline(mut self, deadline: Duration) -> Self {
+        self.config.cycle_deadline = Some(deadline);
+        self
+    }
+
+    /// Skip the remaining callback dispatch of a cycle that has overrun its
+    /// deadline
+    ///
+    /// Events are still published. Has no effect without a
+    /// [`cycle_deadline`](Self::cycle_deadline).
+    pub fn abort_on_deadline(mut self, abort: bool) -> Self {
+        self.config.abort_on_deadline = abort;
+        self
+    }
+
+    /// Set all load limits at once
+    pub fn load_limits(mut self, limits: LoadLimits) -> Self {
+        self.config.limits = limits;
+        self
+    }
+
+    /// Reject programs with more than `max` rungs
+    pub fn max_rungs(mut self, max: usize) -> Self {
+        self.config.limits.max_rungs = Some(max);
+        self
+    }
+
+    /// Reject programs declaring more than `max` signals
+    pub fn max_signals(mut self, max: usize) -> Self {
+        self.config.limits.max_signals = Some(max);
+        self
+    }
+
+    /// Reject programs with guards nested deeper than `max`
+    pub fn max_guard_depth(mut self, max: usize) -> Self {
+        self.config.limits.max_guard_depth = Some(max);
+        self
+    }
+
+    /// Reject programs with more than `max` actions in any rung
+    pub fn max_actions_per_rung(mut self, max: usize) -> Self {
+        self.config.limits.max_actions_per_rung = Some(max);
+        self
+    }
+
+    /// Set how guard and action nodes of unrecognised type are handled
+    ///
+    /// Defaults to [`UnknownNodePolicy::Strict`].
+    pub fn unknown_nodes(mut self, policy: UnknownNodePolicy) -> Self {
+        self.config.unknown_nodes = policy;
+        self
+    }
+
+    /// Set what a rung reading a Bad or Stale signal does
+    ///
+    /// Defaults to [`QualityPolicy::Ignore`]; see [`crate::quality`].
+    pub fn quality_policy(mut self, policy: QualityPolicy) -> Self {
+        self.config.quality = policy;
+        self
+    }
+
+    /// Show a new value of `signal` to rung logic only once it has held
+    /// for `cycles` consecutive cycles
+    ///
+    /// Overrides any filter the IR declares on the signal; see
+    /// [`crate::filter`].
+    pub fn debounce(mut self, signal: &str, cycles: u32) -> Self {
+        self.config
+            .filters
+            .insert(signal.to_string(), InputFilter::Debounce { cycles });
+        self
+    }
+
+    /// Let the value of `signal` seen by rung logic change at most once
+    /// every `cycles` cycles
+    ///
+    /// Overrides any filter the IR declares on the signal; see
+    /// [`crate::filter`].
+    pub fn throttle(mut self, signal: &str, cycles: u32) -> Self {
+        self.config
+            .filters
+            .insert(signal.to_string(), InputFilter::Throttle { cycles });
+        self
+    }
+
+    /// Scan the rungs of `group` every `interval` under
+    /// [`ChartaVM::run_scan_groups`]
+    ///
+    /// Groups without an interval scan only through
+    /// [`ChartaVM::execute_group`]; see [`crate::scan_group`].
+    pub fn scan_group(mut self, group: &str, interval: Duration) -> Self {
+        self.config.scan_groups.insert(group.to_string(), interval);
+        self
+    }
+
+    /// Wait `window` after a signal change before the cycle
+    /// [`ChartaVM::run_event_driven`] triggers, so changes arriving
+    /// together are scanned together
+    ///
+    /// Defaults to zero: each change triggers a cycle at once.
+    pub fn coalesce_window(mut self, window: Duration) -> Self {
+        self.config.coalesce_window = window;
+        self
+    }
+
+    /// Classify `coil` as a safety coil, whatever the IR declares
+    ///
+    /// Manual writes to it need a confirmation token; see
+    /// [`crate::safety`].
+    pub fn safety_coil(mut self, coil: &str) -> Self {
+        self.config.safety_coils.insert(coil.to_string());
+        self
+    }
+
+    /// Set whether test-oriented mutators like `set_coil` are permitted
+    ///
+    /// Defaults to [`VmMode::Simulation`]; see [`crate::mode`].
+    pub fn mode(mut self, mode: VmMode) -> Self {
+        self.config.mode = mode;
+        self
+    }
+
+    /// Set where callbacks run
+    ///
+    /// Defaults to [`DispatchMode::Inline`]; see [`crate::dispatch`].
+    pub fn dispatch(mut self, mode: DispatchMode) -> Self {
+        self.config.dispatch = mode;
+        self
+    }
+
+    /// Checkpoint coil and signal states to `store` every `interval`
+    ///
+    /// The first program loaded restores the stored checkpoint; see
+    /// [`crate::persistence`].
+    pub fn persist_to<S: StateStore + 'static>(mut self, store: S, interval: Duration) -> Self {
+        self.config.persist = Some(Persistence {
+            store: Arc::new(store),
+            interval,
+        });
+        self
+    }
+
+    /// Build the VM
+    pub fn build(self) -> ChartaVM {
+        ChartaVM::with_config(self.config)
+    }
+
+    /// Build a [`blocking::ChartaVM`](crate::blocking::ChartaVM)
+    ///
+    /// The event capacity, cycle deadline, dispatch mode, and persistence do
+    /// not apply to the blocking VM.
+    pub fn build_blocking(self) -> crate::blocking::ChartaVM {
+        crate::blocking::ChartaVM::with_config(self.config)
+    }
+}
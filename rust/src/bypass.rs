@@ -0,0 +1,154 @@
+//! Rung bypass
+//!
+//! Operators can take a malfunctioning rung out of the scan while it is
+//! repaired, under management-of-change control:
+//!
+//! ```no_run
+//! use charta::ChartaVM;
+//!
+//! # async fn example(vm: &mut ChartaVM) -> charta::Result<()> {
+//! vm.disable_rung("sanctions_interlock").await?;
+//! for bypass in vm.disabled_rungs() {
+//!     eprintln!("BYPASSED: {} since cycle {}", bypass.rung, bypass.cycle);
+//! }
+//! vm.enable_rung("sanctions_interlock").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A disabled rung's guard evaluates false: it energises no coils and moves
+//! nothing. Every rung is gated on a derived signal (see [`bypass_signal`])
+//! at load time, so bypassing needs no reload. Each bypass and restore is
+//! emitted as a [`VmEvent`](crate::VmEvent) for audit and logged as a
+//! warning under the `tracing` feature. Loading a program, including a
+//! reload, clears all bypasses.
+
+use alloc::format;
+use alloc::string::String;
+
+/// Prefix of the derived signals that hold while a rung is bypassed
+pub const BYPASS_SIGNAL_PREFIX: &str = "__bypass:";
+
+/// Name of the derived signal that holds while `rung` is bypassed
+pub fn bypass_signal(rung: &str) -> String {
+    format!("{}{}", BYPASS_SIGNAL_PREFIX, rung)
+}
+
+#[cfg(feature = "std")]
+pub use tracking::RungBypass;
+#[cfg(feature = "std")]
+pub(crate) use tracking::{apply, Bypasses};
+
+#[cfg(feature = "std")]
+mod tracking {
+    use super::bypass_signal;
+    use crate::error::{Error, Result};
+    use crate::ir::{ContactType, Guard, Program, SignalDecl};
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeSet;
+    use std::time::SystemTime;
+
+    /// A rung taken out of the scan
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct RungBypass {
+        /// Rung name
+        pub rung: String,
+        /// Cycles executed when the rung was disabled
+        pub cycle: u64,
+        /// When the rung was disabled (serialized as `since_ms`,
+        /// milliseconds since the Unix epoch)
+        #[serde(rename = "since_ms", with = "crate::serde_time::unix_millis")]
+        pub since: SystemTime,
+    }
+
+    /// Rungs currently bypassed, in the order they were disabled
+    #[derive(Debug, Clone, Default)]
+    pub(crate) struct Bypasses {
+        rungs: Vec<RungBypass>,
+    }
+
+    impl Bypasses {
+        /// Record `rung` as bypassed
+        ///
+        /// Returns `false` if it already was. Fails if `program` has no rung
+        /// of that name.
+        pub(crate) fn disable(
+            &mut self,
+            program: Option<&Program>,
+            rung: &str,
+            cycle: u64,
+        ) -> Result<bool> {
+            let declared =
+                program.is_some_and(|program| program.module.rungs.iter().any(|r| r.name == rung));
+            if !declared {
+                return Err(Error::NotFound(format!("rung '{}'", rung)));
+            }
+            if self.contains(rung) {
+                return Ok(false);
+            }
+            self.rungs.push(RungBypass {
+                rung: rung.to_string(),
+                cycle,
+                since: SystemTime::now(),
+            });
+            Ok(true)
+        }
+
+        /// Remove the bypass of `rung`, returning whether there was one
+        pub(crate) fn enable(&mut self, rung: &str) -> bool {
+            let before = self.rungs.len();
+            self.rungs.retain(|bypass| bypass.rung != rung);
+            self.rungs.len() != before
+        }
+
+        /// Check whether `rung` is bypassed
+        pub(crate) fn contains(&self, rung: &str) -> bool {
+            self.rungs.iter().any(|bypass| bypass.rung == rung)
+        }
+
+        /// Get the bypassed rungs
+        pub(crate) fn list(&self) -> &[RungBypass] {
+            &self.rungs
+        }
+
+        /// Record a bypass carried over from a previous load
+        pub(crate) fn restore(&mut self, bypass: RungBypass) {
+            if !self.contains(&bypass.rung) {
+                self.rungs.push(bypass);
+            }
+        }
+
+        pub(crate) fn clear(&mut self) {
+            self.rungs.clear();
+        }
+    }
+
+    /// Gate every rung's guard on a normally closed contact on its bypass
+    /// signal, and declare the bypass signals
+    pub(crate) fn apply(program: &mut Program) {
+        let mut names = BTreeSet::new();
+        for rung in &mut program.module.rungs {
+            rung.guard = Guard::And {
+                left: None,
+                right: None,
+                operands: vec![
+                    rung.guard.clone(),
+                    Guard::Contact {
+                        name: bypass_signal(&rung.name),
+                        contact_type: ContactType::NormallyClosed,
+                    },
+                ],
+            };
+            names.insert(rung.name.clone());
+        }
+        for name in names {
+            program.module.signals.push(SignalDecl {
+                name: bypass_signal(&name),
+                value_type: Default::default(),
+                filter: None,
+                initial: None,
+                meta: Default::default(),
+            });
+        }
+    }
+}
@@ -3,39 +3,165 @@
 //! Provides callback system for reacting to VM events like coil state changes
 //! and cycle completion.
 
+use crate::error::{Error, Result};
+use crate::pattern::Pattern;
+use crate::shadow::ShadowDivergence;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use crate::collections::Map;
 use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Callback function type for coil state changes
-pub type CoilChangeCallback = Arc<dyn Fn(&str, bool, bool) + Send + Sync>;
+pub type CoilChangeCallback = Arc<dyn Fn(&str, bool, bool, &CycleContext) + Send + Sync>;
 
 /// Callback function type for cycle completion
-pub type CycleCompleteCallback = Arc<dyn Fn(&HashMap<String, bool>) + Send + Sync>;
+pub type CycleCompleteCallback = Arc<dyn Fn(&HashMap<String, bool>, &CycleContext) + Send + Sync>;
+
+/// Callback function type for shadow program divergences
+pub type ShadowDivergenceCallback = Arc<dyn Fn(&ShadowDivergence, &CycleContext) + Send + Sync>;
+
+/// Callback function type for callback failures
+pub type CallbackErrorCallback = Arc<dyn Fn(&CallbackError) + Send + Sync>;
+
+/// Callback function type for VM faults
+pub type ErrorCallback = Arc<dyn Fn(&Error, &ErrorContext) + Send + Sync>;
+
+/// Cycle an event or callback belongs to
+///
+/// Passed to every callback and carried by VM events, so consumers can order
+/// and correlate them without querying the VM afterwards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CycleContext {
+    /// Cycle number (for program loads, the number of cycles executed so far)
+    pub cycle: u64,
+    /// When the cycle completed (serialized as `at_ms`, milliseconds since
+    /// the Unix epoch)
+    #[serde(rename = "at_ms", with = "crate::serde_time::unix_millis")]
+    pub at: SystemTime,
+    /// Hash identifying the program that ran the cycle
+    pub program_id: Option<Arc<str>>,
+}
+
+impl CycleContext {
+    /// Create a context stamped with the current time
+    pub fn now(cycle: u64, program_id: Option<&str>) -> Self {
+        Self {
+            cycle,
+            at: SystemTime::now(),
+            program_id: program_id.map(Arc::from),
+        }
+    }
+
+    /// Completion time in milliseconds since the Unix epoch
+    pub fn timestamp_millis(&self) -> u64 {
+        self.at
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+/// Phase of VM operation in which a fault occurred
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPhase {
+    /// Loading or reloading a program
+    Load,
+    /// Executing a scan cycle
+    Cycle,
+    /// Dispatching a user callback
+    Callback,
+    /// Reading inputs from or writing outputs to an external driver
+    Driver,
+    /// Saving a checkpoint to a state store
+    Persistence,
+}
+
+/// Context passed to the fault hook alongside the error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Cycle the fault occurred in (for `Load`, the number of cycles executed
+    /// so far)
+    pub cycle: u64,
+    /// Phase the fault occurred in
+    pub phase: ErrorPhase,
+}
+
+/// What to do when a user callback panics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PanicPolicy {
+    /// Report the panic to the error hook and keep dispatching
+    #[default]
+    LogAndContinue,
+    /// Finish dispatching, then fail the cycle with [`Error::CallbackPanicked`]
+    ///
+    /// The cycle's state changes are already committed when the error is
+    /// returned.
+    ReturnError,
+    /// Abort the process
+    Abort,
+}
+
+/// A user callback that panicked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallbackError {
+    /// Which callback failed (e.g. `coil_change:allow_review`)
+    pub callback: String,
+    /// Panic message
+    pub message: String,
+}
 
 /// Event callback manager
 pub struct CallbackManager {
     /// Callbacks for coil state changes: coil_name -> callback
-    coil_callbacks: HashMap<String, Vec<CoilChangeCallback>>,
+    coil_callbacks: Map<String, Vec<CoilChangeCallback>>,
+    /// Callbacks for coil state changes matching a name pattern
+    pattern_callbacks: Vec<(Pattern, CoilChangeCallback)>,
     /// Callback for cycle completion
     cycle_complete_callback: Option<CycleCompleteCallback>,
+    /// Callback for shadow program divergences
+    shadow_divergence_callback: Option<ShadowDivergenceCallback>,
+    /// Callback for callback failures
+    error_callback: Option<CallbackErrorCallback>,
+    /// Callback for VM faults
+    fault_callback: Option<ErrorCallback>,
+    /// Handling of panicking callbacks
+    panic_policy: PanicPolicy,
 }
 
 impl CallbackManager {
     /// Create a new callback manager
     pub fn new() -> Self {
         Self {
-            coil_callbacks: HashMap::new(),
+            coil_callbacks: Map::default(),
+            pattern_callbacks: Vec::new(),
             cycle_complete_callback: None,
+            shadow_divergence_callback: None,
+            error_callback: None,
+            fault_callback: None,
+            panic_policy: PanicPolicy::default(),
         }
     }
 
     /// Register a callback for a specific coil state change
     ///
-    /// The callback receives: (coil_name, old_value, new_value)
+    /// `coil_name` may also be a pattern such as `"allow_*"` or
+    /// `"safety/**"` (see [`crate::pattern`]).
+    ///
+    /// The callback receives: (coil_name, old_value, new_value, context)
     pub fn on_coil_change<F>(&mut self, coil_name: &str, callback: F)
     where
-        F: Fn(&str, bool, bool) + Send + Sync + 'static,
+        F: Fn(&str, bool, bool, &CycleContext) + Send + Sync + 'static,
     {
+        if coil_name != "*" && Pattern::is_pattern(coil_name) {
+            self.pattern_callbacks
+                .push((Pattern::new(coil_name), Arc::new(callback)));
+            return;
+        }
+
         self.coil_callbacks
             .entry(coil_name.to_string())
             .or_insert_with(Vec::new)
@@ -45,7 +171,7 @@ impl CallbackManager {
     /// Register a callback for all coil changes
     pub fn on_any_coil_change<F>(&mut self, callback: F)
     where
-        F: Fn(&str, bool, bool) + Send + Sync + 'static,
+        F: Fn(&str, bool, bool, &CycleContext) + Send + Sync + 'static,
     {
         self.on_coil_change("*", callback);
     }
@@ -55,46 +181,185 @@ impl CallbackManager {
     /// The callback receives the outputs map (coil_name -> new_state)
     pub fn on_cycle_complete<F>(&mut self, callback: F)
     where
-        F: Fn(&HashMap<String, bool>) + Send + Sync + 'static,
+        F: Fn(&HashMap<String, bool>, &CycleContext) + Send + Sync + 'static,
     {
         self.cycle_complete_callback = Some(Arc::new(callback));
     }
 
+    /// Register a callback for shadow program divergences
+    pub fn on_shadow_divergence<F>(&mut self, callback: F)
+    where
+        F: Fn(&ShadowDivergence, &CycleContext) + Send + Sync + 'static,
+    {
+        self.shadow_divergence_callback = Some(Arc::new(callback));
+    }
+
+    /// Register a callback for panicking callbacks
+    pub fn on_callback_error<F>(&mut self, callback: F)
+    where
+        F: Fn(&CallbackError) + Send + Sync + 'static,
+    {
+        self.error_callback = Some(Arc::new(callback));
+    }
+
+    /// Register a callback for VM faults
+    ///
+    /// The callback receives the error and where it occurred.
+    pub fn on_error<F>(&mut self, callback: F)
+    where
+        F: Fn(&Error, &ErrorContext) + Send + Sync + 'static,
+    {
+        self.fault_callback = Some(Arc::new(callback));
+    }
+
+    /// Set how panicking callbacks are handled
+    pub fn set_panic_policy(&mut self, policy: PanicPolicy) {
+        self.panic_policy = policy;
+    }
+
+    /// Get how panicking callbacks are handled
+    pub fn panic_policy(&self) -> PanicPolicy {
+        self.panic_policy
+    }
+
     /// Trigger callbacks for coil changes
-    pub fn trigger_coil_changes(&self, changes: &HashMap<String, (bool, bool)>) {
+    ///
+    /// Returns the callbacks that panicked.
+    pub fn trigger_coil_changes<S: BuildHasher>(
+        &self,
+        changes: &HashMap<String, (bool, bool), S>,
+        context: &CycleContext,
+    ) -> Vec<CallbackError> {
+        let mut errors = Vec::new();
+
         for (coil_name, (old_value, new_value)) in changes {
+            let label = format!("coil_change:{}", coil_name);
+
             // Call specific callbacks for this coil
             if let Some(callbacks) = self.coil_callbacks.get(coil_name) {
                 for callback in callbacks {
-                    callback(coil_name, *old_value, *new_value);
+                    self.invoke(&mut errors, &label, || callback(coil_name, *old_value, *new_value, context));
                 }
             }
 
             // Call wildcard callbacks
             if let Some(callbacks) = self.coil_callbacks.get("*") {
                 for callback in callbacks {
-                    callback(coil_name, *old_value, *new_value);
+                    self.invoke(&mut errors, &label, || callback(coil_name, *old_value, *new_value, context));
+                }
+            }
+
+            // Call pattern callbacks
+            for (pattern, callback) in &self.pattern_callbacks {
+                if pattern.matches(coil_name) {
+                    self.invoke(&mut errors, &label, || callback(coil_name, *old_value, *new_value, context));
                 }
             }
         }
+
+        errors
     }
 
     /// Trigger cycle complete callback
-    pub fn trigger_cycle_complete(&self, outputs: &HashMap<String, bool>) {
+    ///
+    /// Returns the callbacks that panicked.
+    pub fn trigger_cycle_complete(
+        &self,
+        outputs: &HashMap<String, bool>,
+        context: &CycleContext,
+    ) -> Vec<CallbackError> {
+        let mut errors = Vec::new();
         if let Some(callback) = &self.cycle_complete_callback {
-            callback(outputs);
+            self.invoke(&mut errors, "cycle_complete", || callback(outputs, context));
+        }
+        errors
+    }
+
+    /// Trigger shadow divergence callback
+    ///
+    /// Returns the callbacks that panicked.
+    pub fn trigger_shadow_divergence(
+        &self,
+        divergence: &ShadowDivergence,
+        context: &CycleContext,
+    ) -> Vec<CallbackError> {
+        let mut errors = Vec::new();
+        if let Some(callback) = &self.shadow_divergence_callback {
+            self.invoke(&mut errors, "shadow_divergence", || callback(divergence, context));
         }
+        errors
+    }
+
+    /// Trigger the fault callback
+    pub fn trigger_error(&self, error: &Error, context: &ErrorContext) {
+        if let Some(callback) = &self.fault_callback {
+            // The fault hook is the last line of reporting; never let it unwind
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| callback(error, context)));
+        }
+    }
+
+    /// Apply the panic policy to the failures of one dispatch round
+    pub fn check(&self, errors: &[CallbackError]) -> Result<()> {
+        match (self.panic_policy, errors.first()) {
+            (PanicPolicy::ReturnError, Some(error)) => Err(Error::CallbackPanicked(format!(
+                "{}: {}",
+                error.callback, error.message
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Invoke a callback, isolating panics
+    fn invoke<F: FnOnce()>(&self, errors: &mut Vec<CallbackError>, label: &str, f: F) {
+        let Err(payload) = panic::catch_unwind(AssertUnwindSafe(f)) else {
+            return;
+        };
+
+        if self.panic_policy == PanicPolicy::Abort {
+            std::process::abort();
+        }
+
+        let error = CallbackError {
+            callback: label.to_string(),
+            message: panic_message(payload.as_ref()),
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::error!(callback = %error.callback, message = %error.message, "callback panicked");
+
+        if let Some(hook) = &self.error_callback {
+            // A panicking error hook must not take the cycle down either
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| hook(&error)));
+        }
+        errors.push(error);
     }
 
     /// Clear all callbacks
     pub fn clear(&mut self) {
         self.coil_callbacks.clear();
+        self.pattern_callbacks.clear();
         self.cycle_complete_callback = None;
+        self.shadow_divergence_callback = None;
+        self.error_callback = None;
+        self.fault_callback = None;
     }
 
-    /// Remove callbacks for a specific coil
+    /// Remove callbacks for a specific coil or pattern
     pub fn remove_coil_callbacks(&mut self, coil_name: &str) {
         self.coil_callbacks.remove(coil_name);
+        self.pattern_callbacks
+            .retain(|(pattern, _)| pattern.as_str() != coil_name);
+    }
+}
+
+/// Extract a readable message from a panic payload
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_string()
     }
 }
 
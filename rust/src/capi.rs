@@ -0,0 +1,299 @@
+//! C API for embedding the VM in C and C++ firmware
+//!
+//! Build with `--features capi`; the crate builds as a `cdylib` and a
+//! `staticlib` alongside the rlib, and the build script writes the matching
+//! header to `$OUT_DIR/charta.h`:
+//!
+//! ```c
+//! ChartaVm *vm = charta_vm_new();
+//! if (charta_vm_load(vm, ir_json) != CHARTA_STATUS_OK) {
+//!     fprintf(stderr, "%s\n", charta_last_error());
+//! }
+//! charta_vm_on_coil_change(vm, "allow_*", on_change, ctx);
+//! charta_vm_set_signal(vm, "user_submitted", true);
+//! charta_vm_execute(vm);
+//! bool allowed = false;
+//! charta_vm_get_coil(vm, "allow_review", &allowed);
+//! charta_vm_free(vm);
+//! ```
+//!
+//! Functions return a [`ChartaStatus`]; whenever it is not `Ok`,
+//! [`charta_last_error`] describes the failure. Panics never unwind across the boundary.
+
+use crate::blocking;
+use crate::error::Error;
+use std::cell::RefCell;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+
+/// Opaque VM handle
+pub struct ChartaVm {
+    inner: blocking::ChartaVM,
+}
+
+/// Result of a C API call
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartaStatus {
+    /// Success
+    Ok = 0,
+    /// A required pointer argument was null
+    NullArgument = 1,
+    /// A string argument was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// The signal or coil does not exist
+    NotFound = 3,
+    /// The operation failed
+    Error = 4,
+    /// The call panicked
+    Panic = 5,
+}
+
+/// Coil change callback: `(name, old_value, new_value, user_data)`
+///
+/// `name` is only valid for the duration of the call.
+pub type ChartaCoilCallback =
+    extern "C" fn(name: *const c_char, old: bool, new: bool, user_data: *mut c_void);
+
+/// User data passed back to a callback
+///
+/// The caller guarantees the pointee may be used from whichever thread
+/// executes cycles.
+struct UserData(*mut c_void);
+
+unsafe impl Send for UserData {}
+unsafe impl Sync for UserData {}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Run `f`, converting errors and panics to a status
+fn guard<F: FnOnce() -> Result<(), ChartaStatus>>(f: F) -> ChartaStatus {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => ChartaStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => {
+            set_last_error("panic in charta".to_string());
+            ChartaStatus::Panic
+        }
+    }
+}
+
+fn failed(error: Error) -> ChartaStatus {
+    set_last_error(error.to_string());
+    ChartaStatus::Error
+}
+
+fn null_argument() -> ChartaStatus {
+    set_last_error("null pointer argument".to_string());
+    ChartaStatus::NullArgument
+}
+
+fn not_found(kind: &str, name: &str) -> ChartaStatus {
+    set_last_error(format!("{} '{}' not found", kind, name));
+    ChartaStatus::NotFound
+}
+
+/// Borrow a C string argument
+///
+/// # Safety
+///
+/// `ptr` must be null or a valid NUL-terminated string.
+unsafe fn str_arg<'a>(ptr: *const c_char) -> Result<&'a str, ChartaStatus> {
+    if ptr.is_null() {
+        return Err(null_argument());
+    }
+    CStr::from_ptr(ptr).to_str().map_err(|e| {
+        set_last_error(format!("string argument is not valid UTF-8: {}", e));
+        ChartaStatus::InvalidUtf8
+    })
+}
+
+/// Borrow the VM behind a handle
+///
+/// # Safety
+///
+/// `vm` must be null or a handle returned by [`charta_vm_new`] that has not
+/// been freed.
+unsafe fn vm_arg<'a>(vm: *mut ChartaVm) -> Result<&'a mut ChartaVm, ChartaStatus> {
+    vm.as_mut().ok_or_else(null_argument)
+}
+
+/// Create a VM; free it with [`charta_vm_free`]
+#[no_mangle]
+pub extern "C" fn charta_vm_new() -> *mut ChartaVm {
+    Box::into_raw(Box::new(ChartaVm {
+        inner: blocking::ChartaVM::new(),
+    }))
+}
+
+/// Free a VM created by [`charta_vm_new`]; null is ignored
+///
+/// # Safety
+///
+/// `vm` must be null or a live handle, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn charta_vm_free(vm: *mut ChartaVm) {
+    if !vm.is_null() {
+        drop(Box::from_raw(vm));
+    }
+}
+
+/// Load a program from NUL-terminated IR JSON
+///
+/// # Safety
+///
+/// `vm` must be a live handle and `ir_json` a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn charta_vm_load(vm: *mut ChartaVm, ir_json: *const c_char) -> ChartaStatus {
+    guard(|| {
+        let vm = vm_arg(vm)?;
+        let ir_json = str_arg(ir_json)?;
+        vm.inner.load_program(ir_json).map(|_| ()).map_err(failed)
+    })
+}
+
+/// Set a signal value
+///
+/// # Safety
+///
+/// `vm` must be a live handle and `name` a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn charta_vm_set_signal(
+    vm: *mut ChartaVm,
+    name: *const c_char,
+    value: bool,
+) -> ChartaStatus {
+    guard(|| {
+        let vm = vm_arg(vm)?;
+        let name = str_arg(name)?;
+        vm.inner.set_signal(name, value).map_err(failed)
+    })
+}
+
+/// Execute one scan cycle, invoking coil callbacks
+///
+/// # Safety
+///
+/// `vm` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn charta_vm_execute(vm: *mut ChartaVm) -> ChartaStatus {
+    guard(|| {
+        let vm = vm_arg(vm)?;
+        vm.inner.execute_cycle().map(|_| ()).map_err(failed)
+    })
+}
+
+/// Read a coil into `out`
+///
+/// Returns [`ChartaStatus::NotFound`] if the coil does not exist.
+///
+/// # Safety
+///
+/// `vm` must be a live handle, `name` a valid C string, and `out` a valid
+/// pointer.
+#[no_mangle]
+pub unsafe extern "C" fn charta_vm_get_coil(
+    vm: *mut ChartaVm,
+    name: *const c_char,
+    out: *mut bool,
+) -> ChartaStatus {
+    guard(|| {
+        let vm = vm_arg(vm)?;
+        let name = str_arg(name)?;
+        let out = out.as_mut().ok_or_else(null_argument)?;
+        match vm.inner.get_coil(name).map_err(failed)? {
+            Some(value) => {
+                *out = value;
+                Ok(())
+            }
+            None => Err(not_found("coil", name)),
+        }
+    })
+}
+
+/// Read a signal into `out`
+///
+/// Returns [`ChartaStatus::NotFound`] if the signal does not exist.
+///
+/// # Safety
+///
+/// `vm` must be a live handle, `name` a valid C string, and `out` a valid
+/// pointer.
+#[no_mangle]
+pub unsafe extern "C" fn charta_vm_get_signal(
+    vm: *mut ChartaVm,
+    name: *const c_char,
+    out: *mut bool,
+) -> ChartaStatus {
+    guard(|| {
+        let vm = vm_arg(vm)?;
+        let name = str_arg(name)?;
+        let out = out.as_mut().ok_or_else(null_argument)?;
+        match vm.inner.get_signal(name).map_err(failed)? {
+            Some(value) => {
+                *out = value;
+                Ok(())
+            }
+            None => Err(not_found("signal", name)),
+        }
+    })
+}
+
+/// Register a callback for coils matching a name or pattern like `"allow_*"`
+///
+/// The callback runs on the thread calling [`charta_vm_execute`].
+///
+/// # Safety
+///
+/// `vm` must be a live handle and `pattern` a valid C string. `user_data`
+/// must stay valid while the VM lives.
+#[no_mangle]
+pub unsafe extern "C" fn charta_vm_on_coil_change(
+    vm: *mut ChartaVm,
+    pattern: *const c_char,
+    callback: ChartaCoilCallback,
+    user_data: *mut c_void,
+) -> ChartaStatus {
+    guard(|| {
+        let vm = vm_arg(vm)?;
+        let pattern = str_arg(pattern)?;
+        let user_data = UserData(user_data);
+        vm.inner.on_coil_change(pattern, move |name, old, new, _| {
+            let Ok(name) = CString::new(name) else {
+                return;
+            };
+            let user_data = &user_data;
+            callback(name.as_ptr(), old, new, user_data.0);
+        });
+        Ok(())
+    })
+}
+
+/// Get the number of cycles executed so far
+///
+/// # Safety
+///
+/// `vm` must be null or a live handle; null yields 0.
+#[no_mangle]
+pub unsafe extern "C" fn charta_vm_cycle_count(vm: *const ChartaVm) -> u64 {
+    vm.as_ref().map_or(0, |vm| vm.inner.cycle_count())
+}
+
+/// Describe the last error on this thread, or null if none
+///
+/// The string stays valid until the next failing call on this thread.
+#[no_mangle]
+pub extern "C" fn charta_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
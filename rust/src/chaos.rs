@@ -0,0 +1,100 @@
+//! Fault injection for testing error handling
+//!
+//! A [`FaultInjector`] attached with
+//! [`ChartaVM::set_fault_injector`](crate::ChartaVM::set_fault_injector)
+//! makes chosen cycles fail, delays callback dispatch, or drops input driver
+//! reads, so services embedding the VM can exercise their failure paths:
+//!
+//! ```no_run
+//! # async fn example(vm: &mut charta::ChartaVM) {
+//! use charta::chaos::FaultInjector;
+//! use std::time::Duration;
+//!
+//! let faults = FaultInjector::new();
+//! vm.set_fault_injector(faults.clone());
+//!
+//! faults.fail_cycle(3);
+//! faults.delay_callbacks(Duration::from_millis(50));
+//! faults.drop_reads(2);
+//! # }
+//! ```
+//!
+//! Injected cycle failures surface as [`Error::InjectedFault`] and reach the
+//! fault hook like any other cycle error.
+//!
+//! [`Error::InjectedFault`]: crate::Error::InjectedFault
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+struct Faults {
+    failing_cycles: BTreeSet<u64>,
+    callback_delay: Option<Duration>,
+    dropped_reads: usize,
+}
+
+/// Handle for injecting faults into a VM
+///
+/// Clones share the same faults, so a test can keep one handle and give
+/// another to the VM.
+#[derive(Debug, Clone, Default)]
+pub struct FaultInjector {
+    faults: Arc<Mutex<Faults>>,
+}
+
+impl FaultInjector {
+    /// Create an injector with no faults armed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn faults(&self) -> MutexGuard<'_, Faults> {
+        self.faults.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Fail the given cycle (1-based) before the VM steps
+    ///
+    /// The failure fires once; a retried cycle with the same number succeeds.
+    pub fn fail_cycle(&self, cycle: u64) {
+        self.faults().failing_cycles.insert(cycle);
+    }
+
+    /// Delay callback dispatch by `delay` on every cycle
+    ///
+    /// The delay counts against the cycle deadline.
+    pub fn delay_callbacks(&self, delay: Duration) {
+        self.faults().callback_delay = Some(delay);
+    }
+
+    /// Drop the next `count` input driver reads
+    ///
+    /// A dropped read is never performed, so its signals keep their previous
+    /// values for that cycle.
+    pub fn drop_reads(&self, count: usize) {
+        self.faults().dropped_reads += count;
+    }
+
+    /// Disarm every fault
+    pub fn clear(&self) {
+        *self.faults() = Faults::default();
+    }
+
+    pub(crate) fn take_cycle_failure(&self, cycle: u64) -> bool {
+        self.faults().failing_cycles.remove(&cycle)
+    }
+
+    pub(crate) fn callback_delay(&self) -> Option<Duration> {
+        self.faults().callback_delay
+    }
+
+    pub(crate) fn take_dropped_read(&self) -> bool {
+        let mut faults = self.faults();
+        if faults.dropped_reads == 0 {
+            return false;
+        }
+        faults.dropped_reads -= 1;
+        true
+    }
+}
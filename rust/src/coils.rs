@@ -1,6 +1,7 @@
 //! Coil management for Charta VM
 
 use crate::error::{Error, Result};
+use crate::namespace;
 
 /// Coil manager for reading coil states
 pub struct CoilManager;
@@ -12,11 +13,13 @@ impl CoilManager {
     }
 
     /// Validate coil name
+    ///
+    /// Names may be dotted paths (see [`crate::namespace`]).
     pub fn validate_name(name: &str) -> Result<()> {
         if name.is_empty() {
             return Err(Error::InvalidOperation("Coil name cannot be empty".to_string()));
         }
-        Ok(())
+        namespace::validate_name(name)
     }
 }
 
@@ -0,0 +1,24 @@
+//! Collection types for internal hot-path state
+//!
+//! With the `fast-hash` feature, internal maps keyed by signal and coil
+//! names use FxHash instead of SipHash, and change lists keep their first
+//! entries inline. Public APIs keep returning `std` maps either way.
+//!
+//! FxHash is not HashDoS resistant, and the keys are signal and coil names
+//! from the loaded program. A program crafted with colliding names can
+//! degrade these maps to linear scans, so enable the feature only when the
+//! programs you load come from a trusted source; without it the maps use
+//! SipHash.
+
+#[cfg(feature = "fast-hash")]
+pub(crate) type Map<K, V> = rustc_hash::FxHashMap<K, V>;
+#[cfg(all(feature = "std", not(feature = "fast-hash")))]
+pub(crate) type Map<K, V> = std::collections::HashMap<K, V>;
+#[cfg(not(feature = "std"))]
+pub(crate) type Map<K, V> = hashbrown::HashMap<K, V>;
+
+/// Indices of signals or coils changed since the last scan
+#[cfg(feature = "fast-hash")]
+pub(crate) type ChangeList = smallvec::SmallVec<[usize; 16]>;
+#[cfg(not(feature = "fast-hash"))]
+pub(crate) type ChangeList = alloc::vec::Vec<usize>;
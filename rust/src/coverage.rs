@@ -0,0 +1,245 @@
+//! Rung and guard branch coverage for Charta VM
+//!
+//! Opt-in instrumentation recording which rungs fired and which guard nodes
+//! evaluated true and false. Enable it with
+//! [`ChartaVM::enable_coverage`](crate::ChartaVM::enable_coverage), run a test
+//! suite, then gate on the [`CoverageReport`]:
+//!
+//! ```no_run
+//! # fn check(vm: &charta::ChartaVM) {
+//! let report = vm.coverage_report().unwrap();
+//! assert!(report.unfired_rungs().is_empty(), "{:?}", report.unfired_rungs());
+//! # }
+//! ```
+//!
+//! Every guard node is evaluated for coverage, including operands a
+//! short-circuiting evaluator would skip.
+
+use crate::ir::{ContactType, Guard, Program, RungEvaluation};
+use crate::scan_group;
+use std::collections::HashMap;
+
+/// Outcome counts for one guard node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchCoverage {
+    /// Path of the node within the rung (e.g. `guard.left.operand`)
+    pub path: String,
+    /// Short description of the node (e.g. `contact user_submitted (NO)`)
+    pub node: String,
+    /// Cycles in which the node evaluated true
+    pub true_count: u64,
+    /// Cycles in which the node evaluated false
+    pub false_count: u64,
+}
+
+impl BranchCoverage {
+    /// Check whether the node has evaluated both true and false
+    pub fn is_covered(&self) -> bool {
+        self.true_count > 0 && self.false_count > 0
+    }
+}
+
+/// Coverage of one rung
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RungCoverage {
+    /// Rung name
+    pub rung: String,
+    /// Cycles in which the guard held
+    pub energised: u64,
+    /// Cycles in which the guard did not hold
+    pub de_energised: u64,
+    /// Guard nodes in pre-order, starting with the guard root
+    pub branches: Vec<BranchCoverage>,
+}
+
+impl RungCoverage {
+    /// Check whether the rung has fired at least once
+    pub fn fired(&self) -> bool {
+        self.energised > 0
+    }
+}
+
+/// Coverage accumulated since coverage was enabled or the program loaded
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// Cycles recorded
+    pub cycles: u64,
+    /// Per-rung coverage in scan order
+    pub rungs: Vec<RungCoverage>,
+}
+
+impl CoverageReport {
+    /// Get the names of rungs that never fired
+    pub fn unfired_rungs(&self) -> Vec<&str> {
+        self.rungs
+            .iter()
+            .filter(|rung| !rung.fired())
+            .map(|rung| rung.rung.as_str())
+            .collect()
+    }
+
+    /// Get the fraction of rungs that fired at least once
+    pub fn rung_coverage(&self) -> f64 {
+        if self.rungs.is_empty() {
+            return 1.0;
+        }
+        let fired = self.rungs.iter().filter(|rung| rung.fired()).count();
+        fired as f64 / self.rungs.len() as f64
+    }
+
+    /// Get the fraction of guard node outcomes (true and false) observed
+    pub fn branch_coverage(&self) -> f64 {
+        let branches = self.rungs.iter().flat_map(|rung| &rung.branches);
+        let (seen, total) = branches.fold((0, 0), |(seen, total), branch| {
+            let outcomes = (branch.true_count > 0) as usize + (branch.false_count > 0) as usize;
+            (seen + outcomes, total + 2)
+        });
+        if total == 0 {
+            1.0
+        } else {
+            seen as f64 / total as f64
+        }
+    }
+}
+
+impl CoverageReport {
+    /// Record one scan of `group` against a state snapshot
+    ///
+    /// `state` maps signal and coil names to their values at scan start, and
+    /// `evaluations` holds each rung's result in the scan, with bypasses and
+    /// quality gates applied. Rungs held in another group are not counted.
+    pub(crate) fn record(
+        &mut self,
+        program: &Program,
+        state: &HashMap<String, bool>,
+        evaluations: &[RungEvaluation],
+        group: Option<&str>,
+    ) {
+        if self.rungs.len() != program.module.rungs.len() {
+            self.rungs = program
+                .module
+                .rungs
+                .iter()
+                .map(|rung| {
+                    let mut branches = Vec::new();
+                    collect_nodes(&rung.guard, "guard".to_string(), &mut branches);
+                    RungCoverage {
+                        rung: rung.name.clone(),
+                        energised: 0,
+                        de_energised: 0,
+                        branches,
+                    }
+                })
+                .collect();
+        }
+
+        let mut state = state.clone();
+        let rungs = program.module.rungs.iter().zip(&mut self.rungs);
+        for ((rung, coverage), evaluation) in rungs.zip(evaluations) {
+            if !scan_group::scanned(rung, group) {
+                continue;
+            }
+            let mut next = 0;
+            evaluate(&rung.guard, &state, &mut coverage.branches, &mut next);
+            let energised = evaluation.energised;
+            if energised {
+                coverage.energised += 1;
+            } else {
+                coverage.de_energised += 1;
+            }
+            for coil in rung.target_coils() {
+                state.insert(coil.to_string(), energised);
+            }
+        }
+        self.cycles += 1;
+    }
+}
+
+/// Get the children of a guard node with their path suffixes
+fn children(guard: &Guard) -> Vec<(String, &Guard)> {
+    match guard {
+        Guard::Contact { .. } | Guard::Compare { .. } | Guard::WithinRange { .. } => Vec::new(),
+        Guard::And { left, right, operands } | Guard::Or { left, right, operands } => left
+            .iter()
+            .map(|guard| ("left".to_string(), guard.as_ref()))
+            .chain(right.iter().map(|guard| ("right".to_string(), guard.as_ref())))
+            .chain(
+                operands
+                    .iter()
+                    .enumerate()
+                    .map(|(i, guard)| (format!("operands[{}]", i), guard)),
+            )
+            .collect(),
+        Guard::Not { operand } => vec![("operand".to_string(), operand.as_ref())],
+    }
+}
+
+fn collect_nodes(guard: &Guard, path: String, out: &mut Vec<BranchCoverage>) {
+    let node = match guard {
+        Guard::Contact { name, contact_type } => match contact_type {
+            ContactType::NormallyOpen => format!("contact {} (NO)", name),
+            ContactType::NormallyClosed => format!("contact {} (NC)", name),
+        },
+        Guard::And { .. } => "and".to_string(),
+        Guard::Or { .. } => "or".to_string(),
+        Guard::Not { .. } => "not".to_string(),
+        Guard::Compare { .. } => format!("compare {}", guard.condition().unwrap_or_default()),
+        Guard::WithinRange { .. } => {
+            format!("within_range {}", guard.condition().unwrap_or_default())
+        }
+    };
+    out.push(BranchCoverage {
+        path: path.clone(),
+        node,
+        true_count: 0,
+        false_count: 0,
+    });
+    for (suffix, child) in children(guard) {
+        collect_nodes(child, format!("{}.{}", path, suffix), out);
+    }
+}
+
+/// Evaluate a guard without short-circuiting, counting every node's outcome
+fn evaluate(
+    guard: &Guard,
+    state: &HashMap<String, bool>,
+    branches: &mut [BranchCoverage],
+    next: &mut usize,
+) -> bool {
+    let index = *next;
+    *next += 1;
+
+    let value = match guard {
+        Guard::Contact { name, contact_type } => {
+            let value = state.get(name).copied().unwrap_or(false);
+            match contact_type {
+                ContactType::NormallyOpen => value,
+                ContactType::NormallyClosed => !value,
+            }
+        }
+        Guard::Compare { .. } | Guard::WithinRange { .. } => {
+            let derived = guard.derived_signal().unwrap_or_default();
+            state.get(&derived).copied().unwrap_or(false)
+        }
+        _ => {
+            let values: Vec<bool> = children(guard)
+                .into_iter()
+                .map(|(_, child)| evaluate(child, state, branches, next))
+                .collect();
+            match guard {
+                Guard::And { .. } => values.iter().all(|v| *v),
+                Guard::Or { .. } => values.iter().any(|v| *v),
+                _ => !values.first().copied().unwrap_or(false),
+            }
+        }
+    };
+
+    if let Some(branch) = branches.get_mut(index) {
+        if value {
+            branch.true_count += 1;
+        } else {
+            branch.false_count += 1;
+        }
+    }
+    value
+}
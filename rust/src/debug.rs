@@ -0,0 +1,303 @@
+//! Pausing, stepping, breakpoints, and watches
+//!
+//! A debugger or operator console can freeze scanning and walk through a
+//! scan one rung at a time, inspecting state between steps:
+//!
+//! ```no_run
+//! use charta::ChartaVM;
+//!
+//! # async fn example(vm: &mut ChartaVM) -> charta::Result<()> {
+//! vm.pause();
+//! loop {
+//!     let step = vm.step_single_rung().await?;
+//!     println!("{} -> {}", step.rung, step.energised);
+//!     println!("{:?}", vm.get_all_coils().await?);
+//!     if step.scan_complete {
+//!         break;
+//!     }
+//! }
+//! vm.resume();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! While paused, cycles do not scan: they return the outputs of the last
+//! cycle unchanged and trigger no callbacks. Each step evaluates the next
+//! rung in scan order against the current signals and coils, writes the
+//! coils it drives, and applies its moves; bypassed rungs step as
+//! de-energised. Steps do not count as cycles. Resuming abandons a partly
+//! stepped scan, and the next cycle scans every rung.
+//!
+//! Breakpoints pause the VM at the end of the cycle that hits them and emit
+//! a [`VmEvent::BreakpointHit`](crate::VmEvent::BreakpointHit) carrying the
+//! cycle's signals and outputs:
+//!
+//! ```no_run
+//! use charta::{Breakpoint, ChartaVM};
+//!
+//! # async fn example(vm: &mut ChartaVM) -> charta::Result<()> {
+//! vm.set_breakpoint(Breakpoint::CoilChange("allow_operation".into()));
+//! vm.set_breakpoint(Breakpoint::Rung("sanctions_interlock".into()));
+//! vm.execute_cycle().await?;
+//! if vm.is_paused() {
+//!     // Inspect state, step through the next scan...
+//!     vm.continue_();
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Watches evaluate guard expressions over signals and coils without
+//! changing the program. Each is evaluated when added and after every
+//! cycle, emitting a [`VmEvent::WatchChanged`](crate::VmEvent::WatchChanged)
+//! event when its value changes:
+//!
+//! ```no_run
+//! use charta::ir::Guard;
+//! use charta::ChartaVM;
+//!
+//! # fn example(vm: &mut ChartaVM) -> charta::Result<()> {
+//! let gate: Guard = serde_json::from_str(
+//!     r#"{"type": "and", "operands": [
+//!         {"type": "contact", "name": "kyc_passed"},
+//!         {"type": "contact", "name": "sanctions_hit", "contact_type": "NC"}
+//!     ]}"#,
+//! )?;
+//! println!("gate: {}", vm.add_watch("gate", gate));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! `compare` and `within_range` nodes test the current typed values and
+//! registers; contacts on unknown names read false.
+
+use crate::collections::Map;
+use crate::ir::{Guard, SourceLocation};
+use crate::load::Comparison;
+use crate::value::Value;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Condition that pauses the VM when a cycle meets it
+///
+/// Serializes as `{"type": "coil_change", "name": "allow_operation"}`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "name", rename_all = "snake_case")]
+pub enum Breakpoint {
+    /// The named rung's guard holds
+    Rung(String),
+    /// The named coil changes state
+    CoilChange(String),
+}
+
+/// One rung evaluated by [`step_single_rung`](crate::ChartaVM::step_single_rung)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RungStep {
+    /// Rung name
+    pub rung: String,
+    /// Position of the rung in scan order
+    pub index: usize,
+    /// Whether the guard held
+    pub energised: bool,
+    /// Where the rung was written, if known
+    pub source: Option<SourceLocation>,
+    /// Whether this was the last rung of the scan; the next step starts
+    /// the scan over
+    pub scan_complete: bool,
+}
+
+/// Pause state, step position, and breakpoints of a VM
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Debugger {
+    paused: bool,
+    next_rung: usize,
+    breakpoints: Vec<Breakpoint>,
+}
+
+impl Debugger {
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pause at the start of a scan, returning `false` if already paused
+    pub(crate) fn pause(&mut self) -> bool {
+        if self.paused {
+            return false;
+        }
+        self.paused = true;
+        self.next_rung = 0;
+        true
+    }
+
+    /// Resume scanning, returning `false` if not paused
+    pub(crate) fn resume(&mut self) -> bool {
+        std::mem::replace(&mut self.paused, false)
+    }
+
+    /// Take the position of the next rung to step in a scan of `rungs`
+    /// rungs, and whether it completes the scan
+    pub(crate) fn advance(&mut self, rungs: usize) -> (usize, bool) {
+        let index = self.next_rung % rungs;
+        self.next_rung = (index + 1) % rungs;
+        (index, self.next_rung == 0)
+    }
+
+    /// Start the next step at the beginning of a scan
+    pub(crate) fn rewind(&mut self) {
+        self.next_rung = 0;
+    }
+
+    /// Add a breakpoint, returning `false` if it was already set
+    pub(crate) fn set_breakpoint(&mut self, breakpoint: Breakpoint) -> bool {
+        if self.breakpoints.contains(&breakpoint) {
+            return false;
+        }
+        self.breakpoints.push(breakpoint);
+        true
+    }
+
+    /// Remove a breakpoint, returning whether it was set
+    pub(crate) fn clear_breakpoint(&mut self, breakpoint: &Breakpoint) -> bool {
+        let before = self.breakpoints.len();
+        self.breakpoints.retain(|set| set != breakpoint);
+        self.breakpoints.len() != before
+    }
+
+    pub(crate) fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    pub(crate) fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    /// Check whether any breakpoint needs the rungs a cycle fired
+    pub(crate) fn breaks_on_rungs(&self) -> bool {
+        self.breakpoints
+            .iter()
+            .any(|breakpoint| matches!(breakpoint, Breakpoint::Rung(_)))
+    }
+
+    /// Get the breakpoints hit by a cycle that made `changes` and fired the
+    /// rungs in `fired`, in the order they were set
+    pub(crate) fn hits(
+        &self,
+        changes: &Map<String, (bool, bool)>,
+        fired: &[String],
+    ) -> Vec<Breakpoint> {
+        self.breakpoints
+            .iter()
+            .filter(|breakpoint| match breakpoint {
+                Breakpoint::Rung(rung) => fired.contains(rung),
+                Breakpoint::CoilChange(coil) => changes.contains_key(coil),
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Guard expression re-evaluated after every cycle
+#[derive(Debug, Clone)]
+struct Watch {
+    name: String,
+    expr: Guard,
+    comparisons: Vec<Comparison>,
+    value: bool,
+}
+
+/// State a watch expression reads
+pub(crate) struct WatchState<'a> {
+    pub(crate) signals: &'a HashMap<String, bool>,
+    pub(crate) coils: &'a HashMap<String, bool>,
+    pub(crate) values: &'a HashMap<String, Value>,
+    pub(crate) registers: &'a HashMap<String, Value>,
+}
+
+impl Watch {
+    fn evaluate(&self, state: &WatchState<'_>) -> bool {
+        let derived: HashMap<&str, bool> = self
+            .comparisons
+            .iter()
+            .map(|comparison| {
+                let holds = comparison.evaluate(state.values, state.registers);
+                (comparison.signal.as_str(), holds)
+            })
+            .collect();
+        self.expr.evaluate(&|name: &str| {
+            derived
+                .get(name)
+                .or_else(|| state.signals.get(name))
+                .or_else(|| state.coils.get(name))
+                .copied()
+                .unwrap_or(false)
+        })
+    }
+}
+
+/// Watch expressions of a VM, in the order they were added
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Watches {
+    watches: Vec<Watch>,
+}
+
+impl Watches {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.watches.is_empty()
+    }
+
+    /// Watch `expr` under `name`, replacing any watch of that name, and
+    /// return its current value
+    pub(crate) fn add(&mut self, name: &str, expr: Guard, state: &WatchState<'_>) -> bool {
+        let mut comparisons = Vec::new();
+        collect_comparisons(&expr, &mut comparisons);
+        let mut watch = Watch {
+            name: name.to_string(),
+            expr,
+            comparisons,
+            value: false,
+        };
+        watch.value = watch.evaluate(state);
+        let value = watch.value;
+        match self.watches.iter_mut().find(|watch| watch.name == name) {
+            Some(existing) => *existing = watch,
+            None => self.watches.push(watch),
+        }
+        value
+    }
+
+    /// Remove the watch `name`, returning whether there was one
+    pub(crate) fn remove(&mut self, name: &str) -> bool {
+        let before = self.watches.len();
+        self.watches.retain(|watch| watch.name != name);
+        self.watches.len() != before
+    }
+
+    /// Get the current value of each watch
+    pub(crate) fn values(&self) -> HashMap<String, bool> {
+        self.watches
+            .iter()
+            .map(|watch| (watch.name.clone(), watch.value))
+            .collect()
+    }
+
+    /// Re-evaluate every watch, returning `(name, old, new)` for each that
+    /// changed
+    pub(crate) fn update(&mut self, state: &WatchState<'_>) -> Vec<(String, bool, bool)> {
+        let mut changes = Vec::new();
+        for watch in &mut self.watches {
+            let value = watch.evaluate(state);
+            if value != watch.value {
+                changes.push((watch.name.clone(), watch.value, value));
+                watch.value = value;
+            }
+        }
+        changes
+    }
+}
+
+fn collect_comparisons(guard: &Guard, out: &mut Vec<Comparison>) {
+    out.extend(Comparison::of(guard));
+    for operand in guard.operands() {
+        collect_comparisons(operand, out);
+    }
+}
@@ -0,0 +1,145 @@
+//! Per-cycle decision records for compliance export
+//!
+//! Each cycle can produce a [`DecisionRecord`] collecting what regulators ask
+//! for in one artifact: the signals the scan read, the resulting coils, the
+//! coils that changed, the rungs that fired, the program hash, and a
+//! timestamp. Register a [`DecisionExporter`] with
+//! [`ChartaVM::add_decision_exporter`](crate::ChartaVM::add_decision_exporter)
+//! and it receives every record:
+//!
+//! ```no_run
+//! use charta::decision::{ChannelExporter, FileExporter};
+//! use charta::ChartaVM;
+//!
+//! # async fn example(vm: &mut ChartaVM) -> charta::Result<()> {
+//! vm.add_decision_exporter(FileExporter::new("/var/log/charta/decisions.jsonl"));
+//! let (exporter, mut records) = ChannelExporter::channel(1024);
+//! vm.add_decision_exporter(exporter);
+//!
+//! vm.execute_cycle().await?;
+//! let record = records.recv().await.expect("record");
+//! println!("{} fired {:?}", record.cycle, record.fired_rungs);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Records are built only while an exporter is registered. Export failures
+//! go to the [`on_error`](crate::ChartaVM::on_error) hook with
+//! [`ErrorPhase::Driver`](crate::ErrorPhase::Driver) and do not fail the
+//! cycle.
+
+use crate::error::{Error, Result};
+use crate::io::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::SystemTime;
+use tokio::sync::mpsc;
+
+/// What one cycle read, decided, and changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    /// Cycle number
+    pub cycle: u64,
+    /// Hash identifying the program that made the decision
+    pub program_id: Option<String>,
+    /// When the cycle completed (serialized as `at_ms`)
+    #[serde(rename = "at_ms", with = "crate::serde_time::unix_millis")]
+    pub at: SystemTime,
+    /// Signal states the scan read
+    pub inputs: HashMap<String, bool>,
+    /// Coil states after the scan
+    pub outputs: HashMap<String, bool>,
+    /// Coil name -> (old state, new state)
+    pub changes: HashMap<String, (bool, bool)>,
+    /// Rungs whose guard held, in scan order
+    pub fired_rungs: Vec<String>,
+}
+
+/// Destination for decision records, written after every cycle
+#[async_trait]
+pub trait DecisionExporter: Send {
+    /// Export the record of one cycle
+    async fn export(&mut self, record: &DecisionRecord) -> Result<()>;
+}
+
+/// Exporter appending records to a file as JSON lines
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct FileExporter {
+    path: std::path::PathBuf,
+    file: Option<tokio::fs::File>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileExporter {
+    /// Append records to `path`, creating it on the first export
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            file: None,
+        }
+    }
+
+    /// Path of the record file
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+impl DecisionExporter for FileExporter {
+    async fn export(&mut self, record: &DecisionRecord) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => {
+                let file = tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)
+                    .await?;
+                self.file.insert(file)
+            }
+        };
+        file.write_all(&line).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+/// Exporter sending records over a bounded channel
+///
+/// Waits for room when the channel is full, so a slow consumer slows the
+/// cycle rather than losing records.
+#[derive(Debug, Clone)]
+pub struct ChannelExporter {
+    sender: mpsc::Sender<DecisionRecord>,
+}
+
+impl ChannelExporter {
+    /// Send records to `sender`
+    pub fn new(sender: mpsc::Sender<DecisionRecord>) -> Self {
+        Self { sender }
+    }
+
+    /// Create an exporter and the receiving end of a channel holding up to
+    /// `capacity` records
+    pub fn channel(capacity: usize) -> (Self, mpsc::Receiver<DecisionRecord>) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        (Self::new(sender), receiver)
+    }
+}
+
+#[async_trait]
+impl DecisionExporter for ChannelExporter {
+    async fn export(&mut self, record: &DecisionRecord) -> Result<()> {
+        self.sender
+            .send(record.clone())
+            .await
+            .map_err(|_| Error::Driver("decision record receiver dropped".to_string()))
+    }
+}
@@ -0,0 +1,271 @@
+//! Callback dispatch off the scan path
+//!
+//! By default callbacks run inline inside
+//! [`execute_cycle`](crate::ChartaVM::execute_cycle), so a slow callback
+//! delays the cycle that triggered it. In [`DispatchMode::Queued`] mode each
+//! cycle's callback work is handed to a dedicated task through a bounded
+//! queue, and the cycle returns as soon as it is queued:
+//!
+//! ```no_run
+//! use charta::dispatch::{DispatchMode, OverflowPolicy};
+//! use charta::ChartaVM;
+//!
+//! # async fn example() -> charta::Result<()> {
+//! let mut vm = ChartaVM::builder()
+//!     .dispatch(DispatchMode::Queued {
+//!         capacity: 256,
+//!         overflow: OverflowPolicy::DropOldest,
+//!     })
+//!     .build();
+//! vm.on_any_coil_change(|name, _, new, _| println!("{} -> {}", name, new)).await;
+//! vm.execute_cycle().await?;
+//! // Wait until every queued callback has run
+//! vm.flush_callbacks().await;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Queued callbacks run in cycle order. Their panics are reported to the
+//! [`on_callback_error`](crate::ChartaVM::on_callback_error) and
+//! [`on_error`](crate::ChartaVM::on_error) hooks;
+//! [`PanicPolicy::ReturnError`](crate::callbacks::PanicPolicy::ReturnError)
+//! and [`abort_on_deadline`](crate::ChartaVMBuilder::abort_on_deadline) only
+//! apply to inline dispatch. Event subscribers are unaffected either way.
+
+use crate::callbacks::{CallbackManager, CycleContext, ErrorContext, ErrorPhase};
+use crate::collections::Map;
+use crate::error::{Error, Result};
+use crate::outputs::CycleOutputs;
+use crate::shadow::ShadowDivergence;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{watch, Notify, RwLock};
+
+/// Default capacity of a callback queue
+pub const DEFAULT_DISPATCH_CAPACITY: usize = 1024;
+
+/// Where callbacks run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DispatchMode {
+    /// Inside `execute_cycle`, before it returns
+    #[default]
+    Inline,
+    /// On a dedicated task fed by a bounded queue
+    Queued {
+        /// Cycles whose callbacks may wait in the queue
+        capacity: usize,
+        /// What to do when the queue is full
+        overflow: OverflowPolicy,
+    },
+}
+
+impl DispatchMode {
+    /// Queued dispatch with the default capacity, dropping the oldest work
+    /// on overflow
+    pub fn queued() -> Self {
+        Self::Queued {
+            capacity: DEFAULT_DISPATCH_CAPACITY,
+            overflow: OverflowPolicy::default(),
+        }
+    }
+}
+
+/// What a cycle does when the callback queue is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Discard the oldest queued cycle's callbacks
+    #[default]
+    DropOldest,
+    /// Wait for the dispatcher to make room
+    Block,
+    /// Discard this cycle's callbacks and fail the cycle with
+    /// [`Error::QueueFull`]
+    ///
+    /// The cycle's state changes are already committed when the error is
+    /// returned.
+    Error,
+}
+
+/// Callback work of one cycle
+#[derive(Debug)]
+pub(crate) struct Dispatch {
+    pub(crate) changes: Map<String, (bool, bool)>,
+    pub(crate) outputs: Arc<CycleOutputs>,
+    pub(crate) divergence: Option<ShadowDivergence>,
+    pub(crate) context: CycleContext,
+}
+
+impl Dispatch {
+    /// Run the callbacks, reporting panics to the error hooks
+    fn run(&self, callbacks: &CallbackManager) {
+        let mut errors = Vec::new();
+        if !self.changes.is_empty() {
+            errors.extend(callbacks.trigger_coil_changes(&self.changes, &self.context));
+        }
+        errors.extend(callbacks.trigger_cycle_complete(self.outputs.coils(), &self.context));
+        if let Some(divergence) = &self.divergence {
+            errors.extend(callbacks.trigger_shadow_divergence(divergence, &self.context));
+        }
+
+        let context = ErrorContext {
+            cycle: self.context.cycle,
+            phase: ErrorPhase::Callback,
+        };
+        for error in &errors {
+            let error = Error::CallbackPanicked(format!("{}: {}", error.callback, error.message));
+            callbacks.trigger_error(&error, &context);
+        }
+    }
+}
+
+/// Work waiting in and running on a dispatcher
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Backlog {
+    queued: usize,
+    running: bool,
+}
+
+/// State shared between a dispatcher and its task
+struct Queue {
+    jobs: Mutex<VecDeque<Dispatch>>,
+    backlog: watch::Sender<Backlog>,
+    ready: Notify,
+    closed: AtomicBool,
+    dropped: AtomicU64,
+}
+
+impl Queue {
+    fn jobs(&self) -> std::sync::MutexGuard<'_, VecDeque<Dispatch>> {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn publish(&self, jobs: &VecDeque<Dispatch>, running: bool) {
+        self.backlog.send_replace(Backlog {
+            queued: jobs.len(),
+            running,
+        });
+    }
+}
+
+/// Bounded queue of callback work and the task draining it
+pub(crate) struct Dispatcher {
+    queue: Arc<Queue>,
+    capacity: usize,
+    overflow: OverflowPolicy,
+    callbacks: Arc<RwLock<CallbackManager>>,
+    started: bool,
+}
+
+impl Dispatcher {
+    pub(crate) fn new(
+        capacity: usize,
+        overflow: OverflowPolicy,
+        callbacks: Arc<RwLock<CallbackManager>>,
+    ) -> Self {
+        Self {
+            queue: Arc::new(Queue {
+                jobs: Mutex::new(VecDeque::new()),
+                backlog: watch::channel(Backlog::default()).0,
+                ready: Notify::new(),
+                closed: AtomicBool::new(false),
+                dropped: AtomicU64::new(0),
+            }),
+            capacity: capacity.max(1),
+            overflow,
+            callbacks,
+            started: false,
+        }
+    }
+
+    /// Queue a cycle's callbacks, applying the overflow policy
+    ///
+    /// Starts the dispatch task on first use, so VMs can be built outside a
+    /// runtime.
+    pub(crate) async fn push(&mut self, dispatch: Dispatch) -> Result<()> {
+        if !self.started {
+            tokio::spawn(drain(Arc::clone(&self.queue), Arc::clone(&self.callbacks)));
+            self.started = true;
+        }
+
+        if self.overflow == OverflowPolicy::Block {
+            let capacity = self.capacity;
+            let mut backlog = self.queue.backlog.subscribe();
+            // The sender lives in the queue, so waiting never fails
+            let _ = backlog.wait_for(|backlog| backlog.queued < capacity).await;
+        }
+
+        let cycle = dispatch.context.cycle;
+        {
+            let mut jobs = self.queue.jobs();
+            if jobs.len() >= self.capacity {
+                match self.overflow {
+                    OverflowPolicy::DropOldest | OverflowPolicy::Block => {
+                        jobs.pop_front();
+                        self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                    OverflowPolicy::Error => {
+                        self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+                        return Err(Error::QueueFull(format!(
+                            "callbacks of cycle {} discarded ({} cycles queued)",
+                            cycle,
+                            jobs.len()
+                        )));
+                    }
+                }
+            }
+            jobs.push_back(dispatch);
+            let running = self.queue.backlog.borrow().running;
+            self.queue.publish(&jobs, running);
+        }
+        self.queue.ready.notify_one();
+        Ok(())
+    }
+
+    /// Cycles whose callbacks are queued or running
+    pub(crate) fn pending(&self) -> usize {
+        let backlog = *self.queue.backlog.borrow();
+        backlog.queued + backlog.running as usize
+    }
+
+    /// Cycles whose callbacks were discarded on overflow
+    pub(crate) fn dropped(&self) -> u64 {
+        self.queue.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Wait until every queued callback has run
+    pub(crate) async fn flush(&self) {
+        if !self.started {
+            return;
+        }
+        let mut backlog = self.queue.backlog.subscribe();
+        let _ = backlog
+            .wait_for(|backlog| backlog.queued == 0 && !backlog.running)
+            .await;
+    }
+}
+
+impl Drop for Dispatcher {
+    fn drop(&mut self) {
+        // The task finishes the queued work, then exits
+        self.queue.closed.store(true, Ordering::SeqCst);
+        self.queue.ready.notify_one();
+    }
+}
+
+/// Run queued callbacks until the dispatcher is dropped and the queue empty
+async fn drain(queue: Arc<Queue>, callbacks: Arc<RwLock<CallbackManager>>) {
+    loop {
+        let next = {
+            let mut jobs = queue.jobs();
+            let next = jobs.pop_front();
+            queue.publish(&jobs, next.is_some());
+            next
+        };
+        match next {
+            Some(dispatch) => dispatch.run(&*callbacks.read().await),
+            None if queue.closed.load(Ordering::SeqCst) => break,
+            None => queue.ready.notified().await,
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Synchronous core VM for `no_std` targets
+//!
+//! [`ChartaVM`] evaluates programs from the SDK's [`ir`](crate::ir) model
+//! using only `alloc`: no tokio, no file IO, and `hashbrown` maps when built
+//! with `--no-default-features --features no_std`. It suits on-device use
+//! such as gating actuator commands:
+//!
+//! ```ignore
+//! use charta::embedded::ChartaVM;
+//!
+//! let mut vm = ChartaVM::new();
+//! vm.load_program(IR_JSON)?;
+//! vm.set_signal("interlock_ok", true)?;
+//! vm.execute_cycle()?;
+//! if vm.get_coil("allow_actuate") == Some(true) {
+//!     // drive the actuator
+//! }
+//! ```
+//!
+//! Rungs run in scan order; coils driven by earlier rungs are visible to
+//! later rungs within the same cycle. There are no callbacks or events;
+//! callers read coils after each cycle. Names are resolved once at load;
+//! hot loops should hold [`SignalId`]/[`CoilId`] and use the `_id` methods.
+//! With [`set_incremental`](ChartaVM::set_incremental), each cycle evaluates
+//! only the rungs affected by changes since the last one.
+
+use crate::engine::{CoilId, Engine, ScanState, SignalId};
+use crate::ir::Program;
+use alloc::string::{String, ToString};
+use core::fmt;
+
+/// Errors from the embedded VM
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The IR could not be parsed
+    IRLoad(String),
+    /// No program is loaded
+    NoProgram,
+    /// The signal is not declared by the loaded program
+    UnknownSignal(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IRLoad(message) => write!(f, "IR load error: {}", message),
+            Error::NoProgram => write!(f, "no program loaded"),
+            Error::UnknownSignal(name) => write!(f, "unknown signal: {}", name),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Result type for the embedded VM
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Synchronous, allocation-only Charta VM
+#[derive(Debug, Clone, Default)]
+pub struct ChartaVM {
+    loaded: Option<(Program, Engine)>,
+    state: Option<ScanState>,
+    cycle_count: u64,
+    incremental: bool,
+    compile: bool,
+}
+
+impl ChartaVM {
+    /// Create a VM with no program loaded
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evaluate only rungs affected by changed signals and coils
+    ///
+    /// Produces the same coil states as a full scan; suits large programs
+    /// whose inputs change a few at a time. Off by default.
+    ///
+    /// Only the embedded VM scans through the engine, so only it has this
+    /// setting; [`crate::ChartaVM`] cycles always scan every rung:
+    ///
+    /// ```compile_fail
+    /// charta::ChartaVM::new().set_incremental(true);
+    /// ```
+    pub fn set_incremental(&mut self, incremental: bool) {
+        self.incremental = incremental;
+    }
+
+    /// Whether incremental evaluation is enabled
+    pub fn is_incremental(&self) -> bool {
+        self.incremental
+    }
+
+    /// Compile guards to bytecode on later loads
+    ///
+    /// Avoids walking guard trees recursively each cycle, which pays off for
+    /// deep AND/OR trees. Off by default; takes effect from the next load.
+    pub fn set_compile(&mut self, compile: bool) {
+        self.compile = compile;
+    }
+
+    /// Load a program from IR JSON string
+    pub fn load_program(&mut self, ir_json: &str) -> Result<()> {
+        let program =
+            serde_json::from_str(ir_json).map_err(|e| Error::IRLoad(e.to_string()))?;
+        self.load(program);
+        Ok(())
+    }
+
+    /// Load a parsed program, resetting all signals and coils to false
+    pub fn load(&mut self, program: Program) {
+        let engine = if self.compile {
+            Engine::compiled(&program)
+        } else {
+            Engine::new(&program)
+        };
+        self.state = Some(engine.state());
+        self.loaded = Some((program, engine));
+        self.cycle_count = 0;
+    }
+
+    /// Get the loaded program
+    pub fn program(&self) -> Option<&Program> {
+        self.loaded.as_ref().map(|(program, _)| program)
+    }
+
+    /// Get the compiled form of the loaded program
+    pub fn engine(&self) -> Option<&Engine> {
+        self.loaded.as_ref().map(|(_, engine)| engine)
+    }
+
+    /// Resolve a signal name to its id
+    pub fn signal_id(&self, name: &str) -> Option<SignalId> {
+        self.engine()?.signal_id(name)
+    }
+
+    /// Resolve a coil name to its id
+    pub fn coil_id(&self, name: &str) -> Option<CoilId> {
+        self.engine()?.coil_id(name)
+    }
+
+    /// Set a signal by name
+    pub fn set_signal(&mut self, name: &str, value: bool) -> Result<()> {
+        let id = self
+            .signal_id(name)
+            .ok_or_else(|| Error::UnknownSignal(name.to_string()))?;
+        self.set_signal_id(id, value)
+    }
+
+    /// Set a signal by id
+    ///
+    /// Ids are only valid for the program they were resolved against.
+    pub fn set_signal_id(&mut self, id: SignalId, value: bool) -> Result<()> {
+        match &mut self.state {
+            Some(state) if id.index() < state.signals().len() => {
+                state.set_signal(id, value);
+                Ok(())
+            }
+            Some(_) => Err(Error::UnknownSignal(id.to_string())),
+            None => Err(Error::NoProgram),
+        }
+    }
+
+    /// Get the current state of a signal
+    pub fn get_signal(&self, name: &str) -> Option<bool> {
+        self.get_signal_id(self.signal_id(name)?)
+    }
+
+    /// Get the current state of a signal by id
+    pub fn get_signal_id(&self, id: SignalId) -> Option<bool> {
+        self.state.as_ref()?.signals().get(id.index()).copied()
+    }
+
+    /// Get the current state of a coil
+    pub fn get_coil(&self, name: &str) -> Option<bool> {
+        self.get_coil_id(self.coil_id(name)?)
+    }
+
+    /// Get the current state of a coil by id
+    pub fn get_coil_id(&self, id: CoilId) -> Option<bool> {
+        self.state.as_ref()?.coils().get(id.index()).copied()
+    }
+
+    /// Get all signal states with their names
+    pub fn signals(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.named(|engine| engine.signal_names(), |state| state.signals())
+    }
+
+    /// Get all coil states with their names
+    pub fn coils(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.named(|engine| engine.coil_names(), |state| state.coils())
+    }
+
+    fn named<'a>(
+        &'a self,
+        names: impl Fn(&'a Engine) -> &'a [String],
+        values: impl Fn(&'a ScanState) -> &'a [bool],
+    ) -> impl Iterator<Item = (&'a str, bool)> {
+        let names = self.engine().map(names).unwrap_or_default();
+        let values = self.state.as_ref().map(values).unwrap_or_default();
+        names.iter().map(String::as_str).zip(values.iter().copied())
+    }
+
+    /// Get the scan state, indexed by id
+    pub fn state(&self) -> Option<&ScanState> {
+        self.state.as_ref()
+    }
+
+    /// Get the number of cycles executed since the program loaded
+    pub fn cycle_count(&self) -> u64 {
+        self.cycle_count
+    }
+
+    /// Execute one scan cycle
+    pub fn execute_cycle(&mut self) -> Result<()> {
+        let (Some((_, engine)), Some(state)) = (&self.loaded, &mut self.state) else {
+            return Err(Error::NoProgram);
+        };
+        if self.incremental {
+            engine.scan_incremental(state);
+        } else {
+            engine.scan(state);
+        }
+        self.cycle_count += 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,678 @@
+//! Index-based rung evaluation
+//!
+//! [`Engine`] resolves every signal and coil name of a [`Program`] to a dense
+//! index once, at load time. Scans then read and write flat `bool` slices in
+//! a [`ScanState`] without hashing names. Callers resolve names to
+//! [`SignalId`]/[`CoilId`] when they register interest and use the ids on the
+//! hot path:
+//!
+//! ```
+//! use charta::engine::Engine;
+//! use charta::ir::Program;
+//!
+//! # let ir = r#"{"module": {"name": "m", "signals": [{"name": "a"}], "coils": [{"name": "y"}],
+//! #   "rungs": [{"name": "r", "guard": {"type": "contact", "name": "a"},
+//! #   "actions": [{"type": "energise", "coil": "y"}]}]}}"#;
+//! # let program: Program = serde_json::from_str(ir).unwrap();
+//! let engine = Engine::new(&program);
+//! let a = engine.signal_id("a").unwrap();
+//! let y = engine.coil_id("y").unwrap();
+//!
+//! let mut state = engine.state();
+//! state.set_signal(a, true);
+//! engine.scan(&mut state);
+//! assert!(state.coil(y));
+//! ```
+//!
+//! Contacts resolve to a declared signal first, then to a declared or driven
+//! coil; any other name becomes an implicit signal. `compare` and
+//! `within_range` nodes read their
+//! [derived signal](crate::value::derived_signal), which the host sets.
+//! Available without `std`.
+//!
+//! Only the [embedded VM](crate::embedded::ChartaVM) scans through the
+//! engine, so only it offers id-based access. [`ChartaVM`](crate::ChartaVM)
+//! and the blocking VM execute cycles in the core `charta_vm::VM` by name;
+//! `ChartaVM` uses the engine for batch evaluation only.
+//!
+//! [`Engine::scan_incremental`] evaluates only the rungs reading signals or
+//! coils that changed since the last scan, for large programs whose inputs
+//! change a few at a time. The embedded VM enables it with
+//! [`set_incremental`](crate::embedded::ChartaVM::set_incremental).
+//!
+//! [`Engine::evaluate`] runs one stateless scan from a copy of a base state;
+//! with the `rayon` feature, [`Engine::evaluate_batch_parallel`] shards many
+//! such evaluations across threads.
+//!
+//! [`Engine::compiled`] additionally lowers every guard to flat bytecode run
+//! by a single loop with short-circuit jumps, instead of walking the guard
+//! tree recursively; deep AND/OR trees evaluate noticeably faster. The
+//! embedded VM compiles with
+//! [`set_compile`](crate::embedded::ChartaVM::set_compile).
+
+use crate::ir::{ContactType, Guard, Program};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::collections::{ChangeList, Map};
+
+/// Dense index of a signal within one loaded program
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SignalId(u32);
+
+/// Dense index of a coil within one loaded program
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CoilId(u32);
+
+impl SignalId {
+    /// Get the index into the signal slice
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl CoilId {
+    /// Get the index into the coil slice
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Display for SignalId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "signal#{}", self.0)
+    }
+}
+
+impl fmt::Display for CoilId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "coil#{}", self.0)
+    }
+}
+
+/// Guard lowered to indices
+#[derive(Debug, Clone)]
+pub(crate) enum Node {
+    Signal { index: usize, normally_closed: bool },
+    Coil { index: usize, normally_closed: bool },
+    And(Vec<Node>),
+    Or(Vec<Node>),
+    Not(Box<Node>),
+}
+
+impl Node {
+    fn evaluate(&self, state: &ScanState) -> bool {
+        match self {
+            Node::Signal {
+                index,
+                normally_closed,
+            } => state.signals[*index] != *normally_closed,
+            Node::Coil {
+                index,
+                normally_closed,
+            } => state.coils[*index] != *normally_closed,
+            Node::And(operands) => operands.iter().all(|node| node.evaluate(state)),
+            Node::Or(operands) => operands.iter().any(|node| node.evaluate(state)),
+            Node::Not(operand) => !operand.evaluate(state),
+        }
+    }
+
+    /// Visit every signal and coil index the node reads
+    fn visit(&self, on_signal: &mut impl FnMut(usize), on_coil: &mut impl FnMut(usize)) {
+        match self {
+            Node::Signal { index, .. } => on_signal(*index),
+            Node::Coil { index, .. } => on_coil(*index),
+            Node::And(operands) | Node::Or(operands) => {
+                for node in operands {
+                    node.visit(on_signal, on_coil);
+                }
+            }
+            Node::Not(operand) => operand.visit(on_signal, on_coil),
+        }
+    }
+}
+
+/// Bytecode instruction operating on a single accumulator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Signal { index: usize, normally_closed: bool },
+    Coil { index: usize, normally_closed: bool },
+    Const(bool),
+    Not,
+    /// Jump to an absolute position when the accumulator is false
+    JumpIfFalse(usize),
+    /// Jump to an absolute position when the accumulator is true
+    JumpIfTrue(usize),
+}
+
+/// Append the bytecode for `node` to `code`
+fn emit(node: &Node, code: &mut Vec<Op>) {
+    match node {
+        Node::Signal {
+            index,
+            normally_closed,
+        } => code.push(Op::Signal {
+            index: *index,
+            normally_closed: *normally_closed,
+        }),
+        Node::Coil {
+            index,
+            normally_closed,
+        } => code.push(Op::Coil {
+            index: *index,
+            normally_closed: *normally_closed,
+        }),
+        Node::And(operands) | Node::Or(operands) if operands.is_empty() => {
+            code.push(Op::Const(matches!(node, Node::And(_))));
+        }
+        Node::And(operands) | Node::Or(operands) => {
+            // Every operand but the last may short-circuit to the end
+            let is_and = matches!(node, Node::And(_));
+            let mut jumps = Vec::with_capacity(operands.len() - 1);
+            for (position, operand) in operands.iter().enumerate() {
+                emit(operand, code);
+                if position + 1 < operands.len() {
+                    jumps.push(code.len());
+                    code.push(if is_and { Op::JumpIfFalse(0) } else { Op::JumpIfTrue(0) });
+                }
+            }
+            let end = code.len();
+            for jump in jumps {
+                code[jump] = match code[jump] {
+                    Op::JumpIfFalse(_) => Op::JumpIfFalse(end),
+                    _ => Op::JumpIfTrue(end),
+                };
+            }
+        }
+        Node::Not(operand) => {
+            emit(operand, code);
+            code.push(Op::Not);
+        }
+    }
+}
+
+/// Run the bytecode in `code[start..end]`
+fn run(code: &[Op], start: usize, end: usize, state: &ScanState) -> bool {
+    let mut value = false;
+    let mut pc = start;
+    while pc < end {
+        match code[pc] {
+            Op::Signal {
+                index,
+                normally_closed,
+            } => value = state.signals[index] != normally_closed,
+            Op::Coil {
+                index,
+                normally_closed,
+            } => value = state.coils[index] != normally_closed,
+            Op::Const(constant) => value = constant,
+            Op::Not => value = !value,
+            Op::JumpIfFalse(target) if !value => {
+                pc = target;
+                continue;
+            }
+            Op::JumpIfTrue(target) if value => {
+                pc = target;
+                continue;
+            }
+            Op::JumpIfFalse(_) | Op::JumpIfTrue(_) => {}
+        }
+        pc += 1;
+    }
+    value
+}
+
+/// A rung guard as a tree or as a span of bytecode
+#[derive(Debug, Clone)]
+enum RungGuard {
+    Tree(Node),
+    Code { start: usize, end: usize },
+}
+
+/// A rung lowered to indices
+#[derive(Debug, Clone)]
+struct CompiledRung {
+    name: String,
+    guard: RungGuard,
+    coils: Vec<usize>,
+}
+
+/// Signal and coil values of one program, indexed by id
+///
+/// Also tracks what changed since the last scan, for
+/// [`Engine::scan_incremental`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanState {
+    signals: Vec<bool>,
+    coils: Vec<bool>,
+    /// Rungs to evaluate on the next incremental scan
+    dirty: Vec<bool>,
+    changed_signals: ChangeList,
+    changed_coils: ChangeList,
+}
+
+impl ScanState {
+    /// Get a signal value
+    pub fn signal(&self, id: SignalId) -> bool {
+        self.signals[id.index()]
+    }
+
+    /// Set a signal value
+    pub fn set_signal(&mut self, id: SignalId, value: bool) {
+        let index = id.index();
+        if self.signals[index] != value {
+            self.signals[index] = value;
+            self.changed_signals.push(index);
+        }
+    }
+
+    /// Get a coil value
+    pub fn coil(&self, id: CoilId) -> bool {
+        self.coils[id.index()]
+    }
+
+    /// Set a coil value
+    pub fn set_coil(&mut self, id: CoilId, value: bool) {
+        let index = id.index();
+        if self.coils[index] != value {
+            self.coils[index] = value;
+            self.changed_coils.push(index);
+        }
+    }
+
+    /// Get all signal values in id order
+    pub fn signals(&self) -> &[bool] {
+        &self.signals
+    }
+
+    /// Get all coil values in id order
+    pub fn coils(&self) -> &[bool] {
+        &self.coils
+    }
+}
+
+/// A program compiled for index-based evaluation
+#[derive(Debug, Clone)]
+pub struct Engine {
+    signals: Vec<String>,
+    coils: Vec<String>,
+    signal_ids: Map<String, usize>,
+    coil_ids: Map<String, usize>,
+    rungs: Vec<CompiledRung>,
+    /// Rungs whose guard reads each signal
+    signal_readers: Vec<Vec<usize>>,
+    /// Rungs whose guard reads each coil
+    coil_readers: Vec<Vec<usize>>,
+    /// Rungs driving each coil
+    coil_writers: Vec<Vec<usize>>,
+    /// Bytecode of every guard, when compiled
+    code: Vec<Op>,
+    compiled: bool,
+    /// Native full scan, when the `jit` backend compiled one
+    #[cfg(feature = "jit")]
+    native: Option<alloc::sync::Arc<crate::jit::NativeScan>>,
+}
+
+impl Engine {
+    /// Resolve every name in `program` to an index
+    pub fn new(program: &Program) -> Self {
+        Self::build(program, false)
+    }
+
+    /// Resolve every name in `program` and compile its guards to bytecode
+    pub fn compiled(program: &Program) -> Self {
+        Self::build(program, true)
+    }
+
+    fn build(program: &Program, compile: bool) -> Self {
+        let mut engine = Self {
+            signals: Vec::new(),
+            coils: Vec::new(),
+            signal_ids: Map::default(),
+            coil_ids: Map::default(),
+            rungs: Vec::new(),
+            signal_readers: Vec::new(),
+            coil_readers: Vec::new(),
+            coil_writers: Vec::new(),
+            code: Vec::new(),
+            compiled: compile,
+            #[cfg(feature = "jit")]
+            native: None,
+        };
+
+        let module = &program.module;
+        for signal in &module.signals {
+            engine.intern_signal(&signal.name);
+        }
+        for coil in &module.coils {
+            engine.intern_coil(&coil.name);
+        }
+        for rung in &module.rungs {
+            for coil in rung.target_coils() {
+                engine.intern_coil(coil);
+            }
+        }
+
+        for rung in &module.rungs {
+            let guard = engine.lower(&rung.guard);
+            let coils = rung.target_coils().map(|coil| engine.coil_ids[coil]).collect();
+            engine.rungs.push(CompiledRung {
+                name: rung.name.clone(),
+                guard: RungGuard::Tree(guard),
+                coils,
+            });
+        }
+        engine.index_dependencies();
+        if compile {
+            #[cfg(feature = "jit")]
+            {
+                let rungs = engine.rungs.iter().filter_map(|rung| match &rung.guard {
+                    RungGuard::Tree(node) => Some((node, rung.coils.as_slice())),
+                    RungGuard::Code { .. } => None,
+                });
+                engine.native = crate::jit::NativeScan::compile(rungs)
+                    .ok()
+                    .map(alloc::sync::Arc::new);
+            }
+            engine.compile();
+        }
+        engine
+    }
+
+    fn compile(&mut self) {
+        for rung in &mut self.rungs {
+            if let RungGuard::Tree(node) = &rung.guard {
+                let start = self.code.len();
+                emit(node, &mut self.code);
+                rung.guard = RungGuard::Code {
+                    start,
+                    end: self.code.len(),
+                };
+            }
+        }
+    }
+
+    /// Whether guards were compiled to bytecode
+    pub fn is_compiled(&self) -> bool {
+        self.compiled
+    }
+
+    /// Whether full scans run natively compiled code
+    ///
+    /// Only with the `jit` feature, on hosts Cranelift supports.
+    pub fn is_native(&self) -> bool {
+        #[cfg(feature = "jit")]
+        {
+            self.native.is_some()
+        }
+        #[cfg(not(feature = "jit"))]
+        {
+            false
+        }
+    }
+
+    fn evaluate(&self, rung: &CompiledRung, state: &ScanState) -> bool {
+        match &rung.guard {
+            RungGuard::Tree(node) => node.evaluate(state),
+            RungGuard::Code { start, end } => run(&self.code, *start, *end, state),
+        }
+    }
+
+    fn index_dependencies(&mut self) {
+        let mut signal_readers = alloc::vec![Vec::new(); self.signals.len()];
+        let mut coil_readers = alloc::vec![Vec::new(); self.coils.len()];
+        let mut coil_writers = alloc::vec![Vec::new(); self.coils.len()];
+        for (index, rung) in self.rungs.iter().enumerate() {
+            let RungGuard::Tree(node) = &rung.guard else {
+                continue;
+            };
+            node.visit(
+                &mut |signal| push_unique(&mut signal_readers[signal], index),
+                &mut |coil| push_unique(&mut coil_readers[coil], index),
+            );
+            for &coil in &rung.coils {
+                push_unique(&mut coil_writers[coil], index);
+            }
+        }
+        self.signal_readers = signal_readers;
+        self.coil_readers = coil_readers;
+        self.coil_writers = coil_writers;
+    }
+
+    fn intern_signal(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.signal_ids.get(name) {
+            return index;
+        }
+        let index = self.signals.len();
+        self.signals.push(name.into());
+        self.signal_ids.insert(name.into(), index);
+        index
+    }
+
+    fn intern_coil(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.coil_ids.get(name) {
+            return index;
+        }
+        let index = self.coils.len();
+        self.coils.push(name.into());
+        self.coil_ids.insert(name.into(), index);
+        index
+    }
+
+    fn lower(&mut self, guard: &Guard) -> Node {
+        match guard {
+            Guard::Contact { name, contact_type } => {
+                let normally_closed = *contact_type == ContactType::NormallyClosed;
+                let signal = self.signal_ids.get(name.as_str()).copied();
+                let coil = self.coil_ids.get(name.as_str()).copied();
+                match (signal, coil) {
+                    (None, Some(index)) => Node::Coil {
+                        index,
+                        normally_closed,
+                    },
+                    _ => Node::Signal {
+                        index: self.intern_signal(name),
+                        normally_closed,
+                    },
+                }
+            }
+            Guard::And { .. } => Node::And(self.lower_all(guard)),
+            Guard::Or { .. } => Node::Or(self.lower_all(guard)),
+            Guard::Not { operand } => Node::Not(Box::new(self.lower(operand))),
+            Guard::Compare { .. } | Guard::WithinRange { .. } => {
+                let derived = guard.derived_signal().unwrap_or_default();
+                Node::Signal {
+                    index: self.intern_signal(&derived),
+                    normally_closed: false,
+                }
+            }
+        }
+    }
+
+    fn lower_all(&mut self, guard: &Guard) -> Vec<Node> {
+        guard
+            .operands()
+            .into_iter()
+            .map(|operand| self.lower(operand))
+            .collect()
+    }
+
+    /// Resolve a signal name
+    pub fn signal_id(&self, name: &str) -> Option<SignalId> {
+        self.signal_ids.get(name).map(|&index| SignalId(index as u32))
+    }
+
+    /// Resolve a coil name
+    pub fn coil_id(&self, name: &str) -> Option<CoilId> {
+        self.coil_ids.get(name).map(|&index| CoilId(index as u32))
+    }
+
+    /// Get the name of a signal
+    pub fn signal_name(&self, id: SignalId) -> Option<&str> {
+        self.signals.get(id.index()).map(String::as_str)
+    }
+
+    /// Get the name of a coil
+    pub fn coil_name(&self, id: CoilId) -> Option<&str> {
+        self.coils.get(id.index()).map(String::as_str)
+    }
+
+    /// Get every signal name in id order
+    pub fn signal_names(&self) -> &[String] {
+        &self.signals
+    }
+
+    /// Get every coil name in id order
+    pub fn coil_names(&self) -> &[String] {
+        &self.coils
+    }
+
+    /// Get the rung names in scan order
+    pub fn rung_names(&self) -> impl Iterator<Item = &str> {
+        self.rungs.iter().map(|rung| rung.name.as_str())
+    }
+
+    /// Create a state with every signal and coil false
+    ///
+    /// The first incremental scan of a new state evaluates every rung.
+    pub fn state(&self) -> ScanState {
+        ScanState {
+            signals: alloc::vec![false; self.signals.len()],
+            coils: alloc::vec![false; self.coils.len()],
+            dirty: alloc::vec![true; self.rungs.len()],
+            changed_signals: ChangeList::new(),
+            changed_coils: ChangeList::new(),
+        }
+    }
+
+    /// Evaluate every rung in scan order
+    ///
+    /// Coils driven by earlier rungs are visible to later rungs.
+    pub fn scan(&self, state: &mut ScanState) {
+        if !self.scan_native(state) {
+            for rung in &self.rungs {
+                let energised = self.evaluate(rung, state);
+                for &coil in &rung.coils {
+                    state.coils[coil] = energised;
+                }
+            }
+        }
+        state.dirty.fill(false);
+        state.changed_signals.clear();
+        state.changed_coils.clear();
+    }
+
+    #[cfg(feature = "jit")]
+    fn scan_native(&self, state: &mut ScanState) -> bool {
+        match &self.native {
+            Some(native)
+                if state.signals.len() == self.signals.len()
+                    && state.coils.len() == self.coils.len() =>
+            {
+                // SAFETY: the state has exactly this program's signals and coils
+                unsafe { native.run(&state.signals, &mut state.coils) };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg(not(feature = "jit"))]
+    fn scan_native(&self, _state: &mut ScanState) -> bool {
+        false
+    }
+
+    /// Scan a copy of `base` with `inputs` applied, leaving `base` untouched
+    ///
+    /// Inputs naming no signal of the program are ignored.
+    pub fn evaluate<'a>(
+        &self,
+        base: &ScanState,
+        inputs: impl IntoIterator<Item = (&'a str, bool)>,
+    ) -> ScanState {
+        let mut state = base.clone();
+        for (name, value) in inputs {
+            if let Some(id) = self.signal_id(name) {
+                state.set_signal(id, value);
+            }
+        }
+        self.scan(&mut state);
+        state
+    }
+
+    /// Evaluate independent input sets across the rayon thread pool
+    ///
+    /// Each evaluation starts from its own copy of `base`, as
+    /// [`evaluate`](Self::evaluate); results are in input order.
+    #[cfg(feature = "rayon")]
+    pub fn evaluate_batch_parallel(
+        &self,
+        base: &ScanState,
+        inputs: &[std::collections::HashMap<String, bool>],
+    ) -> Vec<ScanState> {
+        use rayon::prelude::*;
+
+        inputs
+            .par_iter()
+            .map(|inputs| {
+                self.evaluate(base, inputs.iter().map(|(name, value)| (name.as_str(), *value)))
+            })
+            .collect()
+    }
+
+    /// Evaluate only the rungs affected by changes since the last scan
+    ///
+    /// Produces the same coil states as [`scan`](Self::scan). A rung is
+    /// re-evaluated when a signal or coil its guard reads changed, or when
+    /// an earlier rung changed a coil it also drives. Returns the number of
+    /// rungs evaluated.
+    pub fn scan_incremental(&self, state: &mut ScanState) -> usize {
+        for &signal in &state.changed_signals {
+            for &rung in &self.signal_readers[signal] {
+                state.dirty[rung] = true;
+            }
+        }
+        for &coil in &state.changed_coils {
+            for &rung in self.coil_readers[coil].iter().chain(&self.coil_writers[coil]) {
+                state.dirty[rung] = true;
+            }
+        }
+        state.changed_signals.clear();
+        state.changed_coils.clear();
+
+        let mut evaluated = 0;
+        for (index, rung) in self.rungs.iter().enumerate() {
+            if !state.dirty[index] {
+                continue;
+            }
+            state.dirty[index] = false;
+            evaluated += 1;
+
+            let energised = self.evaluate(rung, state);
+            for &coil in &rung.coils {
+                if state.coils[coil] == energised {
+                    continue;
+                }
+                state.coils[coil] = energised;
+                // Readers up to this rung see the change on the next scan
+                for &reader in &self.coil_readers[coil] {
+                    state.dirty[reader] = true;
+                }
+                for &writer in &self.coil_writers[coil] {
+                    if writer > index {
+                        state.dirty[writer] = true;
+                    }
+                }
+            }
+        }
+        evaluated
+    }
+}
+
+fn push_unique(indices: &mut Vec<usize>, index: usize) {
+    if indices.last() != Some(&index) {
+        indices.push(index);
+    }
+}
@@ -31,4 +31,68 @@ pub enum Error {
     /// Invalid operation
     #[error("Invalid operation: {0}")]
     InvalidOperation(String),
+
+    /// Program exceeds a configured load limit
+    #[error("Load limit exceeded: {0}")]
+    LimitExceeded(String),
+
+    /// I/O driver error
+    #[error("Driver error: {0}")]
+    Driver(String),
+
+    /// A test scenario's expectations or a golden trace were not met
+    #[error("{0}")]
+    ScenarioFailed(String),
+
+    /// A user callback panicked
+    #[error("Callback panicked: {0}")]
+    CallbackPanicked(String),
+
+    /// The callback queue was full
+    #[error("Queue full: {0}")]
+    QueueFull(String),
+
+    /// State store backend error
+    #[error("State store error: {0}")]
+    Persistence(String),
+
+    /// Modules could not be linked into one program
+    #[error("Link error: {0}")]
+    Link(String),
+
+    /// Fetching a program from a remote location failed
+    #[error("Fetch error: {0}")]
+    Fetch(String),
+
+    /// Fetched content did not match its expected checksum
+    #[error("Integrity check failed: {0}")]
+    Integrity(String),
+
+    /// Write to a signal outside the caller's granted scope
+    #[error("Access denied: {0}")]
+    AccessDenied(String),
+
+    /// A value did not match the declared type of its signal
+    #[error("Type mismatch: {0}")]
+    TypeMismatch(String),
+
+    /// A rung read a signal of Bad or Stale quality under
+    /// [`QualityPolicy::Fault`](crate::QualityPolicy::Fault)
+    #[error("Signal quality fault: {0}")]
+    SignalQuality(String),
+
+    /// Metrics registration/encoding error
+    #[cfg(feature = "prometheus")]
+    #[error("Metrics error: {0}")]
+    Metrics(#[from] prometheus::Error),
+
+    /// OpenTelemetry exporter error
+    #[cfg(feature = "otel")]
+    #[error("Telemetry error: {0}")]
+    Telemetry(String),
+
+    /// Fault injected by a `chaos::FaultInjector`
+    #[cfg(feature = "chaos")]
+    #[error("Injected fault: {0}")]
+    InjectedFault(String),
 }
@@ -0,0 +1,441 @@
+//! Event streams for Charta VM
+//!
+//! Complements the callback API with a broadcast stream of VM events that any
+//! number of async consumers can subscribe to.
+//!
+//! Subscribers of [`subscribe`](crate::ChartaVM::subscribe) share one ring
+//! buffer. [`subscribe_with`](crate::ChartaVM::subscribe_with) instead gives
+//! a subscriber its own bounded buffer and backpressure policy. Either way a
+//! slow consumer only loses events; it never stalls the VM or grows memory
+//! without bound.
+//!
+//! ```no_run
+//! use charta::events::{Backpressure, SubscriberOptions};
+//! use charta::{ChartaVM, VmEvent};
+//!
+//! # async fn example(vm: &ChartaVM) {
+//! let mut events = vm.subscribe_with(SubscriberOptions {
+//!     capacity: 64,
+//!     backpressure: Backpressure::Coalesce,
+//!     notify_lag: true,
+//! });
+//! while let Some(event) = events.recv().await {
+//!     if let VmEvent::Lagged { skipped } = event {
+//!         eprintln!("missed {} events", skipped);
+//!     }
+//! }
+//! # }
+//! ```
+
+use crate::callbacks::CycleContext;
+use crate::debug::Breakpoint;
+use crate::outputs::CycleOutputs;
+use crate::pattern::Pattern;
+use crate::reload::ProgramDiff;
+use crate::shadow::ShadowDivergence;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::{broadcast, Notify};
+
+/// Default capacity of a VM event channel
+pub const DEFAULT_EVENT_CAPACITY: usize = 1024;
+
+/// Event emitted by a VM
+///
+/// Serializes as a JSON object tagged by `type`, e.g.
+/// `{"type": "coil_changed", "name": "allow_review", "old": false, "new": true,
+/// "context": {"cycle": 7, "at_ms": 1700000000000, "program_id": "..."}}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VmEvent {
+    /// A program was loaded
+    ProgramLoaded {
+        /// Cycles executed so far and the new program's id
+        context: CycleContext,
+    },
+    /// A new version of the program replaced the running one, keeping state
+    ProgramReloaded {
+        /// What the new version changed
+        diff: ProgramDiff,
+        /// Cycles executed so far and the new program's id
+        context: CycleContext,
+    },
+    /// The program was unloaded
+    ProgramUnloaded {
+        /// Cycles executed so far and the unloaded program's id
+        context: CycleContext,
+    },
+    /// Signals, coils, and counters were reset, keeping the program
+    StateReset {
+        /// The reset cycle count and the program's id
+        context: CycleContext,
+    },
+    /// A coil changed state during a cycle
+    CoilChanged {
+        /// Coil name
+        name: String,
+        /// State before the cycle
+        old: bool,
+        /// State after the cycle
+        new: bool,
+        /// Cycle the change happened in
+        context: CycleContext,
+    },
+    /// A scan cycle completed
+    CycleCompleted {
+        /// Coil states after the cycle, shared with every subscriber
+        outputs: Arc<CycleOutputs>,
+        /// Cycle that completed
+        context: CycleContext,
+    },
+    /// The shadow program disagreed with the active program
+    ShadowDiverged {
+        /// Coils on which the programs disagreed
+        divergence: ShadowDivergence,
+        /// Cycle the programs disagreed in
+        context: CycleContext,
+    },
+    /// A cycle took longer than the configured deadline
+    DeadlineExceeded {
+        /// Cycle that overran
+        context: CycleContext,
+        /// Time spent on evaluation plus callbacks
+        #[serde(rename = "elapsed_us", with = "crate::serde_time::micros")]
+        elapsed: Duration,
+        /// Configured deadline
+        #[serde(rename = "deadline_us", with = "crate::serde_time::micros")]
+        deadline: Duration,
+    },
+    /// A signal was not written within its TTL and reverted to its fallback
+    SignalExpired {
+        /// Signal name
+        name: String,
+        /// Value the signal reverted to
+        fallback: bool,
+        /// Cycle the signal reverted in
+        context: CycleContext,
+    },
+    /// A rung was taken out of the scan
+    RungDisabled {
+        /// Rung name
+        rung: String,
+        /// Cycles executed so far
+        context: CycleContext,
+    },
+    /// A disabled rung was returned to the scan
+    RungEnabled {
+        /// Rung name
+        rung: String,
+        /// Cycles executed so far
+        context: CycleContext,
+    },
+    /// A cycle hit a breakpoint and the VM paused
+    BreakpointHit {
+        /// Breakpoint hit
+        breakpoint: Breakpoint,
+        /// Signal states during the cycle
+        signals: HashMap<String, bool>,
+        /// Coil states after the cycle
+        outputs: Arc<CycleOutputs>,
+        /// Cycle that hit the breakpoint
+        context: CycleContext,
+    },
+    /// A watch expression changed value after a cycle
+    WatchChanged {
+        /// Watch name
+        name: String,
+        /// Value before the cycle
+        old: bool,
+        /// Value after the cycle
+        new: bool,
+        /// Cycle the value changed in
+        context: CycleContext,
+    },
+    /// A safety coil was set with a confirmation token
+    SafetyOverride {
+        /// Coil name
+        coil: String,
+        /// Value written
+        value: bool,
+        /// Cycles executed so far
+        context: CycleContext,
+    },
+    /// Events were discarded because this subscriber fell behind
+    Lagged {
+        /// Number of events discarded
+        skipped: u64,
+    },
+}
+
+impl VmEvent {
+    /// Name of the event type, as serialized in its `type` field
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ProgramLoaded { .. } => "program_loaded",
+            Self::ProgramReloaded { .. } => "program_reloaded",
+            Self::ProgramUnloaded { .. } => "program_unloaded",
+            Self::StateReset { .. } => "state_reset",
+            Self::CoilChanged { .. } => "coil_changed",
+            Self::CycleCompleted { .. } => "cycle_completed",
+            Self::ShadowDiverged { .. } => "shadow_diverged",
+            Self::DeadlineExceeded { .. } => "deadline_exceeded",
+            Self::SignalExpired { .. } => "signal_expired",
+            Self::RungDisabled { .. } => "rung_disabled",
+            Self::RungEnabled { .. } => "rung_enabled",
+            Self::BreakpointHit { .. } => "breakpoint_hit",
+            Self::WatchChanged { .. } => "watch_changed",
+            Self::SafetyOverride { .. } => "safety_override",
+            Self::Lagged { .. } => "lagged",
+        }
+    }
+
+    /// Cycle the event belongs to (`None` for [`VmEvent::Lagged`])
+    pub fn context(&self) -> Option<&CycleContext> {
+        match self {
+            Self::ProgramLoaded { context }
+            | Self::ProgramReloaded { context, .. }
+            | Self::ProgramUnloaded { context }
+            | Self::StateReset { context }
+            | Self::CoilChanged { context, .. }
+            | Self::CycleCompleted { context, .. }
+            | Self::ShadowDiverged { context, .. }
+            | Self::DeadlineExceeded { context, .. }
+            | Self::SignalExpired { context, .. }
+            | Self::RungDisabled { context, .. }
+            | Self::RungEnabled { context, .. }
+            | Self::BreakpointHit { context, .. }
+            | Self::WatchChanged { context, .. }
+            | Self::SafetyOverride { context, .. } => Some(context),
+            Self::Lagged { .. } => None,
+        }
+    }
+}
+
+/// Receiving half of a VM event stream
+pub type EventReceiver = broadcast::Receiver<VmEvent>;
+
+/// Event stream filtered to coil changes matching a name pattern
+pub struct CoilEventReceiver {
+    rx: EventReceiver,
+    pattern: Pattern,
+}
+
+impl CoilEventReceiver {
+    pub(crate) fn new(rx: EventReceiver, pattern: Pattern) -> Self {
+        Self { rx, pattern }
+    }
+
+    /// Receive the next matching [`VmEvent::CoilChanged`] event
+    pub async fn recv(&mut self) -> Result<VmEvent, broadcast::error::RecvError> {
+        loop {
+            let event = self.rx.recv().await?;
+            if let VmEvent::CoilChanged { name, .. } = &event {
+                if self.pattern.matches(name) {
+                    return Ok(event);
+                }
+            }
+        }
+    }
+
+    /// Receive the next matching event without waiting
+    pub fn try_recv(&mut self) -> Result<VmEvent, broadcast::error::TryRecvError> {
+        loop {
+            let event = self.rx.try_recv()?;
+            if let VmEvent::CoilChanged { name, .. } = &event {
+                if self.pattern.matches(name) {
+                    return Ok(event);
+                }
+            }
+        }
+    }
+}
+
+/// What a subscription does when events arrive faster than it reads them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Discard the oldest buffered event once the buffer is full
+    #[default]
+    DropOldest,
+    /// Merge a coil change into the same coil's unread change, then discard
+    /// the oldest buffered event if the buffer is still full
+    ///
+    /// A burst of changes to one coil takes a single slot carrying its first
+    /// old and latest new state; changes that cancel out are removed.
+    Coalesce,
+}
+
+/// Buffering of a [`Subscription`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriberOptions {
+    /// Events buffered before backpressure applies
+    pub capacity: usize,
+    /// Handling of events that arrive while the buffer is full
+    pub backpressure: Backpressure,
+    /// Deliver a [`VmEvent::Lagged`] event before the first event after a gap
+    pub notify_lag: bool,
+}
+
+impl Default for SubscriberOptions {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_EVENT_CAPACITY,
+            backpressure: Backpressure::default(),
+            notify_lag: true,
+        }
+    }
+}
+
+/// Events buffered for one subscription
+#[derive(Debug, Default)]
+struct Buffer {
+    events: VecDeque<VmEvent>,
+    /// Events discarded since the last lag notification
+    skipped: u64,
+    /// Events discarded over the subscription's lifetime
+    skipped_total: u64,
+}
+
+/// Bounded event buffer shared between the VM and one subscription
+#[derive(Debug)]
+pub(crate) struct SubscriberQueue {
+    options: SubscriberOptions,
+    buffer: Mutex<Buffer>,
+    ready: Notify,
+    closed: AtomicBool,
+}
+
+impl SubscriberQueue {
+    fn buffer(&self) -> MutexGuard<'_, Buffer> {
+        self.buffer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Buffer an event without waiting, applying the backpressure policy
+    pub(crate) fn push(&self, event: VmEvent) {
+        let mut buffer = self.buffer();
+        if self.options.backpressure == Backpressure::Coalesce && coalesce(&mut buffer.events, &event) {
+            return;
+        }
+        if buffer.events.len() >= self.options.capacity.max(1) {
+            buffer.events.pop_front();
+            buffer.skipped += 1;
+            buffer.skipped_total += 1;
+        }
+        buffer.events.push_back(event);
+        drop(buffer);
+        self.ready.notify_one();
+    }
+
+    /// Wake the subscription for good once the VM and its observers are gone
+    pub(crate) fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.ready.notify_one();
+    }
+
+    fn pop(&self) -> Option<VmEvent> {
+        let mut buffer = self.buffer();
+        if buffer.skipped > 0 && self.options.notify_lag {
+            let skipped = std::mem::take(&mut buffer.skipped);
+            return Some(VmEvent::Lagged { skipped });
+        }
+        buffer.skipped = 0;
+        buffer.events.pop_front()
+    }
+}
+
+/// Merge a coil change into an unread change of the same coil
+///
+/// Returns whether the event was absorbed.
+fn coalesce(events: &mut VecDeque<VmEvent>, event: &VmEvent) -> bool {
+    let VmEvent::CoilChanged {
+        name, new, context, ..
+    } = event
+    else {
+        return false;
+    };
+    let pending = events.iter().rposition(
+        |queued| matches!(queued, VmEvent::CoilChanged { name: coil, .. } if coil == name),
+    );
+    let Some(index) = pending else {
+        return false;
+    };
+    if let Some(VmEvent::CoilChanged {
+        old,
+        new: latest,
+        context: latest_context,
+        ..
+    }) = events.get_mut(index)
+    {
+        if *old == *new {
+            events.remove(index);
+        } else {
+            *latest = *new;
+            *latest_context = context.clone();
+        }
+    }
+    true
+}
+
+/// Event stream with its own bounded buffer and backpressure policy
+///
+/// Created by [`ChartaVM::subscribe_with`](crate::ChartaVM::subscribe_with).
+/// The VM never waits on a subscription; dropping it unsubscribes.
+#[derive(Debug)]
+pub struct Subscription {
+    queue: Arc<SubscriberQueue>,
+}
+
+impl Subscription {
+    pub(crate) fn new(options: SubscriberOptions) -> Self {
+        Self {
+            queue: Arc::new(SubscriberQueue {
+                options,
+                buffer: Mutex::new(Buffer::default()),
+                ready: Notify::new(),
+                closed: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    pub(crate) fn queue(&self) -> &Arc<SubscriberQueue> {
+        &self.queue
+    }
+
+    /// Receive the next event
+    ///
+    /// Returns `None` once the VM and all its observers are dropped and the
+    /// buffer is drained.
+    pub async fn recv(&mut self) -> Option<VmEvent> {
+        loop {
+            if let Some(event) = self.queue.pop() {
+                return Some(event);
+            }
+            if self.queue.closed.load(Ordering::SeqCst) {
+                return None;
+            }
+            self.queue.ready.notified().await;
+        }
+    }
+
+    /// Receive the next buffered event without waiting
+    pub fn try_recv(&mut self) -> Option<VmEvent> {
+        self.queue.pop()
+    }
+
+    /// Number of buffered events
+    pub fn len(&self) -> usize {
+        self.queue.buffer().events.len()
+    }
+
+    /// Check whether no events are buffered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of events discarded over the subscription's lifetime
+    pub fn skipped(&self) -> u64 {
+        self.queue.buffer().skipped_total
+    }
+}
@@ -1,4 +1,89 @@
 //! Execution control for Charta VM
+//!
+//! [`ChartaVM::execute`](crate::ChartaVM::execute) runs one scan cycle as
+//! described by [`CycleOptions`]; `execute_cycle`, `execute_cycle_with_inputs`,
+//! and `execute_group` are shorthands for common options:
+//!
+//! ```no_run
+//! use charta::{ChartaVM, CycleOptions};
+//!
+//! # async fn example(vm: &mut ChartaVM) -> charta::Result<()> {
+//! let cycle = vm
+//!     .execute(
+//!         CycleOptions::new()
+//!             .input("user_submitted", true)
+//!             .trace(true)
+//!             .suppress_callbacks(true),
+//!     )
+//!     .await?;
+//! for evaluation in &cycle.evaluations {
+//!     println!("{} {}", evaluation.rung, evaluation.energised);
+//! }
+//! # Ok(())
+//! # }
+//! ```
 
-// Placeholder for future execution control features
-// (e.g., step-by-step execution, breakpoints, etc.)
+use std::collections::HashMap;
+
+/// How to run one scan cycle
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CycleOptions {
+    /// Input signal states for the cycle
+    pub inputs: HashMap<String, bool>,
+    /// Scan only the rungs of this scan group (see [`crate::scan_group`])
+    pub group: Option<String>,
+    /// Return the evaluation of every rung
+    pub trace: bool,
+    /// Skip coil change, cycle complete, and shadow divergence callbacks;
+    /// events are still emitted
+    pub suppress_callbacks: bool,
+    /// Compute the outputs without committing any state
+    ///
+    /// Signals, coils, registers, and the cycle count are left as they were,
+    /// input drivers are not polled, and no callbacks, events, drivers,
+    /// exporters, or checkpoints run.
+    pub dry_run: bool,
+}
+
+impl CycleOptions {
+    /// Create options for a plain cycle
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the input signal states for the cycle
+    pub fn inputs(mut self, inputs: HashMap<String, bool>) -> Self {
+        self.inputs = inputs;
+        self
+    }
+
+    /// Set one input signal for the cycle
+    pub fn input(mut self, name: impl Into<String>, value: bool) -> Self {
+        self.inputs.insert(name.into(), value);
+        self
+    }
+
+    /// Scan only the rungs of scan group `group`
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Return the evaluation of every rung
+    pub fn trace(mut self, trace: bool) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    /// Skip callbacks for the cycle
+    pub fn suppress_callbacks(mut self, suppress: bool) -> Self {
+        self.suppress_callbacks = suppress;
+        self
+    }
+
+    /// Compute the outputs without committing any state
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
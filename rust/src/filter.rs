@@ -0,0 +1,193 @@
+//! Input conditioning
+//!
+//! A chattering input can be filtered before rung logic sees it, either in
+//! the IR, on the signal declaration:
+//!
+//! ```json
+//! {"name": "door_closed", "filter": {"type": "debounce", "cycles": 3}}
+//! ```
+//!
+//! or when the VM is built, which overrides the IR:
+//!
+//! ```no_run
+//! use charta::ChartaVM;
+//!
+//! let vm = ChartaVM::builder()
+//!     .debounce("door_closed", 3)
+//!     .throttle("risk_flag", 10)
+//!     .build();
+//! ```
+//!
+//! Filters count scan cycles, not time. Writes still set the raw value,
+//! which is what `get_signal` reports; rungs, coverage and traces see the
+//! filtered value. Filter state starts from `false` on every load.
+
+use serde::{Deserialize, Serialize};
+
+/// Conditioning applied to a boolean input before rung logic reads it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InputFilter {
+    /// A new value is visible once it has held for `cycles` consecutive
+    /// cycles
+    Debounce {
+        /// Cycles the value must hold
+        cycles: u32,
+    },
+    /// The visible value changes at most once every `cycles` cycles
+    Throttle {
+        /// Minimum cycles between visible transitions
+        cycles: u32,
+    },
+}
+
+impl InputFilter {
+    /// Cycles the filter counts
+    pub fn cycles(&self) -> u32 {
+        match self {
+            InputFilter::Debounce { cycles } | InputFilter::Throttle { cycles } => *cycles,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) use conditioning::InputFilters;
+
+#[cfg(feature = "std")]
+mod conditioning {
+    use super::InputFilter;
+    use crate::error::{Error, Result};
+    use crate::ir::Program;
+    use std::collections::{BTreeMap, HashMap};
+
+    /// Filter state of one signal
+    #[derive(Debug, Clone)]
+    struct FilterState {
+        filter: InputFilter,
+        /// Value rung logic sees
+        visible: bool,
+        /// Consecutive cycles the raw value differed from the visible one
+        pending: u32,
+        /// Cycles since the visible value last changed
+        since_change: u32,
+    }
+
+    impl FilterState {
+        fn new(filter: InputFilter) -> Self {
+            Self {
+                filter,
+                visible: false,
+                pending: 0,
+                since_change: filter.cycles(),
+            }
+        }
+
+        /// Advance one cycle with the raw value and get the visible one
+        fn step(&mut self, raw: bool) -> bool {
+            match self.filter {
+                InputFilter::Debounce { cycles } => {
+                    if raw == self.visible {
+                        self.pending = 0;
+                    } else {
+                        self.pending += 1;
+                        if self.pending >= cycles {
+                            self.visible = raw;
+                            self.pending = 0;
+                        }
+                    }
+                }
+                InputFilter::Throttle { cycles } => {
+                    if raw != self.visible && self.since_change >= cycles {
+                        self.visible = raw;
+                        self.since_change = 0;
+                    }
+                    self.since_change = self.since_change.saturating_add(1);
+                }
+            }
+            self.visible
+        }
+    }
+
+    /// Filters of a loaded program's inputs
+    #[derive(Debug, Clone, Default)]
+    pub(crate) struct InputFilters {
+        /// Filter state by signal, sorted
+        signals: BTreeMap<String, FilterState>,
+    }
+
+    impl InputFilters {
+        /// Collect the filters declared in `program`, overridden by
+        /// `configured`
+        ///
+        /// Fails if a filter counts zero cycles or is set on a typed signal.
+        pub(crate) fn new(
+            program: Option<&Program>,
+            configured: &HashMap<String, InputFilter>,
+        ) -> Result<Self> {
+            let mut filters: BTreeMap<&str, InputFilter> = BTreeMap::new();
+            if let Some(program) = program {
+                for signal in &program.module.signals {
+                    if let Some(filter) = signal.filter {
+                        filters.insert(&signal.name, filter);
+                    }
+                }
+            }
+            for (name, filter) in configured {
+                filters.insert(name, *filter);
+            }
+
+            let mut signals = BTreeMap::new();
+            for (name, filter) in filters {
+                if filter.cycles() == 0 {
+                    return Err(Error::IRLoad(format!(
+                        "Filter on signal '{}' must count at least one cycle",
+                        name
+                    )));
+                }
+                let typed = program
+                    .and_then(|program| program.module.signals.iter().find(|s| s.name == name))
+                    .is_some_and(|signal| !signal.value_type.is_bool());
+                if typed {
+                    return Err(Error::IRLoad(format!(
+                        "Filter on signal '{}' requires a boolean signal",
+                        name
+                    )));
+                }
+                signals.insert(name.to_string(), FilterState::new(filter));
+            }
+            Ok(Self { signals })
+        }
+
+        /// Check whether no input is filtered
+        pub(crate) fn is_empty(&self) -> bool {
+            self.signals.is_empty()
+        }
+
+        /// Replace the raw values of filtered signals in `inputs` with their
+        /// filtered values
+        ///
+        /// Raw values come from `inputs`, falling back to `signals`, the
+        /// current signal states. Returns the raw values the filter held
+        /// back, to restore once the cycle has run.
+        pub(crate) fn condition(
+            &mut self,
+            signals: &HashMap<String, bool>,
+            inputs: &mut HashMap<String, bool>,
+        ) -> Vec<(String, bool)> {
+            let mut held = Vec::new();
+            for (name, state) in &mut self.signals {
+                let raw = inputs
+                    .get(name)
+                    .or_else(|| signals.get(name))
+                    .copied()
+                    .unwrap_or(false);
+                let visible = state.step(raw);
+                inputs.insert(name.clone(), visible);
+                if visible != raw {
+                    held.push((name.clone(), raw));
+                }
+            }
+            held
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Canonical IR formatting
+//!
+//! Tools that emit IR order keys and indent differently, so the same program
+//! can hash to several [`program_id`](crate::ChartaVM::program_id)s and
+//! produce noisy diffs. [`format_ir`] rewrites IR in one canonical form:
+//! object keys sorted, two-space indentation, and a trailing newline.
+//! [`format_ir_minified`] produces the same ordering without whitespace.
+//!
+//! ```
+//! let ir = r#"{"module": {"rungs": [], "name": "p"}, "version": "0.1.0"}"#;
+//! assert_eq!(
+//!     charta::format_ir_minified(ir).unwrap(),
+//!     r#"{"module":{"name":"p","rungs":[]},"version":"0.1.0"}"#
+//! );
+//! ```
+//!
+//! Formatting is purely syntactic: array order (rung scan order) and every
+//! field, including ones the SDK does not model, are preserved, so
+//! formatting never changes what a program does.
+
+use crate::error::{Error, Result};
+use serde_json::{Map, Value};
+
+/// Format IR in canonical pretty-printed form
+pub fn format_ir(ir_json: &str) -> Result<String> {
+    let mut formatted = serde_json::to_string_pretty(&canonical(ir_json)?)?;
+    formatted.push('\n');
+    Ok(formatted)
+}
+
+/// Format IR in canonical form without whitespace
+pub fn format_ir_minified(ir_json: &str) -> Result<String> {
+    Ok(serde_json::to_string(&canonical(ir_json)?)?)
+}
+
+/// Check whether IR is already in canonical pretty-printed form
+pub fn is_formatted(ir_json: &str) -> Result<bool> {
+    Ok(format_ir(ir_json)? == ir_json)
+}
+
+fn canonical(ir_json: &str) -> Result<Value> {
+    let value: Value = serde_json::from_str(ir_json).map_err(|e| Error::IRLoad(e.to_string()))?;
+    Ok(sort_keys(value))
+}
+
+/// Rebuild objects with their keys inserted in sorted order
+///
+/// Sorting explicitly keeps the output stable even when `serde_json`'s
+/// `preserve_order` feature is enabled elsewhere in the build.
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<(String, Value)> = object.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            let mut sorted = Map::new();
+            for (key, value) in entries {
+                sorted.insert(key, sort_keys(value));
+            }
+            Value::Object(sorted)
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
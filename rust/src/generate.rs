@@ -0,0 +1,288 @@
+//! Random valid programs and input sequences for property testing
+//!
+//! With the `proptest` feature, strategies generate programs whose guards
+//! only reference declared signals and coils, plus input sequences over a
+//! program's signals:
+//!
+//! ```ignore
+//! use charta::generate;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     #[test]
+//!     fn renders_every_program(program in generate::program()) {
+//!         charta::render::ladder_text(&program);
+//!     }
+//! }
+//! ```
+//!
+//! With the `arbitrary` feature, [`Program`] and [`ProgramRun`] implement
+//! `arbitrary::Arbitrary` for fuzzing the loader and evaluator.
+
+use crate::ir::{Action, CoilDecl, ContactType, Guard, Metadata, Module, Program, Rung, SignalDecl};
+use crate::value::ValueType;
+use std::collections::HashMap;
+
+/// Size bounds for generated programs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramShape {
+    /// Maximum declared signals (at least one is always declared)
+    pub max_signals: usize,
+    /// Maximum declared coils (at least one is always declared)
+    pub max_coils: usize,
+    /// Maximum rungs (at least one is always generated)
+    pub max_rungs: usize,
+    /// Maximum guard nesting depth
+    pub max_guard_depth: u32,
+}
+
+impl Default for ProgramShape {
+    fn default() -> Self {
+        Self {
+            max_signals: 6,
+            max_coils: 4,
+            max_rungs: 8,
+            max_guard_depth: 3,
+        }
+    }
+}
+
+/// A generated program with input sequences over its signals
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramRun {
+    /// Program under test
+    pub program: Program,
+    /// Signal values for each cycle
+    pub inputs: Vec<HashMap<String, bool>>,
+}
+
+fn signal_name(i: usize) -> String {
+    format!("s{}", i)
+}
+
+fn coil_name(i: usize) -> String {
+    format!("c{}", i)
+}
+
+/// Get the names a guard may reference: every signal, then every coil
+fn contact_names(signals: usize, coils: usize) -> Vec<String> {
+    (0..signals)
+        .map(signal_name)
+        .chain((0..coils).map(coil_name))
+        .collect()
+}
+
+fn contact(name: String, normally_closed: bool) -> Guard {
+    Guard::Contact {
+        name,
+        contact_type: if normally_closed {
+            ContactType::NormallyClosed
+        } else {
+            ContactType::NormallyOpen
+        },
+    }
+}
+
+/// Assemble a program from guards and the index of the coil each drives
+fn assemble(signals: usize, coils: usize, rungs: Vec<(Guard, usize)>) -> Program {
+    Program {
+        version: "0.1.0".to_string(),
+        imports: Vec::new(),
+        module: Module {
+            name: "generated".to_string(),
+            signals: (0..signals)
+                .map(|i| SignalDecl {
+                    name: signal_name(i),
+                    value_type: ValueType::Bool,
+                    filter: None,
+                    initial: None,
+                    meta: Metadata::default(),
+                })
+                .collect(),
+            coils: (0..coils)
+                .map(|i| CoilDecl {
+                    name: coil_name(i),
+                    initial: None,
+                    retained: false,
+                    meta: Metadata::default(),
+                })
+                .collect(),
+            imports: Vec::new(),
+            exports: Vec::new(),
+            registers: Vec::new(),
+            rungs: rungs
+                .into_iter()
+                .enumerate()
+                .map(|(i, (guard, coil))| Rung {
+                    name: format!("r{}", i),
+                    guard,
+                    actions: vec![Action::Energise { coil: coil_name(coil) }],
+                    priority: 0,
+                    group: None,
+                    source: None,
+                })
+                .collect(),
+        },
+    }
+}
+
+#[cfg(feature = "proptest")]
+mod strategies {
+    use super::*;
+    use proptest::collection::{vec, SizeRange};
+    use proptest::prelude::*;
+    use proptest::sample::select;
+
+    /// Generate programs with the default [`ProgramShape`]
+    pub fn program() -> BoxedStrategy<Program> {
+        program_with(ProgramShape::default())
+    }
+
+    /// Generate programs within the given bounds
+    pub fn program_with(shape: ProgramShape) -> BoxedStrategy<Program> {
+        (1..=shape.max_signals.max(1), 1..=shape.max_coils.max(1))
+            .prop_flat_map(move |(signals, coils)| {
+                let rung = (
+                    guard(contact_names(signals, coils), shape.max_guard_depth),
+                    0..coils,
+                );
+                vec(rung, 1..=shape.max_rungs.max(1))
+                    .prop_map(move |rungs| assemble(signals, coils, rungs))
+            })
+            .boxed()
+    }
+
+    /// Generate guards over `names` nested at most `depth` levels
+    pub fn guard(names: Vec<String>, depth: u32) -> BoxedStrategy<Guard> {
+        let leaf = (select(names), any::<bool>())
+            .prop_map(|(name, normally_closed)| contact(name, normally_closed));
+        leaf.prop_recursive(depth, 16, 3, |inner| {
+            prop_oneof![
+                vec(inner.clone(), 2..=3).prop_map(|operands| Guard::And {
+                    left: None,
+                    right: None,
+                    operands,
+                }),
+                vec(inner.clone(), 2..=3).prop_map(|operands| Guard::Or {
+                    left: None,
+                    right: None,
+                    operands,
+                }),
+                inner.prop_map(|operand| Guard::Not {
+                    operand: Box::new(operand),
+                }),
+            ]
+        })
+        .boxed()
+    }
+
+    /// Generate input sequences assigning every signal of `program` each cycle
+    pub fn inputs(
+        program: &Program,
+        cycles: impl Into<SizeRange>,
+    ) -> BoxedStrategy<Vec<HashMap<String, bool>>> {
+        let names: Vec<String> = program
+            .module
+            .signals
+            .iter()
+            .map(|signal| signal.name.clone())
+            .collect();
+        let cycle = vec(any::<bool>(), names.len())
+            .prop_map(move |values| names.iter().cloned().zip(values).collect());
+        vec(cycle, cycles).boxed()
+    }
+
+    /// Generate programs together with input sequences over their signals
+    pub fn program_run(cycles: impl Into<SizeRange>) -> BoxedStrategy<ProgramRun> {
+        let cycles = cycles.into();
+        program()
+            .prop_flat_map(move |program| {
+                let inputs = inputs(&program, cycles.clone());
+                (Just(program), inputs)
+            })
+            .prop_map(|(program, inputs)| ProgramRun { program, inputs })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "proptest")]
+pub use strategies::{guard, inputs, program, program_run, program_with};
+
+#[cfg(feature = "arbitrary")]
+mod fuzz {
+    use super::*;
+    use arbitrary::{Arbitrary, Result, Unstructured};
+
+    /// Maximum cycles in an arbitrary [`ProgramRun`]
+    const MAX_CYCLES: usize = 16;
+
+    fn arbitrary_guard(u: &mut Unstructured<'_>, names: &[String], depth: u32) -> Result<Guard> {
+        if depth == 0 || u.ratio(1, 2)? {
+            let name = u.choose(names)?.clone();
+            return Ok(contact(name, u.arbitrary()?));
+        }
+
+        Ok(match u.int_in_range(0..=2)? {
+            0 => Guard::And {
+                left: None,
+                right: None,
+                operands: arbitrary_operands(u, names, depth)?,
+            },
+            1 => Guard::Or {
+                left: None,
+                right: None,
+                operands: arbitrary_operands(u, names, depth)?,
+            },
+            _ => Guard::Not {
+                operand: Box::new(arbitrary_guard(u, names, depth - 1)?),
+            },
+        })
+    }
+
+    fn arbitrary_operands(
+        u: &mut Unstructured<'_>,
+        names: &[String],
+        depth: u32,
+    ) -> Result<Vec<Guard>> {
+        let count = u.int_in_range(2..=3)?;
+        (0..count)
+            .map(|_| arbitrary_guard(u, names, depth - 1))
+            .collect()
+    }
+
+    impl<'a> Arbitrary<'a> for Program {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let shape = ProgramShape::default();
+            let signals = u.int_in_range(1..=shape.max_signals)?;
+            let coils = u.int_in_range(1..=shape.max_coils)?;
+            let names = contact_names(signals, coils);
+
+            let count = u.int_in_range(1..=shape.max_rungs)?;
+            let rungs = (0..count)
+                .map(|_| {
+                    let guard = arbitrary_guard(u, &names, shape.max_guard_depth)?;
+                    Ok((guard, u.choose_index(coils)?))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(assemble(signals, coils, rungs))
+        }
+    }
+
+    impl<'a> Arbitrary<'a> for ProgramRun {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let program = Program::arbitrary(u)?;
+            let cycles = u.int_in_range(0..=MAX_CYCLES)?;
+            let inputs = (0..cycles)
+                .map(|_| {
+                    program
+                        .module
+                        .signals
+                        .iter()
+                        .map(|signal| Ok((signal.name.clone(), u.arbitrary()?)))
+                        .collect()
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(ProgramRun { program, inputs })
+        }
+    }
+}
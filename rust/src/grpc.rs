@@ -0,0 +1,257 @@
+//! gRPC service for Charta VM
+//!
+//! Available with the `grpc` feature. Serves the `charta.v1.Charta` service
+//! defined in `proto/charta.proto`, backed by a [`ChartaVM`].
+//!
+//! ```no_run
+//! use charta::grpc::ChartaGrpc;
+//! use charta::ChartaVM;
+//!
+//! # async fn run(vm: ChartaVM) -> Result<(), Box<dyn std::error::Error>> {
+//! tonic::transport::Server::builder()
+//!     .add_service(ChartaGrpc::new(vm).into_service())
+//!     .serve("0.0.0.0:50051".parse()?)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::callbacks::CycleContext;
+use crate::debug::Breakpoint;
+use crate::error::Error;
+use crate::events::VmEvent;
+use crate::observer::ChartaObserver;
+use crate::pattern::Pattern;
+use crate::vm::ChartaVM;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+/// Generated protobuf types and service stubs
+pub mod proto {
+    tonic::include_proto!("charta.v1");
+}
+
+use proto::charta_server::{Charta, ChartaServer};
+use proto::event::Kind;
+
+/// gRPC service backed by a VM
+#[derive(Clone)]
+pub struct ChartaGrpc {
+    /// VM for mutating calls
+    vm: Arc<Mutex<ChartaVM>>,
+    /// Lock-free view for reads and event streams
+    observer: ChartaObserver,
+}
+
+impl ChartaGrpc {
+    /// Serve a VM, taking ownership of it
+    pub fn new(vm: ChartaVM) -> Self {
+        let observer = vm.observer();
+        Self {
+            vm: Arc::new(Mutex::new(vm)),
+            observer,
+        }
+    }
+
+    /// Serve a VM shared with the rest of the application
+    pub fn from_shared(vm: Arc<Mutex<ChartaVM>>, observer: ChartaObserver) -> Self {
+        Self { vm, observer }
+    }
+
+    /// Wrap in the generated tonic service
+    pub fn into_service(self) -> ChartaServer<Self> {
+        ChartaServer::new(self)
+    }
+}
+
+fn status(error: Error) -> Status {
+    match &error {
+        Error::NotFound(_) => Status::not_found(error.to_string()),
+        Error::AccessDenied(_) => Status::permission_denied(error.to_string()),
+        Error::IRLoad(_)
+        | Error::LimitExceeded(_)
+        | Error::InvalidOperation(_)
+        | Error::TypeMismatch(_) => Status::invalid_argument(error.to_string()),
+        _ => Status::internal(error.to_string()),
+    }
+}
+
+#[tonic::async_trait]
+impl Charta for ChartaGrpc {
+    async fn load_program(
+        &self,
+        request: Request<proto::LoadProgramRequest>,
+    ) -> Result<Response<proto::LoadProgramResponse>, Status> {
+        let mut vm = self.vm.lock().await;
+        let report = vm
+            .load_program(&request.into_inner().ir_json)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(proto::LoadProgramResponse {
+            program_id: report.program_id,
+            disabled_rungs: report.disabled_rungs,
+            signals: report.signals as u32,
+            coils: report.coils as u32,
+            rungs: report.rungs as u32,
+            warnings: report.warnings,
+        }))
+    }
+
+    async fn set_signals(
+        &self,
+        request: Request<proto::SetSignalsRequest>,
+    ) -> Result<Response<proto::SetSignalsResponse>, Status> {
+        let mut vm = self.vm.lock().await;
+        for (name, value) in request.into_inner().signals {
+            vm.set_signal(&name, value).await.map_err(status)?;
+        }
+        Ok(Response::new(proto::SetSignalsResponse {}))
+    }
+
+    async fn execute_cycle(
+        &self,
+        request: Request<proto::ExecuteCycleRequest>,
+    ) -> Result<Response<proto::ExecuteCycleResponse>, Status> {
+        let mut vm = self.vm.lock().await;
+        let outputs = vm
+            .execute_cycle_with_inputs(request.into_inner().inputs)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(proto::ExecuteCycleResponse {
+            cycle: outputs.cycle(),
+            outputs: outputs.coils().clone(),
+        }))
+    }
+
+    type StreamEventsStream = Pin<Box<dyn Stream<Item = Result<proto::Event, Status>> + Send>>;
+
+    async fn stream_events(
+        &self,
+        request: Request<proto::StreamEventsRequest>,
+    ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        let pattern = request.into_inner().coil_pattern;
+        let pattern = (!pattern.is_empty()).then(|| Pattern::new(&pattern));
+
+        // Lagged receivers report the missed events rather than ending the stream
+        let stream = BroadcastStream::new(self.observer.subscribe()).filter_map(move |event| {
+            let event = event.unwrap_or_else(|BroadcastStreamRecvError::Lagged(skipped)| {
+                VmEvent::Lagged { skipped }
+            });
+            if let Some(pattern) = &pattern {
+                match &event {
+                    VmEvent::CoilChanged { name, .. } if pattern.matches(name) => {}
+                    VmEvent::Lagged { .. } => {}
+                    _ => return None,
+                }
+            }
+            Some(Ok(proto::Event {
+                context: event.context().map(context_proto),
+                kind: Some(event_kind(event)),
+            }))
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn get_state(
+        &self,
+        _request: Request<proto::GetStateRequest>,
+    ) -> Result<Response<proto::State>, Status> {
+        Ok(Response::new(proto::State {
+            program_id: self.observer.program_id(),
+            cycle_count: self.observer.cycle_count(),
+            signals: self.observer.get_all_signals().await.map_err(status)?,
+            coils: self.observer.get_all_coils().await.map_err(status)?,
+        }))
+    }
+}
+
+/// Convert a cycle context to its protobuf form
+fn context_proto(context: &CycleContext) -> proto::CycleContext {
+    proto::CycleContext {
+        cycle: context.cycle,
+        at_ms: context.timestamp_millis(),
+        program_id: context.program_id.as_deref().map(String::from),
+    }
+}
+
+/// Convert a VM event to its protobuf form
+fn event_kind(event: VmEvent) -> Kind {
+    match event {
+        VmEvent::ProgramLoaded { .. } => Kind::ProgramLoaded(proto::ProgramLoaded {}),
+        VmEvent::ProgramReloaded { diff, .. } => Kind::ProgramReloaded(proto::ProgramReloaded {
+            added_signals: diff.added_signals,
+            removed_signals: diff.removed_signals,
+            added_coils: diff.added_coils,
+            removed_coils: diff.removed_coils,
+            added_rungs: diff.added_rungs,
+            removed_rungs: diff.removed_rungs,
+            changed_rungs: diff.changed_rungs,
+        }),
+        VmEvent::ProgramUnloaded { .. } => Kind::ProgramUnloaded(proto::ProgramUnloaded {}),
+        VmEvent::StateReset { .. } => Kind::StateReset(proto::StateReset {}),
+        VmEvent::CoilChanged { name, old, new, .. } => {
+            Kind::CoilChanged(proto::CoilChanged { name, old, new })
+        }
+        VmEvent::CycleCompleted { outputs, .. } => {
+            Kind::CycleCompleted(proto::CycleCompleted {
+                outputs: outputs.coils().clone(),
+            })
+        }
+        VmEvent::ShadowDiverged { divergence, .. } => Kind::ShadowDiverged(proto::ShadowDiverged {
+            cycle: divergence.cycle,
+            coils: divergence
+                .coils
+                .into_iter()
+                .map(|coil| proto::CoilDivergence {
+                    name: coil.name,
+                    primary: coil.primary,
+                    shadow: coil.shadow,
+                })
+                .collect(),
+            error: divergence.error,
+        }),
+        VmEvent::DeadlineExceeded {
+            context,
+            elapsed,
+            deadline,
+        } => Kind::DeadlineExceeded(proto::DeadlineExceeded {
+            cycle: context.cycle,
+            elapsed_us: elapsed.as_micros() as u64,
+            deadline_us: deadline.as_micros() as u64,
+        }),
+        VmEvent::SignalExpired { name, fallback, .. } => {
+            Kind::SignalExpired(proto::SignalExpired { name, fallback })
+        }
+        VmEvent::RungDisabled { rung, .. } => Kind::RungDisabled(proto::RungDisabled { rung }),
+        VmEvent::RungEnabled { rung, .. } => Kind::RungEnabled(proto::RungEnabled { rung }),
+        VmEvent::BreakpointHit {
+            breakpoint,
+            signals,
+            outputs,
+            ..
+        } => {
+            let (kind, name) = match breakpoint {
+                Breakpoint::Rung(rung) => ("rung", rung),
+                Breakpoint::CoilChange(coil) => ("coil_change", coil),
+            };
+            Kind::BreakpointHit(proto::BreakpointHit {
+                kind: kind.to_string(),
+                name,
+                signals,
+                outputs: outputs.coils().clone(),
+            })
+        }
+        VmEvent::WatchChanged { name, old, new, .. } => {
+            Kind::WatchChanged(proto::WatchChanged { name, old, new })
+        }
+        VmEvent::SafetyOverride { coil, value, .. } => {
+            Kind::SafetyOverride(proto::SafetyOverride { coil, value })
+        }
+        VmEvent::Lagged { skipped } => Kind::Lagged(proto::Lagged { skipped }),
+    }
+}
@@ -0,0 +1,154 @@
+//! Event history for Charta VM
+//!
+//! Opt-in ring buffers of recent coil changes and cycles, queryable by cycle
+//! number or time range.
+
+use crate::collections::Map;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::SystemTime;
+
+/// A recorded coil change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoilChangeRecord {
+    /// Cycle the change occurred in
+    pub cycle: u64,
+    /// When the cycle completed (serialized as `at_ms`)
+    #[serde(rename = "at_ms", with = "crate::serde_time::unix_millis")]
+    pub at: SystemTime,
+    /// Coil name
+    pub name: String,
+    /// State before the cycle
+    pub old: bool,
+    /// State after the cycle
+    pub new: bool,
+}
+
+/// A recorded cycle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CycleRecord {
+    /// Cycle number
+    pub cycle: u64,
+    /// When the cycle completed (serialized as `at_ms`)
+    #[serde(rename = "at_ms", with = "crate::serde_time::unix_millis")]
+    pub at: SystemTime,
+    /// Coil states after the cycle
+    pub outputs: HashMap<String, bool>,
+    /// Number of coils that changed
+    pub changes: usize,
+}
+
+/// Bounded history of coil changes and cycles
+///
+/// Each buffer keeps at most `capacity` entries, discarding the oldest.
+#[derive(Debug, Clone)]
+pub struct History {
+    capacity: usize,
+    coil_changes: VecDeque<CoilChangeRecord>,
+    cycles: VecDeque<CycleRecord>,
+}
+
+impl History {
+    /// Create an empty history
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            coil_changes: VecDeque::with_capacity(capacity),
+            cycles: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Get the capacity of each buffer
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Record a completed cycle
+    pub(crate) fn record(
+        &mut self,
+        cycle: u64,
+        at: SystemTime,
+        outputs: &HashMap<String, bool>,
+        changes: &Map<String, (bool, bool)>,
+    ) {
+        let mut names: Vec<&String> = changes.keys().collect();
+        names.sort();
+        for name in names {
+            let (old, new) = changes[name];
+            if self.coil_changes.len() == self.capacity {
+                self.coil_changes.pop_front();
+            }
+            self.coil_changes.push_back(CoilChangeRecord {
+                cycle,
+                at,
+                name: name.clone(),
+                old,
+                new,
+            });
+        }
+
+        if self.cycles.len() == self.capacity {
+            self.cycles.pop_front();
+        }
+        self.cycles.push_back(CycleRecord {
+            cycle,
+            at,
+            outputs: outputs.clone(),
+            changes: changes.len(),
+        });
+    }
+
+    /// Get all recorded coil changes, oldest first
+    pub fn coil_changes(&self) -> impl Iterator<Item = &CoilChangeRecord> {
+        self.coil_changes.iter()
+    }
+
+    /// Get the most recent `n` coil changes, oldest first
+    pub fn last_coil_changes(&self, n: usize) -> Vec<&CoilChangeRecord> {
+        let skip = self.coil_changes.len().saturating_sub(n);
+        self.coil_changes.iter().skip(skip).collect()
+    }
+
+    /// Get coil changes that occurred after `cycle`
+    pub fn coil_changes_since(&self, cycle: u64) -> Vec<&CoilChangeRecord> {
+        self.coil_changes.iter().filter(|r| r.cycle > cycle).collect()
+    }
+
+    /// Get coil changes recorded within `[from, to]`
+    pub fn coil_changes_between(&self, from: SystemTime, to: SystemTime) -> Vec<&CoilChangeRecord> {
+        self.coil_changes
+            .iter()
+            .filter(|r| r.at >= from && r.at <= to)
+            .collect()
+    }
+
+    /// Get recorded changes of a single coil
+    pub fn coil_changes_for(&self, name: &str) -> Vec<&CoilChangeRecord> {
+        self.coil_changes.iter().filter(|r| r.name == name).collect()
+    }
+
+    /// Get all recorded cycles, oldest first
+    pub fn cycles(&self) -> impl Iterator<Item = &CycleRecord> {
+        self.cycles.iter()
+    }
+
+    /// Get cycles executed after `cycle`
+    pub fn cycles_since(&self, cycle: u64) -> Vec<&CycleRecord> {
+        self.cycles.iter().filter(|r| r.cycle > cycle).collect()
+    }
+
+    /// Get cycles completed within `[from, to]`
+    pub fn cycles_between(&self, from: SystemTime, to: SystemTime) -> Vec<&CycleRecord> {
+        self.cycles
+            .iter()
+            .filter(|r| r.at >= from && r.at <= to)
+            .collect()
+    }
+
+    /// Discard all recorded entries
+    pub fn clear(&mut self) {
+        self.coil_changes.clear();
+        self.cycles.clear();
+    }
+}
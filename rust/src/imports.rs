@@ -0,0 +1,232 @@
+//! Resolving IR file imports
+//!
+//! A program can pull shared rungs and declarations from other IR files
+//! through a top-level `imports` list:
+//!
+//! ```json
+//! {"version": "0.1.0", "imports": ["common_interlocks.ir.json"], "module": {...}}
+//! ```
+//!
+//! Imported files are merged into the importing program before it loads:
+//! their signals, coils, and rungs come first, in import order, and may
+//! themselves import further files. A file reached along several paths is
+//! included once. An import cycle, or a signal, coil, or rung declared by
+//! two files, fails the load naming the files involved.
+//!
+//! [`ChartaVM::load_program_from_file`](crate::ChartaVM::load_program_from_file)
+//! resolves imports relative to the importing file with a [`FileResolver`];
+//! [`ChartaVM::load_program_with_resolver`](crate::ChartaVM::load_program_with_resolver)
+//! takes any [`ImportResolver`], e.g. one backed by a database.
+
+use crate::error::{Error, Result};
+use crate::io::async_trait;
+use crate::ir::{Module, Program};
+use std::collections::{HashMap, HashSet};
+
+/// An imported IR file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolved {
+    /// Identity of the file, used for cycle detection and diagnostics
+    ///
+    /// Two imports naming the same file must resolve to the same id.
+    pub id: String,
+    /// IR JSON of the file
+    pub ir_json: String,
+}
+
+/// Locates the IR files a program imports
+#[async_trait]
+pub trait ImportResolver: Send + Sync {
+    /// Resolve `import` as written in the file identified by `importer`
+    ///
+    /// Fails with [`Error::NotFound`] if there is no such file.
+    async fn resolve(&self, importer: &str, import: &str) -> Result<Resolved>;
+}
+
+/// Resolves imports as paths relative to the importing file
+///
+/// Ids are canonical paths, so the same file reached through different
+/// relative paths is recognised.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileResolver;
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+impl ImportResolver for FileResolver {
+    async fn resolve(&self, importer: &str, import: &str) -> Result<Resolved> {
+        let base = std::path::Path::new(importer)
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."));
+        let not_found = |e: std::io::Error| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                Error::NotFound(format!("'{}' imported by '{}'", import, importer))
+            }
+            _ => e.into(),
+        };
+        let path = tokio::fs::canonicalize(base.join(import))
+            .await
+            .map_err(not_found)?;
+        let ir_json = tokio::fs::read_to_string(&path).await.map_err(not_found)?;
+        Ok(Resolved {
+            id: path.display().to_string(),
+            ir_json,
+        })
+    }
+}
+
+/// Merge the files `ir_json` imports, directly or indirectly, into it
+///
+/// `root` identifies `ir_json` to the resolver. The result keeps the root
+/// program's version and module name and has no imports left.
+pub async fn resolve_imports(
+    root: &str,
+    ir_json: &str,
+    resolver: &dyn ImportResolver,
+) -> Result<Program> {
+    let program = Program::from_json(ir_json)?;
+    let mut merged = Merged::new(&program);
+
+    // Depth-first over the import graph; a file is merged once all of its
+    // imports have been
+    let mut stack = vec![Pending {
+        id: root.to_string(),
+        program,
+        next: 0,
+    }];
+    let mut done = HashSet::new();
+    while let Some(top) = stack.last_mut() {
+        let Some(import) = top.program.imports.get(top.next).cloned() else {
+            let file = stack.pop().expect("stack is not empty");
+            merged.add(&file.id, file.program.module)?;
+            done.insert(file.id);
+            continue;
+        };
+        top.next += 1;
+        let importer = top.id.clone();
+
+        let resolved = resolver.resolve(&importer, &import).await?;
+        if let Some(start) = stack.iter().position(|file| file.id == resolved.id) {
+            let chain: Vec<&str> = stack[start..]
+                .iter()
+                .map(|file| file.id.as_str())
+                .chain([resolved.id.as_str()])
+                .collect();
+            return Err(Error::IRLoad(format!("import cycle: {}", chain.join(" -> "))));
+        }
+        if done.contains(&resolved.id) {
+            continue;
+        }
+        let program = Program::from_json(&resolved.ir_json).map_err(|e| {
+            Error::IRLoad(format!("'{}' imported by '{}': {}", resolved.id, importer, e))
+        })?;
+        stack.push(Pending {
+            id: resolved.id,
+            program,
+            next: 0,
+        });
+    }
+
+    Ok(merged.finish())
+}
+
+/// Read a program file, merging its imports if it has any
+///
+/// A file without imports is returned unchanged, so its program id is the
+/// hash of the file.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn read_program_file(path: &std::path::Path) -> Result<String> {
+    let contents = tokio::fs::read_to_string(path).await?;
+    let has_imports = Program::from_json(&contents).is_ok_and(|program| !program.imports.is_empty());
+    if !has_imports {
+        return Ok(contents);
+    }
+    let root = tokio::fs::canonicalize(path).await?;
+    let program = resolve_imports(&root.display().to_string(), &contents, &FileResolver).await?;
+    Ok(serde_json::to_string(&program)?)
+}
+
+/// A file whose imports are being resolved
+struct Pending {
+    id: String,
+    program: Program,
+    next: usize,
+}
+
+/// Program assembled from the files merged so far
+struct Merged {
+    program: Program,
+    /// File declaring each signal, coil, register, and rung, for diagnostics
+    signals: HashMap<String, String>,
+    coils: HashMap<String, String>,
+    registers: HashMap<String, String>,
+    rungs: HashMap<String, String>,
+}
+
+impl Merged {
+    fn new(root: &Program) -> Self {
+        Self {
+            program: Program {
+                version: root.version.clone(),
+                imports: Vec::new(),
+                module: Module {
+                    name: root.module.name.clone(),
+                    signals: Vec::new(),
+                    coils: Vec::new(),
+                    imports: Vec::new(),
+                    exports: Vec::new(),
+                    registers: Vec::new(),
+                    rungs: Vec::new(),
+                },
+            },
+            signals: HashMap::new(),
+            coils: HashMap::new(),
+            registers: HashMap::new(),
+            rungs: HashMap::new(),
+        }
+    }
+
+    fn add(&mut self, file: &str, module: Module) -> Result<()> {
+        for signal in &module.signals {
+            claim(&mut self.signals, "signal", &signal.name, file)?;
+        }
+        for coil in &module.coils {
+            claim(&mut self.coils, "coil", &coil.name, file)?;
+        }
+        for register in &module.registers {
+            claim(&mut self.registers, "register", &register.name, file)?;
+        }
+        for rung in &module.rungs {
+            claim(&mut self.rungs, "rung", &rung.name, file)?;
+        }
+        let merged = &mut self.program.module;
+        merged.signals.extend(module.signals);
+        merged.coils.extend(module.coils);
+        merged.imports.extend(module.imports);
+        merged.exports.extend(module.exports);
+        merged.registers.extend(module.registers);
+        merged.rungs.extend(module.rungs);
+        Ok(())
+    }
+
+    fn finish(self) -> Program {
+        self.program
+    }
+}
+
+fn claim(owners: &mut HashMap<String, String>, kind: &str, name: &str, file: &str) -> Result<()> {
+    match owners.get(name) {
+        Some(owner) if owner == file => Err(Error::IRLoad(format!(
+            "{} '{}' is declared twice in '{}'",
+            kind, name, file
+        ))),
+        Some(owner) => Err(Error::IRLoad(format!(
+            "{} '{}' is declared in both '{}' and '{}'",
+            kind, name, owner, file
+        ))),
+        None => {
+            owners.insert(name.to_string(), file.to_string());
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Publishing VM events to message brokers
+//!
+//! Available with the `kafka` or `nats` feature. An [`EventSink`] subscribes
+//! to a VM's event stream, serializes each event to JSON (see
+//! [`VmEvent`]), maps it to a topic, and hands batches to an
+//! [`EventPublisher`] on a background task, so the scan loop never waits on
+//! the broker.
+//!
+//! ```no_run
+//! use charta::integrations::event_sink::{Batching, EventSink, TopicMapping};
+//! use charta::integrations::kafka::KafkaPublisher;
+//! use charta::ChartaVM;
+//! use std::time::Duration;
+//!
+//! # fn run(vm: &ChartaVM) -> charta::Result<()> {
+//! let mapping = TopicMapping::new("charta.events")
+//!     .coil("allow_*", "charta.decisions")
+//!     .exclude("cycle_completed");
+//! EventSink::new(KafkaPublisher::new("localhost:9092")?, mapping)
+//!     .batching(Batching {
+//!         max_events: 500,
+//!         max_delay: Duration::from_millis(20),
+//!     })
+//!     .on_error(|e| eprintln!("publish failed: {}", e))
+//!     .attach(&vm.observer());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Coil changes are keyed by coil name, so brokers that partition by key keep
+//! each coil's transitions in order.
+
+use crate::error::{Error, Result};
+use crate::events::{SubscriberOptions, Subscription, VmEvent};
+use crate::io::async_trait;
+use crate::observer::ChartaObserver;
+use crate::pattern::Pattern;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Callback function type for failed publications
+pub type PublishErrorCallback = Arc<dyn Fn(&Error) + Send + Sync>;
+
+/// A serialized event addressed to a topic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// Topic or subject
+    pub topic: String,
+    /// Partitioning key (the coil name for coil changes)
+    pub key: Option<String>,
+    /// Event as JSON
+    pub payload: Vec<u8>,
+}
+
+/// Broker client publishing batches of records
+#[async_trait]
+pub trait EventPublisher: Send {
+    /// Publish a batch of records in order
+    async fn publish(&mut self, records: &[Record]) -> Result<()>;
+}
+
+/// Mapping from VM events to topics
+///
+/// Coil changes go to the topic of the first matching coil pattern; other
+/// events go to the topic set for their [`kind`](VmEvent::kind), then to the
+/// default topic. Events without a topic are not published.
+#[derive(Debug, Clone, Default)]
+pub struct TopicMapping {
+    default: Option<String>,
+    kinds: HashMap<String, Option<String>>,
+    coils: Vec<(Pattern, String)>,
+}
+
+impl TopicMapping {
+    /// Publish every event to `topic` unless mapped otherwise
+    pub fn new(topic: &str) -> Self {
+        Self {
+            default: Some(topic.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// Publish only explicitly mapped events
+    pub fn mapped_only() -> Self {
+        Self::default()
+    }
+
+    /// Publish events of `kind` (e.g. `"coil_changed"`) to `topic`
+    pub fn event(mut self, kind: &str, topic: &str) -> Self {
+        self.kinds.insert(kind.to_string(), Some(topic.to_string()));
+        self
+    }
+
+    /// Do not publish events of `kind`
+    pub fn exclude(mut self, kind: &str) -> Self {
+        self.kinds.insert(kind.to_string(), None);
+        self
+    }
+
+    /// Publish changes of coils matching a name or pattern to `topic`
+    pub fn coil(mut self, pattern: &str, topic: &str) -> Self {
+        self.coils.push((Pattern::new(pattern), topic.to_string()));
+        self
+    }
+
+    /// Get the topic for an event
+    pub fn topic_for(&self, event: &VmEvent) -> Option<&str> {
+        if let VmEvent::CoilChanged { name, .. } = event {
+            if let Some((_, topic)) = self.coils.iter().find(|(pattern, _)| pattern.matches(name)) {
+                return Some(topic);
+            }
+        }
+        match self.kinds.get(event.kind()) {
+            Some(topic) => topic.as_deref(),
+            None => self.default.as_deref(),
+        }
+    }
+
+    /// Serialize an event into a record, if it maps to a topic
+    pub fn record(&self, event: &VmEvent) -> Result<Option<Record>> {
+        let Some(topic) = self.topic_for(event) else {
+            return Ok(None);
+        };
+        let key = match event {
+            VmEvent::CoilChanged { name, .. } => Some(name.clone()),
+            _ => None,
+        };
+        Ok(Some(Record {
+            topic: topic.to_string(),
+            key,
+            payload: serde_json::to_vec(event)?,
+        }))
+    }
+}
+
+/// When a sink hands a batch to its publisher
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Batching {
+    /// Publish once this many records are waiting
+    pub max_events: usize,
+    /// Publish at most this long after the first record of a batch arrived
+    pub max_delay: Duration,
+}
+
+impl Default for Batching {
+    fn default() -> Self {
+        Self {
+            max_events: 100,
+            max_delay: Duration::from_millis(50),
+        }
+    }
+}
+
+impl Batching {
+    /// Publish every event on its own
+    pub fn none() -> Self {
+        Self {
+            max_events: 1,
+            max_delay: Duration::ZERO,
+        }
+    }
+}
+
+/// Background task forwarding VM events to a broker
+pub struct EventSink<P> {
+    publisher: P,
+    mapping: TopicMapping,
+    batching: Batching,
+    subscriber: SubscriberOptions,
+    on_error: Option<PublishErrorCallback>,
+}
+
+impl<P: EventPublisher + 'static> EventSink<P> {
+    /// Create a sink with default batching
+    pub fn new(publisher: P, mapping: TopicMapping) -> Self {
+        Self {
+            publisher,
+            mapping,
+            batching: Batching::default(),
+            subscriber: SubscriberOptions::default(),
+            on_error: None,
+        }
+    }
+
+    /// Set when batches are published
+    pub fn batching(mut self, batching: Batching) -> Self {
+        self.batching = batching;
+        self
+    }
+
+    /// Set the buffer and backpressure of the sink's event subscription
+    ///
+    /// Events arriving while the broker is slow are buffered here; the VM
+    /// never waits on the sink.
+    pub fn subscriber(mut self, options: SubscriberOptions) -> Self {
+        self.subscriber = options;
+        self
+    }
+
+    /// Register a callback for failed serializations and publications
+    ///
+    /// Failed batches are not retried.
+    pub fn on_error<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&Error) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Arc::new(callback));
+        self
+    }
+
+    /// Subscribe to a VM's events and start publishing them
+    ///
+    /// Must be called within a Tokio runtime. The task ends, after
+    /// publishing what it buffered, once the VM and its observers are
+    /// dropped.
+    pub fn attach(self, observer: &ChartaObserver) -> JoinHandle<()> {
+        let events = observer.subscribe_with(self.subscriber);
+        tokio::spawn(self.run(events))
+    }
+
+    /// Forward events from a subscription until it ends
+    pub async fn run(mut self, mut events: Subscription) {
+        let mut batch = Vec::new();
+        while let Some(event) = events.recv().await {
+            self.add(&mut batch, &event);
+            let deadline = Instant::now() + self.batching.max_delay;
+            while batch.len() < self.batching.max_events.max(1) {
+                tokio::select! {
+                    event = events.recv() => match event {
+                        Some(event) => self.add(&mut batch, &event),
+                        None => break,
+                    },
+                    _ = tokio::time::sleep_until(deadline) => break,
+                }
+            }
+            self.flush(&mut batch).await;
+        }
+        self.flush(&mut batch).await;
+    }
+
+    fn add(&self, batch: &mut Vec<Record>, event: &VmEvent) {
+        match self.mapping.record(event) {
+            Ok(Some(record)) => batch.push(record),
+            Ok(None) => {}
+            Err(e) => self.report(&e),
+        }
+    }
+
+    async fn flush(&mut self, batch: &mut Vec<Record>) {
+        if batch.is_empty() {
+            return;
+        }
+        if let Err(e) = self.publisher.publish(batch).await {
+            self.report(&e);
+        }
+        batch.clear();
+    }
+
+    fn report(&self, error: &Error) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(error = %error, "event sink failed");
+        if let Some(callback) = &self.on_error {
+            callback(error);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! GPIO driver for soft-PLC hosts
+//!
+//! Available with the `gpio` feature. Maps signals to input pins and coils to
+//! output pins through `embedded-hal` 1.0 digital traits, so the same driver
+//! works with `rppal` on a Raspberry Pi or a microcontroller HAL:
+//!
+//! ```ignore
+//! use charta::integrations::gpio::{GpioBridge, PinMap};
+//!
+//! let map: PinMap = serde_json::from_str(r#"{
+//!     "inputs":  [{"pin": 17, "signal": "door_closed"},
+//!                 {"pin": 27, "signal": "estop_ok", "active_low": true}],
+//!     "outputs": [{"pin": 22, "coil": "allow_motor"}]
+//! }"#)?;
+//! let (inputs, outputs) = GpioBridge::open(
+//!     &map,
+//!     |pin| gpio.get(pin as u8).map(|p| p.into_input()),
+//!     |pin| gpio.get(pin as u8).map(|p| p.into_output()),
+//! )?;
+//! vm.add_input_source(inputs);
+//! vm.add_output_sink(outputs);
+//! ```
+//!
+//! The synchronous [`GpioInputs::sample`] and [`GpioOutputs::apply`] drive
+//! the blocking and embedded VMs.
+
+use crate::error::{Error, Result};
+use crate::io::{async_trait, CoilChanges, InputSource, OutputSink};
+use embedded_hal::digital::{InputPin, OutputPin};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// An input pin feeding a signal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputPinConfig {
+    /// Pin number passed to the pin opener
+    pub pin: u32,
+    /// Signal driven by the pin
+    pub signal: String,
+    /// Treat a low level as true
+    #[serde(default)]
+    pub active_low: bool,
+}
+
+/// An output pin driven by a coil
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputPinConfig {
+    /// Pin number passed to the pin opener
+    pub pin: u32,
+    /// Coil driving the pin
+    pub coil: String,
+    /// Drive the pin low when the coil is energised
+    #[serde(default)]
+    pub active_low: bool,
+}
+
+/// Pin mapping configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinMap {
+    /// Input pins
+    #[serde(default)]
+    pub inputs: Vec<InputPinConfig>,
+    /// Output pins
+    #[serde(default)]
+    pub outputs: Vec<OutputPinConfig>,
+}
+
+impl PinMap {
+    /// Create an empty mapping
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed `signal` from input `pin`
+    pub fn input(mut self, pin: u32, signal: &str) -> Self {
+        self.inputs.push(InputPinConfig {
+            pin,
+            signal: signal.to_string(),
+            active_low: false,
+        });
+        self
+    }
+
+    /// Feed `signal` from active-low input `pin`
+    pub fn input_active_low(mut self, pin: u32, signal: &str) -> Self {
+        self.inputs.push(InputPinConfig {
+            pin,
+            signal: signal.to_string(),
+            active_low: true,
+        });
+        self
+    }
+
+    /// Drive output `pin` from `coil`
+    pub fn output(mut self, coil: &str, pin: u32) -> Self {
+        self.outputs.push(OutputPinConfig {
+            pin,
+            coil: coil.to_string(),
+            active_low: false,
+        });
+        self
+    }
+
+    /// Drive active-low output `pin` from `coil`
+    pub fn output_active_low(mut self, coil: &str, pin: u32) -> Self {
+        self.outputs.push(OutputPinConfig {
+            pin,
+            coil: coil.to_string(),
+            active_low: true,
+        });
+        self
+    }
+}
+
+/// Opens the pins of a [`PinMap`]
+pub struct GpioBridge;
+
+impl GpioBridge {
+    /// Open every mapped pin with the given openers
+    ///
+    /// Fails with [`Error::Driver`] naming the first pin that could not be
+    /// opened.
+    pub fn open<I, O, EI, EO>(
+        map: &PinMap,
+        mut open_input: impl FnMut(u32) -> std::result::Result<I, EI>,
+        mut open_output: impl FnMut(u32) -> std::result::Result<O, EO>,
+    ) -> Result<(GpioInputs<I>, GpioOutputs<O>)>
+    where
+        I: InputPin,
+        O: OutputPin,
+        EI: Debug,
+        EO: Debug,
+    {
+        let mut inputs = GpioInputs::new();
+        for config in &map.inputs {
+            let pin = open_input(config.pin).map_err(|e| {
+                Error::Driver(format!("failed to open input pin {}: {:?}", config.pin, e))
+            })?;
+            inputs.add(&config.signal, pin, config.active_low);
+        }
+
+        let mut outputs = GpioOutputs::new();
+        for config in &map.outputs {
+            let pin = open_output(config.pin).map_err(|e| {
+                Error::Driver(format!("failed to open output pin {}: {:?}", config.pin, e))
+            })?;
+            outputs.add(&config.coil, pin, config.active_low);
+        }
+        Ok((inputs, outputs))
+    }
+}
+
+/// Input pins read into signals
+pub struct GpioInputs<P> {
+    pins: Vec<(String, P, bool)>,
+}
+
+impl<P: InputPin> GpioInputs<P> {
+    /// Create a driver with no pins
+    pub fn new() -> Self {
+        Self { pins: Vec::new() }
+    }
+
+    /// Feed `signal` from `pin`
+    pub fn add(&mut self, signal: &str, pin: P, active_low: bool) {
+        self.pins.push((signal.to_string(), pin, active_low));
+    }
+
+    /// Read every pin
+    ///
+    /// Pins that fail to read are left out, so their signals keep their
+    /// previous values.
+    pub fn sample(&mut self) -> HashMap<String, bool> {
+        let mut signals = HashMap::with_capacity(self.pins.len());
+        for (signal, pin, active_low) in &mut self.pins {
+            match pin.is_high() {
+                Ok(high) => {
+                    signals.insert(signal.clone(), high != *active_low);
+                }
+                Err(_e) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(signal = %signal, error = ?_e, "GPIO read failed");
+                }
+            }
+        }
+        signals
+    }
+}
+
+impl<P: InputPin> Default for GpioInputs<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<P: InputPin + Send> InputSource for GpioInputs<P> {
+    async fn read(&mut self) -> HashMap<String, bool> {
+        self.sample()
+    }
+}
+
+/// Output pins driven by coils
+pub struct GpioOutputs<P> {
+    pins: Vec<(String, P, bool)>,
+}
+
+impl<P: OutputPin> GpioOutputs<P> {
+    /// Create a driver with no pins
+    pub fn new() -> Self {
+        Self { pins: Vec::new() }
+    }
+
+    /// Drive `pin` from `coil`
+    pub fn add(&mut self, coil: &str, pin: P, active_low: bool) {
+        self.pins.push((coil.to_string(), pin, active_low));
+    }
+
+    /// Drive every pin whose coil appears in `coils`
+    ///
+    /// All pins are attempted; the first failure is returned.
+    pub fn apply(&mut self, coils: &HashMap<String, bool>) -> Result<()> {
+        let mut result = Ok(());
+        for (coil, pin, active_low) in &mut self.pins {
+            let Some(&energised) = coils.get(coil.as_str()) else {
+                continue;
+            };
+            let written = if energised != *active_low {
+                pin.set_high()
+            } else {
+                pin.set_low()
+            };
+            if let Err(e) = written {
+                if result.is_ok() {
+                    result = Err(Error::Driver(format!(
+                        "failed to drive pin for coil {}: {:?}",
+                        coil, e
+                    )));
+                }
+            }
+        }
+        result
+    }
+}
+
+impl<P: OutputPin> Default for GpioOutputs<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<P: OutputPin + Send> OutputSink for GpioOutputs<P> {
+    async fn write(&mut self, changes: &CoilChanges) -> Result<()> {
+        let coils: HashMap<String, bool> = changes
+            .iter()
+            .map(|(name, new)| (name.to_string(), new))
+            .collect();
+        self.apply(&coils)
+    }
+}
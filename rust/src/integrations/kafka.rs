@@ -0,0 +1,90 @@
+//! Kafka publisher for VM events
+//!
+//! Available with the `kafka` feature. [`KafkaPublisher`] is an
+//! [`EventPublisher`] for an [`EventSink`](super::event_sink::EventSink),
+//! producing each record to its mapped topic through `rdkafka`:
+//!
+//! ```no_run
+//! use charta::integrations::event_sink::{EventSink, TopicMapping};
+//! use charta::integrations::kafka::KafkaPublisher;
+//!
+//! # fn run(vm: &charta::ChartaVM) -> charta::Result<()> {
+//! let publisher = KafkaPublisher::new("broker-1:9092,broker-2:9092")?;
+//! EventSink::new(publisher, TopicMapping::new("charta.events")).attach(&vm.observer());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Records of a batch are enqueued together, then awaited, so librdkafka can
+//! group them into produce requests.
+
+use super::event_sink::{EventPublisher, Record};
+use crate::error::{Error, Result};
+use crate::io::async_trait;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use std::time::Duration;
+
+/// Event publisher producing to Kafka
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+    timeout: Duration,
+}
+
+impl KafkaPublisher {
+    /// Connect to a comma-separated list of bootstrap brokers
+    pub fn new(brokers: &str) -> Result<Self> {
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", brokers);
+        Self::from_config(&config)
+    }
+
+    /// Create a publisher from a full librdkafka configuration
+    pub fn from_config(config: &ClientConfig) -> Result<Self> {
+        let producer = config
+            .create()
+            .map_err(|e| Error::Driver(format!("Kafka producer: {}", e)))?;
+        Ok(Self {
+            producer,
+            timeout: Duration::from_secs(5),
+        })
+    }
+
+    /// Set how long a record may wait for space in the producer queue
+    pub fn queue_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[async_trait]
+impl EventPublisher for KafkaPublisher {
+    async fn publish(&mut self, records: &[Record]) -> Result<()> {
+        let mut deliveries = Vec::with_capacity(records.len());
+        for record in records {
+            let mut message: FutureRecord<'_, str, [u8]> =
+                FutureRecord::to(&record.topic).payload(record.payload.as_slice());
+            if let Some(key) = &record.key {
+                message = message.key(key.as_str());
+            }
+            let delivery = self
+                .producer
+                .send_result(message)
+                .map_err(|(e, _)| Error::Driver(format!("Kafka produce to '{}': {}", record.topic, e)))?;
+            deliveries.push((&record.topic, delivery));
+        }
+
+        for (topic, delivery) in deliveries {
+            match delivery.await {
+                Ok(Ok(_)) => {}
+                Ok(Err((e, _))) => {
+                    return Err(Error::Driver(format!("Kafka delivery to '{}': {}", topic, e)));
+                }
+                Err(_) => {
+                    return Err(Error::Driver(format!("Kafka delivery to '{}' cancelled", topic)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
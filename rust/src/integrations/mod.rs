@@ -0,0 +1,26 @@
+//! Ready-made bridges between Charta VMs and external systems
+//!
+//! Each integration is gated behind its own feature and plugs into the VM as
+//! [`io`](crate::io) drivers, or, for the broker sinks, as an event
+//! subscriber.
+
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+
+#[cfg(feature = "modbus")]
+pub mod modbus;
+
+#[cfg(feature = "webhook")]
+pub mod webhook;
+
+#[cfg(feature = "gpio")]
+pub mod gpio;
+
+#[cfg(any(feature = "kafka", feature = "nats"))]
+pub mod event_sink;
+
+#[cfg(feature = "kafka")]
+pub mod kafka;
+
+#[cfg(feature = "nats")]
+pub mod nats;
@@ -0,0 +1,237 @@
+//! Modbus TCP server for Charta VM
+//!
+//! Available with the `modbus` feature. Exposes VM coils as Modbus coils and
+//! VM signals as Modbus discrete inputs so existing HMI/SCADA tools can
+//! observe the VM. Signals may additionally be mapped to writable Modbus
+//! coils to let those tools drive it.
+//!
+//! ```no_run
+//! use charta::integrations::modbus::{ModbusMap, ModbusServer};
+//! use charta::ChartaVM;
+//!
+//! # async fn run(vm: &ChartaVM) -> charta::Result<()> {
+//! let map = ModbusMap::from_program(vm).await?.signal_coil(100, "operator_override");
+//! ModbusServer::new(vm, map).serve("0.0.0.0:502".parse().unwrap()).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Reads covering unmapped addresses return `false` for them; writes to an
+//! unmapped or read-only address fail with `IllegalDataAddress`.
+
+use crate::access::SignalWriter;
+use crate::error::{Error, Result};
+use crate::observer::ChartaObserver;
+use crate::vm::ChartaVM;
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio_modbus::prelude::{ExceptionCode, Request, Response};
+use tokio_modbus::server::tcp::{accept_tcp_connection, Server};
+use tokio_modbus::server::Service;
+
+/// VM point behind a Modbus coil
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoilTarget {
+    /// Read-only view of a VM coil
+    Coil(String),
+    /// Read/write view of a VM signal
+    Signal(String),
+}
+
+/// Address map between Modbus and VM points
+#[derive(Debug, Clone, Default)]
+pub struct ModbusMap {
+    /// Modbus coil address -> VM point
+    coils: HashMap<u16, CoilTarget>,
+    /// Modbus discrete input address -> VM signal
+    discrete_inputs: HashMap<u16, String>,
+}
+
+impl ModbusMap {
+    /// Create an empty map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map VM coils to Modbus coils and VM signals to discrete inputs, both
+    /// numbered from 0 in declaration order
+    pub async fn from_program(vm: &ChartaVM) -> Result<Self> {
+        let mut map = Self::new();
+        for (address, coil) in (0..=u16::MAX).zip(vm.coil_names().await?) {
+            map = map.coil(address, &coil);
+        }
+        for (address, signal) in (0..=u16::MAX).zip(vm.signal_names().await?) {
+            map = map.discrete_input(address, &signal);
+        }
+        Ok(map)
+    }
+
+    /// Expose a VM coil as a read-only Modbus coil
+    pub fn coil(mut self, address: u16, coil: &str) -> Self {
+        self.coils.insert(address, CoilTarget::Coil(coil.to_string()));
+        self
+    }
+
+    /// Expose a VM signal as a writable Modbus coil
+    pub fn signal_coil(mut self, address: u16, signal: &str) -> Self {
+        self.coils.insert(address, CoilTarget::Signal(signal.to_string()));
+        self
+    }
+
+    /// Expose a VM signal as a Modbus discrete input
+    pub fn discrete_input(mut self, address: u16, signal: &str) -> Self {
+        self.discrete_inputs.insert(address, signal.to_string());
+        self
+    }
+
+    /// Get the VM signals writable through this map
+    fn writable_signals(&self) -> Vec<&str> {
+        self.coils
+            .values()
+            .filter_map(|target| match target {
+                CoilTarget::Signal(signal) => Some(signal.as_str()),
+                CoilTarget::Coil(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Modbus TCP server backed by a VM
+#[derive(Clone)]
+pub struct ModbusServer {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    map: ModbusMap,
+    observer: ChartaObserver,
+    /// Writer scoped to the signals mapped to writable coils
+    writer: SignalWriter,
+}
+
+impl ModbusServer {
+    /// Create a server for a VM
+    pub fn new(vm: &ChartaVM, map: ModbusMap) -> Self {
+        let writer = vm.writer_for(&map.writable_signals());
+        Self {
+            inner: Arc::new(Inner {
+                observer: vm.observer(),
+                writer,
+                map,
+            }),
+        }
+    }
+
+    /// Accept Modbus TCP connections on `addr` until an I/O error occurs
+    pub async fn serve(self, addr: SocketAddr) -> Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        let server = Server::new(listener);
+        let new_service = move |_peer| Ok(Some(self.clone()));
+        let on_connected = |stream, peer| {
+            let new_service = new_service.clone();
+            async move { accept_tcp_connection(stream, peer, new_service) }
+        };
+        let on_process_error = |_e| {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %_e, "Modbus connection error");
+        };
+        server
+            .serve(&on_connected, on_process_error)
+            .await
+            .map_err(|e| Error::Driver(format!("Modbus server: {}", e)))
+    }
+}
+
+impl Inner {
+    async fn handle(&self, request: Request<'static>) -> std::result::Result<Response, ExceptionCode> {
+        match request {
+            Request::ReadCoils(address, count) => {
+                let coils = self.observer.get_all_coils().await.map_err(device_failure)?;
+                let signals = self.observer.get_all_signals().await.map_err(device_failure)?;
+                let values = addresses(address, count)?
+                    .map(|address| {
+                        let state = match self.map.coils.get(&address) {
+                            Some(CoilTarget::Coil(name)) => coils.get(name),
+                            Some(CoilTarget::Signal(name)) => signals.get(name),
+                            None => None,
+                        };
+                        state.copied().unwrap_or(false)
+                    })
+                    .collect();
+                Ok(Response::ReadCoils(values))
+            }
+            Request::ReadDiscreteInputs(address, count) => {
+                let signals = self.observer.get_all_signals().await.map_err(device_failure)?;
+                let values = addresses(address, count)?
+                    .map(|address| {
+                        self.map
+                            .discrete_inputs
+                            .get(&address)
+                            .and_then(|name| signals.get(name).copied())
+                            .unwrap_or(false)
+                    })
+                    .collect();
+                Ok(Response::ReadDiscreteInputs(values))
+            }
+            Request::WriteSingleCoil(address, value) => {
+                self.write(&[(address, value)]).await?;
+                Ok(Response::WriteSingleCoil(address, value))
+            }
+            Request::WriteMultipleCoils(address, values) => {
+                let count = u16::try_from(values.len()).map_err(|_| ExceptionCode::IllegalDataValue)?;
+                let writes: Vec<(u16, bool)> = addresses(address, count)?
+                    .zip(values.iter().copied())
+                    .collect();
+                self.write(&writes).await?;
+                Ok(Response::WriteMultipleCoils(address, count))
+            }
+            _ => Err(ExceptionCode::IllegalFunction),
+        }
+    }
+
+    /// Write Modbus coils through to their signals, all or nothing
+    async fn write(&self, writes: &[(u16, bool)]) -> std::result::Result<(), ExceptionCode> {
+        let mut values = HashMap::with_capacity(writes.len());
+        for (address, value) in writes {
+            match self.map.coils.get(address) {
+                Some(CoilTarget::Signal(name)) => {
+                    values.insert(name.clone(), *value);
+                }
+                _ => return Err(ExceptionCode::IllegalDataAddress),
+            }
+        }
+        self.writer
+            .set_signals(&values)
+            .await
+            .map_err(|_| ExceptionCode::ServerDeviceFailure)
+    }
+}
+
+/// Expand a Modbus address range, rejecting ranges past the end of the space
+fn addresses(start: u16, count: u16) -> std::result::Result<impl Iterator<Item = u16>, ExceptionCode> {
+    let end = u32::from(start) + u32::from(count);
+    if end > 1 << 16 {
+        return Err(ExceptionCode::IllegalDataAddress);
+    }
+    Ok((u32::from(start)..end).map(|address| address as u16))
+}
+
+fn device_failure(_: Error) -> ExceptionCode {
+    ExceptionCode::ServerDeviceFailure
+}
+
+impl Service for ModbusServer {
+    type Request = Request<'static>;
+    type Response = Response;
+    type Exception = ExceptionCode;
+    type Future = Pin<Box<dyn Future<Output = std::result::Result<Response, ExceptionCode>> + Send>>;
+
+    fn call(&self, request: Self::Request) -> Self::Future {
+        let inner = self.inner.clone();
+        Box::pin(async move { inner.handle(request).await })
+    }
+}
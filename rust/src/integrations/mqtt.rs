@@ -0,0 +1,213 @@
+//! MQTT bridge for Charta VM
+//!
+//! Available with the `mqtt` feature. Subscribes to mapped topics and feeds
+//! their payloads into signals, and publishes coil changes to mapped topics.
+//!
+//! ```no_run
+//! use charta::integrations::mqtt::{MqttBridge, MqttMapping};
+//! use charta::ChartaVM;
+//! use rumqttc::MqttOptions;
+//!
+//! # async fn run(mut vm: ChartaVM) -> charta::Result<()> {
+//! let mapping = MqttMapping::new()
+//!     .signal("plant/door/closed", "door_closed")
+//!     .coil("allow_entry", "plant/entry/allow");
+//! let (input, output) = MqttBridge::connect(MqttOptions::new("charta", "localhost", 1883), mapping);
+//! vm.add_input_source(input);
+//! vm.add_output_sink(output);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Payloads `true`/`false`, `1`/`0`, and `on`/`off` (any case) are accepted;
+//! coils are published as `true`/`false`.
+
+use crate::error::{Error, Result};
+use crate::io::{async_trait, CoilChanges, InputSource, OutputSink};
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Capacity of the MQTT client request channel
+const REQUEST_CAPACITY: usize = 64;
+
+/// Delay before polling again after a connection error
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Declarative mapping between MQTT topics and VM signals/coils
+#[derive(Debug, Clone)]
+pub struct MqttMapping {
+    /// Topic -> signal name
+    signals: HashMap<String, String>,
+    /// Coil name -> topic
+    coils: HashMap<String, String>,
+    qos: QoS,
+    retain: bool,
+}
+
+impl MqttMapping {
+    /// Create an empty mapping publishing at QoS 1 without retain
+    pub fn new() -> Self {
+        Self {
+            signals: HashMap::new(),
+            coils: HashMap::new(),
+            qos: QoS::AtLeastOnce,
+            retain: false,
+        }
+    }
+
+    /// Feed payloads published on `topic` into `signal`
+    pub fn signal(mut self, topic: &str, signal: &str) -> Self {
+        self.signals.insert(topic.to_string(), signal.to_string());
+        self
+    }
+
+    /// Publish changes of `coil` to `topic`
+    pub fn coil(mut self, coil: &str, topic: &str) -> Self {
+        self.coils.insert(coil.to_string(), topic.to_string());
+        self
+    }
+
+    /// Set the QoS used for subscriptions and publications
+    pub fn qos(mut self, qos: QoS) -> Self {
+        self.qos = qos;
+        self
+    }
+
+    /// Publish coil states as retained messages
+    pub fn retain(mut self, retain: bool) -> Self {
+        self.retain = retain;
+        self
+    }
+}
+
+impl Default for MqttMapping {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Connects a VM to an MQTT broker
+pub struct MqttBridge;
+
+impl MqttBridge {
+    /// Connect to the broker and return the input and output halves
+    ///
+    /// The connection is driven by a background task that resubscribes after
+    /// every reconnect; it stops when the [`MqttInput`] is dropped. Must be
+    /// called within a Tokio runtime.
+    pub fn connect(options: MqttOptions, mapping: MqttMapping) -> (MqttInput, MqttOutput) {
+        let (client, mut eventloop) = AsyncClient::new(options, REQUEST_CAPACITY);
+        let pending = Arc::new(Mutex::new(HashMap::new()));
+
+        let task = {
+            let client = client.clone();
+            let pending = pending.clone();
+            let signals = mapping.signals.clone();
+            let qos = mapping.qos;
+            tokio::spawn(async move {
+                loop {
+                    match eventloop.poll().await {
+                        Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                            for topic in signals.keys() {
+                                let _ = client.try_subscribe(topic.as_str(), qos);
+                            }
+                        }
+                        Ok(Event::Incoming(Packet::Publish(publish))) => {
+                            let Some(signal) = signals.get(&publish.topic) else {
+                                continue;
+                            };
+                            match parse_payload(&publish.payload) {
+                                Some(value) => {
+                                    pending
+                                        .lock()
+                                        .unwrap_or_else(|e| e.into_inner())
+                                        .insert(signal.clone(), value);
+                                }
+                                None => {
+                                    #[cfg(feature = "tracing")]
+                                    tracing::warn!(topic = %publish.topic, "ignoring non-boolean MQTT payload");
+                                }
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(_e) => {
+                            #[cfg(feature = "tracing")]
+                            tracing::warn!(error = %_e, "MQTT connection error");
+                            tokio::time::sleep(RECONNECT_DELAY).await;
+                        }
+                    }
+                }
+            })
+        };
+
+        let input = MqttInput { pending, task };
+        let output = MqttOutput {
+            client,
+            coils: mapping.coils,
+            qos: mapping.qos,
+            retain: mapping.retain,
+        };
+        (input, output)
+    }
+}
+
+/// Input half of an MQTT bridge
+///
+/// Each read returns the latest value received per signal since the previous
+/// read, so signals set by other means are not overwritten every cycle.
+pub struct MqttInput {
+    pending: Arc<Mutex<HashMap<String, bool>>>,
+    task: JoinHandle<()>,
+}
+
+#[async_trait]
+impl InputSource for MqttInput {
+    async fn read(&mut self) -> HashMap<String, bool> {
+        std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl Drop for MqttInput {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Output half of an MQTT bridge
+pub struct MqttOutput {
+    client: AsyncClient,
+    /// Coil name -> topic
+    coils: HashMap<String, String>,
+    qos: QoS,
+    retain: bool,
+}
+
+#[async_trait]
+impl OutputSink for MqttOutput {
+    async fn write(&mut self, changes: &CoilChanges) -> Result<()> {
+        for (coil, state) in changes.iter() {
+            let Some(topic) = self.coils.get(coil) else {
+                continue;
+            };
+            let payload = if state { "true" } else { "false" };
+            self.client
+                .publish(topic.as_str(), self.qos, self.retain, payload)
+                .await
+                .map_err(|e| Error::Driver(format!("MQTT publish to '{}': {}", topic, e)))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a boolean MQTT payload
+pub fn parse_payload(payload: &[u8]) -> Option<bool> {
+    let text = std::str::from_utf8(payload).ok()?.trim();
+    match text.to_ascii_lowercase().as_str() {
+        "true" | "1" | "on" => Some(true),
+        "false" | "0" | "off" => Some(false),
+        _ => None,
+    }
+}
@@ -0,0 +1,72 @@
+//! NATS publisher for VM events
+//!
+//! Available with the `nats` feature. [`NatsPublisher`] is an
+//! [`EventPublisher`] for an [`EventSink`](super::event_sink::EventSink),
+//! publishing each record to its mapped subject:
+//!
+//! ```no_run
+//! use charta::integrations::event_sink::{EventSink, TopicMapping};
+//! use charta::integrations::nats::NatsPublisher;
+//!
+//! # async fn run(vm: &charta::ChartaVM) -> charta::Result<()> {
+//! let publisher = NatsPublisher::connect("nats://localhost:4222").await?;
+//! let mapping = TopicMapping::new("charta.events").coil("safety/**", "charta.safety");
+//! EventSink::new(publisher, mapping).attach(&vm.observer());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Record keys are sent as the `Charta-Key` header. Each batch is flushed
+//! before the next one is published.
+
+use super::event_sink::{EventPublisher, Record};
+use crate::error::{Error, Result};
+use crate::io::async_trait;
+use async_nats::{Client, HeaderMap};
+
+/// Header carrying a record's key
+pub const KEY_HEADER: &str = "Charta-Key";
+
+/// Event publisher for NATS subjects
+pub struct NatsPublisher {
+    client: Client,
+}
+
+impl NatsPublisher {
+    /// Connect to a NATS server
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = async_nats::connect(url)
+            .await
+            .map_err(|e| Error::Driver(format!("NATS connect to '{}': {}", url, e)))?;
+        Ok(Self::new(client))
+    }
+
+    /// Publish through an existing client
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl EventPublisher for NatsPublisher {
+    async fn publish(&mut self, records: &[Record]) -> Result<()> {
+        for record in records {
+            let subject = record.topic.clone();
+            let payload = record.payload.clone().into();
+            let published = match &record.key {
+                Some(key) => {
+                    let mut headers = HeaderMap::new();
+                    headers.insert(KEY_HEADER, key.as_str());
+                    self.client.publish_with_headers(subject, headers, payload).await
+                }
+                None => self.client.publish(subject, payload).await,
+            };
+            published
+                .map_err(|e| Error::Driver(format!("NATS publish to '{}': {}", record.topic, e)))?;
+        }
+        self.client
+            .flush()
+            .await
+            .map_err(|e| Error::Driver(format!("NATS flush: {}", e)))
+    }
+}
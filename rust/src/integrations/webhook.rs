@@ -0,0 +1,255 @@
+//! Webhook notifier for coil transitions
+//!
+//! Available with the `webhook` feature. [`WebhookNotifier`] is an
+//! [`OutputSink`] that POSTs a JSON [`WebhookPayload`] to every configured URL
+//! when a selected coil changes. Deliveries run on a background task so the
+//! scan loop never waits on the network; failed deliveries are retried with
+//! exponential backoff and finally handed to the dead-letter callback.
+//!
+//! ```no_run
+//! use charta::integrations::webhook::WebhookNotifier;
+//! use charta::ChartaVM;
+//!
+//! # fn run(vm: &mut ChartaVM) {
+//! let notifier = WebhookNotifier::builder()
+//!     .url("https://workflow.example.com/hooks/charta")
+//!     .coil("allow_*")
+//!     .max_retries(5)
+//!     .on_dead_letter(|letter| eprintln!("undeliverable: {:?}", letter))
+//!     .build();
+//! vm.add_output_sink(notifier);
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::io::{async_trait, CoilChanges, OutputSink};
+use crate::pattern::Pattern;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+/// Callback function type for undeliverable notifications
+pub type DeadLetterCallback = Arc<dyn Fn(&DeadLetter) + Send + Sync>;
+
+/// JSON body posted for each coil transition
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    /// Coil name
+    pub coil: String,
+    /// State before the cycle
+    pub old: bool,
+    /// State after the cycle
+    pub new: bool,
+    /// Cycle number
+    pub cycle: u64,
+    /// Hash identifying the program
+    pub program_id: Option<String>,
+    /// When the cycle completed, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+/// A notification that could not be delivered
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    /// Target URL
+    pub url: String,
+    /// Notification body
+    pub payload: WebhookPayload,
+    /// Delivery attempts made
+    pub attempts: u32,
+    /// Last delivery error
+    pub error: String,
+}
+
+/// Delivery settings
+#[derive(Clone)]
+struct Delivery {
+    urls: Vec<String>,
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    dead_letter: Option<DeadLetterCallback>,
+}
+
+/// Builder for [`WebhookNotifier`]
+pub struct WebhookNotifierBuilder {
+    delivery: Delivery,
+    coils: Vec<Pattern>,
+    timeout: Duration,
+}
+
+impl WebhookNotifierBuilder {
+    /// Add a target URL
+    pub fn url(mut self, url: &str) -> Self {
+        self.delivery.urls.push(url.to_string());
+        self
+    }
+
+    /// Notify on coils matching a name or pattern
+    ///
+    /// Without any coil selected, every coil is notified.
+    pub fn coil(mut self, pattern: &str) -> Self {
+        self.coils.push(Pattern::new(pattern));
+        self
+    }
+
+    /// Set the number of retries after the first failed attempt
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.delivery.max_retries = retries;
+        self
+    }
+
+    /// Set the delay before the first retry and the upper bound it doubles to
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.delivery.initial_backoff = initial;
+        self.delivery.max_backoff = max;
+        self
+    }
+
+    /// Set the per-request timeout
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Register a callback for notifications that exhausted their retries
+    pub fn on_dead_letter<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&DeadLetter) + Send + Sync + 'static,
+    {
+        self.delivery.dead_letter = Some(Arc::new(callback));
+        self
+    }
+
+    /// Build the notifier and start its delivery task
+    ///
+    /// Must be called within a Tokio runtime. The task stops once the
+    /// notifier is dropped and its queue has drained.
+    pub fn build(self) -> WebhookNotifier {
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .unwrap_or_default();
+        let (queue, rx) = mpsc::unbounded_channel();
+        tokio::spawn(deliver_all(client, self.delivery, rx));
+        WebhookNotifier {
+            coils: self.coils,
+            queue,
+        }
+    }
+}
+
+/// Output sink posting coil transitions to webhooks
+pub struct WebhookNotifier {
+    coils: Vec<Pattern>,
+    queue: mpsc::UnboundedSender<WebhookPayload>,
+}
+
+impl WebhookNotifier {
+    /// Create a builder with 3 retries, 500 ms initial backoff capped at
+    /// 30 s, and a 10 s request timeout
+    pub fn builder() -> WebhookNotifierBuilder {
+        WebhookNotifierBuilder {
+            delivery: Delivery {
+                urls: Vec::new(),
+                max_retries: 3,
+                initial_backoff: Duration::from_millis(500),
+                max_backoff: Duration::from_secs(30),
+                dead_letter: None,
+            },
+            coils: Vec::new(),
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    fn selected(&self, coil: &str) -> bool {
+        self.coils.is_empty() || self.coils.iter().any(|pattern| pattern.matches(coil))
+    }
+}
+
+#[async_trait]
+impl OutputSink for WebhookNotifier {
+    async fn write(&mut self, changes: &CoilChanges) -> Result<()> {
+        let timestamp = changes
+            .at
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0);
+
+        let mut coils: Vec<(&String, &(bool, bool))> = changes
+            .changes
+            .iter()
+            .filter(|(coil, _)| self.selected(coil))
+            .collect();
+        coils.sort_by_key(|(coil, _)| *coil);
+
+        for (coil, (old, new)) in coils {
+            // The delivery task only stops once every sender is gone
+            let _ = self.queue.send(WebhookPayload {
+                coil: coil.clone(),
+                old: *old,
+                new: *new,
+                cycle: changes.cycle,
+                program_id: changes.program_id.clone(),
+                timestamp,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Deliver queued payloads in order until the queue closes
+async fn deliver_all(
+    client: reqwest::Client,
+    delivery: Delivery,
+    mut rx: mpsc::UnboundedReceiver<WebhookPayload>,
+) {
+    while let Some(payload) = rx.recv().await {
+        for url in &delivery.urls {
+            deliver(&client, &delivery, url, &payload).await;
+        }
+    }
+}
+
+/// Deliver one payload to one URL, retrying with exponential backoff
+async fn deliver(
+    client: &reqwest::Client,
+    delivery: &Delivery,
+    url: &str,
+    payload: &WebhookPayload,
+) {
+    let mut backoff = delivery.initial_backoff;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let error = match client.post(url).json(payload).send().await {
+            Ok(response) if response.status().is_success() => return,
+            Ok(response) => format!("HTTP {}", response.status()),
+            Err(e) => e.to_string(),
+        };
+
+        if attempts > delivery.max_retries {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                url,
+                coil = %payload.coil,
+                attempts,
+                error = %error,
+                "webhook undeliverable"
+            );
+            if let Some(dead_letter) = &delivery.dead_letter {
+                dead_letter(&DeadLetter {
+                    url: url.to_string(),
+                    payload: payload.clone(),
+                    attempts,
+                    error,
+                });
+            }
+            return;
+        }
+
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(delivery.max_backoff);
+    }
+}
@@ -0,0 +1,82 @@
+//! I/O drivers for Charta VM
+//!
+//! Formalises the read-inputs → scan → write-outputs pattern. Register an
+//! [`InputSource`] with [`ChartaVM::add_input_source`](crate::ChartaVM::add_input_source)
+//! and it is polled at the start of every cycle to populate signals; register
+//! an [`OutputSink`] with [`ChartaVM::add_output_sink`](crate::ChartaVM::add_output_sink)
+//! and it receives the coils that changed after every cycle.
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+pub use async_trait::async_trait;
+
+/// Source of input signals polled at the start of every cycle
+///
+/// ```no_run
+/// use charta::io::{async_trait, InputSource};
+/// use std::collections::HashMap;
+///
+/// struct Door;
+///
+/// #[async_trait]
+/// impl InputSource for Door {
+///     async fn read(&mut self) -> HashMap<String, bool> {
+///         HashMap::from([("door_closed".to_string(), true)])
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait InputSource: Send {
+    /// Read the current values of the signals this source provides
+    async fn read(&mut self) -> HashMap<String, bool>;
+}
+
+/// Coils that changed during one cycle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoilChanges {
+    /// Cycle number
+    pub cycle: u64,
+    /// Hash identifying the program that produced the changes
+    pub program_id: Option<String>,
+    /// When the cycle completed (serialized as `at_ms`)
+    #[serde(rename = "at_ms", with = "crate::serde_time::unix_millis")]
+    pub at: SystemTime,
+    /// Coil name -> (old state, new state)
+    pub changes: HashMap<String, (bool, bool)>,
+}
+
+impl CoilChanges {
+    /// Check whether no coil changed
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Get the number of changed coils
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Get the new state of a coil, if it changed
+    pub fn get(&self, name: &str) -> Option<bool> {
+        self.changes.get(name).map(|(_, new)| *new)
+    }
+
+    /// Iterate over changed coils and their new states
+    pub fn iter(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.changes.iter().map(|(name, (_, new))| (name.as_str(), *new))
+    }
+}
+
+/// Destination for coil changes, written after every cycle
+///
+/// Called once per cycle, even when nothing changed, so sinks can use it as
+/// a heartbeat. Errors are passed to the VM fault hook with
+/// [`ErrorPhase::Driver`](crate::ErrorPhase::Driver) and do not fail the cycle.
+#[async_trait]
+pub trait OutputSink: Send {
+    /// Push the changes of one cycle to the external system
+    async fn write(&mut self, changes: &CoilChanges) -> Result<()>;
+}
@@ -0,0 +1,457 @@
+//! SDK-side model of Charta IR
+//!
+//! Mirrors the IR JSON structure so the SDK can inspect loaded programs
+//! (rungs, guards, actions) independently of the VM's internal representation.
+
+#[cfg(feature = "std")]
+use crate::error::{Error, Result};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::filter::InputFilter;
+use crate::value::{derived_signal, CompareOp, Expr, Value, ValueType};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// A Charta IR program
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Program {
+    /// IR format version
+    #[serde(default)]
+    pub version: String,
+    /// IR files included into this program before loading
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<String>,
+    /// Program module
+    pub module: Module,
+}
+
+/// A program module
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Module {
+    /// Module name
+    pub name: String,
+    /// Declared input signals
+    #[serde(default)]
+    pub signals: Vec<SignalDecl>,
+    /// Declared output coils
+    #[serde(default)]
+    pub coils: Vec<CoilDecl>,
+    /// Qualified names (`module.name`) this module reads from other
+    /// modules when linked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<String>,
+    /// Local signals and coils other modules may import
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exports: Vec<String>,
+    /// Declared registers, written by `move` actions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub registers: Vec<RegisterDecl>,
+    /// Rungs in scan order
+    #[serde(default)]
+    pub rungs: Vec<Rung>,
+}
+
+/// Signal declaration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignalDecl {
+    /// Signal name
+    pub name: String,
+    /// Value type; boolean unless declared
+    #[serde(default, rename = "type", skip_serializing_if = "ValueType::is_bool")]
+    pub value_type: ValueType,
+    /// Conditioning applied before rung logic reads the signal
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<InputFilter>,
+    /// Value on load, instead of false or the type's default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial: Option<Value>,
+    /// Optional descriptive metadata
+    #[serde(flatten)]
+    pub meta: Metadata,
+}
+
+/// Register declaration
+///
+/// Registers hold a typed value that persists across cycles. `move` actions
+/// write them; `compare`, `within_range`, and expressions read them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegisterDecl {
+    /// Register name
+    pub name: String,
+    /// Value type; boolean unless declared
+    #[serde(default, rename = "type", skip_serializing_if = "ValueType::is_bool")]
+    pub value_type: ValueType,
+    /// Optional descriptive metadata
+    #[serde(flatten)]
+    pub meta: Metadata,
+}
+
+/// Coil declaration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoilDecl {
+    /// Coil name
+    pub name: String,
+    /// State on load, read by seal-in rungs before the first scan drives it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial: Option<bool>,
+    /// Whether the state survives reloads and restarts (see
+    /// [`crate::persistence`])
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub retained: bool,
+    /// Optional descriptive metadata
+    #[serde(flatten)]
+    pub meta: Metadata,
+}
+
+/// Human-readable metadata attached to a signal or coil
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// Description of what the point represents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Free-form tags (e.g. `safety`, `compliance`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Engineering unit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// Whether the coil is safety-classified (see [`crate::safety`]);
+    /// ignored on signals
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub safety: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl Metadata {
+    /// Check whether the metadata carries a tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+/// A rung: a guard and the actions taken when it holds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rung {
+    /// Rung name
+    pub name: String,
+    /// Guard condition
+    pub guard: Guard,
+    /// Actions driven by the guard
+    #[serde(default)]
+    pub actions: Vec<Action>,
+    /// Scan priority; higher priorities scan first (see [`crate::order`])
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub priority: i32,
+    /// Scan group; ungrouped rungs scan in every cycle (see
+    /// [`crate::scan_group`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Where the rung was written, if the compiler recorded it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceLocation>,
+}
+
+fn is_zero(priority: &i32) -> bool {
+    *priority == 0
+}
+
+/// Position in the source a program was compiled from
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SourceLocation {
+    /// Source file, as given to the compiler
+    pub file: String,
+    /// Line number, starting at 1
+    pub line: u32,
+    /// Column number, starting at 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<u32>,
+}
+
+impl core::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ":{}", column)?;
+        }
+        Ok(())
+    }
+}
+
+/// Contact type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ContactType {
+    /// Normally open: passes when the operand is true
+    #[default]
+    #[serde(rename = "NO")]
+    NormallyOpen,
+    /// Normally closed: passes when the operand is false
+    #[serde(rename = "NC")]
+    NormallyClosed,
+}
+
+/// Guard expression
+///
+/// `and`/`or` nodes accept either a `left`/`right` pair or an `operands` list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Guard {
+    /// Contact on a signal or coil
+    Contact {
+        /// Signal or coil name
+        name: String,
+        /// Contact type
+        #[serde(default)]
+        contact_type: ContactType,
+    },
+    /// Conjunction
+    And {
+        /// Left operand
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        left: Option<Box<Guard>>,
+        /// Right operand
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        right: Option<Box<Guard>>,
+        /// Operand list
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        operands: Vec<Guard>,
+    },
+    /// Disjunction
+    Or {
+        /// Left operand
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        left: Option<Box<Guard>>,
+        /// Right operand
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        right: Option<Box<Guard>>,
+        /// Operand list
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        operands: Vec<Guard>,
+    },
+    /// Negation
+    Not {
+        /// Negated operand
+        operand: Box<Guard>,
+    },
+    /// Comparison of a typed signal or expression against a constant
+    Compare {
+        /// Typed signal name, unless `expr` is given
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        name: String,
+        /// Expression compared in place of a signal
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expr: Option<Expr>,
+        /// Operator, applied as `signal op value`
+        op: CompareOp,
+        /// Constant to compare against
+        value: Value,
+    },
+    /// Inclusive range check of a typed signal or expression
+    WithinRange {
+        /// Typed signal name, unless `expr` is given
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        name: String,
+        /// Expression checked in place of a signal
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expr: Option<Expr>,
+        /// Lower bound
+        min: Value,
+        /// Upper bound
+        max: Value,
+    },
+}
+
+/// Rung action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Action {
+    /// Drive a coil with the guard result
+    Energise {
+        /// Target coil
+        coil: String,
+    },
+    /// Write the value of an expression into a register while the guard holds
+    Move {
+        /// Target register
+        dest: String,
+        /// Value to write
+        expr: Expr,
+    },
+}
+
+impl Program {
+    /// Parse a program from IR JSON
+    #[cfg(feature = "std")]
+    pub fn from_json(ir_json: &str) -> Result<Self> {
+        serde_json::from_str(ir_json).map_err(|e| Error::IRLoad(e.to_string()))
+    }
+}
+
+impl Rung {
+    /// Quote the rung name for diagnostics, with its source location if
+    /// known: `'operation_gate' (program.charta:42)`
+    pub fn describe(&self) -> String {
+        describe_rung(&self.name, self.source.as_ref())
+    }
+
+    /// Get the coils this rung drives
+    pub fn target_coils(&self) -> impl Iterator<Item = &str> {
+        self.actions.iter().filter_map(|action| match action {
+            Action::Energise { coil } => Some(coil.as_str()),
+            Action::Move { .. } => None,
+        })
+    }
+
+    /// Describe the rung's structure for introspection
+    pub fn info(&self) -> RungInfo {
+        RungInfo {
+            name: self.name.clone(),
+            target_coils: self.target_coils().map(String::from).collect(),
+            guard: self.guard.clone(),
+            priority: self.priority,
+            group: self.group.clone(),
+            source: self.source.clone(),
+        }
+    }
+}
+
+impl Guard {
+    /// Get the direct operands of this node
+    pub fn operands(&self) -> Vec<&Guard> {
+        match self {
+            Guard::Contact { .. } | Guard::Compare { .. } | Guard::WithinRange { .. } => {
+                Vec::new()
+            }
+            Guard::And { left, right, operands } | Guard::Or { left, right, operands } => left
+                .iter()
+                .chain(right.iter())
+                .map(|guard| guard.as_ref())
+                .chain(operands.iter())
+                .collect(),
+            Guard::Not { operand } => vec![operand.as_ref()],
+        }
+    }
+
+    /// Get the signal or expression a `compare` or `within_range` node tests
+    pub fn subject(&self) -> Option<Expr> {
+        match self {
+            Guard::Compare { name, expr, .. } | Guard::WithinRange { name, expr, .. } => {
+                Some(expr.clone().unwrap_or_else(|| Expr::Signal { name: name.clone() }))
+            }
+            _ => None,
+        }
+    }
+
+    /// Describe the condition a `compare` or `within_range` node tests,
+    /// e.g. `amount > 10000` or `(limit - exposure) in [0, 500]`
+    pub fn condition(&self) -> Option<String> {
+        let subject = self.subject()?;
+        match self {
+            Guard::Compare { op, value, .. } => {
+                Some(alloc::format!("{} {} {}", subject, op, value))
+            }
+            Guard::WithinRange { min, max, .. } => {
+                Some(alloc::format!("{} in [{}, {}]", subject, min, max))
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the derived signal a `compare` or `within_range` node is lowered to
+    pub fn derived_signal(&self) -> Option<String> {
+        self.condition().map(|condition| derived_signal(&condition))
+    }
+
+    /// Evaluate the guard, resolving contact names with `lookup`
+    ///
+    /// `compare` and `within_range` nodes look up their
+    /// [derived signal](Self::derived_signal).
+    pub fn evaluate<F>(&self, lookup: &F) -> bool
+    where
+        F: Fn(&str) -> bool,
+    {
+        match self {
+            Guard::Contact { name, contact_type } => match contact_type {
+                ContactType::NormallyOpen => lookup(name),
+                ContactType::NormallyClosed => !lookup(name),
+            },
+            Guard::And { .. } => self.operands().iter().all(|guard| guard.evaluate(lookup)),
+            Guard::Or { .. } => self.operands().iter().any(|guard| guard.evaluate(lookup)),
+            Guard::Not { operand } => !operand.evaluate(lookup),
+            Guard::Compare { .. } | Guard::WithinRange { .. } => {
+                lookup(&self.derived_signal().unwrap_or_default())
+            }
+        }
+    }
+}
+
+/// Quote a rung name for diagnostics, with its source location if known
+pub(crate) fn describe_rung(name: &str, source: Option<&SourceLocation>) -> String {
+    match source {
+        Some(source) => alloc::format!("'{}' ({})", name, source),
+        None => alloc::format!("'{}'", name),
+    }
+}
+
+/// Structure of a rung, for tools introspecting a loaded program
+///
+/// The guard serializes in the IR's guard syntax.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RungInfo {
+    /// Rung name
+    pub name: String,
+    /// Coils the rung energises
+    pub target_coils: Vec<String>,
+    /// Guard condition
+    pub guard: Guard,
+    /// Scan priority
+    pub priority: i32,
+    /// Scan group, if any
+    pub group: Option<String>,
+    /// Where the rung was written, if known
+    pub source: Option<SourceLocation>,
+}
+
+/// Result of evaluating one rung during a scan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RungEvaluation {
+    /// Rung name
+    pub rung: String,
+    /// Whether the guard held
+    pub energised: bool,
+    /// Where the rung was written, if known
+    pub source: Option<SourceLocation>,
+}
+
+#[cfg(feature = "std")]
+impl Program {
+    /// Evaluate every rung in scan order against a state snapshot
+    ///
+    /// `state` maps signal and coil names to values. Coils driven by earlier
+    /// rungs are visible to later rungs, mirroring the VM's scan semantics.
+    pub fn evaluate_rungs(&self, state: &HashMap<String, bool>) -> Vec<RungEvaluation> {
+        let mut state = state.clone();
+        let mut evaluations = Vec::with_capacity(self.module.rungs.len());
+
+        for rung in &self.module.rungs {
+            let energised = rung
+                .guard
+                .evaluate(&|name: &str| state.get(name).copied().unwrap_or(false));
+            for coil in rung.target_coils() {
+                state.insert(coil.to_string(), energised);
+            }
+            evaluations.push(RungEvaluation {
+                rung: rung.name.clone(),
+                energised,
+                source: rung.source.clone(),
+            });
+        }
+
+        evaluations
+    }
+}
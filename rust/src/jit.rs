@@ -0,0 +1,212 @@
+//! Native code backend for the engine
+//!
+//! With the `jit` feature, [`Engine::compiled`](crate::engine::Engine::compiled)
+//! also compiles the whole rung set to one native function via Cranelift.
+//! Full scans then call it directly; incremental scans and hosts Cranelift
+//! does not support fall back to the bytecode interpreter.
+//!
+//! This speeds up scans run by the engine: those of a compiling
+//! [embedded VM](crate::embedded::ChartaVM), direct
+//! [`Engine`](crate::engine::Engine) use, and
+//! `ChartaVM::evaluate_batch_parallel`, whose engine is always compiled
+//! under this feature. [`ChartaVM`](crate::ChartaVM) cycles run in the core
+//! VM and are unaffected.
+//!
+//! ```
+//! use charta::engine::Engine;
+//! use charta::ir::Program;
+//!
+//! # let ir = r#"{"module": {"name": "m", "signals": [{"name": "a"}], "coils": [{"name": "y"}],
+//! #   "rungs": [{"name": "r", "guard": {"type": "contact", "name": "a"},
+//! #   "actions": [{"type": "energise", "coil": "y"}]}]}}"#;
+//! # let program: Program = serde_json::from_str(ir).unwrap();
+//! let engine = Engine::compiled(&program);
+//! assert_eq!(engine.is_native(), charta::jit::is_available());
+//! ```
+
+use cranelift_codegen::ir::{types, AbiParam, InstBuilder, MemFlags, Value};
+use cranelift_codegen::settings::{self, Configurable};
+use cranelift_codegen::isa::OwnedTargetIsa;
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{Linkage, Module};
+use std::fmt;
+
+use crate::engine::Node;
+
+/// Signature of a compiled scan: signal values in, coil values in and out
+type ScanFn = unsafe extern "C" fn(*const bool, *mut bool);
+
+/// Whether Cranelift supports the host
+pub fn is_available() -> bool {
+    host_isa().is_ok()
+}
+
+fn host_isa() -> Result<OwnedTargetIsa, String> {
+    let mut flags = settings::builder();
+    flags
+        .set("opt_level", "speed")
+        .map_err(|e| e.to_string())?;
+    let isa = cranelift_native::builder()?;
+    isa.finish(settings::Flags::new(flags))
+        .map_err(|e| e.to_string())
+}
+
+/// A rung set compiled to native code
+pub(crate) struct NativeScan {
+    module: Option<JITModule>,
+    function: ScanFn,
+}
+
+// The generated code and its memory are immutable once finalized
+unsafe impl Send for NativeScan {}
+unsafe impl Sync for NativeScan {}
+
+impl fmt::Debug for NativeScan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeScan").finish_non_exhaustive()
+    }
+}
+
+impl NativeScan {
+    /// Compile rungs, given as guard and driven coil indices, in scan order
+    pub(crate) fn compile<'a>(
+        rungs: impl Iterator<Item = (&'a Node, &'a [usize])>,
+    ) -> Result<Self, String> {
+        let builder = JITBuilder::with_isa(host_isa()?, cranelift_module::default_libcall_names());
+        let mut module = JITModule::new(builder);
+        let pointer = module.target_config().pointer_type();
+
+        let mut context = module.make_context();
+        context.func.signature.params.push(AbiParam::new(pointer));
+        context.func.signature.params.push(AbiParam::new(pointer));
+
+        let mut function_context = FunctionBuilderContext::new();
+        {
+            let mut builder = FunctionBuilder::new(&mut context.func, &mut function_context);
+            let entry = builder.create_block();
+            builder.append_block_params_for_function_params(entry);
+            builder.switch_to_block(entry);
+            let signals = builder.block_params(entry)[0];
+            let coils = builder.block_params(entry)[1];
+
+            for (guard, driven) in rungs {
+                let energised = emit(&mut builder, guard, signals, coils)?;
+                for &coil in driven {
+                    builder
+                        .ins()
+                        .store(MemFlags::trusted(), energised, coils, offset(coil)?);
+                }
+            }
+            builder.ins().return_(&[]);
+            builder.seal_all_blocks();
+            builder.finalize();
+        }
+
+        let id = module
+            .declare_function("charta_scan", Linkage::Local, &context.func.signature)
+            .map_err(|e| e.to_string())?;
+        module
+            .define_function(id, &mut context)
+            .map_err(|e| e.to_string())?;
+        module.clear_context(&mut context);
+        module.finalize_definitions().map_err(|e| e.to_string())?;
+
+        let code = module.get_finalized_function(id);
+        // SAFETY: the function was declared with exactly this signature
+        let function = unsafe { std::mem::transmute::<*const u8, ScanFn>(code) };
+        Ok(Self {
+            module: Some(module),
+            function,
+        })
+    }
+
+    /// Run a full scan
+    ///
+    /// # Safety
+    ///
+    /// `signals` and `coils` must hold at least as many values as the
+    /// program compiled into this scan declares.
+    pub(crate) unsafe fn run(&self, signals: &[bool], coils: &mut [bool]) {
+        (self.function)(signals.as_ptr(), coils.as_mut_ptr());
+    }
+}
+
+impl Drop for NativeScan {
+    fn drop(&mut self) {
+        if let Some(module) = self.module.take() {
+            // SAFETY: the function pointer is dropped along with self
+            unsafe { module.free_memory() };
+        }
+    }
+}
+
+fn offset(index: usize) -> Result<i32, String> {
+    i32::try_from(index).map_err(|_| format!("index {} out of range", index))
+}
+
+/// Emit a guard, producing an `i8` that is 0 or 1
+fn emit(
+    builder: &mut FunctionBuilder,
+    node: &Node,
+    signals: Value,
+    coils: Value,
+) -> Result<Value, String> {
+    Ok(match node {
+        Node::Signal {
+            index,
+            normally_closed,
+        } => {
+            let value = builder
+                .ins()
+                .load(types::I8, MemFlags::trusted(), signals, offset(*index)?);
+            contact(builder, value, *normally_closed)
+        }
+        Node::Coil {
+            index,
+            normally_closed,
+        } => {
+            let value = builder
+                .ins()
+                .load(types::I8, MemFlags::trusted(), coils, offset(*index)?);
+            contact(builder, value, *normally_closed)
+        }
+        Node::And(operands) | Node::Or(operands) if operands.is_empty() => {
+            let identity = matches!(node, Node::And(_)) as i64;
+            builder.ins().iconst(types::I8, identity)
+        }
+        Node::And(operands) | Node::Or(operands) => {
+            // Every operand but the last may short-circuit to the merge block
+            let is_and = matches!(node, Node::And(_));
+            let merge = builder.create_block();
+            let result = builder.append_block_param(merge, types::I8);
+            let (last, rest) = operands.split_last().expect("operands are non-empty");
+            for operand in rest {
+                let value = emit(builder, operand, signals, coils)?;
+                let next = builder.create_block();
+                if is_and {
+                    builder.ins().brif(value, next, &[], merge, &[value]);
+                } else {
+                    builder.ins().brif(value, merge, &[value], next, &[]);
+                }
+                builder.switch_to_block(next);
+            }
+            let value = emit(builder, last, signals, coils)?;
+            builder.ins().jump(merge, &[value]);
+            builder.switch_to_block(merge);
+            result
+        }
+        Node::Not(operand) => {
+            let value = emit(builder, operand, signals, coils)?;
+            builder.ins().bxor_imm(value, 1)
+        }
+    })
+}
+
+fn contact(builder: &mut FunctionBuilder, value: Value, normally_closed: bool) -> Value {
+    if normally_closed {
+        builder.ins().bxor_imm(value, 1)
+    } else {
+        value
+    }
+}
@@ -30,14 +30,240 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! Without the default `std` feature, only [`ir`], [`engine`], and the
+//! synchronous [`embedded`] VM are available (enable `no_std` for its `hashbrown` maps).
+//!
+//! On `wasm32-unknown-unknown` the crate builds without tokio's fs, timer,
+//! and spawn support, so `VmManager`, `testing`, and file loading are left
+//! out; use [`blocking::ChartaVM`] or the `wasm` feature's JavaScript facade.
+
+#![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(not(any(feature = "std", feature = "no_std")))]
+compile_error!("enable either the `std` (default) or the `no_std` feature");
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
 pub mod vm;
+#[cfg(feature = "std")]
+pub mod blocking;
+#[cfg(feature = "std")]
+pub mod builder;
+#[cfg(feature = "std")]
+pub mod observer;
+#[cfg(feature = "std")]
+pub mod access;
+#[cfg(feature = "std")]
 pub mod execution;
+#[cfg(feature = "std")]
 pub mod signals;
+#[cfg(feature = "std")]
 pub mod coils;
+#[cfg(feature = "std")]
 pub mod callbacks;
+#[cfg(feature = "std")]
+pub mod events;
+#[cfg(feature = "std")]
+pub mod dispatch;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "std")]
+pub mod integrations;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "tower")]
+pub mod middleware;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "std")]
+pub mod pattern;
+#[cfg(feature = "std")]
+pub mod namespace;
+#[cfg(feature = "std")]
+pub mod limits;
+#[cfg(feature = "std")]
+pub mod load;
+#[cfg(feature = "std")]
+pub mod order;
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "std")]
+pub mod link;
+#[cfg(feature = "std")]
+pub mod imports;
+#[cfg(feature = "std")]
+pub mod format;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub mod manager;
+#[cfg(feature = "std")]
+pub mod pool;
+#[cfg(feature = "std")]
+pub mod pipeline;
+#[cfg(feature = "std")]
+pub mod shadow;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod history;
+#[cfg(feature = "std")]
+pub mod coverage;
+#[cfg(feature = "std")]
+pub mod debug;
+#[cfg(feature = "std")]
+pub mod recorder;
+#[cfg(feature = "std")]
+pub mod decision;
+#[cfg(feature = "std")]
+pub mod safety;
+#[cfg(feature = "std")]
+pub mod mode;
+#[cfg(feature = "std")]
+pub mod outputs;
+#[cfg(feature = "std")]
+pub mod snapshot;
+#[cfg(feature = "std")]
+pub mod persistence;
+#[cfg(feature = "std")]
+pub mod shutdown;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "std")]
+pub mod reload;
+#[cfg(feature = "notify")]
+mod watch;
+#[cfg(feature = "std")]
+pub mod program_store;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(feature = "prometheus")]
+pub mod metrics;
+#[cfg(feature = "otel")]
+pub mod otel;
+#[cfg(feature = "std")]
 pub mod error;
+pub mod ir;
+pub mod value;
+pub mod quality;
+pub mod filter;
+pub mod bypass;
+pub mod scan_group;
+#[cfg(feature = "std")]
+mod scan;
+mod collections;
+#[cfg(feature = "std")]
+mod serde_time;
+pub mod engine;
+pub mod embedded;
+#[cfg(feature = "std")]
+pub mod render;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub mod testing;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+#[cfg(any(feature = "proptest", feature = "arbitrary"))]
+pub mod generate;
+#[cfg(feature = "jit")]
+pub mod jit;
+#[cfg(feature = "bench")]
+pub mod bench;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "uniffi")]
+pub mod mobile;
+#[cfg(feature = "python")]
+pub mod python;
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
 
+#[cfg(feature = "std")]
 pub use vm::ChartaVM;
+#[cfg(feature = "std")]
+pub use builder::ChartaVMBuilder;
+#[cfg(feature = "std")]
+pub use observer::ChartaObserver;
+#[cfg(feature = "std")]
+pub use access::SignalWriter;
+#[cfg(feature = "std")]
 pub use error::{Error, Result};
-pub use callbacks::{CallbackManager, CoilChangeCallback, CycleCompleteCallback};
+#[cfg(feature = "std")]
+pub use callbacks::{
+    CallbackError, CallbackErrorCallback, CallbackManager, CoilChangeCallback,
+    CycleCompleteCallback, CycleContext, ErrorCallback, ErrorContext, ErrorPhase, PanicPolicy,
+    ShadowDivergenceCallback,
+};
+#[cfg(feature = "std")]
+pub use events::{
+    Backpressure, CoilEventReceiver, EventReceiver, SubscriberOptions, Subscription, VmEvent,
+};
+#[cfg(feature = "std")]
+pub use dispatch::{DispatchMode, OverflowPolicy};
+#[cfg(feature = "std")]
+pub use io::{CoilChanges, InputSource, OutputSink};
+#[cfg(feature = "std")]
+pub use pattern::Pattern;
+#[cfg(feature = "std")]
+pub use limits::LoadLimits;
+#[cfg(feature = "std")]
+pub use load::{LoadOptions, LoadReport, NodeKind, UnknownNode, UnknownNodePolicy};
+#[cfg(feature = "std")]
+pub use order::OrderIssue;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub use manager::{EvictionPolicy, TenantEvent, VmManager};
+#[cfg(feature = "std")]
+pub use pool::{PoolMetrics, PooledVm, VmPool};
+#[cfg(feature = "std")]
+pub use pipeline::Pipeline;
+#[cfg(feature = "std")]
+pub use shadow::{CoilDivergence, ShadowDivergence};
+#[cfg(feature = "std")]
+pub use stats::CoilStats;
+pub use ir::{Metadata, SourceLocation};
+pub use engine::{CoilId, SignalId};
+pub use value::Value;
+pub use quality::{Quality, QualityPolicy};
+pub use filter::InputFilter;
+#[cfg(feature = "std")]
+pub use bypass::RungBypass;
+#[cfg(feature = "std")]
+pub use history::{CoilChangeRecord, CycleRecord, History};
+#[cfg(feature = "std")]
+pub use coverage::{BranchCoverage, CoverageReport, RungCoverage};
+#[cfg(feature = "std")]
+pub use debug::{Breakpoint, RungStep};
+#[cfg(feature = "std")]
+pub use recorder::RecordedCycle;
+#[cfg(feature = "std")]
+pub use decision::{DecisionExporter, DecisionRecord};
+#[cfg(feature = "std")]
+pub use mode::VmMode;
+#[cfg(feature = "std")]
+pub use execution::CycleOptions;
+#[cfg(feature = "std")]
+pub use outputs::{CoilChange, CycleDetails, CycleOutputs};
+#[cfg(feature = "std")]
+pub use snapshot::StateSnapshot;
+#[cfg(feature = "std")]
+pub use persistence::{Checkpoint, StateStore};
+#[cfg(feature = "std")]
+pub use shutdown::{ShutdownHandle, ShutdownOptions};
+#[cfg(feature = "std")]
+pub use registry::{ProgramRegistry, ProgramVersion, RegistryEvent};
+#[cfg(feature = "std")]
+pub use reload::ProgramDiff;
+#[cfg(feature = "std")]
+pub use imports::ImportResolver;
+#[cfg(feature = "std")]
+pub use format::{format_ir, format_ir_minified};
+#[cfg(feature = "std")]
+pub use program_store::{ProgramOrigin, ProgramStore};
+#[cfg(feature = "prometheus")]
+pub use metrics::VmMetrics;
+#[cfg(feature = "otel")]
+pub use otel::OtelExporter;
@@ -0,0 +1,82 @@
+//! Load-time resource limits for untrusted IR
+//!
+//! Programs exceeding any configured limit are rejected before they reach the
+//! VM with [`Error::LimitExceeded`].
+
+use crate::error::{Error, Result};
+use crate::ir::{Guard, Program};
+
+/// Upper bounds on the size of a loaded program
+///
+/// Every limit is optional; unset limits are not checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadLimits {
+    /// Maximum number of rungs
+    pub max_rungs: Option<usize>,
+    /// Maximum number of declared signals
+    pub max_signals: Option<usize>,
+    /// Maximum nesting depth of a rung guard (a lone contact has depth 1)
+    pub max_guard_depth: Option<usize>,
+    /// Maximum number of actions in a single rung
+    pub max_actions_per_rung: Option<usize>,
+}
+
+impl LoadLimits {
+    /// Check whether any limit is set
+    pub fn is_enabled(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Check a program against the limits
+    pub fn check(&self, program: &Program) -> Result<()> {
+        let module = &program.module;
+        exceeds("rungs", module.rungs.len(), self.max_rungs)?;
+        exceeds("signals", module.signals.len(), self.max_signals)?;
+
+        for rung in &module.rungs {
+            if let Some(max) = self.max_guard_depth {
+                let depth = guard_depth(&rung.guard);
+                if depth > max {
+                    return Err(Error::LimitExceeded(format!(
+                        "Rung {} has guard depth {} (limit {})",
+                        rung.describe(),
+                        depth,
+                        max
+                    )));
+                }
+            }
+            if let Some(max) = self.max_actions_per_rung {
+                if rung.actions.len() > max {
+                    return Err(Error::LimitExceeded(format!(
+                        "Rung {} has {} actions (limit {})",
+                        rung.describe(),
+                        rung.actions.len(),
+                        max
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn exceeds(what: &str, count: usize, limit: Option<usize>) -> Result<()> {
+    match limit {
+        Some(max) if count > max => Err(Error::LimitExceeded(format!(
+            "Program has {} {} (limit {})",
+            count, what, max
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Get the nesting depth of a guard
+pub fn guard_depth(guard: &Guard) -> usize {
+    1 + guard
+        .operands()
+        .into_iter()
+        .map(guard_depth)
+        .max()
+        .unwrap_or(0)
+}
@@ -0,0 +1,281 @@
+//! Linking several IR modules into one program
+//!
+//! Large programs can be split into modules owned by different teams.
+//! [`ChartaVM::load_modules`](crate::ChartaVM::load_modules) links them into
+//! a single program in which every signal, coil, and rung is namespaced by
+//! its module name (`interlock_ok` in module `safety` becomes
+//! `safety.interlock_ok`). A module reads another module's points only
+//! through explicit declarations:
+//!
+//! ```json
+//! {"module": {"name": "safety", "exports": ["interlock_ok"], ...}}
+//! {"module": {"name": "orders", "imports": ["safety.interlock_ok"], ...}}
+//! ```
+//!
+//! Guards in `orders` then refer to `safety.interlock_ok` by its qualified
+//! name. Linking fails if an import names a module or point that is not
+//! exported, a guard names an undeclared point, or a rung drives a coil it
+//! does not own. Rungs run in module order.
+
+use crate::error::{Error, Result};
+use crate::ir::{Action, Guard, Module, Program, Rung};
+use crate::namespace::{self, SEPARATOR};
+use crate::value::Expr;
+use std::collections::{HashMap, HashSet};
+
+/// Link modules, given in scan order, into one program
+pub fn link(modules: &[Program]) -> Result<Program> {
+    let Some(first) = modules.first() else {
+        return Err(Error::Link("no modules to link".to_string()));
+    };
+
+    let mut exports: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for program in modules {
+        let module = &program.module;
+        namespace::validate_name(&module.name).map_err(|e| Error::Link(e.to_string()))?;
+        if !program.imports.is_empty() {
+            return Err(Error::Link(format!(
+                "module '{}' has unresolved file imports",
+                module.name
+            )));
+        }
+        let locals = local_names(module);
+        for export in &module.exports {
+            if !locals.contains(export.as_str()) {
+                return Err(Error::Link(format!(
+                    "module '{}' exports undeclared '{}'",
+                    module.name, export
+                )));
+            }
+        }
+        let declared = module.exports.iter().map(String::as_str).collect();
+        if exports.insert(&module.name, declared).is_some() {
+            return Err(Error::Link(format!("duplicate module '{}'", module.name)));
+        }
+    }
+
+    let mut linked = Module {
+        name: modules
+            .iter()
+            .map(|program| program.module.name.as_str())
+            .collect::<Vec<_>>()
+            .join("+"),
+        signals: Vec::new(),
+        coils: Vec::new(),
+        imports: Vec::new(),
+        exports: Vec::new(),
+        registers: Vec::new(),
+        rungs: Vec::new(),
+    };
+
+    for program in modules {
+        let module = &program.module;
+        for import in &module.imports {
+            let (owner, name) = split(import).ok_or_else(|| {
+                Error::Link(format!(
+                    "module '{}' imports '{}', which is not a qualified name",
+                    module.name, import
+                ))
+            })?;
+            let exported = exports.get(owner).ok_or_else(|| {
+                Error::Link(format!(
+                    "module '{}' imports '{}' from unknown module '{}'",
+                    module.name, import, owner
+                ))
+            })?;
+            if owner == module.name || !exported.contains(name) {
+                return Err(Error::Link(format!(
+                    "module '{}' imports '{}', which '{}' does not export",
+                    module.name, import, owner
+                )));
+            }
+        }
+
+        let linker = ModuleLinker {
+            module,
+            coils: module.coils.iter().map(|c| c.name.as_str()).collect(),
+            locals: local_names(module),
+        };
+        linked.signals.extend(module.signals.iter().map(|signal| {
+            let mut signal = signal.clone();
+            signal.name = linker.qualify(&signal.name);
+            signal
+        }));
+        linked.coils.extend(module.coils.iter().map(|coil| {
+            let mut coil = coil.clone();
+            coil.name = linker.qualify(&coil.name);
+            coil
+        }));
+        linked.registers.extend(module.registers.iter().map(|register| {
+            let mut register = register.clone();
+            register.name = linker.qualify(&register.name);
+            register
+        }));
+        for rung in &module.rungs {
+            linked.rungs.push(linker.rung(rung)?);
+        }
+    }
+
+    Ok(Program {
+        version: first.version.clone(),
+        imports: Vec::new(),
+        module: linked,
+    })
+}
+
+/// Link modules given as IR JSON
+pub fn link_json(modules: &[&str]) -> Result<Program> {
+    let programs = modules
+        .iter()
+        .map(|ir_json| Program::from_json(ir_json))
+        .collect::<Result<Vec<_>>>()?;
+    link(&programs)
+}
+
+/// Split `module.name` at its last dot
+fn split(qualified: &str) -> Option<(&str, &str)> {
+    let module = namespace::namespace_of(qualified)?;
+    Some((module, namespace::local_name(qualified)))
+}
+
+fn local_names(module: &Module) -> HashSet<&str> {
+    module
+        .signals
+        .iter()
+        .map(|s| s.name.as_str())
+        .chain(module.coils.iter().map(|c| c.name.as_str()))
+        .chain(module.registers.iter().map(|r| r.name.as_str()))
+        .collect()
+}
+
+/// Rewrites the rungs of one module
+struct ModuleLinker<'a> {
+    module: &'a Module,
+    coils: HashSet<&'a str>,
+    locals: HashSet<&'a str>,
+}
+
+impl ModuleLinker<'_> {
+    fn qualify(&self, name: &str) -> String {
+        format!("{}{}{}", self.module.name, SEPARATOR, name)
+    }
+
+    fn rung(&self, rung: &Rung) -> Result<Rung> {
+        let actions = rung
+            .actions
+            .iter()
+            .map(|action| match action {
+                Action::Energise { coil } if self.coils.contains(coil.as_str()) => {
+                    Ok(Action::Energise {
+                        coil: self.qualify(coil),
+                    })
+                }
+                Action::Energise { coil } => Err(Error::Link(format!(
+                    "rung {} of module '{}' drives '{}', which the module does not declare as a coil",
+                    rung.describe(),
+                    self.module.name,
+                    coil
+                ))),
+                Action::Move { dest, expr }
+                    if self.module.registers.iter().any(|r| r.name == *dest) =>
+                {
+                    Ok(Action::Move {
+                        dest: self.qualify(dest),
+                        expr: expr.try_map_signals(&mut |name| self.resolve(name, rung))?,
+                    })
+                }
+                Action::Move { dest, .. } => Err(Error::Link(format!(
+                    "rung {} of module '{}' moves into '{}', which the module does not declare as a register",
+                    rung.describe(),
+                    self.module.name,
+                    dest
+                ))),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Rung {
+            name: self.qualify(&rung.name),
+            guard: self.guard(&rung.guard, rung)?,
+            actions,
+            source: rung.source.clone(),
+        })
+    }
+
+    fn guard(&self, guard: &Guard, rung: &Rung) -> Result<Guard> {
+        let operands = |operands: &[Guard]| {
+            operands
+                .iter()
+                .map(|operand| self.guard(operand, rung))
+                .collect::<Result<Vec<_>>>()
+        };
+        let boxed = |operand: &Option<Box<Guard>>| {
+            operand
+                .as_deref()
+                .map(|operand| self.guard(operand, rung).map(Box::new))
+                .transpose()
+        };
+        Ok(match guard {
+            Guard::Contact { name, contact_type } => Guard::Contact {
+                name: self.resolve(name, rung)?,
+                contact_type: *contact_type,
+            },
+            Guard::And { left, right, operands: list } => Guard::And {
+                left: boxed(left)?,
+                right: boxed(right)?,
+                operands: operands(list)?,
+            },
+            Guard::Or { left, right, operands: list } => Guard::Or {
+                left: boxed(left)?,
+                right: boxed(right)?,
+                operands: operands(list)?,
+            },
+            Guard::Not { operand } => Guard::Not {
+                operand: Box::new(self.guard(operand, rung)?),
+            },
+            Guard::Compare { name, expr, op, value } => Guard::Compare {
+                name: self.resolve_subject(name, rung)?,
+                expr: self.expr(expr, rung)?,
+                op: *op,
+                value: value.clone(),
+            },
+            Guard::WithinRange { name, expr, min, max } => Guard::WithinRange {
+                name: self.resolve_subject(name, rung)?,
+                expr: self.expr(expr, rung)?,
+                min: min.clone(),
+                max: max.clone(),
+            },
+        })
+    }
+
+    /// Resolve a comparison's signal name, left empty when it tests an
+    /// expression
+    fn resolve_subject(&self, name: &str, rung: &Rung) -> Result<String> {
+        if name.is_empty() {
+            Ok(String::new())
+        } else {
+            self.resolve(name, rung)
+        }
+    }
+
+    /// Resolve the signals an expression reads
+    fn expr(&self, expr: &Option<Expr>, rung: &Rung) -> Result<Option<Expr>> {
+        expr.as_ref()
+            .map(|expr| expr.try_map_signals(&mut |name| self.resolve(name, rung)))
+            .transpose()
+    }
+
+    /// Qualify a local name or pass an import through
+    fn resolve(&self, name: &str, rung: &Rung) -> Result<String> {
+        if self.locals.contains(name) {
+            Ok(self.qualify(name))
+        } else if self.module.imports.iter().any(|import| import == name) {
+            Ok(name.to_string())
+        } else {
+            Err(Error::Link(format!(
+                "rung {} of module '{}' reads '{}', which is neither declared nor imported",
+                rung.describe(),
+                self.module.name,
+                name
+            )))
+        }
+    }
+}
@@ -0,0 +1,794 @@
+//! Program load reporting
+//!
+//! Detects guard and action node types the SDK does not recognise before the
+//! IR reaches the VM. In [`UnknownNodePolicy::Strict`] mode loading fails
+//! naming the offending node; in [`UnknownNodePolicy::Permissive`] mode the
+//! affected rungs are disabled (left out of the loaded program) and listed in
+//! the [`LoadReport`].
+//!
+//! Signals and coils start false, and typed signals at their type's default,
+//! unless the IR declares an `initial` value or [`LoadOptions`] sets one:
+//!
+//! ```no_run
+//! use charta::{ChartaVM, LoadOptions};
+//!
+//! # async fn example(vm: &mut ChartaVM, ir_json: &str) -> charta::Result<()> {
+//! let options = LoadOptions::new()
+//!     .signal("door_closed", true)
+//!     .signal("setpoint", 72.5)
+//!     .coil("motor_running", true);
+//! vm.load_program_with_options(ir_json, &options).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::bypass;
+use crate::error::{Error, Result};
+use crate::ir::{describe_rung, Action, ContactType, Guard, Program, SignalDecl, SourceLocation};
+use crate::limits::LoadLimits;
+use crate::namespace;
+use crate::order::{self, OrderIssue};
+use crate::quality::{QualityGates, QualityPolicy};
+use crate::scan::Scanner;
+use crate::scan_group;
+use crate::value::{self, Assignment, CompareOp, Expr, Value as SignalValue, ValueType};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+/// Guard node types the SDK understands
+const GUARD_TYPES: &[&str] = &["contact", "and", "or", "not", "compare", "within_range"];
+
+/// Action node types the SDK understands
+const ACTION_TYPES: &[&str] = &["energise", "move"];
+
+/// How to treat IR nodes of unrecognised type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownNodePolicy {
+    /// Fail the load with the exact offending node
+    #[default]
+    Strict,
+    /// Disable rungs containing unknown nodes and report them
+    Permissive,
+}
+
+/// Kind of IR node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// Guard expression node
+    Guard,
+    /// Rung action
+    Action,
+}
+
+/// An IR node of unrecognised type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownNode {
+    /// Name of the rung containing the node
+    pub rung: String,
+    /// Whether the node is a guard or an action
+    pub kind: NodeKind,
+    /// The unrecognised `type` value
+    pub node_type: String,
+    /// JSON path of the node (e.g. `module.rungs[2].guard.left`)
+    pub path: String,
+    /// Where the rung was written, if the IR records it
+    pub source: Option<SourceLocation>,
+}
+
+/// What a program load actually loaded
+///
+/// Returned by `load_program` and kept for
+/// [`ChartaVM::load_report`](crate::ChartaVM::load_report).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    /// Hash of the loaded IR, as reported by `program_id()`
+    pub program_id: String,
+    /// Number of signals the program declares
+    pub signals: usize,
+    /// Number of coils the program declares
+    pub coils: usize,
+    /// Number of rungs loaded, disabled rungs excluded
+    pub rungs: usize,
+    /// One line per ignored node and order issue, for logs and rollout
+    /// checks; empty when the program loaded as written
+    pub warnings: Vec<String>,
+    /// Rungs left out of the program because they contain unknown nodes
+    pub disabled_rungs: Vec<String>,
+    /// Unknown nodes found, in document order
+    pub ignored_nodes: Vec<UnknownNode>,
+    /// Order dependencies of the loaded rungs (see [`crate::order`])
+    pub order_issues: Vec<OrderIssue>,
+}
+
+/// Initial states applied when a program loads
+///
+/// Values set here override the `initial` values the IR declares. A
+/// persisted checkpoint, when the VM restores one, overrides both.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadOptions {
+    /// Signal name -> initial value
+    pub signals: HashMap<String, SignalValue>,
+    /// Coil name -> initial state
+    pub coils: HashMap<String, bool>,
+}
+
+impl LoadOptions {
+    /// Create options leaving every initial value to the IR
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start signal `name` at `value`
+    pub fn signal(mut self, name: impl Into<String>, value: impl Into<SignalValue>) -> Self {
+        self.signals.insert(name.into(), value.into());
+        self
+    }
+
+    /// Start coil `name` at `value`
+    pub fn coil(mut self, name: impl Into<String>, value: bool) -> Self {
+        self.coils.insert(name.into(), value);
+        self
+    }
+}
+
+/// Initial states resolved against a program, split the way the VM holds them
+#[derive(Debug, Default)]
+pub(crate) struct InitialState {
+    /// Boolean signals, set in the VM core
+    pub(crate) signals: HashMap<String, bool>,
+    /// Typed signal values
+    pub(crate) values: HashMap<String, SignalValue>,
+    /// Coil states, set in the VM core
+    pub(crate) coils: HashMap<String, bool>,
+}
+
+impl InitialState {
+    /// Write the signal and coil states into `vm`
+    pub(crate) fn apply(&self, vm: &mut charta_vm::VM) {
+        for (name, value) in &self.signals {
+            vm.set_signal(name.clone(), *value);
+        }
+        for (name, value) in &self.coils {
+            vm.set_coil(name.clone(), *value);
+        }
+    }
+}
+
+/// Collect the initial values `program` declares, overridden by `options`
+///
+/// Fails with [`Error::NotFound`] for a name the program does not declare
+/// and [`Error::TypeMismatch`] for a value of the wrong type. IR the SDK
+/// could not parse is not checked.
+pub(crate) fn initial_state(
+    program: Option<&Program>,
+    options: &LoadOptions,
+) -> Result<InitialState> {
+    let mut signals: HashMap<&str, SignalValue> = HashMap::new();
+    let mut coils: HashMap<&str, bool> = HashMap::new();
+    if let Some(program) = program {
+        for signal in &program.module.signals {
+            if let Some(value) = &signal.initial {
+                signals.insert(&signal.name, value.clone());
+            }
+        }
+        for coil in &program.module.coils {
+            if let Some(value) = coil.initial {
+                coils.insert(&coil.name, value);
+            }
+        }
+        for name in options.signals.keys() {
+            if !program
+                .module
+                .signals
+                .iter()
+                .any(|signal| &signal.name == name)
+            {
+                return Err(Error::NotFound(format!("signal '{}'", name)));
+            }
+        }
+        for name in options.coils.keys() {
+            if !program.module.coils.iter().any(|coil| &coil.name == name) {
+                return Err(Error::NotFound(format!("coil '{}'", name)));
+            }
+        }
+    }
+    signals.extend(
+        options
+            .signals
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone())),
+    );
+    coils.extend(
+        options
+            .coils
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value)),
+    );
+
+    let mut initial = InitialState::default();
+    for (name, value) in signals {
+        match value::assign(program, name, value)? {
+            Assignment::Typed(value) => {
+                initial.values.insert(name.to_string(), value);
+            }
+            Assignment::Bool(value) => {
+                initial.signals.insert(name.to_string(), value);
+            }
+        }
+    }
+    initial.coils = coils
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+    Ok(initial)
+}
+
+/// IR checked against the unknown-node policy and load limits
+pub(crate) struct ValidatedIr<'a> {
+    /// IR to hand to the VM
+    pub(crate) ir_json: Cow<'a, str>,
+    /// SDK model of the program, if the SDK could parse it
+    pub(crate) program: Option<Program>,
+    /// Program the VM core scans, with quality gates, bypasses, typed
+    /// nodes, and scan groups lowered, if the SDK could parse it
+    pub(crate) lowered: Option<Program>,
+    /// Unknown nodes dropped under the permissive policy
+    pub(crate) ignored: Vec<UnknownNode>,
+    /// Order dependencies of the program in scan order
+    pub(crate) order_issues: Vec<OrderIssue>,
+    /// Comparisons, `move` actions, quality gates, and scan groups the SDK
+    /// runs around each scan
+    pub(crate) scanner: Scanner,
+}
+
+/// A `compare` or `within_range` node, lowered to a contact on its derived
+/// signal
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Comparison {
+    /// Derived signal the VM core reads
+    pub(crate) signal: String,
+    /// Signal or expression tested
+    pub(crate) subject: Expr,
+    /// Bounds the subject must all satisfy
+    pub(crate) bounds: Vec<(CompareOp, SignalValue)>,
+}
+
+impl Comparison {
+    /// Get the comparison a `compare` or `within_range` node tests, without
+    /// checking its types
+    pub(crate) fn of(guard: &Guard) -> Option<Self> {
+        let bounds = match guard {
+            Guard::Compare { op, value, .. } => vec![(*op, value.clone())],
+            Guard::WithinRange { min, max, .. } => {
+                vec![(CompareOp::Ge, min.clone()), (CompareOp::Le, max.clone())]
+            }
+            _ => return None,
+        };
+        Some(Self {
+            signal: guard.derived_signal()?,
+            subject: guard.subject()?,
+            bounds,
+        })
+    }
+
+    /// Evaluate against the current typed values and registers
+    pub(crate) fn evaluate(
+        &self,
+        values: &HashMap<String, SignalValue>,
+        registers: &HashMap<String, SignalValue>,
+    ) -> bool {
+        let Some(current) = self.subject.evaluate(&typed_lookup(values, registers)) else {
+            return false;
+        };
+        self.bounds
+            .iter()
+            .all(|(op, bound)| current.compare(*op, bound))
+    }
+}
+
+/// A `move` action, taken out of the program the VM core scans
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RegisterMove {
+    /// Index of the rung in scan order
+    pub(crate) rung: usize,
+    /// Register written
+    pub(crate) dest: String,
+    /// Declared type of the register
+    pub(crate) ty: ValueType,
+    pub(crate) expr: Expr,
+}
+
+/// Apply the moves of energised rungs in scan order
+///
+/// `energised` holds each rung's guard result for the scan just run. A move
+/// sees the registers written by moves before it in the same scan; a move
+/// whose value is unknown leaves its register unchanged.
+pub(crate) fn apply_moves(
+    moves: &[RegisterMove],
+    energised: &[bool],
+    values: &HashMap<String, SignalValue>,
+    registers: &mut HashMap<String, SignalValue>,
+) {
+    for register_move in moves {
+        if !energised.get(register_move.rung).copied().unwrap_or(false) {
+            continue;
+        }
+        let value = register_move
+            .expr
+            .evaluate(&typed_lookup(values, registers))
+            .and_then(|value| value.coerce(register_move.ty));
+        if let Some(value) = value {
+            registers.insert(register_move.dest.clone(), value);
+        }
+    }
+}
+
+/// Resolve a name to a typed signal value or register
+fn typed_lookup<'a>(
+    values: &'a HashMap<String, SignalValue>,
+    registers: &'a HashMap<String, SignalValue>,
+) -> impl Fn(&str) -> Option<SignalValue> + 'a {
+    move |name| values.get(name).or_else(|| registers.get(name)).cloned()
+}
+
+/// Apply the unknown-node policy, load limits, and namespace rules
+///
+/// With limits set, IR the SDK cannot model is rejected rather than loaded
+/// blind.
+pub(crate) fn validate(
+    ir_json: &str,
+    policy: UnknownNodePolicy,
+    limits: &LoadLimits,
+    quality: QualityPolicy,
+) -> Result<ValidatedIr<'_>> {
+    let (mut ir_json, ignored) = resolve_unknown_nodes(ir_json, policy)?;
+    let program = match Program::from_json(&ir_json) {
+        Ok(mut program) => {
+            if !program.imports.is_empty() {
+                return Err(Error::IRLoad(format!(
+                    "program imports {:?}, which must be resolved before loading",
+                    program.imports
+                )));
+            }
+            limits.check(&program)?;
+            namespace::validate_program(&program)?;
+            order::sort(&mut program);
+            Some(program)
+        }
+        Err(e) if limits.is_enabled() => return Err(e),
+        Err(_) => None,
+    };
+    let order_issues = program.as_ref().map(order::analyze).unwrap_or_default();
+    let gates = program.as_ref().map(QualityGates::of).unwrap_or_default();
+    let groups = program.as_ref().map(scan_group::groups).unwrap_or_default();
+    let gated = program.as_ref().map(|program| {
+        let mut gated = program.clone();
+        if quality == QualityPolicy::EvaluateFalse {
+            gates.apply(&mut gated);
+        }
+        bypass::apply(&mut gated);
+        gated
+    });
+    let mut comparisons = Vec::new();
+    let mut moves = Vec::new();
+    let lowered = match gated {
+        Some(gated) => Some(lower_typed(&gated, &mut comparisons, &mut moves)?.unwrap_or(gated)),
+        None => None,
+    };
+    let lowered = lowered.map(|mut lowered| {
+        scan_group::apply(&mut lowered);
+        lowered
+    });
+    if let Some(lowered) = &lowered {
+        ir_json = Cow::Owned(serde_json::to_string(lowered)?);
+    }
+    Ok(ValidatedIr {
+        ir_json,
+        program,
+        lowered,
+        ignored,
+        order_issues,
+        scanner: Scanner::new(comparisons, moves, gates, groups, quality),
+    })
+}
+
+/// Rewrite a program with typed signals for the boolean VM core
+///
+/// Typed signal and register declarations are dropped and each `compare` or
+/// `within_range` node becomes a contact on its derived signal, declared in
+/// their place. `move` actions are taken out into `moves`, along with rungs
+/// left without actions. Returns `None` if the program uses none of these.
+/// Contacts on typed signals or registers, and comparisons of anything
+/// else, are rejected.
+fn lower_typed(
+    program: &Program,
+    comparisons: &mut Vec<Comparison>,
+    moves: &mut Vec<RegisterMove>,
+) -> Result<Option<Program>> {
+    let module = &program.module;
+    let mut lowering = Lowering {
+        typed: module
+            .signals
+            .iter()
+            .filter(|signal| !signal.value_type.is_bool())
+            .map(|signal| (signal.name.clone(), signal.value_type))
+            .chain(
+                module
+                    .registers
+                    .iter()
+                    .map(|register| (register.name.clone(), register.value_type)),
+            )
+            .collect(),
+        derived: BTreeMap::new(),
+    };
+    let mut lowered = program.clone();
+    for (index, rung) in lowered.module.rungs.iter_mut().enumerate() {
+        let described = rung.describe();
+        lowering.guard(&mut rung.guard, &described)?;
+        for action in &rung.actions {
+            if let Action::Move { dest, expr } = action {
+                moves.push(lowering.register_move(program, index, dest, expr, &described)?);
+            }
+        }
+    }
+    if lowering.typed.is_empty() && lowering.derived.is_empty() {
+        return Ok(None);
+    }
+
+    for rung in &mut lowered.module.rungs {
+        rung.actions.retain(|action| !matches!(action, Action::Move { .. }));
+    }
+    let original = &program.module.rungs;
+    let mut index = 0;
+    lowered.module.rungs.retain(|rung| {
+        let keep = !rung.actions.is_empty() || original[index].actions.is_empty();
+        index += 1;
+        keep
+    });
+    lowered.module.registers.clear();
+    lowered.module.signals.retain(|signal| signal.value_type.is_bool());
+    for (signal, comparison) in lowering.derived {
+        lowered.module.signals.push(SignalDecl {
+            name: signal,
+            value_type: ValueType::Bool,
+            filter: None,
+            initial: None,
+            meta: Default::default(),
+        });
+        comparisons.push(comparison);
+    }
+    Ok(Some(lowered))
+}
+
+struct Lowering {
+    /// Declared types of typed signals
+    typed: HashMap<String, ValueType>,
+    /// Comparisons by derived signal
+    derived: BTreeMap<String, Comparison>,
+}
+
+impl Lowering {
+    fn guard(&mut self, guard: &mut Guard, rung: &str) -> Result<()> {
+        let derived = guard.derived_signal().unwrap_or_default();
+        match guard {
+            Guard::Compare { name, expr, op, value } => {
+                let bounds = vec![(*op, value.clone())];
+                let comparison = self.comparison(name, expr, bounds, derived, rung)?;
+                self.lower(guard, comparison);
+            }
+            Guard::WithinRange { name, expr, min, max } => {
+                let bounds = vec![(CompareOp::Ge, min.clone()), (CompareOp::Le, max.clone())];
+                let comparison = self.comparison(name, expr, bounds, derived, rung)?;
+                if !min.compare(CompareOp::Le, max) {
+                    return Err(Error::IRLoad(format!(
+                        "Rung {}: range [{}, {}] of '{}' is empty",
+                        rung, min, max, comparison.subject
+                    )));
+                }
+                self.lower(guard, comparison);
+            }
+            Guard::Contact { name, .. } => {
+                if let Some(ty) = self.typed.get(name.as_str()) {
+                    return Err(Error::IRLoad(format!(
+                        "Rung {}: contact on {} '{}'; use a compare node",
+                        rung, ty, name
+                    )));
+                }
+            }
+            Guard::Not { operand } => self.guard(operand, rung)?,
+            Guard::And { left, right, operands } | Guard::Or { left, right, operands } => {
+                for operand in left.iter_mut().chain(right.iter_mut()) {
+                    self.guard(operand, rung)?;
+                }
+                for operand in operands {
+                    self.guard(operand, rung)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a comparison node's subject against its bounds
+    ///
+    /// The node names a signal or carries an expression, but not both.
+    fn comparison(
+        &self,
+        name: &str,
+        expr: &Option<Expr>,
+        bounds: Vec<(CompareOp, SignalValue)>,
+        signal: String,
+        rung: &str,
+    ) -> Result<Comparison> {
+        let subject = match (name, expr) {
+            ("", Some(expr)) => expr.clone(),
+            (name, None) if !name.is_empty() => Expr::Signal {
+                name: name.to_string(),
+            },
+            _ => {
+                return Err(Error::IRLoad(format!(
+                    "Rung {}: comparison needs exactly one of 'name' and 'expr'",
+                    rung
+                )))
+            }
+        };
+        let ty = self.expr_type(&subject, rung)?;
+        for (_, bound) in &bounds {
+            if !ty.is_comparable_with(bound.value_type()) {
+                return Err(Error::IRLoad(format!(
+                    "Rung {}: compares {} '{}' with {} {}",
+                    rung,
+                    ty,
+                    subject,
+                    bound.value_type(),
+                    bound
+                )));
+            }
+        }
+        Ok(Comparison {
+            signal,
+            subject,
+            bounds,
+        })
+    }
+
+    /// Replace a comparison node with a contact on its derived signal
+    fn lower(&mut self, guard: &mut Guard, comparison: Comparison) {
+        *guard = Guard::Contact {
+            name: comparison.signal.clone(),
+            contact_type: ContactType::NormallyOpen,
+        };
+        self.derived.insert(comparison.signal.clone(), comparison);
+    }
+
+    /// Check a `move` action against the register it writes
+    fn register_move(
+        &self,
+        program: &Program,
+        rung: usize,
+        dest: &str,
+        expr: &Expr,
+        described: &str,
+    ) -> Result<RegisterMove> {
+        let Some(register) = program.module.registers.iter().find(|r| r.name == dest) else {
+            return Err(Error::IRLoad(format!(
+                "Rung {}: moves into '{}', which is not a declared register",
+                described, dest
+            )));
+        };
+        let ty = self.expr_type(expr, described)?;
+        let widens = ty == ValueType::Int && register.value_type == ValueType::Float;
+        if ty != register.value_type && !widens {
+            return Err(Error::IRLoad(format!(
+                "Rung {}: moves {} '{}' into {} register '{}'",
+                described, ty, expr, register.value_type, dest
+            )));
+        }
+        Ok(RegisterMove {
+            rung,
+            dest: dest.to_string(),
+            ty: register.value_type,
+            expr: expr.clone(),
+        })
+    }
+
+    /// Infer the type of an expression over typed signals
+    fn expr_type(&self, expr: &Expr, rung: &str) -> Result<ValueType> {
+        let numeric = |ty: ValueType, operand: &Expr| {
+            if ty.is_numeric() {
+                Ok(ty)
+            } else {
+                Err(Error::IRLoad(format!(
+                    "Rung {}: arithmetic on {} '{}'",
+                    rung, ty, operand
+                )))
+            }
+        };
+        match expr {
+            Expr::Signal { name } => self.typed.get(name.as_str()).copied().ok_or_else(|| {
+                Error::IRLoad(format!(
+                    "Rung {}: reads '{}', which is not a typed signal or register",
+                    rung, name
+                ))
+            }),
+            Expr::Const { value } => Ok(value.value_type()),
+            Expr::Add { operands } => {
+                if operands.is_empty() {
+                    return Err(Error::IRLoad(format!("Rung {}: add has no operands", rung)));
+                }
+                let mut ty = ValueType::Int;
+                for operand in operands {
+                    if numeric(self.expr_type(operand, rung)?, operand)? == ValueType::Float {
+                        ty = ValueType::Float;
+                    }
+                }
+                Ok(ty)
+            }
+            Expr::Sub { left, right } => {
+                let left_ty = numeric(self.expr_type(left, rung)?, left)?;
+                let right_ty = numeric(self.expr_type(right, rung)?, right)?;
+                if left_ty == ValueType::Int && right_ty == ValueType::Int {
+                    Ok(ValueType::Int)
+                } else {
+                    Ok(ValueType::Float)
+                }
+            }
+        }
+    }
+}
+
+/// Find unknown nodes and apply the policy
+///
+/// Returns the IR to hand to the VM and the nodes that were ignored. IR that
+/// is not valid JSON is passed through unchanged for the loader to reject.
+pub(crate) fn resolve_unknown_nodes(
+    ir_json: &str,
+    policy: UnknownNodePolicy,
+) -> Result<(Cow<'_, str>, Vec<UnknownNode>)> {
+    let Ok(mut root) = serde_json::from_str::<Value>(ir_json) else {
+        return Ok((Cow::Borrowed(ir_json), Vec::new()));
+    };
+    let Some(rungs) = root
+        .pointer_mut("/module/rungs")
+        .and_then(Value::as_array_mut)
+    else {
+        return Ok((Cow::Borrowed(ir_json), Vec::new()));
+    };
+
+    let mut unknown = Vec::new();
+    let mut keep = Vec::with_capacity(rungs.len());
+    for (index, rung) in rungs.iter().enumerate() {
+        let name = rung
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("rungs[{}]", index));
+        let path = format!("module.rungs[{}]", index);
+        let found = unknown.len();
+
+        if let Some(guard) = rung.get("guard") {
+            scan_guard(guard, &name, &format!("{}.guard", path), &mut unknown);
+        }
+        if let Some(actions) = rung.get("actions").and_then(Value::as_array) {
+            for (i, action) in actions.iter().enumerate() {
+                let node_type = node_type(action);
+                if !ACTION_TYPES.contains(&node_type) {
+                    unknown.push(UnknownNode {
+                        rung: name.clone(),
+                        kind: NodeKind::Action,
+                        node_type: node_type.to_string(),
+                        path: format!("{}.actions[{}]", path, i),
+                        source: None,
+                    });
+                }
+            }
+        }
+
+        if unknown.len() > found {
+            let source: Option<SourceLocation> = rung
+                .get("source")
+                .and_then(|source| serde_json::from_value(source.clone()).ok());
+            for node in &mut unknown[found..] {
+                node.source = source.clone();
+            }
+        }
+        keep.push(unknown.len() == found);
+    }
+
+    let Some(first) = unknown.first() else {
+        return Ok((Cow::Borrowed(ir_json), unknown));
+    };
+    if policy == UnknownNodePolicy::Strict {
+        return Err(Error::IRLoad(format!(
+            "Rung {}: unknown {} type '{}' at {}",
+            describe_rung(&first.rung, first.source.as_ref()),
+            match first.kind {
+                NodeKind::Guard => "guard",
+                NodeKind::Action => "action",
+            },
+            first.node_type,
+            first.path
+        )));
+    }
+
+    let mut keep = keep.into_iter();
+    rungs.retain(|_| keep.next().unwrap_or(true));
+    Ok((Cow::Owned(root.to_string()), unknown))
+}
+
+fn node_type(node: &Value) -> &str {
+    node.get("type").and_then(Value::as_str).unwrap_or("")
+}
+
+fn scan_guard(guard: &Value, rung: &str, path: &str, unknown: &mut Vec<UnknownNode>) {
+    let node_type = node_type(guard);
+    if !GUARD_TYPES.contains(&node_type) {
+        unknown.push(UnknownNode {
+            rung: rung.to_string(),
+            kind: NodeKind::Guard,
+            node_type: node_type.to_string(),
+            path: path.to_string(),
+            source: None,
+        });
+        return;
+    }
+
+    for key in ["left", "right", "operand"] {
+        if let Some(child) = guard.get(key) {
+            scan_guard(child, rung, &format!("{}.{}", path, key), unknown);
+        }
+    }
+    if let Some(operands) = guard.get("operands").and_then(Value::as_array) {
+        for (i, child) in operands.iter().enumerate() {
+            scan_guard(child, rung, &format!("{}.operands[{}]", path, i), unknown);
+        }
+    }
+}
+
+impl LoadReport {
+    pub(crate) fn new(
+        program_id: String,
+        program: Option<&Program>,
+        ignored_nodes: Vec<UnknownNode>,
+        order_issues: Vec<OrderIssue>,
+    ) -> Self {
+        let mut disabled_rungs: Vec<String> = Vec::new();
+        let mut warnings = Vec::new();
+        for node in &ignored_nodes {
+            if !disabled_rungs.contains(&node.rung) {
+                disabled_rungs.push(node.rung.clone());
+            }
+            warnings.push(format!(
+                "rung '{}' disabled: unknown node type '{}' at {}",
+                node.rung, node.node_type, node.path
+            ));
+        }
+        for issue in &order_issues {
+            warnings.push(match issue {
+                OrderIssue::ReadBeforeWrite {
+                    reader,
+                    writer,
+                    coil,
+                } => format!(
+                    "rung '{}' reads coil '{}' before rung '{}' drives it",
+                    reader, coil, writer
+                ),
+                OrderIssue::SharedCoil { coil, rungs } => format!(
+                    "coil '{}' is driven by several rungs: {}",
+                    coil,
+                    rungs.join(", ")
+                ),
+            });
+        }
+        Self {
+            program_id,
+            signals: program.map_or(0, |program| program.module.signals.len()),
+            coils: program.map_or(0, |program| program.module.coils.len()),
+            rungs: program.map_or(0, |program| program.module.rungs.len()),
+            warnings,
+            disabled_rungs,
+            ignored_nodes,
+            order_issues,
+        }
+    }
+}
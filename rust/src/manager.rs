@@ -0,0 +1,225 @@
+//! Multi-VM management for Charta
+//!
+//! Owns many named VM instances (e.g. one per tenant or workflow), loads their
+//! programs on demand, evicts instances according to a policy, and merges
+//! their event streams into a single tenant-tagged stream.
+
+use crate::error::Result;
+use crate::events::{VmEvent, DEFAULT_EVENT_CAPACITY};
+use crate::vm::ChartaVM;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
+
+/// Policy deciding when managed VMs are evicted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Keep every VM until it is removed explicitly
+    Never,
+    /// Keep at most this many VMs, evicting the least recently used
+    MaxInstances(usize),
+    /// Evict VMs not accessed within this duration
+    ///
+    /// Checked whenever a VM is looked up or loaded, and by
+    /// [`VmManager::evict_idle`].
+    IdleTimeout(Duration),
+}
+
+/// Event from a managed VM, tagged with its tenant
+#[derive(Debug, Clone, PartialEq)]
+pub struct TenantEvent {
+    /// Tenant the event originated from
+    pub tenant: String,
+    /// The VM event
+    pub event: VmEvent,
+}
+
+/// Managed VM entry
+struct Entry {
+    vm: Arc<Mutex<ChartaVM>>,
+    last_used: Instant,
+    /// Task forwarding this VM's events into the aggregate stream
+    forwarder: JoinHandle<()>,
+}
+
+/// Manager owning one VM per tenant
+pub struct VmManager {
+    entries: Mutex<HashMap<String, Entry>>,
+    policy: EvictionPolicy,
+    events: broadcast::Sender<TenantEvent>,
+}
+
+impl VmManager {
+    /// Create a manager that never evicts
+    pub fn new() -> Self {
+        Self::with_eviction(EvictionPolicy::Never)
+    }
+
+    /// Create a manager with the given eviction policy
+    pub fn with_eviction(policy: EvictionPolicy) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            policy,
+            events: broadcast::channel(DEFAULT_EVENT_CAPACITY).0,
+        }
+    }
+
+    /// Get the VM for a tenant, creating it and loading `ir_json` if absent
+    ///
+    /// The IR is only used when the tenant has no VM yet; an existing VM keeps
+    /// its currently loaded program. The program is loaded without holding
+    /// the manager, so other tenants are not held up by a slow load.
+    pub async fn get_or_load(&self, tenant: &str, ir_json: &str) -> Result<Arc<Mutex<ChartaVM>>> {
+        if let Some(vm) = self.get(tenant).await {
+            return Ok(vm);
+        }
+
+        let mut vm = ChartaVM::new();
+        vm.load_program(ir_json).await?;
+
+        let mut entries = self.entries.lock().await;
+        // Another caller may have loaded the tenant meanwhile
+        if let Some(entry) = entries.get_mut(tenant) {
+            entry.last_used = Instant::now();
+            return Ok(entry.vm.clone());
+        }
+
+        let forwarder = self.spawn_forwarder(tenant, &vm);
+        let vm = Arc::new(Mutex::new(vm));
+        entries.insert(
+            tenant.to_string(),
+            Entry {
+                vm: vm.clone(),
+                last_used: Instant::now(),
+                forwarder,
+            },
+        );
+
+        if let EvictionPolicy::MaxInstances(max) = self.policy {
+            while entries.len() > max.max(1) {
+                let oldest = entries
+                    .iter()
+                    .filter(|(name, _)| name.as_str() != tenant)
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(name, _)| name.clone());
+                match oldest {
+                    Some(name) => {
+                        if let Some(entry) = entries.remove(&name) {
+                            entry.forwarder.abort();
+                        }
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        Ok(vm)
+    }
+
+    /// Get the VM for a tenant if it is loaded
+    pub async fn get(&self, tenant: &str) -> Option<Arc<Mutex<ChartaVM>>> {
+        let mut entries = self.entries.lock().await;
+        self.expire(&mut entries);
+        entries.get_mut(tenant).map(|entry| {
+            entry.last_used = Instant::now();
+            entry.vm.clone()
+        })
+    }
+
+    /// Remove a tenant's VM
+    ///
+    /// Returns true if the tenant was loaded.
+    pub async fn remove(&self, tenant: &str) -> bool {
+        let mut entries = self.entries.lock().await;
+        match entries.remove(tenant) {
+            Some(entry) => {
+                entry.forwarder.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Evict VMs idle for longer than the configured timeout
+    ///
+    /// Only applies under [`EvictionPolicy::IdleTimeout`]. Lookups evict
+    /// idle VMs too; this frees them without waiting for one. Returns the
+    /// evicted tenants.
+    pub async fn evict_idle(&self) -> Vec<String> {
+        let mut entries = self.entries.lock().await;
+        self.expire(&mut entries)
+    }
+
+    /// Remove the entries idle past the timeout, returning their tenants
+    fn expire(&self, entries: &mut HashMap<String, Entry>) -> Vec<String> {
+        let timeout = match self.policy {
+            EvictionPolicy::IdleTimeout(timeout) => timeout,
+            _ => return Vec::new(),
+        };
+
+        let idle: Vec<String> = entries
+            .iter()
+            .filter(|(_, entry)| entry.last_used.elapsed() > timeout)
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        for name in &idle {
+            if let Some(entry) = entries.remove(name) {
+                entry.forwarder.abort();
+            }
+        }
+
+        idle
+    }
+
+    /// Get the names of all loaded tenants
+    pub async fn tenants(&self) -> Vec<String> {
+        let entries = self.entries.lock().await;
+        entries.keys().cloned().collect()
+    }
+
+    /// Get the number of loaded VMs
+    pub async fn len(&self) -> usize {
+        self.entries.lock().await.len()
+    }
+
+    /// Check whether no VMs are loaded
+    pub async fn is_empty(&self) -> bool {
+        self.entries.lock().await.is_empty()
+    }
+
+    /// Subscribe to the aggregate event stream of all managed VMs
+    pub fn subscribe(&self) -> broadcast::Receiver<TenantEvent> {
+        self.events.subscribe()
+    }
+
+    /// Forward a VM's events into the aggregate stream
+    fn spawn_forwarder(&self, tenant: &str, vm: &ChartaVM) -> JoinHandle<()> {
+        let mut rx = vm.subscribe();
+        let tx = self.events.clone();
+        let tenant = tenant.to_string();
+
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => {
+                        let _ = tx.send(TenantEvent {
+                            tenant: tenant.clone(),
+                            event,
+                        });
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+impl Default for VmManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,96 @@
+//! Prometheus metrics for Charta VM
+//!
+//! Available with the `prometheus` feature. Attach a [`VmMetrics`] to a VM
+//! with [`ChartaVM::set_metrics`](crate::ChartaVM::set_metrics) and either
+//! merge its [`Registry`] into your own or serve [`VmMetrics::encode_text`]
+//! from a `/metrics` endpoint.
+
+use crate::error::Result;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+/// Prometheus metrics collected from VM execution
+#[derive(Clone)]
+pub struct VmMetrics {
+    registry: Registry,
+    cycle_duration: Histogram,
+    cycles_total: IntCounter,
+    coil_transitions: IntCounterVec,
+    callback_errors: IntCounter,
+}
+
+impl VmMetrics {
+    /// Create metrics registered in a fresh registry
+    pub fn new() -> Result<Self> {
+        Self::with_registry(Registry::new())
+    }
+
+    /// Create metrics registered in the given registry
+    ///
+    /// Each registry can hold only one `VmMetrics`; use
+    /// [`Registry::new_custom`] with a prefix or const labels to keep several
+    /// VMs apart.
+    pub fn with_registry(registry: Registry) -> Result<Self> {
+        let cycle_duration = Histogram::with_opts(HistogramOpts::new(
+            "charta_cycle_duration_seconds",
+            "Time spent executing a scan cycle, including callbacks",
+        ))?;
+        let cycles_total = IntCounter::new("charta_cycles_total", "Number of executed scan cycles")?;
+        let coil_transitions = IntCounterVec::new(
+            Opts::new("charta_coil_transitions_total", "Number of coil state changes"),
+            &["coil", "state"],
+        )?;
+        let callback_errors = IntCounter::new(
+            "charta_callback_errors_total",
+            "Number of failed callback invocations",
+        )?;
+
+        registry.register(Box::new(cycle_duration.clone()))?;
+        registry.register(Box::new(cycles_total.clone()))?;
+        registry.register(Box::new(coil_transitions.clone()))?;
+        registry.register(Box::new(callback_errors.clone()))?;
+
+        Ok(Self {
+            registry,
+            cycle_duration,
+            cycles_total,
+            coil_transitions,
+            callback_errors,
+        })
+    }
+
+    /// Get the registry holding these metrics
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Encode all metrics in the Prometheus text exposition format
+    pub fn encode_text(&self) -> Result<String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+
+    /// Record a completed cycle
+    pub fn observe_cycle<S: BuildHasher>(
+        &self,
+        duration: Duration,
+        changes: &HashMap<String, (bool, bool), S>,
+    ) {
+        self.cycle_duration.observe(duration.as_secs_f64());
+        self.cycles_total.inc();
+        for (coil, (_, new_value)) in changes {
+            let state = if *new_value { "energised" } else { "de-energised" };
+            self.coil_transitions.with_label_values(&[coil.as_str(), state]).inc();
+        }
+    }
+
+    /// Record a failed callback invocation
+    pub fn record_callback_error(&self) {
+        self.callback_errors.inc();
+    }
+}
@@ -0,0 +1,159 @@
+//! tower middleware gating requests on a VM decision
+//!
+//! Available with the `tower` feature. [`DecisionLayer`] maps each request to
+//! input signals with a user closure, evaluates a dry-run cycle, and forwards
+//! the request only if the configured coil is energised; otherwise it
+//! responds `403 Forbidden`.
+//!
+//! ```no_run
+//! use charta::middleware::DecisionLayer;
+//! use charta::ChartaVM;
+//! use std::collections::HashMap;
+//! use std::sync::Arc;
+//! use tokio::sync::Mutex;
+//!
+//! # fn layer(vm: Arc<Mutex<ChartaVM>>) {
+//! let layer = DecisionLayer::new(vm, "allow_request", |parts: &http::request::Parts| {
+//!     HashMap::from([(
+//!         "authenticated".to_string(),
+//!         parts.headers.contains_key("authorization"),
+//!     )])
+//! });
+//! # }
+//! ```
+//!
+//! Requests are evaluated one at a time against the shared VM with
+//! [`execute_cycle_dry_run`](ChartaVM::execute_cycle_dry_run), so each sees
+//! the VM's own state plus its inputs and nothing leaks from one request to
+//! the next. Evaluation errors deny the request.
+
+use crate::vm::ChartaVM;
+use http::request::Parts;
+use http::{Request, Response, StatusCode};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::Mutex;
+use tower::{Layer, Service};
+
+/// Shared decision configuration
+struct Gate<F> {
+    vm: Arc<Mutex<ChartaVM>>,
+    /// Coil that must be energised for the request to pass
+    coil: String,
+    /// Request -> input signals
+    signals: F,
+}
+
+impl<F> Gate<F>
+where
+    F: Fn(&Parts) -> HashMap<String, bool>,
+{
+    async fn allows(&self, parts: &Parts) -> bool {
+        let inputs = (self.signals)(parts);
+        let mut vm = self.vm.lock().await;
+        match vm.execute_cycle_dry_run(inputs).await {
+            Ok(outputs) => outputs.get(&self.coil).copied().unwrap_or(false),
+            Err(_e) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    error = %_e,
+                    coil = %self.coil,
+                    "decision evaluation failed; denying request"
+                );
+                false
+            }
+        }
+    }
+}
+
+/// Layer gating requests on a coil
+pub struct DecisionLayer<F> {
+    gate: Arc<Gate<F>>,
+}
+
+impl<F> DecisionLayer<F>
+where
+    F: Fn(&Parts) -> HashMap<String, bool>,
+{
+    /// Gate requests on `coil`, deriving input signals with `signals`
+    pub fn new(vm: Arc<Mutex<ChartaVM>>, coil: &str, signals: F) -> Self {
+        Self {
+            gate: Arc::new(Gate {
+                vm,
+                coil: coil.to_string(),
+                signals,
+            }),
+        }
+    }
+}
+
+impl<F> Clone for DecisionLayer<F> {
+    fn clone(&self) -> Self {
+        Self {
+            gate: self.gate.clone(),
+        }
+    }
+}
+
+impl<S, F> Layer<S> for DecisionLayer<F> {
+    type Service = DecisionService<S, F>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DecisionService {
+            inner,
+            gate: self.gate.clone(),
+        }
+    }
+}
+
+/// Service produced by [`DecisionLayer`]
+pub struct DecisionService<S, F> {
+    inner: S,
+    gate: Arc<Gate<F>>,
+}
+
+impl<S: Clone, F> Clone for DecisionService<S, F> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            gate: self.gate.clone(),
+        }
+    }
+}
+
+impl<S, F, ReqBody, ResBody> Service<Request<ReqBody>> for DecisionService<S, F>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send,
+    F: Fn(&Parts) -> HashMap<String, bool> + Send + Sync + 'static,
+    ReqBody: Send + 'static,
+    ResBody: Default,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        // Use the service that was polled ready, leaving a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let gate = self.gate.clone();
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            if !gate.allows(&parts).await {
+                let mut response = Response::new(ResBody::default());
+                *response.status_mut() = StatusCode::FORBIDDEN;
+                return Ok(response);
+            }
+            inner.call(Request::from_parts(parts, body)).await
+        })
+    }
+}
@@ -0,0 +1,142 @@
+//! Kotlin and Swift bindings via uniffi
+//!
+//! Exposes [`MobileVM`] so Android and iOS clients can run governance
+//! programs locally instead of re-implementing policy logic. Build the
+//! library and generate bindings from it:
+//!
+//! ```text
+//! cargo rustc --release --features uniffi --crate-type cdylib
+//! uniffi-bindgen generate --library target/release/libcharta.so --language kotlin --out-dir out
+//! ```
+//!
+//! ```kotlin
+//! val vm = MobileVm()
+//! vm.loadProgram(irJson)
+//! vm.setSignal("user_submitted", true)
+//! val coils = vm.executeCycle()
+//! ```
+
+use crate::blocking;
+use crate::error::Error;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Error surfaced to Kotlin and Swift
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+pub enum MobileError {
+    /// The program could not be loaded
+    #[error("{message}")]
+    Load {
+        /// Error description
+        message: String,
+    },
+    /// A cycle or state access failed
+    #[error("{message}")]
+    Vm {
+        /// Error description
+        message: String,
+    },
+}
+
+impl MobileError {
+    fn vm(error: Error) -> Self {
+        MobileError::Vm {
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Listener notified of coil changes, implemented in Kotlin or Swift
+#[uniffi::export(callback_interface)]
+pub trait CoilListener: Send + Sync {
+    /// Called for each matching coil that changed during a cycle
+    fn on_coil_change(&self, name: String, old: bool, new: bool);
+}
+
+/// Charta VM for mobile clients
+#[derive(uniffi::Object)]
+pub struct MobileVM {
+    inner: Mutex<blocking::ChartaVM>,
+}
+
+impl MobileVM {
+    fn vm(&self) -> MutexGuard<'_, blocking::ChartaVM> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[uniffi::export]
+impl MobileVM {
+    /// Create a VM with no program loaded
+    #[uniffi::constructor]
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(blocking::ChartaVM::new()),
+        }
+    }
+
+    /// Load a program from IR JSON
+    pub fn load_program(&self, ir_json: String) -> Result<(), MobileError> {
+        self.vm()
+            .load_program(&ir_json)
+            .map(drop)
+            .map_err(|e| MobileError::Load {
+                message: e.to_string(),
+            })
+    }
+
+    /// Set a signal value
+    pub fn set_signal(&self, name: String, value: bool) -> Result<(), MobileError> {
+        self.vm().set_signal(&name, value).map_err(MobileError::vm)
+    }
+
+    /// Execute one scan cycle and return the coil states
+    pub fn execute_cycle(&self) -> Result<HashMap<String, bool>, MobileError> {
+        self.vm().execute_cycle().map_err(MobileError::vm)
+    }
+
+    /// Execute one scan cycle with input signals
+    pub fn execute_cycle_with_inputs(
+        &self,
+        inputs: HashMap<String, bool>,
+    ) -> Result<HashMap<String, bool>, MobileError> {
+        self.vm()
+            .execute_cycle_with_inputs(inputs)
+            .map_err(MobileError::vm)
+    }
+
+    /// Get a coil state, or null if unknown
+    pub fn get_coil(&self, name: String) -> Result<Option<bool>, MobileError> {
+        self.vm().get_coil(&name).map_err(MobileError::vm)
+    }
+
+    /// Get a signal state, or null if unknown
+    pub fn get_signal(&self, name: String) -> Result<Option<bool>, MobileError> {
+        self.vm().get_signal(&name).map_err(MobileError::vm)
+    }
+
+    /// Get all coil states
+    pub fn get_all_coils(&self) -> Result<HashMap<String, bool>, MobileError> {
+        self.vm().get_all_coils().map_err(MobileError::vm)
+    }
+
+    /// Register a listener for coils matching a name or pattern like `"allow_*"`
+    ///
+    /// Listeners run synchronously on the thread executing the cycle.
+    pub fn on_coil_change(&self, pattern: String, listener: Box<dyn CoilListener>) {
+        self.vm().on_coil_change(&pattern, move |name, old, new, _| {
+            listener.on_coil_change(name.to_string(), old, new);
+        });
+    }
+
+    /// Get the number of cycles executed so far
+    pub fn cycle_count(&self) -> u64 {
+        self.vm().cycle_count()
+    }
+}
+
+impl Default for MobileVM {
+    fn default() -> Self {
+        Self::new()
+    }
+}
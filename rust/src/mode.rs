@@ -0,0 +1,49 @@
+//! Simulation and production modes
+//!
+//! VMs run in [`VmMode::Simulation`] unless built otherwise. A VM built for
+//! production rejects the mutators meant for tests and debugging with
+//! [`Error::InvalidOperation`], so a debug code path left enabled cannot
+//! write coils or wind back state in a live deployment:
+//!
+//! ```no_run
+//! use charta::{ChartaVM, VmMode};
+//!
+//! # async fn example() -> charta::Result<()> {
+//! let mut vm = ChartaVM::builder().mode(VmMode::Production).build();
+//! vm.load_program_from_file("program.ir.json").await?;
+//! assert!(vm.set_coil("allow_operation", true).await.is_err());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Production mode rejects `set_coil`, safety coil overrides
+//! (`request_safety_override` / `set_safety_coil`), `step_single_rung`,
+//! `rewind`, and `replay_forward`. The mode is fixed when the VM is built.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// Whether test-oriented mutators are permitted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VmMode {
+    /// Permit every operation
+    #[default]
+    Simulation,
+    /// Reject test-oriented mutators
+    Production,
+}
+
+impl VmMode {
+    /// Fail with [`Error::InvalidOperation`] if `operation` is not
+    /// permitted in this mode
+    pub(crate) fn permit(self, operation: &str) -> Result<()> {
+        match self {
+            VmMode::Simulation => Ok(()),
+            VmMode::Production => Err(Error::InvalidOperation(format!(
+                "{} is not permitted in production mode",
+                operation
+            ))),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Hierarchical names for Charta signals and coils
+//!
+//! Names may be dotted paths such as `motor.start` or
+//! `governance.compliance_ok`. Everything before the last dot is the name's
+//! namespace; namespaces nest (`plant.line1.motor.start` is in `plant`,
+//! `plant.line1`, and `plant.line1.motor`).
+
+use crate::error::{Error, Result};
+use crate::ir::Program;
+use std::collections::{BTreeSet, HashSet};
+
+/// Separator between namespace segments
+pub const SEPARATOR: char = '.';
+
+/// Validate a dotted name: non-empty segments separated by single dots
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(Error::InvalidOperation("Name cannot be empty".to_string()));
+    }
+    if name.split(SEPARATOR).any(str::is_empty) {
+        return Err(Error::InvalidOperation(format!(
+            "Name '{}' has an empty namespace segment",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Get the namespace of a name (`motor.start` -> `Some("motor")`)
+pub fn namespace_of(name: &str) -> Option<&str> {
+    name.rfind(SEPARATOR).map(|i| &name[..i])
+}
+
+/// Get the last segment of a name (`motor.start` -> `start`)
+pub fn local_name(name: &str) -> &str {
+    name.rfind(SEPARATOR).map_or(name, |i| &name[i + 1..])
+}
+
+/// Check whether a name lies in a namespace, at any depth
+pub fn in_namespace(name: &str, namespace: &str) -> bool {
+    namespace.is_empty()
+        || (name.len() > namespace.len()
+            && name.starts_with(namespace)
+            && name[namespace.len()..].starts_with(SEPARATOR))
+}
+
+/// Get every namespace used by a set of names, including parents
+pub fn namespaces<'a, I>(names: I) -> BTreeSet<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut namespaces = BTreeSet::new();
+    for name in names {
+        let mut current = name;
+        while let Some(namespace) = namespace_of(current) {
+            namespaces.insert(namespace.to_string());
+            current = namespace;
+        }
+    }
+    namespaces
+}
+
+/// Validate the names declared by a program
+///
+/// Rejects malformed names, duplicate declarations, and names that are also
+/// used as a namespace (declaring both `motor` and `motor.start`).
+pub fn validate_program(program: &Program) -> Result<()> {
+    let module = &program.module;
+    let mut declared = HashSet::new();
+
+    let names = module
+        .signals
+        .iter()
+        .map(|s| s.name.as_str())
+        .chain(module.coils.iter().map(|c| c.name.as_str()))
+        .chain(module.registers.iter().map(|r| r.name.as_str()));
+
+    for name in names.clone() {
+        validate_name(name).map_err(|e| Error::IRLoad(e.to_string()))?;
+        if !declared.insert(name) {
+            return Err(Error::IRLoad(format!("Duplicate declaration of '{}'", name)));
+        }
+    }
+
+    for namespace in namespaces(names) {
+        if declared.contains(namespace.as_str()) {
+            return Err(Error::IRLoad(format!(
+                "'{}' is declared as a name but also used as a namespace",
+                namespace
+            )));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,444 @@
+//! Read-only observer handles for Charta VM
+//!
+//! A [`ChartaObserver`] shares state with the VM it was created from but only
+//! exposes getters, event streams, statistics, and history. Hand it to
+//! dashboards and monitoring code that must not mutate governance state.
+
+use crate::bypass::{Bypasses, RungBypass};
+use crate::coverage::CoverageReport;
+use crate::engine::Engine;
+use crate::error::Result;
+use crate::events::{
+    CoilEventReceiver, EventReceiver, SubscriberOptions, SubscriberQueue, Subscription, VmEvent,
+};
+use crate::history::History;
+use crate::ir::{Metadata, Program, Rung, RungInfo};
+use crate::load::LoadReport;
+use crate::namespace;
+use crate::outputs::CycleOutputs;
+use crate::pattern::Pattern;
+use crate::quality::{Quality, QualityTracker};
+use crate::scan::Scanner;
+use crate::snapshot::StateSnapshot;
+use crate::stats::{CoilStats, StatsTracker};
+use crate::value::Value;
+use arc_swap::ArcSwap;
+use charta_vm::VM;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock as StdRwLock, Weak};
+use tokio::sync::{broadcast, Notify, RwLock};
+
+/// The loaded program and its identity
+#[derive(Default)]
+pub(crate) struct LoadedProgram {
+    /// SDK-side model of the program
+    pub(crate) program: Option<Arc<Program>>,
+    /// Hash identifying the program
+    pub(crate) id: Option<String>,
+    /// What the load disabled or ignored
+    pub(crate) report: LoadReport,
+    /// Engine over the program the VM core scans, for batch evaluation;
+    /// compiled with the `jit` feature
+    pub(crate) engine: Option<Arc<Engine>>,
+    /// What the SDK runs around each scan
+    pub(crate) scanner: Arc<Scanner>,
+}
+
+/// State shared between a VM and its observers
+#[derive(Default)]
+pub(crate) struct SharedState {
+    /// Number of cycles executed
+    pub(crate) cycle_count: AtomicU64,
+    /// Currently loaded program
+    pub(crate) program: StdRwLock<LoadedProgram>,
+    /// Per-coil statistics
+    pub(crate) stats: Mutex<StatsTracker>,
+    /// Opt-in history of recent coil changes and cycles
+    pub(crate) history: Mutex<Option<History>>,
+    /// Opt-in rung and branch coverage
+    pub(crate) coverage: Mutex<Option<CoverageReport>>,
+    /// Latest published signal and coil states
+    pub(crate) snapshot: ArcSwap<StateSnapshot>,
+    /// Values of typed signals
+    pub(crate) values: Mutex<HashMap<String, Value>>,
+    /// Values of registers
+    pub(crate) registers: Mutex<HashMap<String, Value>>,
+    /// Quality of signal values
+    pub(crate) quality: Mutex<QualityTracker>,
+    /// Rungs disabled at runtime
+    pub(crate) bypasses: Mutex<Bypasses>,
+    /// Buffers of subscriptions with their own backpressure policy
+    pub(crate) subscribers: Mutex<Vec<Weak<SubscriberQueue>>>,
+    /// Bumped whenever a write changes the published signals
+    pub(crate) signal_version: AtomicU64,
+    /// Signal version as of the last cycle
+    pub(crate) scanned_version: AtomicU64,
+    /// Woken when a write changes the published signals
+    pub(crate) signal_changed: Notify,
+}
+
+impl SharedState {
+    pub(crate) fn stats(&self) -> MutexGuard<'_, StatsTracker> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn history(&self) -> MutexGuard<'_, Option<History>> {
+        self.history.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn coverage(&self) -> MutexGuard<'_, Option<CoverageReport>> {
+        self.coverage.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn values(&self) -> MutexGuard<'_, HashMap<String, Value>> {
+        self.values.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn registers(&self) -> MutexGuard<'_, HashMap<String, Value>> {
+        self.registers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn quality(&self) -> MutexGuard<'_, QualityTracker> {
+        self.quality.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn bypasses(&self) -> MutexGuard<'_, Bypasses> {
+        self.bypasses.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn loaded(&self) -> std::sync::RwLockReadGuard<'_, LoadedProgram> {
+        self.program.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn set_loaded(&self, loaded: LoadedProgram) {
+        *self.program.write().unwrap_or_else(|e| e.into_inner()) = loaded;
+    }
+
+    pub(crate) fn snapshot(&self) -> Arc<StateSnapshot> {
+        self.snapshot.load_full()
+    }
+
+    /// Publish the states of `vm` with the outputs of the last cycle
+    ///
+    /// Callers hold the VM write lock, so publications follow writes in order.
+    pub(crate) fn publish(&self, vm: &VM, outputs: Arc<CycleOutputs>) {
+        let cycle = self.cycle_count.load(Ordering::SeqCst);
+        let snapshot = self.snapshot().with_state(vm, cycle, outputs);
+        self.snapshot.store(Arc::new(snapshot));
+    }
+
+    /// Record that a cycle has scanned the published signals
+    pub(crate) fn mark_scanned(&self) {
+        let version = self.signal_version.load(Ordering::SeqCst);
+        self.scanned_version.store(version, Ordering::SeqCst);
+    }
+
+    /// Publish the signal states of `vm`, keeping the published coils
+    pub(crate) fn publish_signals(&self, vm: &VM) {
+        let previous = self.snapshot();
+        let snapshot = previous.with_signals(vm);
+        let changed = snapshot.signals != previous.signals;
+        self.snapshot.store(Arc::new(snapshot));
+        if changed {
+            self.signal_version.fetch_add(1, Ordering::SeqCst);
+            self.signal_changed.notify_one();
+        }
+    }
+
+    /// Wait until a write has changed the signals since the last cycle
+    pub(crate) async fn signals_changed(&self) {
+        while self.signal_version.load(Ordering::SeqCst)
+            == self.scanned_version.load(Ordering::SeqCst)
+        {
+            self.signal_changed.notified().await;
+        }
+    }
+
+    pub(crate) fn subscribers(&self) -> MutexGuard<'_, Vec<Weak<SubscriberQueue>>> {
+        self.subscribers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for SharedState {
+    fn drop(&mut self) {
+        for subscriber in self.subscribers().iter().filter_map(Weak::upgrade) {
+            subscriber.close();
+        }
+    }
+}
+
+/// Read-only handle to a VM
+///
+/// Cheap to clone; every clone observes the same VM.
+#[derive(Clone)]
+pub struct ChartaObserver {
+    pub(crate) vm: Arc<RwLock<VM>>,
+    pub(crate) events: broadcast::Sender<VmEvent>,
+    pub(crate) state: Arc<SharedState>,
+}
+
+impl ChartaObserver {
+    pub(crate) fn new(vm: VM, event_capacity: usize) -> Self {
+        Self {
+            vm: Arc::new(RwLock::new(vm)),
+            events: broadcast::channel(event_capacity).0,
+            state: Arc::new(SharedState::default()),
+        }
+    }
+
+    /// Get the SDK-side model of the loaded program
+    pub(crate) fn program(&self) -> Option<Arc<Program>> {
+        self.state.loaded().program.clone()
+    }
+
+    pub(crate) fn engine(&self) -> Option<Arc<Engine>> {
+        self.state.loaded().engine.clone()
+    }
+
+    /// Get the latest published signal and coil states
+    ///
+    /// Never waits for the VM lock. Reads from one snapshot are mutually
+    /// consistent; separate getter calls may see different cycles.
+    pub fn snapshot(&self) -> Arc<StateSnapshot> {
+        self.state.snapshot()
+    }
+
+    /// Get the current state of a coil
+    pub async fn get_coil(&self, name: &str) -> Result<Option<bool>> {
+        Ok(self.snapshot().get_coil(name))
+    }
+
+    /// Get the current state of a signal
+    pub async fn get_signal(&self, name: &str) -> Result<Option<bool>> {
+        Ok(self.snapshot().get_signal(name))
+    }
+
+    /// Get all coil states
+    pub async fn get_all_coils(&self) -> Result<HashMap<String, bool>> {
+        Ok(self.snapshot().coils().clone())
+    }
+
+    /// Get all signal states
+    pub async fn get_all_signals(&self) -> Result<HashMap<String, bool>> {
+        Ok(self.snapshot().signals().clone())
+    }
+
+    /// Get the value of a signal, typed or boolean
+    pub async fn get_value(&self, name: &str) -> Result<Option<Value>> {
+        if let Some(value) = self.state.values().get(name) {
+            return Ok(Some(value.clone()));
+        }
+        Ok(self.snapshot().get_signal(name).map(Value::Bool))
+    }
+
+    /// Get the values of all typed signals
+    pub async fn get_all_values(&self) -> Result<HashMap<String, Value>> {
+        Ok(self.state.values().clone())
+    }
+
+    /// Get the quality of a signal's value
+    ///
+    /// Signals never written with a quality are [`Quality::Good`].
+    pub async fn get_signal_quality(&self, name: &str) -> Result<Quality> {
+        Ok(self.state.quality().quality(name))
+    }
+
+    /// Get the value of a register
+    pub async fn get_register(&self, name: &str) -> Result<Option<Value>> {
+        Ok(self.state.registers().get(name).cloned())
+    }
+
+    /// Get the values of all registers
+    pub async fn get_all_registers(&self) -> Result<HashMap<String, Value>> {
+        Ok(self.state.registers().clone())
+    }
+
+    /// Get all signal states within a namespace
+    ///
+    /// `signals_in("governance")` returns `governance.compliance_ok`,
+    /// `governance.review.done`, and so on.
+    pub async fn signals_in(&self, namespace: &str) -> Result<HashMap<String, bool>> {
+        Ok(in_namespace(self.snapshot().signals(), namespace))
+    }
+
+    /// Get all coil states within a namespace
+    pub async fn coils_in(&self, namespace: &str) -> Result<HashMap<String, bool>> {
+        Ok(in_namespace(self.snapshot().coils(), namespace))
+    }
+
+    /// Get every namespace used by signal or coil names, sorted
+    pub async fn namespaces(&self) -> Result<Vec<String>> {
+        let snapshot = self.snapshot();
+        let names = snapshot
+            .signal_names()
+            .iter()
+            .chain(snapshot.coil_names())
+            .map(String::as_str);
+        Ok(namespace::namespaces(names).into_iter().collect())
+    }
+
+    /// Get signal names
+    pub async fn signal_names(&self) -> Result<Vec<String>> {
+        Ok(self.snapshot().signal_names().to_vec())
+    }
+
+    /// Get coil names
+    pub async fn coil_names(&self) -> Result<Vec<String>> {
+        Ok(self.snapshot().coil_names().to_vec())
+    }
+
+    /// Get the metadata declared for a signal
+    pub fn signal_meta(&self, name: &str) -> Option<Metadata> {
+        let program = self.program()?;
+        program
+            .module
+            .signals
+            .iter()
+            .find(|signal| signal.name == name)
+            .map(|signal| signal.meta.clone())
+    }
+
+    /// Get the metadata declared for a coil
+    pub fn coil_meta(&self, name: &str) -> Option<Metadata> {
+        let program = self.program()?;
+        program
+            .module
+            .coils
+            .iter()
+            .find(|coil| coil.name == name)
+            .map(|coil| coil.meta.clone())
+    }
+
+    /// Get the names of all signals and coils carrying a tag
+    pub fn find_by_tag(&self, tag: &str) -> Vec<String> {
+        let Some(program) = self.program() else {
+            return Vec::new();
+        };
+        let module = &program.module;
+        module
+            .signals
+            .iter()
+            .map(|signal| (&signal.name, &signal.meta))
+            .chain(module.coils.iter().map(|coil| (&coil.name, &coil.meta)))
+            .filter(|(_, meta)| meta.has_tag(tag))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Get the hash identifying the loaded program
+    pub fn program_id(&self) -> Option<String> {
+        self.state.loaded().id.clone()
+    }
+
+    /// Get the loaded rungs' names in scan order
+    pub fn rung_order(&self) -> Vec<String> {
+        let Some(program) = self.program() else {
+            return Vec::new();
+        };
+        program.module.rungs.iter().map(|rung| rung.name.clone()).collect()
+    }
+
+    /// Get the structure of the loaded rungs in scan order
+    pub fn rungs(&self) -> Vec<RungInfo> {
+        let Some(program) = self.program() else {
+            return Vec::new();
+        };
+        program.module.rungs.iter().map(Rung::info).collect()
+    }
+
+    /// Get the rungs currently disabled, in the order they were disabled
+    ///
+    /// Non-empty means the program is not running as written; surface it
+    /// wherever the VM's status is shown.
+    pub fn disabled_rungs(&self) -> Vec<RungBypass> {
+        self.state.bypasses().list().to_vec()
+    }
+
+    /// Get the report of the last successful program load
+    pub fn load_report(&self) -> Option<LoadReport> {
+        let loaded = self.state.loaded();
+        loaded.id.as_ref().map(|_| loaded.report.clone())
+    }
+
+    /// Get the number of cycles executed so far
+    pub fn cycle_count(&self) -> u64 {
+        self.state.cycle_count.load(Ordering::SeqCst)
+    }
+
+    /// Get statistics for a coil
+    ///
+    /// Returns `None` if the coil has not appeared in any cycle output since
+    /// the program was loaded.
+    pub fn coil_stats(&self, name: &str) -> Option<CoilStats> {
+        self.state.stats().get(name)
+    }
+
+    /// Get statistics for all coils
+    pub fn all_coil_stats(&self) -> HashMap<String, CoilStats> {
+        self.state.stats().all()
+    }
+
+    /// Get a snapshot of the recorded history, if enabled
+    pub fn history(&self) -> Option<History> {
+        self.state.history().clone()
+    }
+
+    /// Get a snapshot of the recorded coverage, if enabled
+    pub fn coverage_report(&self) -> Option<CoverageReport> {
+        self.state.coverage().clone()
+    }
+
+    /// Subscribe to the VM event stream
+    ///
+    /// Each receiver sees every event emitted after it subscribed. Slow
+    /// receivers that fall more than the channel capacity behind observe
+    /// `RecvError::Lagged` and skip the missed events.
+    pub fn subscribe(&self) -> EventReceiver {
+        self.events.subscribe()
+    }
+
+    /// Subscribe to changes of coils matching a name or pattern
+    ///
+    /// Accepts exact names or patterns like `"allow_*"` and `"safety/**"`.
+    pub fn subscribe_coils(&self, pattern: &str) -> CoilEventReceiver {
+        CoilEventReceiver::new(self.events.subscribe(), Pattern::new(pattern))
+    }
+
+    /// Subscribe to the event stream with a dedicated buffer
+    ///
+    /// See [`SubscriberOptions`] for the buffer size and backpressure policy.
+    pub fn subscribe_with(&self, options: SubscriberOptions) -> Subscription {
+        let subscription = Subscription::new(options);
+        self.state
+            .subscribers()
+            .push(Arc::downgrade(subscription.queue()));
+        subscription
+    }
+
+    /// Publish an event to every subscriber
+    ///
+    /// Never waits: full buffers apply their backpressure policy instead.
+    pub(crate) fn emit(&self, event: VmEvent) {
+        {
+            let mut subscribers = self.state.subscribers();
+            subscribers.retain(|subscriber| match subscriber.upgrade() {
+                Some(subscriber) => {
+                    subscriber.push(event.clone());
+                    true
+                }
+                None => false,
+            });
+        }
+        // Sending fails only when nobody listens
+        let _ = self.events.send(event);
+    }
+}
+
+fn in_namespace(states: &HashMap<String, bool>, namespace: &str) -> HashMap<String, bool> {
+    states
+        .iter()
+        .filter(|(name, _)| namespace::in_namespace(name, namespace))
+        .map(|(name, value)| (name.clone(), *value))
+        .collect()
+}
@@ -0,0 +1,143 @@
+//! Rung scan order
+//!
+//! Rungs scan in document order unless they declare a `priority`: higher
+//! priorities scan first, and rungs of equal priority keep their document
+//! order. Within a scan a coil reads as last written, so order decides
+//! what a rung sees of coils driven by later rungs, and which rung wins a
+//! coil driven by several:
+//!
+//! ```json
+//! {"name": "safety_trip", "priority": 10, "guard": {...}, "actions": [...]}
+//! ```
+//!
+//! [`analyze`] reports the order dependencies of a program; the load report
+//! carries them for every loaded program, and
+//! [`set_rung_order`](crate::ChartaVM::set_rung_order) returns them for the
+//! order it sets.
+
+use crate::error::{Error, Result};
+use crate::ir::{Guard, Program};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Order dependency worth reviewing in a program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OrderIssue {
+    /// `reader` reads `coil` before `writer` drives it, so it sees the
+    /// coil's state from the previous scan
+    ReadBeforeWrite {
+        /// Rung reading the coil
+        reader: String,
+        /// Later rung driving the coil
+        writer: String,
+        /// Coil read
+        coil: String,
+    },
+    /// Several rungs drive `coil`; the last one in scan order wins
+    SharedCoil {
+        /// Coil driven
+        coil: String,
+        /// Rungs driving it, in scan order
+        rungs: Vec<String>,
+    },
+}
+
+/// Get the order dependencies of `program`, in scan order
+pub fn analyze(program: &Program) -> Vec<OrderIssue> {
+    let rungs = &program.module.rungs;
+    let coils: HashSet<&str> = program
+        .module
+        .coils
+        .iter()
+        .map(|coil| coil.name.as_str())
+        .collect();
+
+    let mut issues = Vec::new();
+    for (index, rung) in rungs.iter().enumerate() {
+        let mut reads = Vec::new();
+        collect_contacts(&rung.guard, &mut reads);
+        reads.retain(|name| coils.contains(name));
+        for coil in reads {
+            for writer in &rungs[index + 1..] {
+                if writer.target_coils().any(|target| target == coil) {
+                    issues.push(OrderIssue::ReadBeforeWrite {
+                        reader: rung.name.clone(),
+                        writer: writer.name.clone(),
+                        coil: coil.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut writers: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for rung in rungs {
+        for coil in rung.target_coils() {
+            let names = writers.entry(coil).or_default();
+            if !names.contains(&rung.name) {
+                names.push(rung.name.clone());
+            }
+        }
+    }
+    issues.extend(
+        writers
+            .into_iter()
+            .filter(|(_, rungs)| rungs.len() > 1)
+            .map(|(coil, rungs)| OrderIssue::SharedCoil {
+                coil: coil.to_string(),
+                rungs,
+            }),
+    );
+    issues
+}
+
+/// Sort rungs into scan order: higher priority first, then document order
+pub(crate) fn sort(program: &mut Program) {
+    program
+        .module
+        .rungs
+        .sort_by_key(|rung| std::cmp::Reverse(rung.priority));
+}
+
+/// Rearrange rungs into `order`, which must name each exactly once
+///
+/// Priorities are cleared so the order survives [`sort`].
+pub(crate) fn arrange(program: &mut Program, order: &[String]) -> Result<()> {
+    let mut rungs = program.module.rungs.clone();
+    let mut arranged = Vec::with_capacity(rungs.len());
+    for name in order {
+        let index = rungs
+            .iter()
+            .position(|rung| &rung.name == name)
+            .ok_or_else(|| {
+                Error::InvalidOperation(format!(
+                    "Rung order names '{}', which is not a rung or is listed twice",
+                    name
+                ))
+            })?;
+        let mut rung = rungs.remove(index);
+        rung.priority = 0;
+        arranged.push(rung);
+    }
+    if let Some(rung) = rungs.first() {
+        return Err(Error::InvalidOperation(format!(
+            "Rung order omits rung '{}'",
+            rung.name
+        )));
+    }
+    program.module.rungs = arranged;
+    Ok(())
+}
+
+/// Collect the names a guard's contacts read, without duplicates
+fn collect_contacts<'a>(guard: &'a Guard, out: &mut Vec<&'a str>) {
+    if let Guard::Contact { name, .. } = guard {
+        if !out.contains(&name.as_str()) {
+            out.push(name);
+        }
+    }
+    for operand in guard.operands() {
+        collect_contacts(operand, out);
+    }
+}
@@ -0,0 +1,104 @@
+//! OpenTelemetry decision spans for Charta VM
+//!
+//! Available with the `otel` feature. Attach an [`OtelExporter`] to a VM with
+//! [`ChartaVM::set_otel_exporter`](crate::ChartaVM::set_otel_exporter) to emit
+//! one span per scan cycle, carrying the cycle's input signals and changed
+//! coils as attributes.
+
+use crate::error::{Error, Result};
+use opentelemetry::global::{self, BoxedTracer};
+use opentelemetry::trace::{Span, Tracer, TracerProvider as _};
+use opentelemetry::{Array, KeyValue, StringValue, Value};
+use opentelemetry_sdk::trace::TracerProvider;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::time::SystemTime;
+
+/// Instrumentation scope name used for cycle spans
+pub const TRACER_NAME: &str = "charta";
+
+/// Emits one OpenTelemetry span per VM cycle
+pub struct OtelExporter {
+    tracer: BoxedTracer,
+    /// Provider owned by this exporter, if it created one
+    provider: Option<TracerProvider>,
+}
+
+impl OtelExporter {
+    /// Create an exporter using the globally installed tracer provider
+    pub fn from_global() -> Self {
+        Self {
+            tracer: global::tracer(TRACER_NAME),
+            provider: None,
+        }
+    }
+
+    /// Create an exporter sending spans to an OTLP/gRPC collector
+    pub fn otlp(endpoint: &str) -> Result<Self> {
+        use opentelemetry_otlp::WithExportConfig;
+
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint)
+            .build()
+            .map_err(|e| Error::Telemetry(e.to_string()))?;
+        let provider = TracerProvider::builder()
+            .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+            .build();
+
+        Ok(Self {
+            tracer: BoxedTracer::new(Box::new(provider.tracer(TRACER_NAME))),
+            provider: Some(provider),
+        })
+    }
+
+    /// Record a completed cycle as a span
+    pub fn record_cycle<S: BuildHasher>(
+        &self,
+        cycle: u64,
+        program_id: Option<&str>,
+        inputs: &HashMap<String, bool>,
+        changes: &HashMap<String, (bool, bool), S>,
+        started: SystemTime,
+    ) {
+        let mut attributes = Vec::with_capacity(inputs.len() + changes.len() + 3);
+        attributes.push(KeyValue::new("charta.cycle", cycle as i64));
+        if let Some(program_id) = program_id {
+            attributes.push(KeyValue::new("charta.program_id", program_id.to_string()));
+        }
+
+        let mut changed: Vec<&String> = changes.keys().collect();
+        changed.sort();
+        attributes.push(KeyValue::new(
+            "charta.changed_coils",
+            Value::Array(Array::String(
+                changed.iter().map(|name| StringValue::from(name.to_string())).collect(),
+            )),
+        ));
+
+        for (name, value) in inputs {
+            attributes.push(KeyValue::new(format!("charta.input.{}", name), *value));
+        }
+        for (name, (_, new_value)) in changes {
+            attributes.push(KeyValue::new(format!("charta.coil.{}", name), *new_value));
+        }
+
+        let mut span = self
+            .tracer
+            .span_builder("charta.cycle")
+            .with_start_time(started)
+            .with_attributes(attributes)
+            .start(&self.tracer);
+        span.end();
+    }
+
+    /// Flush and shut down the provider created by [`OtelExporter::otlp`]
+    pub fn shutdown(&self) -> Result<()> {
+        if let Some(provider) = &self.provider {
+            provider
+                .shutdown()
+                .map_err(|e| Error::Telemetry(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
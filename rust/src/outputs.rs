@@ -0,0 +1,162 @@
+//! Coil outputs of a scan cycle
+//!
+//! Each cycle's outputs hold the coil states after the cycle and the states
+//! before it. The states before are the previous cycle's coil map, shared
+//! rather than copied, so detecting changes never clones the coil map.
+//! [`ChartaVM::execute_cycle`](crate::ChartaVM::execute_cycle) returns them
+//! as an `Arc` shared with [`VmEvent::CycleCompleted`](crate::VmEvent)
+//! subscribers rather than copied for each consumer.
+//!
+//! ```rust,ignore
+//! let outputs = vm.execute_cycle().await?;
+//! if outputs.get("allow_review") == Some(&true) {
+//!     // ...
+//! }
+//! for (name, old, new) in outputs.changes() {
+//!     println!("{}: {} -> {}", name, old, new);
+//! }
+//! ```
+//!
+//! [`ChartaVM::execute_cycle_detailed`](crate::ChartaVM::execute_cycle_detailed)
+//! returns the outputs together with the changes as owned [`CoilChange`]s:
+//!
+//! ```rust,ignore
+//! let cycle = vm.execute_cycle_detailed().await?;
+//! for change in &cycle.changes {
+//!     println!("{}: {} -> {}", change.name, change.old, change.new);
+//! }
+//! ```
+
+use crate::ir::RungEvaluation;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Coil states after a cycle, alongside the states before it
+///
+/// Dereferences to the map of coil names to their new states.
+///
+/// Serializes as `{"cycle", "coils", "previous"}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CycleOutputs {
+    cycle: u64,
+    #[serde(rename = "coils")]
+    current: Arc<HashMap<String, bool>>,
+    previous: Arc<HashMap<String, bool>>,
+}
+
+impl CycleOutputs {
+    /// Cycle that produced these outputs (0 before the first cycle)
+    pub fn cycle(&self) -> u64 {
+        self.cycle
+    }
+
+    /// Coil states after the cycle
+    pub fn coils(&self) -> &HashMap<String, bool> {
+        &self.current
+    }
+
+    /// State of a coil before the cycle
+    pub fn previous(&self, name: &str) -> Option<bool> {
+        self.previous.get(name).copied()
+    }
+
+    /// Whether a coil changed state during the cycle
+    pub fn changed(&self, name: &str) -> bool {
+        match self.current.get(name) {
+            Some(&new) => self.previous(name).unwrap_or(false) != new,
+            None => false,
+        }
+    }
+
+    /// Coils that changed state, as `(name, old, new)`
+    ///
+    /// Coils absent before the cycle are treated as de-energised.
+    pub fn changes(&self) -> impl Iterator<Item = (&str, bool, bool)> + '_ {
+        self.current.iter().filter_map(|(name, &new)| {
+            let old = self.previous(name).unwrap_or(false);
+            (old != new).then_some((name.as_str(), old, new))
+        })
+    }
+
+    /// Coils that changed state, sorted by name
+    ///
+    /// Coils absent before the cycle are treated as de-energised.
+    pub fn coil_changes(&self) -> Vec<CoilChange> {
+        let mut changes: Vec<CoilChange> = self
+            .changes()
+            .map(|(name, old, new)| CoilChange {
+                name: name.to_string(),
+                old,
+                new,
+            })
+            .collect();
+        changes.sort_by(|a, b| a.name.cmp(&b.name));
+        changes
+    }
+
+    /// Outputs standing for the coil states of a freshly loaded program
+    pub(crate) fn loaded(coils: HashMap<String, bool>) -> Self {
+        Self {
+            cycle: 0,
+            current: Arc::new(coils),
+            previous: Arc::default(),
+        }
+    }
+
+    /// Outputs of the cycle after `last`
+    ///
+    /// Shares the coil map of `last` as the previous states.
+    pub(crate) fn next(last: &Self, cycle: u64, outputs: HashMap<String, bool>) -> Self {
+        Self {
+            cycle,
+            current: Arc::new(outputs),
+            previous: Arc::clone(&last.current),
+        }
+    }
+
+    /// Overwrite a coil between cycles
+    ///
+    /// Copies the coil map first if a snapshot or subscriber still shares it.
+    pub(crate) fn set(&mut self, name: &str, value: bool) {
+        let current = Arc::make_mut(&mut self.current);
+        match current.get_mut(name) {
+            Some(state) => *state = value,
+            None => {
+                current.insert(name.to_string(), value);
+            }
+        }
+    }
+}
+
+impl Deref for CycleOutputs {
+    type Target = HashMap<String, bool>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.current
+    }
+}
+
+/// A coil that changed state during a cycle
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CoilChange {
+    /// Coil name
+    pub name: String,
+    /// State before the cycle
+    pub old: bool,
+    /// State after the cycle
+    pub new: bool,
+}
+
+/// Outputs of a cycle together with the coils it changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleDetails {
+    /// Coil states after the cycle, shared with event subscribers
+    pub outputs: Arc<CycleOutputs>,
+    /// Coils that changed state, sorted by name
+    pub changes: Vec<CoilChange>,
+    /// Evaluation of every rung in scan order, if the cycle was traced with
+    /// [`CycleOptions::trace`](crate::CycleOptions::trace)
+    pub evaluations: Vec<RungEvaluation>,
+}
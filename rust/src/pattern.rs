@@ -0,0 +1,79 @@
+//! Name patterns for Charta VM subscriptions
+//!
+//! Patterns select signals or coils by name:
+//!
+//! - `*` matches any run of characters within one segment
+//! - `**` matches any run of characters across segments
+//! - `?` matches a single character other than a separator
+//! - anything else matches literally
+//!
+//! Segments are separated by `/` or by the namespace separator `.`.
+//!
+//! A lone `"*"` matches every name, so existing wildcard subscriptions keep
+//! working for namespaced names.
+
+/// Compiled name pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    source: String,
+}
+
+impl Pattern {
+    /// Create a pattern
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            source: pattern.to_string(),
+        }
+    }
+
+    /// Check whether a string contains pattern syntax
+    pub fn is_pattern(pattern: &str) -> bool {
+        pattern.contains(['*', '?'])
+    }
+
+    /// Get the pattern source
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Check whether a name matches the pattern
+    pub fn matches(&self, name: &str) -> bool {
+        if self.source == "*" {
+            return true;
+        }
+        let pattern: Vec<char> = self.source.chars().collect();
+        let name: Vec<char> = name.chars().collect();
+        match_from(&pattern, &name)
+    }
+}
+
+fn is_separator(c: char) -> bool {
+    c == '/' || c == crate::namespace::SEPARATOR
+}
+
+fn match_from(pattern: &[char], name: &[char]) -> bool {
+    match pattern.first() {
+        None => name.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let rest = &pattern[2..];
+            (0..=name.len()).any(|i| match_from(rest, &name[i..]))
+        }
+        Some('*') => {
+            let rest = &pattern[1..];
+            for i in 0..=name.len() {
+                if match_from(rest, &name[i..]) {
+                    return true;
+                }
+                if name.get(i).copied().is_some_and(is_separator) {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => match name.first() {
+            Some(c) if !is_separator(*c) => match_from(&pattern[1..], &name[1..]),
+            _ => false,
+        },
+        Some(c) => name.first() == Some(c) && match_from(&pattern[1..], &name[1..]),
+    }
+}
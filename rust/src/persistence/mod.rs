@@ -0,0 +1,243 @@
+//! Persisting VM state across restarts
+//!
+//! Coil states live in memory, so a restarted service starts with every
+//! latched coil released. A [`StateStore`] keeps [`Checkpoint`]s of the
+//! signal states and the states of coils marked `"retained": true` in the
+//! IR; a VM built with
+//! [`persist_to`](crate::ChartaVMBuilder::persist_to) saves one
+//! periodically and restores the latest when its first program loads:
+//!
+//! ```no_run
+//! use charta::persistence::FileStore;
+//! use charta::ChartaVM;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> charta::Result<()> {
+//! let mut vm = ChartaVM::builder()
+//!     .persist_to(FileStore::new("/var/lib/charta/state.json"), Duration::from_secs(5))
+//!     .build();
+//! // Restores the coils saved by the previous run, if any
+//! vm.load_program_from_file("program.ir.json").await?;
+//! vm.execute_cycle().await?;
+//! // Save now rather than at the next interval
+//! vm.save_checkpoint().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Checkpoints are taken after a cycle once the interval has elapsed since
+//! the last one. Save failures go to the
+//! [`on_error`](crate::ChartaVM::on_error) hook with
+//! [`ErrorPhase::Persistence`](crate::ErrorPhase::Persistence) and do not
+//! fail the cycle.
+//!
+//! Coils not marked retained are transient, as on a PLC: checkpoints leave
+//! them out, and they restart at their initial state. Checkpoints saved
+//! with transient coils restore only the retained ones on load.
+//!
+//! The `sled` and `sqlite` features add stores keeping a history of
+//! checkpoints, pruned according to a [`Retention`]; the `redis` feature adds
+//! a store shared between replicas.
+
+use crate::error::Result;
+use crate::io::async_trait;
+use crate::ir::Program;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "sled")]
+pub mod sled;
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+#[cfg(feature = "redis")]
+pub mod redis;
+
+/// Saved coil and signal states of a VM
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Program the states were taken from
+    pub program_id: Option<String>,
+    /// Cycles executed when the checkpoint was taken
+    pub cycle: u64,
+    /// When the checkpoint was taken
+    #[serde(rename = "saved_at_ms", with = "crate::serde_time::unix_millis")]
+    pub saved_at: SystemTime,
+    /// Coil states, including latched coils
+    pub coils: HashMap<String, bool>,
+    /// Signal states
+    pub signals: HashMap<String, bool>,
+}
+
+impl Checkpoint {
+    /// Write the states of coils and signals `vm` declares into it
+    ///
+    /// Names the program no longer declares are skipped. Returns the number
+    /// of states restored.
+    pub(crate) fn apply(&self, vm: &mut charta_vm::VM) -> usize {
+        let coils: HashSet<String> = vm.coil_names().iter().cloned().collect();
+        let signals: HashSet<String> = vm.signal_names().iter().cloned().collect();
+        let mut restored = 0;
+        for (name, value) in &self.coils {
+            if coils.contains(name) {
+                vm.set_coil(name.clone(), *value);
+                restored += 1;
+            }
+        }
+        for (name, value) in &self.signals {
+            if signals.contains(name) {
+                vm.set_signal(name.clone(), *value);
+                restored += 1;
+            }
+        }
+        restored
+    }
+
+    /// Drop the states of coils `program` does not mark retained
+    pub(crate) fn retain_coils(&mut self, program: &Program) {
+        self.coils.retain(|name, _| {
+            program
+                .module
+                .coils
+                .iter()
+                .any(|coil| coil.retained && &coil.name == name)
+        });
+    }
+}
+
+/// Durable storage for VM checkpoints
+///
+/// A store holds the state of one VM; give each VM its own store.
+#[async_trait]
+pub trait StateStore: Send + Sync {
+    /// Replace the stored checkpoint
+    async fn save(&self, checkpoint: &Checkpoint) -> Result<()>;
+
+    /// Read the stored checkpoint, if one was saved
+    async fn load(&self) -> Result<Option<Checkpoint>>;
+}
+
+/// Which checkpoints a store keeping a history retains
+///
+/// The newest checkpoint is always kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retention {
+    /// Keep at most this many checkpoints
+    pub max_checkpoints: Option<usize>,
+    /// Drop checkpoints saved longer ago than this
+    pub max_age: Option<Duration>,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self::keep_last(DEFAULT_RETAINED_CHECKPOINTS)
+    }
+}
+
+/// Default number of checkpoints retained
+pub const DEFAULT_RETAINED_CHECKPOINTS: usize = 100;
+
+impl Retention {
+    /// Keep the newest `count` checkpoints
+    pub fn keep_last(count: usize) -> Self {
+        Self {
+            max_checkpoints: Some(count.max(1)),
+            max_age: None,
+        }
+    }
+
+    /// Keep checkpoints saved within `age`
+    pub fn max_age(age: Duration) -> Self {
+        Self {
+            max_checkpoints: None,
+            max_age: Some(age),
+        }
+    }
+
+    /// Keep every checkpoint
+    pub fn keep_all() -> Self {
+        Self {
+            max_checkpoints: None,
+            max_age: None,
+        }
+    }
+}
+
+/// Wrap a backend error
+#[cfg(any(feature = "sled", feature = "sqlite", feature = "redis"))]
+pub(crate) fn store_error(error: impl std::fmt::Display) -> crate::Error {
+    crate::Error::Persistence(error.to_string())
+}
+
+/// Checkpoint stored as a JSON file
+///
+/// Saves write a temporary file next to the target, sync it, and rename it
+/// over the target, so a crash mid-save leaves the previous checkpoint
+/// intact.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    path: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStore {
+    /// Store checkpoints at `path`
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Path of the checkpoint file
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    fn temp_path(&self) -> std::path::PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        self.path.with_file_name(name)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+impl StateStore for FileStore {
+    async fn save(&self, checkpoint: &Checkpoint) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let json = serde_json::to_vec_pretty(checkpoint)?;
+        let temp = self.temp_path();
+        let mut file = tokio::fs::File::create(&temp).await?;
+        file.write_all(&json).await?;
+        file.sync_all().await?;
+        drop(file);
+        tokio::fs::rename(&temp, &self.path).await?;
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Option<Checkpoint>> {
+        match tokio::fs::read(&self.path).await {
+            Ok(json) => Ok(Some(serde_json::from_slice(&json)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Store and checkpoint interval of a VM
+#[derive(Clone)]
+pub(crate) struct Persistence {
+    pub(crate) store: Arc<dyn StateStore>,
+    pub(crate) interval: Duration,
+}
+
+impl std::fmt::Debug for Persistence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Persistence")
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,207 @@
+//! State shared between replicas through Redis
+//!
+//! Available with the `redis` feature. Replicas of a service behind a load
+//! balancer each run their own VM; pointing them at the same [`RedisStore`]
+//! key makes them restore the same checkpoint at startup, and a
+//! [`RedisSignalMirror`] gives them the same signals every cycle, so their
+//! latched coils stay in step:
+//!
+//! ```no_run
+//! use charta::persistence::redis::RedisStore;
+//! use charta::ChartaVM;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> charta::Result<()> {
+//! let store = RedisStore::connect("redis://localhost:6379", "charta:orders").await?;
+//! let signals = store.signal_mirror();
+//!
+//! let mut vm = ChartaVM::builder()
+//!     .persist_to(store, Duration::from_secs(1))
+//!     .build();
+//! // Read the shared signals at the start of every cycle
+//! vm.add_input_source(signals.clone());
+//! vm.load_program_from_file("program.ir.json").await?;
+//!
+//! // Visible to every replica from its next cycle on
+//! signals.set_signal("system_ok", true).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The checkpoint is stored as JSON under the key; mirrored signals live in
+//! the hash `<key>:signals` as `1` or `0`. Checkpoints from different
+//! replicas overwrite each other, the newest winning.
+
+use super::{store_error, Checkpoint, StateStore};
+use crate::error::Result;
+use crate::io::{async_trait, InputSource};
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use std::collections::HashMap;
+
+/// State store keeping the latest checkpoint under a Redis key
+#[derive(Clone)]
+pub struct RedisStore {
+    conn: ConnectionManager,
+    key: String,
+}
+
+impl RedisStore {
+    /// Connect to a Redis server and store checkpoints under `key`
+    ///
+    /// The connection is re-established automatically after failures.
+    pub async fn connect(url: &str, key: &str) -> Result<Self> {
+        let client = redis::Client::open(url).map_err(store_error)?;
+        let conn = ConnectionManager::new(client)
+            .await
+            .map_err(|e| store_error(format!("Redis connect to '{}': {}", url, e)))?;
+        Ok(Self::new(conn, key))
+    }
+
+    /// Store checkpoints under `key` using an existing connection
+    pub fn new(conn: ConnectionManager, key: &str) -> Self {
+        Self {
+            conn,
+            key: key.to_string(),
+        }
+    }
+
+    /// Key holding the checkpoint
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Signals shared under this store's key
+    pub fn signal_mirror(&self) -> RedisSignalMirror {
+        RedisSignalMirror::new(self.conn.clone(), &format!("{}:signals", self.key))
+    }
+}
+
+impl std::fmt::Debug for RedisStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisStore")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl StateStore for RedisStore {
+    async fn save(&self, checkpoint: &Checkpoint) -> Result<()> {
+        let json = serde_json::to_string(checkpoint)?;
+        let mut conn = self.conn.clone();
+        conn.set::<_, _, ()>(&self.key, json)
+            .await
+            .map_err(store_error)
+    }
+
+    async fn load(&self) -> Result<Option<Checkpoint>> {
+        let mut conn = self.conn.clone();
+        let json: Option<String> = conn.get(&self.key).await.map_err(store_error)?;
+        Ok(json.as_deref().map(serde_json::from_str).transpose()?)
+    }
+}
+
+/// Signals shared between replicas in a Redis hash
+///
+/// As an [`InputSource`] it reads the whole hash at the start of every
+/// cycle; write signals with [`set_signal`](Self::set_signal) instead of
+/// [`ChartaVM::set_signal`](crate::ChartaVM::set_signal) so every replica
+/// sees them. If Redis is unreachable, a cycle reads no signals and the VM
+/// keeps their previous values.
+#[derive(Clone)]
+pub struct RedisSignalMirror {
+    conn: ConnectionManager,
+    key: String,
+}
+
+impl RedisSignalMirror {
+    /// Share signals in the hash at `key`
+    pub fn new(conn: ConnectionManager, key: &str) -> Self {
+        Self {
+            conn,
+            key: key.to_string(),
+        }
+    }
+
+    /// Key of the hash holding the signals
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Set a shared signal
+    pub async fn set_signal(&self, name: &str, value: bool) -> Result<()> {
+        let mut conn = self.conn.clone();
+        conn.hset::<_, _, _, ()>(&self.key, name, encode_value(value))
+            .await
+            .map_err(store_error)
+    }
+
+    /// Set several shared signals at once
+    pub async fn set_signals(&self, signals: &HashMap<String, bool>) -> Result<()> {
+        if signals.is_empty() {
+            return Ok(());
+        }
+        let fields: Vec<(&str, &str)> = signals
+            .iter()
+            .map(|(name, value)| (name.as_str(), encode_value(*value)))
+            .collect();
+        let mut conn = self.conn.clone();
+        conn.hset_multiple::<_, _, _, ()>(&self.key, &fields)
+            .await
+            .map_err(store_error)
+    }
+
+    /// Read every shared signal
+    ///
+    /// Fields holding anything but a boolean are skipped.
+    pub async fn signals(&self) -> Result<HashMap<String, bool>> {
+        let mut conn = self.conn.clone();
+        let fields: HashMap<String, Vec<u8>> = conn.hgetall(&self.key).await.map_err(store_error)?;
+        Ok(fields
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, parse_value(&value)?)))
+            .collect())
+    }
+}
+
+impl std::fmt::Debug for RedisSignalMirror {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisSignalMirror")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl InputSource for RedisSignalMirror {
+    async fn read(&mut self) -> HashMap<String, bool> {
+        match self.signals().await {
+            Ok(signals) => signals,
+            Err(_e) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(key = %self.key, error = %_e, "reading mirrored signals failed");
+                HashMap::new()
+            }
+        }
+    }
+}
+
+fn encode_value(value: bool) -> &'static str {
+    if value {
+        "1"
+    } else {
+        "0"
+    }
+}
+
+/// Parse a mirrored signal value (`1`/`0` or `true`/`false`)
+pub fn parse_value(value: &[u8]) -> Option<bool> {
+    match std::str::from_utf8(value).ok()?.trim() {
+        "1" => Some(true),
+        "0" => Some(false),
+        value if value.eq_ignore_ascii_case("true") => Some(true),
+        value if value.eq_ignore_ascii_case("false") => Some(false),
+        _ => None,
+    }
+}
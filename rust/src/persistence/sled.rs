@@ -0,0 +1,140 @@
+//! Checkpoint history in a sled database
+//!
+//! Available with the `sled` feature. [`SledStore`] appends every checkpoint
+//! to a sled tree and prunes old ones according to its [`Retention`]:
+//!
+//! ```no_run
+//! use charta::persistence::sled::SledStore;
+//! use charta::persistence::Retention;
+//! use charta::ChartaVM;
+//! use std::time::Duration;
+//!
+//! # fn example() -> charta::Result<()> {
+//! let store = SledStore::open("/var/lib/charta/state")?
+//!     .retention(Retention::max_age(Duration::from_secs(3600)));
+//! let vm = ChartaVM::builder()
+//!     .persist_to(store, Duration::from_secs(1))
+//!     .build();
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{store_error, Checkpoint, Retention, StateStore};
+use crate::error::Result;
+use crate::io::async_trait;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Name of the tree holding checkpoints
+pub const TREE_NAME: &str = "charta_checkpoints";
+
+/// State store keeping a history of checkpoints in a sled tree
+///
+/// Checkpoints are keyed by a big-endian sequence number, oldest first.
+#[derive(Debug, Clone)]
+pub struct SledStore {
+    tree: ::sled::Tree,
+    retention: Retention,
+}
+
+impl SledStore {
+    /// Open or create a database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let db = ::sled::open(path).map_err(store_error)?;
+        Self::new(&db)
+    }
+
+    /// Store checkpoints in the [`TREE_NAME`] tree of an open database
+    pub fn new(db: &::sled::Db) -> Result<Self> {
+        let tree = db.open_tree(TREE_NAME).map_err(store_error)?;
+        Ok(Self::with_tree(tree))
+    }
+
+    /// Store checkpoints in `tree`
+    pub fn with_tree(tree: ::sled::Tree) -> Self {
+        Self {
+            tree,
+            retention: Retention::default(),
+        }
+    }
+
+    /// Set which checkpoints are kept
+    ///
+    /// Applied after every save.
+    pub fn retention(mut self, retention: Retention) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// All retained checkpoints, oldest first
+    pub fn history(&self) -> Result<Vec<Checkpoint>> {
+        self.tree
+            .iter()
+            .values()
+            .map(|value| decode(&value.map_err(store_error)?))
+            .collect()
+    }
+
+    /// Drop checkpoints outside the retention, returning how many were dropped
+    pub fn prune(&self) -> Result<usize> {
+        let mut excess = match self.retention.max_checkpoints {
+            Some(max) => self.tree.len().saturating_sub(max.max(1)),
+            None => 0,
+        };
+        let now = SystemTime::now();
+        let mut pruned = 0;
+        while self.tree.len() > 1 {
+            let Some((key, value)) = self.tree.first().map_err(store_error)? else {
+                break;
+            };
+            let expired = excess > 0
+                || self.retention.max_age.is_some_and(|age| {
+                    decode(&value).is_ok_and(|checkpoint| {
+                        now.duration_since(checkpoint.saved_at)
+                            .is_ok_and(|elapsed| elapsed > age)
+                    })
+                });
+            if !expired {
+                break;
+            }
+            self.tree.remove(key).map_err(store_error)?;
+            excess = excess.saturating_sub(1);
+            pruned += 1;
+        }
+        Ok(pruned)
+    }
+
+    fn next_key(&self) -> Result<[u8; 8]> {
+        let next = match self.tree.last().map_err(store_error)? {
+            Some((key, _)) => {
+                let mut last = [0; 8];
+                last.copy_from_slice(&key[..8]);
+                u64::from_be_bytes(last) + 1
+            }
+            None => 0,
+        };
+        Ok(next.to_be_bytes())
+    }
+}
+
+fn decode(value: &[u8]) -> Result<Checkpoint> {
+    Ok(serde_json::from_slice(value)?)
+}
+
+#[async_trait]
+impl StateStore for SledStore {
+    async fn save(&self, checkpoint: &Checkpoint) -> Result<()> {
+        let json = serde_json::to_vec(checkpoint)?;
+        self.tree.insert(self.next_key()?, json).map_err(store_error)?;
+        self.prune()?;
+        self.tree.flush_async().await.map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Option<Checkpoint>> {
+        match self.tree.last().map_err(store_error)? {
+            Some((_, value)) => Ok(Some(decode(&value)?)),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Checkpoint history in a SQLite database
+//!
+//! Available with the `sqlite` feature. [`SqliteStore`] appends every
+//! checkpoint to a table and prunes old ones according to its
+//! [`Retention`]:
+//!
+//! ```no_run
+//! use charta::persistence::sqlite::SqliteStore;
+//! use charta::persistence::Retention;
+//! use charta::ChartaVM;
+//! use std::time::Duration;
+//!
+//! # fn example() -> charta::Result<()> {
+//! let store = SqliteStore::open("/var/lib/charta/state.db")?
+//!     .retention(Retention::keep_last(3600));
+//! let vm = ChartaVM::builder()
+//!     .persist_to(store, Duration::from_secs(1))
+//!     .build();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Database calls run on Tokio's blocking thread pool.
+
+use super::{store_error, Checkpoint, Retention, StateStore};
+use crate::error::Result;
+use crate::io::async_trait;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Name of the table holding checkpoints
+pub const TABLE_NAME: &str = "charta_checkpoints";
+
+/// State store keeping a history of checkpoints in a SQLite table
+#[derive(Debug, Clone)]
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+    retention: Retention,
+}
+
+impl SqliteStore {
+    /// Open or create a database file at `path`
+    ///
+    /// File databases use write-ahead logging.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open(path).map_err(store_error)?;
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(store_error)?;
+        Self::new(conn)
+    }
+
+    /// Create a store in a private in-memory database
+    pub fn in_memory() -> Result<Self> {
+        Self::new(Connection::open_in_memory().map_err(store_error)?)
+    }
+
+    /// Store checkpoints in the [`TABLE_NAME`] table of an open connection,
+    /// creating it if missing
+    pub fn new(conn: Connection) -> Result<Self> {
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {TABLE_NAME} (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                saved_at_ms INTEGER NOT NULL,
+                checkpoint TEXT NOT NULL
+            )"
+        ))
+        .map_err(store_error)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            retention: Retention::default(),
+        })
+    }
+
+    /// Set which checkpoints are kept
+    ///
+    /// Applied after every save.
+    pub fn retention(mut self, retention: Retention) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// All retained checkpoints, oldest first
+    pub fn history(&self) -> Result<Vec<Checkpoint>> {
+        let conn = self.conn();
+        let mut statement = conn
+            .prepare(&format!("SELECT checkpoint FROM {TABLE_NAME} ORDER BY id"))
+            .map_err(store_error)?;
+        let rows = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(store_error)?;
+        rows.map(|json| decode(&json.map_err(store_error)?))
+            .collect()
+    }
+
+    /// Drop checkpoints outside the retention, returning how many were dropped
+    pub fn prune(&self) -> Result<usize> {
+        prune(&self.conn(), &self.retention)
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Run `f` with the connection on the blocking thread pool
+    async fn blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection, &Retention) -> Result<T> + Send + 'static,
+    {
+        let conn = Arc::clone(&self.conn);
+        let retention = self.retention;
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|e| e.into_inner());
+            f(&conn, &retention)
+        })
+        .await
+        .map_err(store_error)?
+    }
+}
+
+fn decode(json: &str) -> Result<Checkpoint> {
+    Ok(serde_json::from_str(json)?)
+}
+
+fn unix_millis(at: SystemTime) -> i64 {
+    at.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Delete checkpoints outside `retention`, always keeping the newest
+fn prune(conn: &Connection, retention: &Retention) -> Result<usize> {
+    let mut pruned = 0;
+    if let Some(max) = retention.max_checkpoints {
+        pruned += conn
+            .execute(
+                &format!(
+                    "DELETE FROM {TABLE_NAME} WHERE id NOT IN
+                        (SELECT id FROM {TABLE_NAME} ORDER BY id DESC LIMIT ?1)"
+                ),
+                params![max.max(1) as i64],
+            )
+            .map_err(store_error)?;
+    }
+    if let Some(age) = retention.max_age {
+        let cutoff = unix_millis(SystemTime::now()) - age.as_millis() as i64;
+        pruned += conn
+            .execute(
+                &format!(
+                    "DELETE FROM {TABLE_NAME} WHERE saved_at_ms < ?1
+                        AND id < (SELECT MAX(id) FROM {TABLE_NAME})"
+                ),
+                params![cutoff],
+            )
+            .map_err(store_error)?;
+    }
+    Ok(pruned)
+}
+
+#[async_trait]
+impl StateStore for SqliteStore {
+    async fn save(&self, checkpoint: &Checkpoint) -> Result<()> {
+        let json = serde_json::to_string(checkpoint)?;
+        let saved_at = unix_millis(checkpoint.saved_at);
+        self.blocking(move |conn, retention| {
+            conn.execute(
+                &format!("INSERT INTO {TABLE_NAME} (saved_at_ms, checkpoint) VALUES (?1, ?2)"),
+                params![saved_at, json],
+            )
+            .map_err(store_error)?;
+            prune(conn, retention)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn load(&self) -> Result<Option<Checkpoint>> {
+        self.blocking(|conn, _| {
+            let json: Option<String> = conn
+                .query_row(
+                    &format!("SELECT checkpoint FROM {TABLE_NAME} ORDER BY id DESC LIMIT 1"),
+                    [],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(store_error)?;
+            json.as_deref().map(decode).transpose()
+        })
+        .await
+    }
+}
@@ -0,0 +1,126 @@
+//! Multi-stage VM pipelines
+//!
+//! Chains VMs so that coil outputs of an upstream stage are fed as input
+//! signals into the next stage on every cycle.
+//!
+//! ```no_run
+//! use charta::{ChartaVM, Pipeline};
+//! use std::collections::HashMap;
+//!
+//! # async fn run(vm_a: ChartaVM, vm_b: ChartaVM) -> charta::Result<()> {
+//! let mut pipeline = Pipeline::new()
+//!     .stage(vm_a)
+//!     .stage(vm_b)
+//!     .map("allow_x", "x_approved");
+//!
+//! let outputs = pipeline.execute_cycle(HashMap::new()).await?;
+//! let final_outputs = outputs.last().unwrap();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Error, Result};
+use crate::vm::ChartaVM;
+use std::collections::HashMap;
+
+/// A pipeline stage and the mappings feeding it
+struct Stage {
+    vm: ChartaVM,
+    /// Upstream coil name -> this stage's signal name
+    mappings: Vec<(String, String)>,
+}
+
+/// Chain of VMs wired coil-to-signal
+#[derive(Default)]
+pub struct Pipeline {
+    stages: Vec<Stage>,
+}
+
+impl Pipeline {
+    /// Create an empty pipeline
+    pub fn new() -> Self {
+        Self { stages: Vec::new() }
+    }
+
+    /// Append a stage
+    pub fn stage(mut self, vm: ChartaVM) -> Self {
+        self.stages.push(Stage {
+            vm,
+            mappings: Vec::new(),
+        });
+        self
+    }
+
+    /// Feed `coil` of the previous stage into `signal` of the most recently
+    /// added stage
+    pub fn map(mut self, coil: &str, signal: &str) -> Self {
+        if let Some(stage) = self.stages.last_mut() {
+            stage.mappings.push((coil.to_string(), signal.to_string()));
+        }
+        self
+    }
+
+    /// Get the number of stages
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Check whether the pipeline has no stages
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Get the VM of a stage
+    pub fn stage_vm(&self, index: usize) -> Option<&ChartaVM> {
+        self.stages.get(index).map(|stage| &stage.vm)
+    }
+
+    /// Get the VM of a stage mutably
+    pub fn stage_vm_mut(&mut self, index: usize) -> Option<&mut ChartaVM> {
+        self.stages.get_mut(index).map(|stage| &mut stage.vm)
+    }
+
+    /// Execute one cycle of every stage in order
+    ///
+    /// `inputs` are applied to the first stage; each later stage receives the
+    /// mapped coil outputs of the stage before it. Returns the outputs of
+    /// every stage, the last entry being the pipeline's final outputs.
+    pub async fn execute_cycle(
+        &mut self,
+        inputs: HashMap<String, bool>,
+    ) -> Result<Vec<HashMap<String, bool>>> {
+        if self.stages.is_empty() {
+            return Err(Error::InvalidOperation("Pipeline has no stages".to_string()));
+        }
+        if !self.stages[0].mappings.is_empty() {
+            return Err(Error::InvalidOperation(
+                "First pipeline stage has no upstream stage to map from".to_string(),
+            ));
+        }
+
+        let mut results: Vec<HashMap<String, bool>> = Vec::with_capacity(self.stages.len());
+        let mut first_inputs = Some(inputs);
+
+        for stage in &mut self.stages {
+            let stage_inputs = match results.last() {
+                None => first_inputs.take().unwrap_or_default(),
+                Some(upstream) => {
+                    let mut mapped = HashMap::with_capacity(stage.mappings.len());
+                    for (coil, signal) in &stage.mappings {
+                        let value = upstream
+                            .get(coil)
+                            .copied()
+                            .ok_or_else(|| Error::NotFound(coil.clone()))?;
+                        mapped.insert(signal.clone(), value);
+                    }
+                    mapped
+                }
+            };
+
+            let outputs = stage.vm.execute_cycle_with_inputs(stage_inputs).await?;
+            results.push(outputs.coils().clone());
+        }
+
+        Ok(results)
+    }
+}
@@ -0,0 +1,207 @@
+//! VM pooling for Charta
+//!
+//! Pre-loads a fixed number of identical VMs running the same program and
+//! hands them out for independent, high-throughput evaluations. Each
+//! borrower gets a freshly loaded VM.
+
+use crate::error::Result;
+use crate::vm::ChartaVM;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Snapshot of pool metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolMetrics {
+    /// Total number of VMs in the pool
+    pub size: usize,
+    /// Number of VMs currently checked out
+    pub in_use: usize,
+    /// Number of completed checkouts
+    pub checkouts: u64,
+    /// Total time spent waiting for a VM across all checkouts
+    pub total_wait: Duration,
+    /// Longest time a single checkout waited for a VM
+    pub max_wait: Duration,
+}
+
+impl PoolMetrics {
+    /// Average time a checkout waited for a VM
+    pub fn average_wait(&self) -> Duration {
+        if self.checkouts == 0 {
+            Duration::ZERO
+        } else {
+            self.total_wait / self.checkouts as u32
+        }
+    }
+}
+
+/// Shared pool state
+struct PoolInner {
+    ir_json: Arc<str>,
+    /// Freshly loaded VMs no borrower has used
+    idle: Mutex<Vec<ChartaVM>>,
+    permits: Arc<Semaphore>,
+    size: usize,
+    checkouts: AtomicU64,
+    total_wait_micros: AtomicU64,
+    max_wait_micros: AtomicU64,
+}
+
+/// Pool of identical pre-loaded VMs
+///
+/// Every checkout hands out a VM freshly loaded with the pool's program.
+/// Returned VMs are discarded rather than reused, so nothing a borrower
+/// changed (state, bypasses, callbacks, drivers, or the loaded program)
+/// leaks to the next one. Each return loads a replacement in the
+/// background; a checkout that finds none ready loads one itself.
+#[derive(Clone)]
+pub struct VmPool {
+    inner: Arc<PoolInner>,
+}
+
+impl VmPool {
+    /// Create a pool of `size` VMs, each loaded with `ir_json`
+    ///
+    /// All VMs are loaded up front so the first checkouts don't pay the
+    /// program load cost.
+    pub async fn new(ir_json: &str, size: usize) -> Result<Self> {
+        let size = size.max(1);
+        let mut vms = Vec::with_capacity(size);
+        for _ in 0..size {
+            vms.push(load(ir_json).await?);
+        }
+
+        Ok(Self {
+            inner: Arc::new(PoolInner {
+                ir_json: Arc::from(ir_json),
+                idle: Mutex::new(vms),
+                permits: Arc::new(Semaphore::new(size)),
+                size,
+                checkouts: AtomicU64::new(0),
+                total_wait_micros: AtomicU64::new(0),
+                max_wait_micros: AtomicU64::new(0),
+            }),
+        })
+    }
+
+    /// Check out a VM, waiting until one is available
+    ///
+    /// The VM is replaced with a fresh one when the guard is dropped. Fails
+    /// if no loaded VM is ready and loading one fails.
+    pub async fn checkout(&self) -> Result<PooledVm> {
+        let started = Instant::now();
+        let permit = self
+            .inner
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("pool semaphore is never closed");
+        self.record_wait(started.elapsed());
+        self.take(permit).await
+    }
+
+    /// Check out a VM without waiting for one to be returned
+    ///
+    /// Returns `None` if every VM is in use.
+    pub async fn try_checkout(&self) -> Result<Option<PooledVm>> {
+        let Ok(permit) = self.inner.permits.clone().try_acquire_owned() else {
+            return Ok(None);
+        };
+        self.record_wait(Duration::ZERO);
+        self.take(permit).await.map(Some)
+    }
+
+    /// Get a snapshot of the pool metrics
+    pub fn metrics(&self) -> PoolMetrics {
+        PoolMetrics {
+            size: self.inner.size,
+            in_use: self.inner.size - self.inner.permits.available_permits(),
+            checkouts: self.inner.checkouts.load(Ordering::Relaxed),
+            total_wait: Duration::from_micros(self.inner.total_wait_micros.load(Ordering::Relaxed)),
+            max_wait: Duration::from_micros(self.inner.max_wait_micros.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Get the number of VMs in the pool
+    pub fn size(&self) -> usize {
+        self.inner.size
+    }
+
+    fn record_wait(&self, wait: Duration) {
+        let micros = wait.as_micros() as u64;
+        self.inner.checkouts.fetch_add(1, Ordering::Relaxed);
+        self.inner.total_wait_micros.fetch_add(micros, Ordering::Relaxed);
+        self.inner.max_wait_micros.fetch_max(micros, Ordering::Relaxed);
+    }
+
+    async fn take(&self, permit: OwnedSemaphorePermit) -> Result<PooledVm> {
+        let idle = self
+            .inner
+            .idle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop();
+        let vm = match idle {
+            Some(vm) => vm,
+            None => load(&self.inner.ir_json).await?,
+        };
+        Ok(PooledVm {
+            vm,
+            pool: self.inner.clone(),
+            _permit: permit,
+        })
+    }
+}
+
+async fn load(ir_json: &str) -> Result<ChartaVM> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(ir_json).await?;
+    Ok(vm)
+}
+
+/// VM checked out from a [`VmPool`]
+///
+/// Dereferences to [`ChartaVM`]. The VM is discarded on drop and the pool
+/// loads a replacement.
+pub struct PooledVm {
+    vm: ChartaVM,
+    pool: Arc<PoolInner>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Deref for PooledVm {
+    type Target = ChartaVM;
+
+    fn deref(&self) -> &ChartaVM {
+        &self.vm
+    }
+}
+
+impl DerefMut for PooledVm {
+    fn deref_mut(&mut self) -> &mut ChartaVM {
+        &mut self.vm
+    }
+}
+
+impl Drop for PooledVm {
+    fn drop(&mut self) {
+        // Without a runtime to load on, the next checkout loads instead
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let pool = Arc::clone(&self.pool);
+        runtime.spawn(async move {
+            // A failed load leaves the slot to the next checkout
+            if let Ok(vm) = load(&pool.ir_json).await {
+                let mut idle = pool.idle.lock().unwrap_or_else(|e| e.into_inner());
+                if idle.len() < pool.size {
+                    idle.push(vm);
+                }
+            }
+        });
+    }
+}
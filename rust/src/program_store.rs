@@ -0,0 +1,172 @@
+//! Fetching published program versions
+//!
+//! A [`ProgramStore`] returns the IR of a program by identifier and version.
+//! [`ProgramRegistry::pull`](crate::ProgramRegistry::pull) registers a
+//! fetched version and [`pin`](crate::ProgramRegistry::pin) also activates
+//! it, so a deployment names the policy it runs instead of shipping its IR:
+//!
+//! ```no_run
+//! use charta::program_store::DirectoryStore;
+//! use charta::{ChartaVM, ProgramRegistry};
+//!
+//! # async fn example() -> charta::Result<()> {
+//! let store = DirectoryStore::new("/srv/policies");
+//! let mut registry = ProgramRegistry::new();
+//! let mut vm = ChartaVM::new();
+//! // Loads /srv/policies/orders/v12.ir.json
+//! registry.pin(&mut vm, &store, "orders", "v12").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! With the `s3` or `gcs` feature, [`BucketStore`] reads the same layout
+//! from object storage.
+
+use crate::error::{Error, Result};
+use crate::io::async_trait;
+
+/// Identifier and version a program was fetched as
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProgramOrigin {
+    /// Program identifier
+    pub id: String,
+    /// Published version
+    pub version: String,
+}
+
+/// Source of published program versions
+///
+/// Published versions are treated as immutable: a registry fetches each
+/// `(id, version)` once.
+#[async_trait]
+pub trait ProgramStore: Send + Sync {
+    /// Fetch the IR of program `id` at `version`
+    ///
+    /// Fails with [`Error::NotFound`] if the store has no such version.
+    async fn fetch(&self, id: &str, version: &str) -> Result<String>;
+}
+
+/// Object key or relative path of a version: `<id>/<version>.ir.json`
+pub fn program_key(id: &str, version: &str) -> Result<String> {
+    for part in [id, version] {
+        let valid = !part.is_empty()
+            && part != "."
+            && part != ".."
+            && !part.contains(['/', '\\']);
+        if !valid {
+            return Err(Error::InvalidOperation(format!(
+                "'{}' is not a valid program id or version",
+                part
+            )));
+        }
+    }
+    Ok(format!("{}/{}.ir.json", id, version))
+}
+
+/// Program versions stored as files under a directory
+///
+/// Version `v12` of `orders` lives at `<root>/orders/v12.ir.json`.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct DirectoryStore {
+    root: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl DirectoryStore {
+    /// Read programs from under `root`
+    pub fn new(root: impl Into<std::path::PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+impl ProgramStore for DirectoryStore {
+    async fn fetch(&self, id: &str, version: &str) -> Result<String> {
+        let path = self.root.join(program_key(id, version)?);
+        match tokio::fs::read_to_string(&path).await {
+            Ok(ir) => Ok(ir),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(Error::NotFound(format!(
+                "program '{}' version '{}'",
+                id, version
+            ))),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Program versions stored in an object storage bucket
+///
+/// Version `v12` of `orders` lives at `<prefix>/orders/v12.ir.json`.
+#[cfg(any(feature = "s3", feature = "gcs"))]
+#[derive(Debug, Clone)]
+pub struct BucketStore {
+    objects: std::sync::Arc<dyn object_store::ObjectStore>,
+    prefix: String,
+}
+
+#[cfg(any(feature = "s3", feature = "gcs"))]
+impl BucketStore {
+    /// Read programs from any `object_store` backend
+    pub fn new(objects: std::sync::Arc<dyn object_store::ObjectStore>) -> Self {
+        Self {
+            objects,
+            prefix: String::new(),
+        }
+    }
+
+    /// Read programs from an S3 bucket, configured from the `AWS_*`
+    /// environment variables
+    #[cfg(feature = "s3")]
+    pub fn s3(bucket: &str) -> Result<Self> {
+        let objects = object_store::aws::AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .map_err(|e| Error::Fetch(e.to_string()))?;
+        Ok(Self::new(std::sync::Arc::new(objects)))
+    }
+
+    /// Read programs from a Google Cloud Storage bucket, configured from the
+    /// `GOOGLE_*` environment variables
+    #[cfg(feature = "gcs")]
+    pub fn gcs(bucket: &str) -> Result<Self> {
+        let objects = object_store::gcp::GoogleCloudStorageBuilder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .map_err(|e| Error::Fetch(e.to_string()))?;
+        Ok(Self::new(std::sync::Arc::new(objects)))
+    }
+
+    /// Read programs from under `prefix` in the bucket
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.trim_matches('/').to_string();
+        self
+    }
+}
+
+#[cfg(any(feature = "s3", feature = "gcs"))]
+#[async_trait]
+impl ProgramStore for BucketStore {
+    async fn fetch(&self, id: &str, version: &str) -> Result<String> {
+        let key = program_key(id, version)?;
+        let key = if self.prefix.is_empty() {
+            key
+        } else {
+            format!("{}/{}", self.prefix, key)
+        };
+        let bytes = match self.objects.get(&object_store::path::Path::from(key)).await {
+            Ok(object) => object.bytes().await,
+            Err(e) => Err(e),
+        };
+        let bytes = bytes.map_err(|e| match e {
+            object_store::Error::NotFound { .. } => {
+                Error::NotFound(format!("program '{}' version '{}'", id, version))
+            }
+            e => Error::Fetch(format!("program '{}' version '{}': {}", id, version, e)),
+        })?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| {
+            Error::IRLoad(format!("program '{}' version '{}' is not UTF-8", id, version))
+        })
+    }
+}
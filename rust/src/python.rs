@@ -0,0 +1,154 @@
+//! Python module via PyO3
+//!
+//! Exposes a synchronous `charta.ChartaVM` class so data-science and ops
+//! tooling can simulate policies without a Rust toolchain. Build a wheel with
+//! maturin:
+//!
+//! ```text
+//! maturin build --release --features python,pyo3/extension-module
+//! ```
+//!
+//! ```python
+//! import charta
+//!
+//! vm = charta.ChartaVM()
+//! vm.load_program(open("policy.json").read())
+//! vm.on_coil_change("allow_*", lambda name, old, new: print(name, new))
+//! outputs = vm.execute_cycle({"user_submitted": True})
+//! ```
+//!
+//! Callback exceptions are re-raised from the `execute_cycle` call that
+//! triggered them.
+
+use crate::blocking;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+create_exception!(charta, ChartaError, PyException, "Charta VM error");
+
+fn to_py_err(error: crate::Error) -> PyErr {
+    ChartaError::new_err(error.to_string())
+}
+
+/// First exception raised by a callback during the current cycle
+type PendingError = Arc<Mutex<Option<PyErr>>>;
+
+/// Charta VM exported to Python as `charta.ChartaVM`
+#[pyclass(name = "ChartaVM")]
+pub struct PyChartaVM {
+    inner: blocking::ChartaVM,
+    pending: PendingError,
+}
+
+impl PyChartaVM {
+    fn record(pending: &PendingError, result: PyResult<PyObject>) {
+        if let Err(e) = result {
+            let mut pending = pending.lock().unwrap_or_else(|e| e.into_inner());
+            pending.get_or_insert(e);
+        }
+    }
+}
+
+#[pymethods]
+impl PyChartaVM {
+    /// Create a VM with no program loaded
+    #[new]
+    fn new() -> Self {
+        Self {
+            inner: blocking::ChartaVM::new(),
+            pending: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Load a program from IR JSON
+    fn load_program(&mut self, ir_json: &str) -> PyResult<()> {
+        self.inner.load_program(ir_json).map_err(to_py_err)?;
+        Ok(())
+    }
+
+    /// Load a program from a file
+    fn load_program_from_file(&mut self, path: &str) -> PyResult<()> {
+        self.inner.load_program_from_file(path).map_err(to_py_err)?;
+        Ok(())
+    }
+
+    /// Set a signal value
+    fn set_signal(&mut self, name: &str, value: bool) -> PyResult<()> {
+        self.inner.set_signal(name, value).map_err(to_py_err)
+    }
+
+    /// Get a signal value, or None if unknown
+    fn get_signal(&self, name: &str) -> PyResult<Option<bool>> {
+        self.inner.get_signal(name).map_err(to_py_err)
+    }
+
+    /// Get a coil value, or None if unknown
+    fn get_coil(&self, name: &str) -> PyResult<Option<bool>> {
+        self.inner.get_coil(name).map_err(to_py_err)
+    }
+
+    /// Get all signal states as a dict
+    fn get_all_signals(&self) -> PyResult<HashMap<String, bool>> {
+        self.inner.get_all_signals().map_err(to_py_err)
+    }
+
+    /// Get all coil states as a dict
+    fn get_all_coils(&self) -> PyResult<HashMap<String, bool>> {
+        self.inner.get_all_coils().map_err(to_py_err)
+    }
+
+    /// Execute one scan cycle, optionally with input signals
+    #[pyo3(signature = (inputs=None))]
+    fn execute_cycle(
+        &mut self,
+        inputs: Option<HashMap<String, bool>>,
+    ) -> PyResult<HashMap<String, bool>> {
+        let outputs = self
+            .inner
+            .execute_cycle_with_inputs(inputs.unwrap_or_default())
+            .map_err(to_py_err)?;
+        let pending = self.pending.lock().unwrap_or_else(|e| e.into_inner()).take();
+        match pending {
+            Some(e) => Err(e),
+            None => Ok(outputs),
+        }
+    }
+
+    /// Call `callback(name, old, new)` for coils matching a name or pattern
+    fn on_coil_change(&mut self, pattern: &str, callback: PyObject) {
+        let pending = self.pending.clone();
+        self.inner.on_coil_change(pattern, move |name, old, new, _| {
+            Python::with_gil(|py| Self::record(&pending, callback.call1(py, (name, old, new))));
+        });
+    }
+
+    /// Call `callback(outputs)` after every cycle
+    fn on_cycle_complete(&mut self, callback: PyObject) {
+        let pending = self.pending.clone();
+        self.inner.on_cycle_complete(move |outputs, _| {
+            Python::with_gil(|py| Self::record(&pending, callback.call1(py, (outputs.clone(),))));
+        });
+    }
+
+    /// Remove all callbacks
+    fn clear_callbacks(&mut self) {
+        self.inner.clear_callbacks();
+    }
+
+    /// Number of cycles executed so far
+    #[getter]
+    fn cycle_count(&self) -> u64 {
+        self.inner.cycle_count()
+    }
+}
+
+/// The `charta` Python module
+#[pymodule]
+fn charta(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyChartaVM>()?;
+    m.add("ChartaError", m.py().get_type_bound::<ChartaError>())?;
+    Ok(())
+}
@@ -0,0 +1,316 @@
+//! Signal quality
+//!
+//! Inputs from flaky upstream systems can be set with a [`Quality`] alongside
+//! their value, so a lost feed is not silently read as `false`:
+//!
+//! ```no_run
+//! use charta::quality::{Quality, QualityPolicy};
+//! use charta::ChartaVM;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> charta::Result<()> {
+//! let mut vm = ChartaVM::builder()
+//!     .quality_policy(QualityPolicy::EvaluateFalse)
+//!     .build();
+//! vm.load_program_from_file("policy.json").await?;
+//! vm.set_stale_timeout("kyc_verified", Duration::from_secs(30));
+//! vm.set_signal_with_quality("kyc_verified", true, Quality::Bad).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Plain writes (`set_signal`, `set_value`, cycle inputs) mark a signal
+//! [`Quality::Good`]; a signal with a stale timeout that is not written
+//! within it reads as [`Quality::Stale`]. The [`QualityPolicy`] decides what
+//! a rung reading a Bad or Stale signal does. Under
+//! [`QualityPolicy::EvaluateFalse`] each rung's guard is gated on the
+//! quality of the signals it reads, through derived signals (see
+//! [`quality_signal`]) set at the start of every cycle.
+
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the derived signals rung guards are gated on
+pub const QUALITY_SIGNAL_PREFIX: &str = "__quality:";
+
+/// Quality of a signal's value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Quality {
+    /// The value is current and trustworthy
+    #[default]
+    Good,
+    /// The source reported the value as unreliable
+    Bad,
+    /// The value was not refreshed within its stale timeout
+    Stale,
+}
+
+/// What a rung reading a Bad or Stale signal does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QualityPolicy {
+    /// Quality is recorded but does not affect evaluation
+    #[default]
+    Ignore,
+    /// The rung's guard evaluates false
+    EvaluateFalse,
+    /// The cycle fails with [`Error::SignalQuality`](crate::Error::SignalQuality)
+    Fault,
+}
+
+impl Quality {
+    /// Check whether the quality is [`Quality::Good`]
+    pub fn is_good(&self) -> bool {
+        *self == Quality::Good
+    }
+}
+
+impl fmt::Display for Quality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Quality::Good => "good",
+            Quality::Bad => "bad",
+            Quality::Stale => "stale",
+        })
+    }
+}
+
+/// Name of the derived signal that holds while `signal` has good quality
+pub fn quality_signal(signal: &str) -> String {
+    format!("{}{}", QUALITY_SIGNAL_PREFIX, signal)
+}
+
+#[cfg(feature = "std")]
+pub(crate) use tracking::{QualityGates, QualityTracker};
+
+#[cfg(feature = "std")]
+mod tracking {
+    use super::{quality_signal, Quality, QualityPolicy};
+    use crate::error::{Error, Result};
+    use crate::ir::{Guard, Program, SignalDecl};
+    use std::collections::{BTreeSet, HashMap, HashSet};
+    use std::time::{Duration, Instant};
+
+    /// Latest quality of each signal, and when signals with a stale timeout
+    /// were last written
+    ///
+    /// The clock is only read for signals with a timeout, so quality works on
+    /// targets without one.
+    #[derive(Debug, Default)]
+    pub(crate) struct QualityTracker {
+        signals: HashMap<String, (Quality, Option<Instant>)>,
+        timeouts: HashMap<String, Duration>,
+        fallbacks: HashMap<String, bool>,
+    }
+
+    impl QualityTracker {
+        /// Record a write of `name` with `quality`
+        pub(crate) fn update(&mut self, name: &str, quality: Quality) {
+            let written = self.timeouts.contains_key(name).then(Instant::now);
+            self.signals.insert(name.to_string(), (quality, written));
+        }
+
+        /// Mark `name` stale if not written for `timeout`, counting from now
+        pub(crate) fn set_timeout(&mut self, name: &str, timeout: Duration) {
+            self.timeouts.insert(name.to_string(), timeout);
+            let entry = self
+                .signals
+                .entry(name.to_string())
+                .or_insert((Quality::Good, None));
+            entry.1 = Some(Instant::now());
+        }
+
+        /// Revert `name` to `fallback` when not written for `ttl`
+        pub(crate) fn set_ttl(&mut self, name: &str, ttl: Duration, fallback: bool) {
+            self.set_timeout(name, ttl);
+            self.fallbacks.insert(name.to_string(), fallback);
+        }
+
+        /// Mark signals whose TTL ran out stale and return their fallbacks
+        ///
+        /// Each expiry is returned once; the next write restarts the TTL.
+        pub(crate) fn expire(&mut self) -> Vec<(String, bool)> {
+            let mut expired: Vec<(String, bool)> = self
+                .fallbacks
+                .iter()
+                .filter(|(name, _)| {
+                    matches!(self.signals.get(name.as_str()), Some((Quality::Good, _)))
+                        && self.quality(name) == Quality::Stale
+                })
+                .map(|(name, fallback)| (name.clone(), *fallback))
+                .collect();
+            expired.sort();
+            for (name, _) in &expired {
+                self.signals.insert(name.clone(), (Quality::Stale, None));
+            }
+            expired
+        }
+
+        /// Mark every signal good, restarting timeouts and TTLs from now
+        pub(crate) fn restart(&mut self) {
+            let now = Instant::now();
+            self.signals = self
+                .timeouts
+                .keys()
+                .map(|name| (name.clone(), (Quality::Good, Some(now))))
+                .collect();
+        }
+
+        /// Get the quality of `name`; signals never written are good
+        pub(crate) fn quality(&self, name: &str) -> Quality {
+            match self.signals.get(name) {
+                Some((Quality::Good, Some(written)))
+                    if self
+                        .timeouts
+                        .get(name)
+                        .is_some_and(|timeout| written.elapsed() > *timeout) =>
+                {
+                    Quality::Stale
+                }
+                Some((quality, _)) => *quality,
+                None => Quality::Good,
+            }
+        }
+    }
+
+    /// Declared signals each rung reads, for quality propagation
+    #[derive(Debug, Clone, Default)]
+    pub(crate) struct QualityGates {
+        /// Described rung name and the signals its guard reads
+        rungs: Vec<(String, Vec<String>)>,
+    }
+
+    impl QualityGates {
+        pub(crate) fn of(program: &Program) -> Self {
+            let declared: HashSet<&str> = program
+                .module
+                .signals
+                .iter()
+                .map(|signal| signal.name.as_str())
+                .collect();
+            let rungs = program
+                .module
+                .rungs
+                .iter()
+                .map(|rung| {
+                    let mut reads = Vec::new();
+                    collect_reads(&rung.guard, &declared, &mut reads);
+                    (rung.describe(), reads)
+                })
+                .collect();
+            Self { rungs }
+        }
+
+        /// Check whether no rung reads a declared signal
+        pub(crate) fn is_empty(&self) -> bool {
+            self.rungs.iter().all(|(_, reads)| reads.is_empty())
+        }
+
+        /// Get every signal some rung reads, sorted
+        pub(crate) fn signals(&self) -> BTreeSet<&str> {
+            self.rungs
+                .iter()
+                .flat_map(|(_, reads)| reads.iter().map(String::as_str))
+                .collect()
+        }
+
+        /// Gate each rung's guard on the quality of the signals it reads
+        ///
+        /// Declares the derived quality signals in `program`.
+        pub(crate) fn apply(&self, program: &mut Program) {
+            for (rung, (_, reads)) in program.module.rungs.iter_mut().zip(&self.rungs) {
+                if reads.is_empty() {
+                    continue;
+                }
+                let mut operands = vec![rung.guard.clone()];
+                operands.extend(reads.iter().map(|signal| Guard::Contact {
+                    name: quality_signal(signal),
+                    contact_type: Default::default(),
+                }));
+                rung.guard = Guard::And {
+                    left: None,
+                    right: None,
+                    operands,
+                };
+            }
+            for signal in self.signals() {
+                program.module.signals.push(SignalDecl {
+                    name: quality_signal(signal),
+                    value_type: Default::default(),
+                    filter: None,
+                    initial: None,
+                    meta: Default::default(),
+                });
+            }
+        }
+
+        /// Check whether every signal the rung at `index` reads had good
+        /// quality in a scan of `state`
+        ///
+        /// Reads the derived quality signals set under
+        /// [`QualityPolicy::EvaluateFalse`].
+        pub(crate) fn passes(&self, index: usize, state: &HashMap<String, bool>) -> bool {
+            self.rungs.get(index).map_or(true, |(_, reads)| {
+                reads
+                    .iter()
+                    .all(|signal| state.get(&quality_signal(signal)).copied().unwrap_or(true))
+            })
+        }
+
+        /// Apply `policy` at the start of a cycle
+        ///
+        /// Sets the derived quality signals in `inputs` under
+        /// [`QualityPolicy::EvaluateFalse`]; fails naming the first rung that
+        /// reads a degraded signal under [`QualityPolicy::Fault`].
+        pub(crate) fn check(
+            &self,
+            policy: QualityPolicy,
+            tracker: &QualityTracker,
+            inputs: &mut HashMap<String, bool>,
+        ) -> Result<()> {
+            match policy {
+                QualityPolicy::Ignore => {}
+                QualityPolicy::EvaluateFalse => {
+                    for signal in self.signals() {
+                        let good = tracker.quality(signal).is_good();
+                        inputs.insert(quality_signal(signal), good);
+                    }
+                }
+                QualityPolicy::Fault => {
+                    for (rung, reads) in &self.rungs {
+                        for signal in reads {
+                            let quality = tracker.quality(signal);
+                            if !quality.is_good() {
+                                return Err(Error::SignalQuality(format!(
+                                    "Rung {} reads signal '{}' with {} quality",
+                                    rung, signal, quality
+                                )));
+                            }
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Collect the declared signals a guard reads, without duplicates
+    fn collect_reads(guard: &Guard, declared: &HashSet<&str>, out: &mut Vec<String>) {
+        let subject = guard.subject();
+        let names = match (guard, &subject) {
+            (Guard::Contact { name, .. }, _) => vec![name.as_str()],
+            (_, Some(subject)) => subject.signals(),
+            _ => Vec::new(),
+        };
+        for name in names {
+            if declared.contains(name) && !out.iter().any(|read| read == name) {
+                out.push(name.to_string());
+            }
+        }
+        for operand in guard.operands() {
+            collect_reads(operand, declared, out);
+        }
+    }
+}
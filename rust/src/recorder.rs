@@ -0,0 +1,150 @@
+//! Time-travel recording
+//!
+//! An opt-in recorder keeps the state each of the last N cycles started
+//! from, with the inputs it ran with, so post-incident analysis can ask
+//! what the VM believed some cycles ago:
+//!
+//! ```no_run
+//! use charta::ChartaVM;
+//!
+//! # async fn example(vm: &mut ChartaVM) -> charta::Result<()> {
+//! vm.enable_recording(100);
+//! // ... cycles run ...
+//! vm.rewind(20).await?;
+//! println!("{:?}", vm.get_all_coils().await?);
+//! while vm.is_rewound() {
+//!     let replayed = vm.replay_forward().await?;
+//!     println!("cycle {} ran with {:?}", replayed.cycle, replayed.inputs);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Rewinding restores signals, coils, typed values, registers, and the
+//! cycle count without running cycles, triggering callbacks, or emitting
+//! events. [`replay_forward`](crate::ChartaVM::replay_forward) moves to the
+//! state after the next recorded cycle the same way, until it is back at
+//! the state rewinding left. Executing a cycle while rewound continues from
+//! the restored state and discards the recorded cycles after it.
+
+use crate::error::{Error, Result};
+use crate::value::Value;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// A recorded cycle: the state it started from and the inputs it ran with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCycle {
+    /// Cycle number
+    pub cycle: u64,
+    /// Inputs passed in or polled from drivers
+    pub inputs: HashMap<String, bool>,
+    /// Signal states before the cycle
+    pub signals: HashMap<String, bool>,
+    /// Coil states before the cycle
+    pub coils: HashMap<String, bool>,
+    /// Typed signal values before the cycle
+    pub values: HashMap<String, Value>,
+    /// Register values before the cycle
+    pub registers: HashMap<String, Value>,
+}
+
+/// Ring buffer of recorded cycles and the rewind position
+#[derive(Debug, Clone)]
+pub(crate) struct Recorder {
+    capacity: usize,
+    cycles: VecDeque<RecordedCycle>,
+    /// Index of the recorded cycle whose starting state is restored
+    position: Option<usize>,
+    /// State before rewinding, restored by replaying past the last cycle
+    present: Option<RecordedCycle>,
+}
+
+impl Recorder {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            cycles: VecDeque::with_capacity(capacity),
+            position: None,
+            present: None,
+        }
+    }
+
+    /// Record a cycle, discarding any recorded after a rewind position
+    pub(crate) fn record(&mut self, cycle: RecordedCycle) {
+        if let Some(position) = self.position.take() {
+            self.cycles.truncate(position);
+            self.present = None;
+        }
+        if self.cycles.len() == self.capacity {
+            self.cycles.pop_front();
+        }
+        self.cycles.push_back(cycle);
+    }
+
+    /// Forget every recorded cycle, keeping the capacity
+    pub(crate) fn clear(&mut self) {
+        self.cycles.clear();
+        self.position = None;
+        self.present = None;
+    }
+
+    pub(crate) fn is_rewound(&self) -> bool {
+        self.position.is_some()
+    }
+
+    pub(crate) fn cycles(&self) -> Vec<RecordedCycle> {
+        self.cycles.iter().cloned().collect()
+    }
+
+    /// Move back `cycles` recorded cycles from the current position and get
+    /// the state to restore
+    ///
+    /// `present` is kept as the end of replay on the first rewind.
+    pub(crate) fn rewind(
+        &mut self,
+        cycles: usize,
+        present: Option<RecordedCycle>,
+    ) -> Result<&RecordedCycle> {
+        let from = self.position.unwrap_or(self.cycles.len());
+        let target = from.checked_sub(cycles).ok_or_else(|| {
+            Error::InvalidOperation(format!(
+                "Cannot rewind {} cycles; {} are recorded before the current state",
+                cycles, from
+            ))
+        })?;
+        if self.position.is_none() {
+            self.present = present;
+        }
+        self.position = Some(target);
+        Ok(self.state_at(target))
+    }
+
+    /// Move forward past the recorded cycle at the current position
+    ///
+    /// Returns that cycle and the state it left.
+    pub(crate) fn forward(&mut self) -> Result<(RecordedCycle, RecordedCycle)> {
+        let index = self
+            .position
+            .ok_or_else(|| Error::InvalidOperation("VM is not rewound".to_string()))?;
+        let replayed = self.cycles[index].clone();
+        let after = if index + 1 < self.cycles.len() {
+            self.position = Some(index + 1);
+            self.cycles[index + 1].clone()
+        } else {
+            self.position = None;
+            self.present
+                .take()
+                .expect("present state saved when rewound")
+        };
+        Ok((replayed, after))
+    }
+
+    fn state_at(&self, index: usize) -> &RecordedCycle {
+        self.cycles
+            .get(index)
+            .or(self.present.as_ref())
+            .expect("present state saved when rewound")
+    }
+}
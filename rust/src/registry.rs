@@ -0,0 +1,238 @@
+//! Program version registry for Charta
+//!
+//! Keeps the history of program versions loaded into a VM so operators can
+//! switch between them and roll back a bad rollout in one call. Every switch
+//! is reported to registered audit listeners. Versions published to a
+//! [`ProgramStore`] can be pulled and pinned by identifier.
+
+use crate::error::{Error, Result};
+use crate::program_store::{ProgramOrigin, ProgramStore};
+use crate::vm::ChartaVM;
+use charta_vm::ir::load_ir;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Compute the SHA-256 hash of program IR as a lowercase hex string
+pub fn program_hash(ir_json: &str) -> String {
+    let digest = Sha256::digest(ir_json.as_bytes());
+    let mut hex = String::with_capacity(digest.len() * 2);
+    for byte in digest {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    hex
+}
+
+/// A registered program version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramVersion {
+    /// Version number, starting at 1
+    pub version: u32,
+    /// SHA-256 hash of the IR source
+    pub hash: String,
+    /// When the version was registered
+    pub registered_at: SystemTime,
+    /// IR JSON source
+    pub source: String,
+    /// Identifier and version it was pulled as, if pulled from a store
+    pub origin: Option<ProgramOrigin>,
+}
+
+/// Audit event emitted by a [`ProgramRegistry`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryEvent {
+    /// A new version was registered
+    Registered {
+        /// Registered version
+        version: u32,
+        /// Hash of the registered IR
+        hash: String,
+    },
+    /// A version was activated on a VM
+    Activated {
+        /// Previously active version
+        from: Option<u32>,
+        /// Newly active version
+        to: u32,
+        /// When the switch happened
+        at: SystemTime,
+    },
+    /// The active version was rolled back
+    RolledBack {
+        /// Version rolled back from
+        from: u32,
+        /// Version rolled back to
+        to: u32,
+        /// When the switch happened
+        at: SystemTime,
+    },
+}
+
+/// Callback function type for registry audit events
+pub type RegistryCallback = Arc<dyn Fn(&RegistryEvent) + Send + Sync>;
+
+/// Registry of program versions with activation history
+#[derive(Default)]
+pub struct ProgramRegistry {
+    versions: Vec<ProgramVersion>,
+    /// Activation history, most recent last
+    activations: Vec<u32>,
+    listeners: Vec<RegistryCallback>,
+}
+
+impl ProgramRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a program version
+    ///
+    /// The IR is validated before it is stored. Registering a source that is
+    /// already known returns the existing version number.
+    pub fn register(&mut self, source: impl Into<String>) -> Result<u32> {
+        let source = source.into();
+        let hash = program_hash(&source);
+
+        if let Some(existing) = self.versions.iter().find(|v| v.hash == hash) {
+            return Ok(existing.version);
+        }
+
+        load_ir(&source).map_err(|e| Error::IRLoad(e.to_string()))?;
+
+        let version = self.versions.len() as u32 + 1;
+        self.versions.push(ProgramVersion {
+            version,
+            hash: hash.clone(),
+            registered_at: SystemTime::now(),
+            source,
+            origin: None,
+        });
+        self.emit(RegistryEvent::Registered { version, hash });
+
+        Ok(version)
+    }
+
+    /// Get a registered version
+    pub fn get(&self, version: u32) -> Option<&ProgramVersion> {
+        self.versions.iter().find(|v| v.version == version)
+    }
+
+    /// Get the version pulled as `id` at `version`
+    pub fn find(&self, id: &str, version: &str) -> Option<&ProgramVersion> {
+        self.versions.iter().find(|v| {
+            v.origin
+                .as_ref()
+                .is_some_and(|origin| origin.id == id && origin.version == version)
+        })
+    }
+
+    /// Fetch program `id` at `version` from a store and register it
+    ///
+    /// Versions pulled before are not fetched again. Returns the registry
+    /// version number.
+    pub async fn pull<S>(&mut self, store: &S, id: &str, version: &str) -> Result<u32>
+    where
+        S: ProgramStore + ?Sized,
+    {
+        if let Some(existing) = self.find(id, version) {
+            return Ok(existing.version);
+        }
+
+        let source = store.fetch(id, version).await?;
+        let registered = self.register(source)?;
+        if let Some(entry) = self.versions.iter_mut().find(|v| v.version == registered) {
+            entry.origin.get_or_insert_with(|| ProgramOrigin {
+                id: id.to_string(),
+                version: version.to_string(),
+            });
+        }
+        Ok(registered)
+    }
+
+    /// Pull program `id` at `version` and activate it on a VM
+    ///
+    /// Returns the registry version number.
+    pub async fn pin<S>(&mut self, vm: &mut ChartaVM, store: &S, id: &str, version: &str) -> Result<u32>
+    where
+        S: ProgramStore + ?Sized,
+    {
+        let registered = self.pull(store, id, version).await?;
+        self.activate(vm, registered).await?;
+        Ok(registered)
+    }
+
+    /// Get all registered versions, oldest first
+    pub fn versions(&self) -> &[ProgramVersion] {
+        &self.versions
+    }
+
+    /// Get the currently active version
+    pub fn active(&self) -> Option<&ProgramVersion> {
+        self.activations.last().and_then(|&version| self.get(version))
+    }
+
+    /// Load a registered version into a VM and mark it active
+    pub async fn activate(&mut self, vm: &mut ChartaVM, version: u32) -> Result<()> {
+        let source = self
+            .get(version)
+            .map(|v| v.source.clone())
+            .ok_or_else(|| Error::NotFound(format!("program version {}", version)))?;
+
+        vm.load_program(&source).await?;
+
+        let from = self.activations.last().copied();
+        self.activations.push(version);
+        self.emit(RegistryEvent::Activated {
+            from,
+            to: version,
+            at: SystemTime::now(),
+        });
+
+        Ok(())
+    }
+
+    /// Re-activate the version that was active before the current one
+    ///
+    /// Returns the version rolled back to.
+    pub async fn rollback(&mut self, vm: &mut ChartaVM) -> Result<u32> {
+        if self.activations.len() < 2 {
+            return Err(Error::InvalidOperation(
+                "No previous program version to roll back to".to_string(),
+            ));
+        }
+
+        let from = self.activations[self.activations.len() - 1];
+        let to = self.activations[self.activations.len() - 2];
+        let source = self
+            .get(to)
+            .map(|v| v.source.clone())
+            .ok_or_else(|| Error::NotFound(format!("program version {}", to)))?;
+
+        vm.load_program(&source).await?;
+
+        self.activations.pop();
+        self.emit(RegistryEvent::RolledBack {
+            from,
+            to,
+            at: SystemTime::now(),
+        });
+
+        Ok(to)
+    }
+
+    /// Register a listener for audit events
+    pub fn on_event<F>(&mut self, callback: F)
+    where
+        F: Fn(&RegistryEvent) + Send + Sync + 'static,
+    {
+        self.listeners.push(Arc::new(callback));
+    }
+
+    fn emit(&self, event: RegistryEvent) {
+        for listener in &self.listeners {
+            listener(&event);
+        }
+    }
+}
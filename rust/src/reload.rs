@@ -0,0 +1,105 @@
+//! Replacing a running program
+//!
+//! [`ChartaVM::reload_program`](crate::ChartaVM::reload_program) swaps in a
+//! new version of a program without losing state: the new IR is validated
+//! before anything changes, signal states and retained coil states carry
+//! over to the names the new version still declares, and a
+//! [`VmEvent::ProgramReloaded`](crate::VmEvent::ProgramReloaded) event
+//! reports what changed as a [`ProgramDiff`].
+
+use crate::ir::Program;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Declarations and rungs that differ between two program versions
+///
+/// Names are sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramDiff {
+    /// Signals only the new version declares
+    pub added_signals: Vec<String>,
+    /// Signals only the old version declares
+    pub removed_signals: Vec<String>,
+    /// Coils only the new version declares
+    pub added_coils: Vec<String>,
+    /// Coils only the old version declares
+    pub removed_coils: Vec<String>,
+    /// Rungs only the new version has
+    pub added_rungs: Vec<String>,
+    /// Rungs only the old version has
+    pub removed_rungs: Vec<String>,
+    /// Rungs both versions have, with different guards or actions
+    pub changed_rungs: Vec<String>,
+}
+
+impl ProgramDiff {
+    /// Compare two versions of a program
+    pub fn between(old: &Program, new: &Program) -> Self {
+        let (added_signals, removed_signals) = compare(
+            old.module.signals.iter().map(|s| s.name.as_str()),
+            new.module.signals.iter().map(|s| s.name.as_str()),
+        );
+        let (added_coils, removed_coils) = compare(
+            old.module.coils.iter().map(|c| c.name.as_str()),
+            new.module.coils.iter().map(|c| c.name.as_str()),
+        );
+        let (added_rungs, removed_rungs) = compare(
+            old.module.rungs.iter().map(|r| r.name.as_str()),
+            new.module.rungs.iter().map(|r| r.name.as_str()),
+        );
+
+        let old_rungs: HashMap<&str, _> = old
+            .module
+            .rungs
+            .iter()
+            .map(|rung| (rung.name.as_str(), rung))
+            .collect();
+        let mut changed_rungs: Vec<String> = new
+            .module
+            .rungs
+            .iter()
+            .filter(|rung| {
+                old_rungs
+                    .get(rung.name.as_str())
+                    .is_some_and(|old| old.guard != rung.guard || old.actions != rung.actions)
+            })
+            .map(|rung| rung.name.clone())
+            .collect();
+        changed_rungs.sort();
+        changed_rungs.dedup();
+
+        Self {
+            added_signals,
+            removed_signals,
+            added_coils,
+            removed_coils,
+            added_rungs,
+            removed_rungs,
+            changed_rungs,
+        }
+    }
+
+    /// Check whether the versions are equivalent
+    pub fn is_empty(&self) -> bool {
+        self.added_signals.is_empty()
+            && self.removed_signals.is_empty()
+            && self.added_coils.is_empty()
+            && self.removed_coils.is_empty()
+            && self.added_rungs.is_empty()
+            && self.removed_rungs.is_empty()
+            && self.changed_rungs.is_empty()
+    }
+}
+
+/// Sorted names only in `new`, and only in `old`
+fn compare<'a>(
+    old: impl Iterator<Item = &'a str>,
+    new: impl Iterator<Item = &'a str>,
+) -> (Vec<String>, Vec<String>) {
+    let old: BTreeSet<&str> = old.collect();
+    let new: BTreeSet<&str> = new.collect();
+    (
+        new.difference(&old).map(|name| name.to_string()).collect(),
+        old.difference(&new).map(|name| name.to_string()).collect(),
+    )
+}
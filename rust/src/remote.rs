@@ -0,0 +1,186 @@
+//! Loading programs published at a URL
+//!
+//! Available with the `remote` feature. A [`RemoteLoader`] fetches IR over
+//! HTTPS, checks its SHA-256 against the expected hash, and caches it on
+//! disk keyed by that hash, so later loads of the same version need no
+//! network:
+//!
+//! ```no_run
+//! use charta::remote::RemoteLoader;
+//! use charta::ChartaVM;
+//!
+//! # async fn example() -> charta::Result<()> {
+//! let mut vm = ChartaVM::new();
+//! vm.load_program_from_url(
+//!     "https://policies.example.com/orders/v12.ir.json",
+//!     "9f2c0e6b4f0cbd8cd0ed6e1ea9f5b5c3a6d2c8e1b7f4a0d9e8c7b6a5f4e3d2c1",
+//! )
+//! .await?;
+//!
+//! // Or with a dedicated cache directory
+//! let loader = RemoteLoader::new().cache_dir("/var/cache/charta");
+//! let ir = loader
+//!     .fetch(
+//!         "https://policies.example.com/orders/v12.ir.json",
+//!         "9f2c0e6b4f0cbd8cd0ed6e1ea9f5b5c3a6d2c8e1b7f4a0d9e8c7b6a5f4e3d2c1",
+//!     )
+//!     .await?;
+//! vm.load_program(&ir).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The expected hash is the [`program_id`](crate::ChartaVM::program_id) the
+//! program loads with.
+
+use crate::error::{Error, Result};
+use crate::registry::program_hash;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default timeout of a fetch
+pub const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default cache directory: `charta-programs` in the system temp directory
+pub fn default_cache_dir() -> PathBuf {
+    std::env::temp_dir().join("charta-programs")
+}
+
+/// Fetches, verifies, and caches program IR
+#[derive(Debug, Clone)]
+pub struct RemoteLoader {
+    cache_dir: Option<PathBuf>,
+    timeout: Duration,
+    allow_http: bool,
+}
+
+impl Default for RemoteLoader {
+    fn default() -> Self {
+        Self {
+            cache_dir: Some(default_cache_dir()),
+            timeout: DEFAULT_FETCH_TIMEOUT,
+            allow_http: false,
+        }
+    }
+}
+
+impl RemoteLoader {
+    /// Create a loader caching in [`default_cache_dir`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cache fetched programs in `dir`
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Always fetch, never reading or writing a cache
+    pub fn no_cache(mut self) -> Self {
+        self.cache_dir = None;
+        self
+    }
+
+    /// Set the timeout of a fetch
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Accept plain `http://` URLs
+    ///
+    /// Off by default; the checksum still protects integrity, but not
+    /// confidentiality.
+    pub fn allow_http(mut self, allow: bool) -> Self {
+        self.allow_http = allow;
+        self
+    }
+
+    /// Path a program with `sha256` is cached at, if caching
+    pub fn cache_path(&self, sha256: &str) -> Option<PathBuf> {
+        let dir = self.cache_dir.as_ref()?;
+        Some(dir.join(format!("{}.ir.json", sha256.to_ascii_lowercase())))
+    }
+
+    /// Get the IR with hash `expected_sha256`, from the cache or from `url`
+    ///
+    /// Cached copies are verified before use; a corrupt copy is fetched
+    /// again.
+    pub async fn fetch(&self, url: &str, expected_sha256: &str) -> Result<String> {
+        let expected = expected_sha256.trim().to_ascii_lowercase();
+        if expected.len() != 64 || !expected.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(Error::InvalidOperation(format!(
+                "'{}' is not a SHA-256 hex digest",
+                expected_sha256
+            )));
+        }
+
+        let cache_path = self.cache_path(&expected);
+        if let Some(path) = &cache_path {
+            if let Ok(ir) = tokio::fs::read_to_string(path).await {
+                if program_hash(&ir) == expected {
+                    return Ok(ir);
+                }
+                #[cfg(feature = "tracing")]
+                tracing::warn!(path = %path.display(), "cached program corrupt, fetching again");
+            }
+        }
+
+        let ir = self.download(url).await?;
+        let actual = program_hash(&ir);
+        if actual != expected {
+            return Err(Error::Integrity(format!(
+                "program at '{}' has SHA-256 {}, expected {}",
+                url, actual, expected
+            )));
+        }
+
+        if let Some(path) = &cache_path {
+            write_cache(path, &ir).await?;
+        }
+        Ok(ir)
+    }
+
+    async fn download(&self, url: &str) -> Result<String> {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| Error::Fetch(format!("invalid URL '{}': {}", url, e)))?;
+        match parsed.scheme() {
+            "https" => {}
+            "http" if self.allow_http => {}
+            scheme => {
+                return Err(Error::Fetch(format!(
+                    "refusing to fetch '{}' over {}",
+                    url, scheme
+                )))
+            }
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .map_err(|e| Error::Fetch(e.to_string()))?;
+        let response = client
+            .get(parsed)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| Error::Fetch(format!("'{}': {}", url, e)))?;
+        response
+            .text()
+            .await
+            .map_err(|e| Error::Fetch(format!("'{}': {}", url, e)))
+    }
+}
+
+/// Write a cache entry through a temporary file and a rename
+async fn write_cache(path: &Path, ir: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let mut temp = path.as_os_str().to_os_string();
+    temp.push(".tmp");
+    tokio::fs::write(&temp, ir).await?;
+    tokio::fs::rename(&temp, path).await?;
+    Ok(())
+}
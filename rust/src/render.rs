@@ -0,0 +1,451 @@
+//! Human-readable renderings of Charta programs
+//!
+//! [`ladder_text`] draws rungs as ladder diagrams for terminals and logs:
+//!
+//! ```text
+//! allow_review
+//! |--[ user_submitted ]--+-[ system_ok ]-+--( allow_review )
+//!                        +-[/override ]--+
+//! ```
+//!
+//! Normally open contacts render as `[ name ]`, normally closed ones as
+//! `[/name ]`, OR branches as parallel rows, and coils as `( name )`.
+//!
+//! [`to_mermaid`] and [`to_svg`] instead draw the dependency graph of a
+//! program (signals → rungs → coils) for documentation portals and review
+//! tools. Edges from contacts that pass when their operand is false are
+//! labelled `NC` (Mermaid) or dashed (SVG).
+
+use crate::ir::{Action, ContactType, Guard, Program, Rung};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Character set used for ladder diagrams
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LadderStyle {
+    /// Plain ASCII (`-`, `|`, `+`)
+    #[default]
+    Ascii,
+    /// Unicode box-drawing characters
+    Unicode,
+}
+
+struct Glyphs {
+    wire: char,
+    rail: char,
+    bar: char,
+    top_left: char,
+    mid_left: char,
+    bottom_left: char,
+    top_right: char,
+    mid_right: char,
+    bottom_right: char,
+}
+
+impl LadderStyle {
+    fn glyphs(self) -> Glyphs {
+        match self {
+            LadderStyle::Ascii => Glyphs {
+                wire: '-',
+                rail: '|',
+                bar: '|',
+                top_left: '+',
+                mid_left: '+',
+                bottom_left: '+',
+                top_right: '+',
+                mid_right: '+',
+                bottom_right: '+',
+            },
+            LadderStyle::Unicode => Glyphs {
+                wire: '─',
+                rail: '┃',
+                bar: '│',
+                top_left: '┬',
+                mid_left: '├',
+                bottom_left: '└',
+                top_right: '┬',
+                mid_right: '┤',
+                bottom_right: '┘',
+            },
+        }
+    }
+}
+
+/// A rectangular piece of diagram whose first row carries the circuit
+struct Block {
+    lines: Vec<String>,
+    width: usize,
+}
+
+impl Block {
+    fn element(text: String) -> Self {
+        let width = text.chars().count();
+        Self {
+            lines: vec![text],
+            width,
+        }
+    }
+
+    /// Pad every row to `width`, extending the circuit row with wire
+    fn padded(&self, width: usize, wire: char) -> Vec<String> {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(row, line)| {
+                let fill = if row == 0 { wire } else { ' ' };
+                let mut line = line.clone();
+                line.extend(std::iter::repeat(fill).take(width - self.width));
+                line
+            })
+            .collect()
+    }
+}
+
+/// Render every rung of a program as an ASCII ladder diagram
+pub fn ladder_text(program: &Program) -> String {
+    ladder_text_with(program, LadderStyle::Ascii)
+}
+
+/// Render every rung of a program as a ladder diagram in the given style
+pub fn ladder_text_with(program: &Program, style: LadderStyle) -> String {
+    let glyphs = style.glyphs();
+    let mut out = String::new();
+    for rung in &program.module.rungs {
+        out.push_str(&rung_text(rung, &glyphs));
+        out.push('\n');
+    }
+    out
+}
+
+fn rung_text(rung: &Rung, glyphs: &Glyphs) -> String {
+    let coils: Vec<Block> = rung
+        .actions
+        .iter()
+        .map(|action| match action {
+            Action::Energise { coil } => Block::element(format!("( {} )", coil)),
+            Action::Move { dest, expr } => Block::element(format!("[MOV {} -> {}]", expr, dest)),
+        })
+        .collect();
+    let coils = match coils.len() {
+        0 => Block::element(String::new()),
+        1 => coils.into_iter().next().unwrap_or_else(|| Block::element(String::new())),
+        _ => parallel(coils, glyphs),
+    };
+    let diagram = series(vec![guard_block(&rung.guard, glyphs), coils], glyphs);
+
+    let mut out = format!("{}\n", rung.name);
+    for (row, line) in diagram.lines.iter().enumerate() {
+        let (rail, lead) = if row == 0 {
+            (glyphs.rail, glyphs.wire)
+        } else {
+            (' ', ' ')
+        };
+        out.push(rail);
+        out.push(lead);
+        out.push(lead);
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out.pop();
+    out
+}
+
+fn guard_block(guard: &Guard, glyphs: &Glyphs) -> Block {
+    match guard {
+        Guard::Contact { name, contact_type } => Block::element(match contact_type {
+            ContactType::NormallyOpen => format!("[ {} ]", name),
+            ContactType::NormallyClosed => format!("[/{} ]", name),
+        }),
+        Guard::And { .. } => series(
+            guard.operands().into_iter().map(|g| guard_block(g, glyphs)).collect(),
+            glyphs,
+        ),
+        Guard::Or { .. } => parallel(
+            guard.operands().into_iter().map(|g| guard_block(g, glyphs)).collect(),
+            glyphs,
+        ),
+        Guard::Compare { .. } | Guard::WithinRange { .. } => {
+            Block::element(format!("[{}]", guard.condition().unwrap_or_default()))
+        }
+        Guard::Not { operand } => match operand.as_ref() {
+            Guard::Contact { name, contact_type } => Block::element(match contact_type {
+                ContactType::NormallyOpen => format!("[/{} ]", name),
+                ContactType::NormallyClosed => format!("[ {} ]", name),
+            }),
+            operand => {
+                let inner = guard_block(operand, glyphs);
+                let lines = inner
+                    .lines
+                    .iter()
+                    .enumerate()
+                    .map(|(row, line)| match row {
+                        0 => format!("NOT({})", line),
+                        _ => format!("    {} ", line),
+                    })
+                    .collect();
+                Block {
+                    lines,
+                    width: inner.width + 5,
+                }
+            }
+        },
+    }
+}
+
+/// Join blocks left to right with wire
+fn series(blocks: Vec<Block>, glyphs: &Glyphs) -> Block {
+    let height = blocks.iter().map(|block| block.lines.len()).max().unwrap_or(1);
+    let joint: String = [glyphs.wire, glyphs.wire].iter().collect();
+    let mut lines = vec![String::new(); height];
+    let mut width = 0;
+
+    for (index, block) in blocks.iter().enumerate() {
+        for (row, line) in lines.iter_mut().enumerate() {
+            if index > 0 {
+                line.push_str(if row == 0 { &joint } else { "  " });
+            }
+            match block.lines.get(row) {
+                Some(text) => line.push_str(text),
+                None => line.extend(std::iter::repeat(' ').take(block.width)),
+            }
+        }
+        width += block.width + if index > 0 { 2 } else { 0 };
+    }
+
+    Block { lines, width }
+}
+
+/// Stack blocks as parallel branches
+fn parallel(blocks: Vec<Block>, glyphs: &Glyphs) -> Block {
+    let width = blocks.iter().map(|block| block.width).max().unwrap_or(0);
+    let last = blocks.len().saturating_sub(1);
+    let mut lines = Vec::new();
+
+    for (index, block) in blocks.iter().enumerate() {
+        let (left, right) = match index {
+            0 => (glyphs.top_left, glyphs.top_right),
+            i if i == last => (glyphs.bottom_left, glyphs.bottom_right),
+            _ => (glyphs.mid_left, glyphs.mid_right),
+        };
+        for (row, text) in block.padded(width, glyphs.wire).into_iter().enumerate() {
+            let line = if row == 0 {
+                format!("{}{}{}{}{}", left, glyphs.wire, text, glyphs.wire, right)
+            } else if index < last {
+                format!("{} {} {}", glyphs.bar, text, glyphs.bar)
+            } else {
+                format!("  {}  ", text)
+            };
+            lines.push(line);
+        }
+    }
+
+    Block {
+        lines,
+        width: width + 4,
+    }
+}
+
+/// Column of a node in the dependency graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Column {
+    Signal,
+    Rung,
+    Coil,
+}
+
+/// Signals → rungs → coils dependency graph of a program
+struct Graph {
+    /// Node kind and name, in first-seen order within each kind
+    nodes: Vec<(Column, String)>,
+    /// (from, to, inverted) node indices
+    edges: Vec<(usize, usize, bool)>,
+}
+
+impl Graph {
+    fn new(program: &Program) -> Self {
+        let module = &program.module;
+        let mut graph = Self {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+        let mut index = BTreeMap::new();
+        for signal in &module.signals {
+            graph.node(&mut index, Column::Signal, &signal.name);
+        }
+        for coil in &module.coils {
+            graph.node(&mut index, Column::Coil, &coil.name);
+        }
+
+        for rung in &module.rungs {
+            let rung_node = graph.node(&mut index, Column::Rung, &rung.name);
+            let mut contacts = Vec::new();
+            contacts_of(&rung.guard, false, &mut contacts);
+            for (name, inverted) in contacts {
+                // Contacts on coils read back the coil; anything else is a signal
+                let kind = if module.coils.iter().any(|coil| coil.name == name) {
+                    Column::Coil
+                } else {
+                    Column::Signal
+                };
+                let from = graph.node(&mut index, kind, name);
+                graph.edges.push((from, rung_node, inverted));
+            }
+            for coil in rung.target_coils() {
+                let to = graph.node(&mut index, Column::Coil, coil);
+                graph.edges.push((rung_node, to, false));
+            }
+        }
+        graph
+    }
+
+    fn node(
+        &mut self,
+        index: &mut BTreeMap<(Column, String), usize>,
+        kind: Column,
+        name: &str,
+    ) -> usize {
+        *index.entry((kind, name.to_string())).or_insert_with(|| {
+            self.nodes.push((kind, name.to_string()));
+            self.nodes.len() - 1
+        })
+    }
+
+    fn of_kind(&self, kind: Column) -> impl Iterator<Item = (usize, &str)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(move |(_, (k, _))| *k == kind)
+            .map(|(i, (_, name))| (i, name.as_str()))
+    }
+}
+
+/// Collect contact names with whether they pass on a false operand
+fn contacts_of<'a>(guard: &'a Guard, negated: bool, out: &mut Vec<(&'a str, bool)>) {
+    match guard {
+        Guard::Contact { name, contact_type } => {
+            let inverted = negated != (*contact_type == ContactType::NormallyClosed);
+            if !out.contains(&(name.as_str(), inverted)) {
+                out.push((name.as_str(), inverted));
+            }
+        }
+        Guard::Not { operand } => contacts_of(operand, !negated, out),
+        Guard::Compare { name, expr, .. } | Guard::WithinRange { name, expr, .. } => {
+            let names = match expr {
+                Some(expr) => expr.signals(),
+                None => vec![name.as_str()],
+            };
+            for name in names {
+                if !out.contains(&(name, negated)) {
+                    out.push((name, negated));
+                }
+            }
+        }
+        _ => {
+            for operand in guard.operands() {
+                contacts_of(operand, negated, out);
+            }
+        }
+    }
+}
+
+/// Render the dependency graph of a program as a Mermaid flowchart
+pub fn to_mermaid(program: &Program) -> String {
+    let graph = Graph::new(program);
+    let mut out = String::from("flowchart LR\n");
+    for (i, (kind, name)) in graph.nodes.iter().enumerate() {
+        let label = name.replace('"', "#quot;");
+        let _ = match kind {
+            Column::Signal => writeln!(out, "    n{}([\"{}\"])", i, label),
+            Column::Rung => writeln!(out, "    n{}[\"{}\"]", i, label),
+            Column::Coil => writeln!(out, "    n{}((\"{}\"))", i, label),
+        };
+    }
+    for (from, to, inverted) in &graph.edges {
+        let _ = if *inverted {
+            writeln!(out, "    n{} -- NC --> n{}", from, to)
+        } else {
+            writeln!(out, "    n{} --> n{}", from, to)
+        };
+    }
+    out
+}
+
+/// Render the dependency graph of a program as a standalone SVG document
+///
+/// Signals, rungs, and coils are laid out in three columns.
+pub fn to_svg(program: &Program) -> String {
+    const COLUMN_WIDTH: usize = 240;
+    const ROW_HEIGHT: usize = 40;
+    const BOX_WIDTH: usize = 180;
+    const BOX_HEIGHT: usize = 26;
+    const MARGIN: usize = 20;
+
+    let graph = Graph::new(program);
+    let mut position = vec![(0, 0); graph.nodes.len()];
+    let mut rows = 0;
+    let columns = [Column::Signal, Column::Rung, Column::Coil];
+    for (column, kind) in columns.into_iter().enumerate() {
+        let mut row = 0;
+        for (i, _) in graph.of_kind(kind) {
+            position[i] = (MARGIN + column * COLUMN_WIDTH, MARGIN + row * ROW_HEIGHT);
+            row += 1;
+        }
+        rows = rows.max(row);
+    }
+
+    let width = 2 * MARGIN + 2 * COLUMN_WIDTH + BOX_WIDTH;
+    let height = 2 * MARGIN + rows.max(1) * ROW_HEIGHT;
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="monospace" font-size="12">"#,
+        w = width,
+        h = height
+    );
+
+    for (from, to, inverted) in &graph.edges {
+        let (x1, y1) = position[*from];
+        let (x2, y2) = position[*to];
+        let dash = if *inverted { r#" stroke-dasharray="4 3""# } else { "" };
+        let _ = writeln!(
+            out,
+            r##"  <line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#555"{}/>"##,
+            x1 + BOX_WIDTH,
+            y1 + BOX_HEIGHT / 2,
+            x2,
+            y2 + BOX_HEIGHT / 2,
+            dash
+        );
+    }
+
+    for (i, (kind, name)) in graph.nodes.iter().enumerate() {
+        let (x, y) = position[i];
+        let (fill, radius) = match kind {
+            Column::Signal => ("#e3f2fd", BOX_HEIGHT / 2),
+            Column::Rung => ("#f5f5f5", 0),
+            Column::Coil => ("#e8f5e9", BOX_HEIGHT / 2),
+        };
+        let _ = writeln!(
+            out,
+            r##"  <rect x="{}" y="{}" width="{}" height="{}" rx="{}" fill="{}" stroke="#333"/>"##,
+            x, y, BOX_WIDTH, BOX_HEIGHT, radius, fill
+        );
+        let _ = writeln!(
+            out,
+            r#"  <text x="{}" y="{}" text-anchor="middle" dominant-baseline="middle">{}</text>"#,
+            x + BOX_WIDTH / 2,
+            y + BOX_HEIGHT / 2,
+            escape_xml(name)
+        );
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,115 @@
+//! Safety-classified coils
+//!
+//! Coils marked `"safety": true` in the IR, or with
+//! [`ChartaVMBuilder::safety_coil`](crate::ChartaVMBuilder::safety_coil), are
+//! protected from manual writes. [`set_coil`](crate::ChartaVM::set_coil)
+//! refuses them with [`Error::AccessDenied`]; overriding one takes a
+//! one-time confirmation token issued for that coil and value:
+//!
+//! ```no_run
+//! use charta::ChartaVM;
+//!
+//! # async fn example(vm: &mut ChartaVM) -> charta::Result<()> {
+//! let token = vm.request_safety_override("allow_operation", true)?;
+//! // ... an operator confirms ...
+//! vm.set_safety_coil("allow_operation", true, &token).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Tokens expire after [`TOKEN_TTL`] and are consumed by their first use.
+//! Every override is logged at error level with the `tracing` feature and
+//! emits a [`VmEvent::SafetyOverride`](crate::VmEvent::SafetyOverride).
+//! Scans drive safety coils as usual.
+
+use crate::error::{Error, Result};
+use crate::ir::Program;
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+use std::time::{Duration, Instant};
+
+/// How long a confirmation token stays valid
+pub const TOKEN_TTL: Duration = Duration::from_secs(60);
+
+/// Check whether `name` is a safety coil, by IR marker or VM configuration
+pub(crate) fn is_safety_coil(
+    program: Option<&Program>,
+    configured: &HashSet<String>,
+    name: &str,
+) -> bool {
+    configured.contains(name)
+        || program.is_some_and(|program| {
+            program
+                .module
+                .coils
+                .iter()
+                .any(|coil| coil.name == name && coil.meta.safety)
+        })
+}
+
+/// Error for a manual write to a safety coil
+pub(crate) fn refused(name: &str) -> Error {
+    Error::AccessDenied(format!(
+        "coil '{}' is safety-classified; request a confirmation token and use set_safety_coil",
+        name
+    ))
+}
+
+/// Error for requesting an override of an ordinary coil
+pub(crate) fn not_safety(name: &str) -> Error {
+    Error::InvalidOperation(format!(
+        "coil '{}' is not safety-classified; use set_coil",
+        name
+    ))
+}
+
+#[derive(Debug, Clone)]
+struct PendingOverride {
+    token: String,
+    coil: String,
+    value: bool,
+    expires: Instant,
+}
+
+/// Confirmation tokens issued and not yet used
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SafetyOverrides {
+    pending: Vec<PendingOverride>,
+}
+
+impl SafetyOverrides {
+    /// Issue a token confirming a write of `value` to `coil`
+    pub(crate) fn issue(&mut self, coil: &str, value: bool) -> String {
+        let now = Instant::now();
+        self.pending.retain(|pending| pending.expires > now);
+        let seed = std::collections::hash_map::RandomState::new().hash_one((coil, value, now));
+        let token = format!("{:08x}", seed as u32);
+        self.pending.push(PendingOverride {
+            token: token.clone(),
+            coil: coil.to_string(),
+            value,
+            expires: now + TOKEN_TTL,
+        });
+        token
+    }
+
+    /// Consume the token confirming a write of `value` to `coil`
+    pub(crate) fn redeem(&mut self, coil: &str, value: bool, token: &str) -> Result<()> {
+        let now = Instant::now();
+        self.pending.retain(|pending| pending.expires > now);
+        let index = self
+            .pending
+            .iter()
+            .position(|pending| {
+                pending.token == token && pending.coil == coil && pending.value == value
+            })
+            .ok_or_else(|| {
+                Error::AccessDenied(format!(
+                    "invalid or expired confirmation token for coil '{}'",
+                    coil
+                ))
+            })?;
+        self.pending.swap_remove(index);
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+//! Scan cycle steps shared by the async and blocking VMs
+//!
+//! The VM core only knows boolean signals, coils, and rungs. Everything the
+//! SDK adds around a scan (comparisons lowered to derived signals, quality
+//! gates, scan groups, bypasses, and register moves) is run by a
+//! [`Scanner`] built at load time. [`crate::ChartaVM`] and
+//! [`crate::blocking::ChartaVM`] both drive their cycles through it and only
+//! differ in how they hold their state and what they do with the results.
+
+use crate::bypass::Bypasses;
+use crate::error::{Error, Result};
+use crate::filter::InputFilters;
+use crate::ir::{Program, RungEvaluation};
+use crate::load::{self, Comparison, RegisterMove};
+use crate::quality::{Quality, QualityGates, QualityPolicy, QualityTracker};
+use crate::scan_group;
+use crate::value::{self, Value};
+use charta_vm::VM;
+use std::collections::HashMap;
+
+/// What a loaded program runs around each scan of the VM core
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Scanner {
+    /// Comparisons set as derived signals before each cycle
+    comparisons: Vec<Comparison>,
+    /// Register moves applied after each cycle
+    moves: Vec<RegisterMove>,
+    /// Signals each rung reads, for quality propagation
+    gates: QualityGates,
+    /// Scan groups rungs are assigned to, sorted by name
+    groups: Vec<String>,
+    /// What rungs reading a Bad or Stale signal do
+    policy: QualityPolicy,
+}
+
+impl Scanner {
+    pub(crate) fn new(
+        comparisons: Vec<Comparison>,
+        moves: Vec<RegisterMove>,
+        gates: QualityGates,
+        groups: Vec<String>,
+        policy: QualityPolicy,
+    ) -> Self {
+        Self {
+            comparisons,
+            moves,
+            gates,
+            groups,
+            policy,
+        }
+    }
+
+    /// Scan groups rungs are assigned to, sorted by name
+    pub(crate) fn groups(&self) -> &[String] {
+        &self.groups
+    }
+
+    /// Fail unless some rung is in `group`
+    pub(crate) fn check_group(&self, group: &str) -> Result<()> {
+        if self.groups.iter().any(|name| name == group) {
+            Ok(())
+        } else {
+            Err(Error::NotFound(format!("scan group '{}'", group)))
+        }
+    }
+
+    /// Check whether the program has `move` actions
+    pub(crate) fn has_moves(&self) -> bool {
+        !self.moves.is_empty()
+    }
+
+    /// Set the derived signals a scan of `group` reads and apply the quality
+    /// policy
+    ///
+    /// Comparisons are evaluated against `values` and `registers`; groups
+    /// other than `group` are held, and every group scans if it is `None`.
+    pub(crate) fn prepare(
+        &self,
+        inputs: &mut HashMap<String, bool>,
+        group: Option<&str>,
+        values: &HashMap<String, Value>,
+        registers: &HashMap<String, Value>,
+        quality: &QualityTracker,
+    ) -> Result<()> {
+        for name in &self.groups {
+            let scanned = !matches!(group, Some(group) if group != name);
+            inputs.insert(scan_group::group_signal(name), scanned);
+        }
+        self.derive(inputs, values, registers, quality)
+    }
+
+    /// Set the comparison and quality signals in `state` and apply the
+    /// quality policy, for replaying a rung outside a cycle
+    pub(crate) fn derive(
+        &self,
+        state: &mut HashMap<String, bool>,
+        values: &HashMap<String, Value>,
+        registers: &HashMap<String, Value>,
+        quality: &QualityTracker,
+    ) -> Result<()> {
+        for comparison in &self.comparisons {
+            let holds = comparison.evaluate(values, registers);
+            state.insert(comparison.signal.clone(), holds);
+        }
+        self.gates.check(self.policy, quality, state)
+    }
+
+    /// Replay the rungs of `program` against the state a scan of `group`
+    /// started from
+    ///
+    /// The SDK model has no bypass, group, or quality contacts, so they are
+    /// applied here as the core applies them: disabled rungs and rungs
+    /// reading a degraded signal evaluate false and de-energise their
+    /// coils, and rungs held in another group evaluate false and leave
+    /// their coils as they were.
+    pub(crate) fn evaluate(
+        &self,
+        program: &Program,
+        state: &HashMap<String, bool>,
+        bypasses: &Bypasses,
+        group: Option<&str>,
+    ) -> Vec<RungEvaluation> {
+        let mut state = state.clone();
+        let mut evaluations = Vec::with_capacity(program.module.rungs.len());
+        for (index, rung) in program.module.rungs.iter().enumerate() {
+            let scanned = scan_group::scanned(rung, group);
+            let energised = scanned && self.rung_holds(program, index, &state, bypasses);
+            if scanned {
+                for coil in rung.target_coils() {
+                    state.insert(coil.to_string(), energised);
+                }
+            }
+            evaluations.push(RungEvaluation {
+                rung: rung.name.clone(),
+                energised,
+                source: rung.source.clone(),
+            });
+        }
+        evaluations
+    }
+
+    /// Check whether the rung at `index` energises in a scan of `state`
+    ///
+    /// Disabled rungs and, under [`QualityPolicy::EvaluateFalse`], rungs
+    /// reading a degraded signal do not.
+    pub(crate) fn rung_holds(
+        &self,
+        program: &Program,
+        index: usize,
+        state: &HashMap<String, bool>,
+        bypasses: &Bypasses,
+    ) -> bool {
+        let rung = &program.module.rungs[index];
+        !bypasses.contains(&rung.name)
+            && (self.policy != QualityPolicy::EvaluateFalse || self.gates.passes(index, state))
+            && rung
+                .guard
+                .evaluate(&|name: &str| state.get(name).copied().unwrap_or(false))
+    }
+
+    /// Apply the moves of the rungs energised in `evaluations`
+    pub(crate) fn apply_moves(
+        &self,
+        evaluations: &[RungEvaluation],
+        values: &HashMap<String, Value>,
+        registers: &mut HashMap<String, Value>,
+    ) {
+        let energised: Vec<bool> = evaluations
+            .iter()
+            .map(|evaluation| evaluation.energised)
+            .collect();
+        load::apply_moves(&self.moves, &energised, values, registers);
+    }
+
+    /// Apply the moves of the rung at `index` alone
+    pub(crate) fn apply_rung_moves(
+        &self,
+        index: usize,
+        rungs: usize,
+        values: &HashMap<String, Value>,
+        registers: &mut HashMap<String, Value>,
+    ) {
+        let mut energised = vec![false; rungs];
+        energised[index] = true;
+        load::apply_moves(&self.moves, &energised, values, registers);
+    }
+}
+
+/// Fail if `inputs` write a derived signal
+pub(crate) fn check_inputs(inputs: &HashMap<String, bool>) -> Result<()> {
+    for name in inputs.keys() {
+        value::check_writable(name)?;
+    }
+    Ok(())
+}
+
+/// Mark the signals in `inputs` fresh, returning the signals past their TTL
+/// with the fallback they revert to
+pub(crate) fn refresh_quality(
+    quality: &mut QualityTracker,
+    inputs: &HashMap<String, bool>,
+) -> Vec<(String, bool)> {
+    for name in inputs.keys().filter(|name| !value::is_derived_signal(name)) {
+        quality.update(name, Quality::Good);
+    }
+    quality.expire()
+}
+
+/// State the rungs of a scan start from: signals, then coils, then the
+/// cycle's inputs
+pub(crate) fn scan_state(
+    mut signals: HashMap<String, bool>,
+    coils: &HashMap<String, bool>,
+    inputs: &HashMap<String, bool>,
+) -> HashMap<String, bool> {
+    signals.extend(coils.iter().map(|(name, value)| (name.clone(), *value)));
+    signals.extend(inputs.iter().map(|(name, value)| (name.clone(), *value)));
+    signals
+}
+
+/// Step `vm` with `inputs`, then put its signals and coils back
+///
+/// Filters condition the inputs from a copy, so their state is unchanged.
+/// With `trace` set, also returns the state the scan started from.
+pub(crate) fn dry_step(
+    vm: &mut VM,
+    filters: &InputFilters,
+    mut inputs: HashMap<String, bool>,
+    trace: bool,
+) -> Result<(HashMap<String, bool>, Option<HashMap<String, bool>>)> {
+    let signals = vm.get_all_signals();
+    let coils = vm.get_all_coils();
+    if !filters.is_empty() {
+        // Filter state advances as inputs are conditioned; use a copy
+        filters.clone().condition(&signals, &mut inputs);
+    }
+    let state = trace.then(|| scan_state(signals.clone(), &coils, &inputs));
+    let stepped = vm.step(inputs);
+    for (name, value) in signals {
+        vm.set_signal(name, value);
+    }
+    for (name, value) in coils {
+        vm.set_coil(name, value);
+    }
+    let outputs = stepped.map_err(Error::VM)?;
+    Ok((outputs, state))
+}
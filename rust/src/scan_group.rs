@@ -0,0 +1,205 @@
+//! Scan groups
+//!
+//! Rungs can be assigned to a named scan group so that each group scans at
+//! its own rate: a safety loop every 10 ms and a compliance loop every 5 s,
+//! without scanning the slow rungs at the fast rate:
+//!
+//! ```json
+//! {"name": "overspeed_trip", "group": "fast", "guard": {...}, "actions": [...]}
+//! ```
+//!
+//! [`execute_group`](crate::ChartaVM::execute_group) scans one group.
+//! Rungs of other groups are held: their coils keep their values and they
+//! move nothing. Rungs without a group scan in every cycle, and
+//! [`execute_cycle`](crate::ChartaVM::execute_cycle) scans every group.
+//! Groups share the VM's signals and coils, and cycles never overlap.
+//!
+//! Groups given an interval with
+//! [`ChartaVMBuilder::scan_group`](crate::ChartaVMBuilder::scan_group) are
+//! driven by [`run_scan_groups`](crate::ChartaVM::run_scan_groups); the
+//! rest, such as an `on_demand` group, scan only when asked:
+//!
+//! ```no_run
+//! use charta::ChartaVM;
+//! use std::time::Duration;
+//!
+//! # async fn example(ir_json: &str) -> charta::Result<()> {
+//! let mut vm = ChartaVM::builder()
+//!     .scan_group("fast", Duration::from_millis(10))
+//!     .scan_group("slow", Duration::from_secs(5))
+//!     .build();
+//! vm.load_program(ir_json).await?;
+//! vm.run_scan_groups().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Each grouped rung is gated at load time on a derived signal (see
+//! [`group_signal`]) and split into one rung per coil, each sealing in its
+//! coil while the group is held.
+
+use alloc::format;
+use alloc::string::String;
+
+/// Prefix of the derived signals that hold while a group is scanned
+pub const GROUP_SIGNAL_PREFIX: &str = "__scan_group:";
+
+/// Name of the derived signal that holds while `group` is scanned
+pub fn group_signal(group: &str) -> String {
+    format!("{}{}", GROUP_SIGNAL_PREFIX, group)
+}
+
+#[cfg(feature = "std")]
+pub(crate) use scheduling::{apply, groups, scanned, ScanSchedule};
+
+#[cfg(feature = "std")]
+mod scheduling {
+    use super::group_signal;
+    use crate::error::{Error, Result};
+    use crate::ir::{Action, ContactType, Guard, Program, Rung, SignalDecl};
+    use std::collections::{BTreeSet, HashMap};
+    use std::time::{Duration, Instant};
+
+    /// Get the scan groups `program` assigns rungs to, sorted by name
+    pub(crate) fn groups(program: &Program) -> Vec<String> {
+        let groups: BTreeSet<&String> = program
+            .module
+            .rungs
+            .iter()
+            .filter_map(|rung| rung.group.as_ref())
+            .collect();
+        groups.into_iter().cloned().collect()
+    }
+
+    /// Check whether `rung` scans when `group` is scanned, or in a full
+    /// cycle if `group` is `None`
+    pub(crate) fn scanned(rung: &Rung, group: Option<&str>) -> bool {
+        match (group, &rung.group) {
+            (Some(scanned), Some(group)) => scanned == group,
+            _ => true,
+        }
+    }
+
+    /// Gate grouped rungs on their group signal, holding their coils while
+    /// the group is not scanned, and declare the group signals
+    ///
+    /// A grouped rung driving several coils becomes one rung per coil, the
+    /// first keeping the rung's name, the others named `rung/coil`.
+    pub(crate) fn apply(program: &mut Program) {
+        let groups = groups(program);
+        if groups.is_empty() {
+            return;
+        }
+        let mut rungs = Vec::with_capacity(program.module.rungs.len());
+        for rung in std::mem::take(&mut program.module.rungs) {
+            let Some(group) = rung.group.clone() else {
+                rungs.push(rung);
+                continue;
+            };
+            let coils: Vec<String> = rung.target_coils().map(String::from).collect();
+            for (index, coil) in coils.into_iter().enumerate() {
+                let mut held = rung.clone();
+                if index > 0 {
+                    held.name = format!("{}/{}", rung.name, coil);
+                }
+                held.guard = hold(&rung.guard, &group, &coil);
+                held.actions = vec![Action::Energise { coil }];
+                rungs.push(held);
+            }
+        }
+        program.module.rungs = rungs;
+        for group in groups {
+            program.module.signals.push(SignalDecl {
+                name: group_signal(&group),
+                value_type: Default::default(),
+                filter: None,
+                initial: None,
+                meta: Default::default(),
+            });
+        }
+    }
+
+    /// `(guard AND scanned) OR (NOT scanned AND coil)`
+    fn hold(guard: &Guard, group: &str, coil: &str) -> Guard {
+        let scanned = |contact_type| Guard::Contact {
+            name: group_signal(group),
+            contact_type,
+        };
+        let and = |operands| Guard::And {
+            left: None,
+            right: None,
+            operands,
+        };
+        Guard::Or {
+            left: None,
+            right: None,
+            operands: vec![
+                and(vec![guard.clone(), scanned(ContactType::NormallyOpen)]),
+                and(vec![
+                    scanned(ContactType::NormallyClosed),
+                    Guard::Contact {
+                        name: coil.to_string(),
+                        contact_type: ContactType::NormallyOpen,
+                    },
+                ]),
+            ],
+        }
+    }
+
+    /// When each scheduled group is next due
+    pub(crate) struct ScanSchedule {
+        groups: Vec<(String, Duration, Instant)>,
+    }
+
+    impl ScanSchedule {
+        /// Schedule each group at its interval, all due at `start`
+        ///
+        /// Faster groups come first when several are due together.
+        pub(crate) fn new(intervals: &HashMap<String, Duration>, start: Instant) -> Result<Self> {
+            if intervals.is_empty() {
+                return Err(Error::InvalidOperation(
+                    "No scan groups are scheduled".to_string(),
+                ));
+            }
+            let mut groups = Vec::with_capacity(intervals.len());
+            for (group, interval) in intervals {
+                if interval.is_zero() {
+                    return Err(Error::InvalidOperation(format!(
+                        "Scan group '{}' has a zero interval",
+                        group
+                    )));
+                }
+                groups.push((group.clone(), *interval, start));
+            }
+            groups.sort_by(|a, b| (a.1, &a.0).cmp(&(b.1, &b.0)));
+            Ok(Self { groups })
+        }
+
+        /// Get when the next group is due
+        pub(crate) fn next_due(&self) -> Instant {
+            self.groups
+                .iter()
+                .map(|(_, _, due)| *due)
+                .min()
+                .expect("schedule has groups")
+        }
+
+        /// Take the groups due at `now` and schedule their next scans
+        ///
+        /// A group that fell behind skips its missed scans rather than
+        /// running them back to back.
+        pub(crate) fn take_due(&mut self, now: Instant) -> Vec<String> {
+            let mut due = Vec::new();
+            for (group, interval, next) in &mut self.groups {
+                if *next <= now {
+                    due.push(group.clone());
+                    *next += *interval;
+                    if *next <= now {
+                        *next = now + *interval;
+                    }
+                }
+            }
+            due
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! HTTP control server for Charta VM
+//!
+//! Available with the `server` feature. [`ChartaServer::router`] returns an
+//! axum [`Router`] exposing the VM over REST:
+//!
+//! | Method | Path              | Description                              |
+//! |--------|-------------------|------------------------------------------|
+//! | GET    | `/signals`        | All signal states                        |
+//! | GET    | `/signals/{name}` | One signal state                         |
+//! | PUT    | `/signals/{name}` | Set a signal (`{"value": true}`)         |
+//! | GET    | `/coils`          | All coil states                          |
+//! | GET    | `/coils/{name}`   | One coil state                           |
+//! | POST   | `/cycle`          | Execute a cycle (optional inputs body)   |
+//! | GET    | `/events`         | Server-sent event stream of VM events    |
+//! | GET    | `/ws`             | WebSocket stream of VM events            |
+//! | GET    | `/program`        | Program information and disabled rungs   |
+//!
+//! WebSocket clients receive one JSON text frame per event, shaped
+//! `{"event": "coil_changed", "data": {...}}`; the event names and payloads
+//! match the SSE stream. Payloads are the serde form of [`VmEvent`] without
+//! its `type` tag, which becomes the event name. A client that falls behind receives a `lagged`
+//! event with the number of skipped events.
+//!
+//! ```no_run
+//! use charta::server::ChartaServer;
+//! use charta::ChartaVM;
+//!
+//! # async fn run(vm: ChartaVM) -> std::io::Result<()> {
+//! let app = ChartaServer::new(vm).router();
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await?;
+//! axum::serve(listener, app).await
+//! # }
+//! ```
+
+use crate::bypass::RungBypass;
+use crate::error::Error;
+use crate::events::{EventReceiver, VmEvent};
+use crate::observer::ChartaObserver;
+use crate::vm::ChartaVM;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Mutex;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+
+/// Shared handle serving a VM over HTTP
+#[derive(Clone)]
+pub struct ChartaServer {
+    /// VM for mutating requests
+    vm: Arc<Mutex<ChartaVM>>,
+    /// Lock-free view for reads and event streams
+    observer: ChartaObserver,
+}
+
+/// Body of a signal write
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalValue {
+    /// New signal value
+    pub value: bool,
+}
+
+/// Loaded program information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgramInfo {
+    /// Hash identifying the loaded program
+    pub program_id: Option<String>,
+    /// Number of cycles executed
+    pub cycle_count: u64,
+    /// Declared signal names
+    pub signals: Vec<String>,
+    /// Declared coil names
+    pub coils: Vec<String>,
+    /// Rungs disabled at runtime
+    #[serde(default)]
+    pub disabled_rungs: Vec<RungBypass>,
+}
+
+impl ChartaServer {
+    /// Serve a VM, taking ownership of it
+    pub fn new(vm: ChartaVM) -> Self {
+        let observer = vm.observer();
+        Self {
+            vm: Arc::new(Mutex::new(vm)),
+            observer,
+        }
+    }
+
+    /// Serve a VM shared with the rest of the application
+    pub fn from_shared(vm: Arc<Mutex<ChartaVM>>, observer: ChartaObserver) -> Self {
+        Self { vm, observer }
+    }
+
+    /// Get the served VM
+    pub fn vm(&self) -> Arc<Mutex<ChartaVM>> {
+        self.vm.clone()
+    }
+
+    /// Build the router
+    ///
+    /// Nest it under a prefix with [`Router::nest`] to mount it inside a
+    /// larger application.
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/signals", get(list_signals))
+            .route("/signals/:name", get(get_signal).put(set_signal))
+            .route("/coils", get(list_coils))
+            .route("/coils/:name", get(get_coil))
+            .route("/cycle", post(execute_cycle))
+            .route("/events", get(events))
+            .route("/ws", get(websocket))
+            .route("/program", get(program_info))
+            .with_state(self)
+    }
+}
+
+/// Error response: `{"error": "..."}` with a status matching the error kind
+struct ApiError(Error);
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self.0 {
+            Error::NotFound(_) => StatusCode::NOT_FOUND,
+            Error::AccessDenied(_) => StatusCode::FORBIDDEN,
+            Error::InvalidOperation(_) | Error::TypeMismatch(_) | Error::JSON(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(json!({ "error": self.0.to_string() }))).into_response()
+    }
+}
+
+type ApiResult<T> = std::result::Result<Json<T>, ApiError>;
+
+async fn list_signals(State(server): State<ChartaServer>) -> ApiResult<HashMap<String, bool>> {
+    Ok(Json(server.observer.get_all_signals().await?))
+}
+
+async fn get_signal(
+    State(server): State<ChartaServer>,
+    Path(name): Path<String>,
+) -> ApiResult<SignalValue> {
+    match server.observer.get_signal(&name).await? {
+        Some(value) => Ok(Json(SignalValue { value })),
+        None => Err(Error::NotFound(name).into()),
+    }
+}
+
+async fn set_signal(
+    State(server): State<ChartaServer>,
+    Path(name): Path<String>,
+    Json(body): Json<SignalValue>,
+) -> ApiResult<SignalValue> {
+    let mut vm = server.vm.lock().await;
+    vm.set_signal(&name, body.value).await?;
+    Ok(Json(body))
+}
+
+async fn list_coils(State(server): State<ChartaServer>) -> ApiResult<HashMap<String, bool>> {
+    Ok(Json(server.observer.get_all_coils().await?))
+}
+
+async fn get_coil(
+    State(server): State<ChartaServer>,
+    Path(name): Path<String>,
+) -> ApiResult<SignalValue> {
+    match server.observer.get_coil(&name).await? {
+        Some(value) => Ok(Json(SignalValue { value })),
+        None => Err(Error::NotFound(name).into()),
+    }
+}
+
+async fn execute_cycle(
+    State(server): State<ChartaServer>,
+    inputs: Option<Json<HashMap<String, bool>>>,
+) -> ApiResult<HashMap<String, bool>> {
+    let inputs = inputs.map(|Json(inputs)| inputs).unwrap_or_default();
+    let mut vm = server.vm.lock().await;
+    Ok(Json(vm.execute_cycle_with_inputs(inputs).await?.coils().clone()))
+}
+
+async fn events(
+    State(server): State<ChartaServer>,
+) -> Sse<impl Stream<Item = std::result::Result<Event, Infallible>>> {
+    // Lagged receivers report the missed events rather than closing the stream
+    let stream = BroadcastStream::new(server.observer.subscribe()).map(|event| {
+        let event = event.unwrap_or_else(|BroadcastStreamRecvError::Lagged(skipped)| {
+            VmEvent::Lagged { skipped }
+        });
+        let (name, data) = event_json(&event);
+        Ok(Event::default().event(name).data(data.to_string()))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn websocket(State(server): State<ChartaServer>, upgrade: WebSocketUpgrade) -> Response {
+    let events = server.observer.subscribe();
+    upgrade.on_upgrade(move |socket| stream_events(socket, events))
+}
+
+/// Push events to a WebSocket client until either side closes
+async fn stream_events(mut socket: WebSocket, mut events: EventReceiver) {
+    loop {
+        let frame = tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let (name, data) = event_json(&event);
+                    json!({ "event": name, "data": data })
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    let (name, data) = event_json(&VmEvent::Lagged { skipped });
+                    json!({ "event": name, "data": data })
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                // Incoming frames carry no commands; only watch for close
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+        if socket.send(Message::Text(frame.to_string())).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn program_info(State(server): State<ChartaServer>) -> ApiResult<ProgramInfo> {
+    let observer = &server.observer;
+    Ok(Json(ProgramInfo {
+        program_id: observer.program_id(),
+        cycle_count: observer.cycle_count(),
+        signals: observer.signal_names().await?,
+        coils: observer.coil_names().await?,
+        disabled_rungs: observer.disabled_rungs(),
+    }))
+}
+
+/// Get the SSE event name and JSON payload for a VM event
+///
+/// The payload is the event's serde form without its `type` tag, which
+/// becomes the event name.
+fn event_json(event: &VmEvent) -> (&'static str, serde_json::Value) {
+    let mut data = serde_json::to_value(event).unwrap_or_default();
+    if let Some(object) = data.as_object_mut() {
+        object.remove("type");
+    }
+    (event.kind(), data)
+}
@@ -0,0 +1,102 @@
+//! Shadow execution for Charta VM
+//!
+//! Runs a candidate program alongside the active one with identical inputs
+//! and reports every cycle where their outputs disagree.
+
+use crate::error::{Error, Result};
+use charta_vm::{VM, ir::load_ir};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Disagreement on a single coil between the primary and shadow programs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoilDivergence {
+    /// Coil name
+    pub name: String,
+    /// State in the primary program (`None` if it has no such coil)
+    pub primary: Option<bool>,
+    /// State in the shadow program (`None` if it has no such coil)
+    pub shadow: Option<bool>,
+}
+
+/// Divergence between the primary and shadow programs in one cycle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShadowDivergence {
+    /// Cycle number the divergence occurred in
+    pub cycle: u64,
+    /// Coils whose states differ
+    pub coils: Vec<CoilDivergence>,
+    /// Error message if the shadow program failed to execute the cycle
+    pub error: Option<String>,
+}
+
+/// Candidate program executed in the shadow of the active one
+pub(crate) struct Shadow {
+    vm: VM,
+}
+
+impl Shadow {
+    /// Load a candidate program
+    pub(crate) fn load(ir_json: &str) -> Result<Self> {
+        let ir = load_ir(ir_json).map_err(|e| Error::IRLoad(e.to_string()))?;
+        let mut vm = VM::new();
+        vm.load_program(ir).map_err(Error::VM)?;
+        Ok(Self { vm })
+    }
+
+    /// Execute a cycle with the same signals and inputs as the primary and
+    /// compare outputs
+    ///
+    /// `signals` is the primary's signal state before its cycle. Returns
+    /// `None` when both programs agree.
+    pub(crate) fn compare(
+        &mut self,
+        cycle: u64,
+        signals: HashMap<String, bool>,
+        inputs: HashMap<String, bool>,
+        primary: &HashMap<String, bool>,
+    ) -> Option<ShadowDivergence> {
+        for (name, value) in signals {
+            self.vm.set_signal(name, value);
+        }
+
+        let shadow = match self.vm.step(inputs) {
+            Ok(outputs) => outputs,
+            Err(e) => {
+                return Some(ShadowDivergence {
+                    cycle,
+                    coils: Vec::new(),
+                    error: Some(e.to_string()),
+                })
+            }
+        };
+
+        let names: BTreeSet<&String> = primary.keys().chain(shadow.keys()).collect();
+        let coils: Vec<CoilDivergence> = names
+            .into_iter()
+            .filter_map(|name| {
+                let primary = primary.get(name).copied();
+                let shadow = shadow.get(name).copied();
+                if primary != shadow {
+                    Some(CoilDivergence {
+                        name: name.clone(),
+                        primary,
+                        shadow,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        if coils.is_empty() {
+            None
+        } else {
+            Some(ShadowDivergence {
+                cycle,
+                coils,
+                error: None,
+            })
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Graceful VM shutdown
+//!
+//! [`ChartaVM::shutdown`](crate::ChartaVM::shutdown) tears a VM down in a
+//! fixed order: it marks the VM as stopping, optionally runs a final cycle
+//! with the [`SHUTDOWN_SIGNAL`] set, waits for queued callbacks, saves a last
+//! checkpoint, and marks the VM stopped. Cycles fail afterwards.
+//!
+//! Scan loops and other tasks hold a [`ShutdownHandle`] to request shutdown
+//! and to learn when it has started or completed:
+//!
+//! ```no_run
+//! use charta::shutdown::ShutdownOptions;
+//! use charta::ChartaVM;
+//! use std::time::Duration;
+//!
+//! # async fn example(mut vm: ChartaVM) -> charta::Result<()> {
+//! let shutdown = vm.shutdown_handle();
+//! tokio::spawn({
+//!     let shutdown = shutdown.clone();
+//!     async move {
+//!         tokio::signal::ctrl_c().await.ok();
+//!         shutdown.request();
+//!     }
+//! });
+//!
+//! let mut ticker = tokio::time::interval(Duration::from_millis(100));
+//! while !shutdown.is_requested() {
+//!     tokio::select! {
+//!         _ = ticker.tick() => { vm.execute_cycle().await?; }
+//!         _ = shutdown.requested() => {}
+//!     }
+//! }
+//! vm.shutdown(ShutdownOptions::default().final_cycle(true)).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// System signal set during the final cycle of a shutdown
+///
+/// Programs declaring it can release or latch coils on the way down. It is
+/// only passed to programs that declare it.
+pub const SHUTDOWN_SIGNAL: &str = "__shutdown";
+
+/// Lifecycle of a VM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownState {
+    /// Executing cycles normally
+    Running,
+    /// Shutdown requested or in progress
+    Stopping,
+    /// Shut down; cycles fail
+    Stopped,
+}
+
+/// What [`ChartaVM::shutdown`](crate::ChartaVM::shutdown) does on the way
+/// down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownOptions {
+    /// Run a final cycle with [`SHUTDOWN_SIGNAL`] set
+    pub final_cycle: bool,
+    /// Save a checkpoint to the configured state store
+    pub checkpoint: bool,
+}
+
+impl Default for ShutdownOptions {
+    fn default() -> Self {
+        Self {
+            final_cycle: false,
+            checkpoint: true,
+        }
+    }
+}
+
+impl ShutdownOptions {
+    /// Set whether a final cycle runs
+    pub fn final_cycle(mut self, final_cycle: bool) -> Self {
+        self.final_cycle = final_cycle;
+        self
+    }
+
+    /// Set whether a last checkpoint is saved
+    pub fn checkpoint(mut self, checkpoint: bool) -> Self {
+        self.checkpoint = checkpoint;
+        self
+    }
+}
+
+/// Shared view of a VM's lifecycle
+///
+/// Cloning is cheap; every clone observes the same VM.
+#[derive(Debug, Clone)]
+pub struct ShutdownHandle {
+    state: Arc<watch::Sender<ShutdownState>>,
+}
+
+impl ShutdownHandle {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: Arc::new(watch::channel(ShutdownState::Running).0),
+        }
+    }
+
+    /// Ask the owner of the VM to shut it down
+    ///
+    /// Scan loops watching [`requested`](Self::requested) stop; the owner
+    /// still calls [`ChartaVM::shutdown`](crate::ChartaVM::shutdown).
+    pub fn request(&self) {
+        self.advance(ShutdownState::Stopping);
+    }
+
+    /// Current lifecycle state
+    pub fn state(&self) -> ShutdownState {
+        *self.state.borrow()
+    }
+
+    /// Check whether shutdown was requested or has completed
+    pub fn is_requested(&self) -> bool {
+        self.state() != ShutdownState::Running
+    }
+
+    /// Wait until shutdown is requested
+    pub async fn requested(&self) {
+        let mut state = self.state.subscribe();
+        // The sender lives in this handle, so waiting never fails
+        let _ = state.wait_for(|state| *state != ShutdownState::Running).await;
+    }
+
+    /// Wait until shutdown has completed
+    pub async fn completed(&self) {
+        let mut state = self.state.subscribe();
+        let _ = state.wait_for(|state| *state == ShutdownState::Stopped).await;
+    }
+
+    pub(crate) fn complete(&self) {
+        self.advance(ShutdownState::Stopped);
+    }
+
+    /// Move forward to `next`, never back
+    fn advance(&self, next: ShutdownState) {
+        self.state.send_if_modified(|state| {
+            let forward = matches!(
+                (*state, next),
+                (ShutdownState::Running, _) | (ShutdownState::Stopping, ShutdownState::Stopped)
+            );
+            if forward {
+                *state = next;
+            }
+            forward
+        });
+    }
+}
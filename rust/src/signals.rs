@@ -1,6 +1,7 @@
 //! Signal management for Charta VM
 
 use crate::error::{Error, Result};
+use crate::namespace;
 
 /// Signal manager for setting and getting signal values
 pub struct SignalManager;
@@ -12,11 +13,13 @@ impl SignalManager {
     }
 
     /// Validate signal name
+    ///
+    /// Names may be dotted paths (see [`crate::namespace`]).
     pub fn validate_name(name: &str) -> Result<()> {
         if name.is_empty() {
             return Err(Error::InvalidOperation("Signal name cannot be empty".to_string()));
         }
-        Ok(())
+        namespace::validate_name(name)
     }
 }
 
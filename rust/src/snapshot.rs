@@ -0,0 +1,119 @@
+//! Published snapshots of VM state
+//!
+//! Every write to a VM (program load, cycle, signal or coil write) publishes
+//! an immutable [`StateSnapshot`]. Reads through
+//! [`ChartaObserver`](crate::ChartaObserver) load the latest snapshot
+//! without taking the VM lock, so dashboards polling at high frequency never
+//! stall a cycle in progress, and a cycle never blocks them.
+//!
+//! ```rust,ignore
+//! let snapshot = observer.snapshot();
+//! // Both reads come from the same point in time
+//! let ready = snapshot.get_signal("ready");
+//! let allowed = snapshot.get_coil("allow_review");
+//! ```
+
+use crate::outputs::CycleOutputs;
+use crate::value::is_derived_signal;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Signal and coil states at one point in time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StateSnapshot {
+    pub(crate) cycle: u64,
+    pub(crate) signals: Arc<HashMap<String, bool>>,
+    pub(crate) outputs: Arc<CycleOutputs>,
+    pub(crate) signal_names: Arc<Vec<String>>,
+    pub(crate) coil_names: Arc<Vec<String>>,
+}
+
+impl StateSnapshot {
+    /// Number of cycles executed when the snapshot was published
+    pub fn cycle(&self) -> u64 {
+        self.cycle
+    }
+
+    /// Get a signal state
+    pub fn get_signal(&self, name: &str) -> Option<bool> {
+        self.signals.get(name).copied()
+    }
+
+    /// Get a coil state
+    pub fn get_coil(&self, name: &str) -> Option<bool> {
+        self.outputs.get(name).copied()
+    }
+
+    /// Get all signal states
+    pub fn signals(&self) -> &HashMap<String, bool> {
+        &self.signals
+    }
+
+    /// Get all coil states
+    pub fn coils(&self) -> &HashMap<String, bool> {
+        self.outputs.coils()
+    }
+
+    /// Get the outputs of the last cycle
+    pub fn outputs(&self) -> &Arc<CycleOutputs> {
+        &self.outputs
+    }
+
+    /// Get signal names
+    pub fn signal_names(&self) -> &[String] {
+        &self.signal_names
+    }
+
+    /// Get coil names
+    pub fn coil_names(&self) -> &[String] {
+        &self.coil_names
+    }
+
+    /// Copy of this snapshot with the signals and names of `vm`
+    pub(crate) fn with_signals(&self, vm: &charta_vm::VM) -> Self {
+        self.with_state(vm, self.cycle, Arc::clone(&self.outputs))
+    }
+
+    /// Copy of this snapshot with the signals of `vm` and new coil outputs
+    ///
+    /// The signal map and name lists are shared with this snapshot when
+    /// unchanged; names are only fetched from `vm` when the set of signals
+    /// or coils differs. Derived comparison signals are left out.
+    pub(crate) fn with_state(
+        &self,
+        vm: &charta_vm::VM,
+        cycle: u64,
+        outputs: Arc<CycleOutputs>,
+    ) -> Self {
+        let mut signals = vm.get_all_signals();
+        signals.retain(|name, _| !is_derived_signal(name));
+        let signal_names = if same_names(&self.signal_names, &signals) {
+            Arc::clone(&self.signal_names)
+        } else {
+            let mut names = vm.signal_names().to_vec();
+            names.retain(|name| !is_derived_signal(name));
+            Arc::new(names)
+        };
+        let coil_names = if same_names(&self.coil_names, outputs.coils()) {
+            Arc::clone(&self.coil_names)
+        } else {
+            Arc::new(vm.coil_names().to_vec())
+        };
+        let signals = if *self.signals == signals {
+            Arc::clone(&self.signals)
+        } else {
+            Arc::new(signals)
+        };
+        Self {
+            cycle,
+            signals,
+            outputs,
+            signal_names,
+            coil_names,
+        }
+    }
+}
+
+fn same_names(names: &[String], states: &HashMap<String, bool>) -> bool {
+    names.len() == states.len() && names.iter().all(|name| states.contains_key(name))
+}
@@ -0,0 +1,79 @@
+//! Per-coil statistics for Charta VM
+//!
+//! Maintained incrementally as cycles execute, for spotting chattering
+//! interlocks and permissions that never energise.
+
+use crate::collections::Map;
+use std::collections::HashMap;
+
+/// Statistics for a single coil
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CoilStats {
+    /// Number of false -> true transitions
+    pub energisations: u64,
+    /// Number of cycles the coil ended energised
+    pub cycles_energised: u64,
+    /// Number of cycles the coil has been observed in
+    pub cycles_observed: u64,
+    /// Cycle of the most recent state change
+    pub last_change_cycle: Option<u64>,
+}
+
+impl CoilStats {
+    /// Percentage of observed cycles the coil was energised (0-100)
+    pub fn duty_cycle(&self) -> f64 {
+        if self.cycles_observed == 0 {
+            0.0
+        } else {
+            self.cycles_energised as f64 * 100.0 / self.cycles_observed as f64
+        }
+    }
+}
+
+/// Tracks statistics for every coil of a VM
+#[derive(Debug, Default)]
+pub(crate) struct StatsTracker {
+    coils: Map<String, CoilStats>,
+}
+
+impl StatsTracker {
+    /// Record the outputs of a cycle
+    pub(crate) fn record(
+        &mut self,
+        cycle: u64,
+        outputs: &HashMap<String, bool>,
+        changes: &Map<String, (bool, bool)>,
+    ) {
+        for (name, &value) in outputs {
+            let stats = self.coils.entry(name.clone()).or_default();
+            stats.cycles_observed += 1;
+            if value {
+                stats.cycles_energised += 1;
+            }
+            if let Some(&(_, new_value)) = changes.get(name) {
+                stats.last_change_cycle = Some(cycle);
+                if new_value {
+                    stats.energisations += 1;
+                }
+            }
+        }
+    }
+
+    /// Get statistics for a coil
+    pub(crate) fn get(&self, name: &str) -> Option<CoilStats> {
+        self.coils.get(name).copied()
+    }
+
+    /// Get statistics for all coils
+    pub(crate) fn all(&self) -> HashMap<String, CoilStats> {
+        self.coils
+            .iter()
+            .map(|(name, stats)| (name.clone(), *stats))
+            .collect()
+    }
+
+    /// Forget all statistics
+    pub(crate) fn clear(&mut self) {
+        self.coils.clear();
+    }
+}
@@ -0,0 +1,252 @@
+//! Scenario-based test harness for Charta programs
+//!
+//! ```no_run
+//! use charta::testing::Scenario;
+//!
+//! # fn main() -> charta::Result<()> {
+//! # let ir = "";
+//! Scenario::new(ir)
+//!     .given_signal("user_submitted", true)
+//!     .given_signal("system_ok", true)
+//!     .when_cycles(2)
+//!     .expect_coil("allow_review", true)
+//!     .run()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Consecutive expectations are checked together after the preceding cycles,
+//! and a failure reports every mismatch plus the full coil state:
+//!
+//! ```text
+//! scenario failed after cycle 2:
+//!   coil                     expected  actual
+//! - allow_review             true      false
+//!   notify                   -         true
+//! ```
+//!
+//! [`assert_matches_golden`] replays recorded inputs against a loaded VM and
+//! diffs the resulting coil states against a stored golden trace, so policy
+//! refactors can prove behavior did not change. Set `CHARTA_UPDATE_GOLDEN=1`
+//! to regenerate the goldens instead.
+
+use crate::error::{Error, Result};
+use crate::vm::ChartaVM;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::Path;
+
+/// Environment variable that makes [`assert_matches_golden`] rewrite goldens
+pub const UPDATE_GOLDEN_ENV: &str = "CHARTA_UPDATE_GOLDEN";
+
+/// One step of a scenario
+#[derive(Debug, Clone)]
+enum Step {
+    Signal(String, bool),
+    Cycles(u64),
+    ExpectCoil(String, bool),
+}
+
+/// Given/when/then scenario run against a fresh VM
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    ir: String,
+    steps: Vec<Step>,
+}
+
+impl Scenario {
+    /// Start a scenario for a program
+    pub fn new(ir_json: &str) -> Self {
+        Self {
+            ir: ir_json.to_string(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Set a signal before the following cycles
+    pub fn given_signal(mut self, name: &str, value: bool) -> Self {
+        self.steps.push(Step::Signal(name.to_string(), value));
+        self
+    }
+
+    /// Set a signal between cycles
+    ///
+    /// Identical to [`given_signal`](Self::given_signal); reads better after
+    /// the first cycles have run.
+    pub fn when_signal(self, name: &str, value: bool) -> Self {
+        self.given_signal(name, value)
+    }
+
+    /// Execute `cycles` scan cycles
+    pub fn when_cycles(mut self, cycles: u64) -> Self {
+        self.steps.push(Step::Cycles(cycles));
+        self
+    }
+
+    /// Expect a coil state after the preceding cycles
+    pub fn expect_coil(mut self, name: &str, value: bool) -> Self {
+        self.steps.push(Step::ExpectCoil(name.to_string(), value));
+        self
+    }
+
+    /// Run the scenario on a dedicated runtime
+    ///
+    /// Use [`run_async`](Self::run_async) from within an async context.
+    pub fn run(self) -> Result<()> {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(self.run_async())
+    }
+
+    /// Run the scenario
+    ///
+    /// Fails with [`Error::ScenarioFailed`] describing every mismatched coil
+    /// at the first failing checkpoint.
+    pub async fn run_async(self) -> Result<()> {
+        let mut vm = ChartaVM::new();
+        vm.load_program(&self.ir).await?;
+
+        let mut expected: BTreeMap<String, bool> = BTreeMap::new();
+        for step in self.steps {
+            if !matches!(step, Step::ExpectCoil(..)) {
+                check(&vm, &mut expected).await?;
+            }
+            match step {
+                Step::Signal(name, value) => vm.set_signal(&name, value).await?,
+                Step::Cycles(cycles) => {
+                    for _ in 0..cycles {
+                        vm.execute_cycle().await?;
+                    }
+                }
+                Step::ExpectCoil(name, value) => {
+                    expected.insert(name, value);
+                }
+            }
+        }
+        check(&vm, &mut expected).await
+    }
+}
+
+/// Check and clear pending expectations
+async fn check(vm: &ChartaVM, expected: &mut BTreeMap<String, bool>) -> Result<()> {
+    if expected.is_empty() {
+        return Ok(());
+    }
+    let actual = vm.get_all_coils().await?;
+    let failed = expected
+        .iter()
+        .any(|(name, value)| actual.get(name) != Some(value));
+    if failed {
+        let header = format!("scenario failed after cycle {}:", vm.cycle_count());
+        return Err(Error::ScenarioFailed(diff(&header, expected, &actual)));
+    }
+    expected.clear();
+    Ok(())
+}
+
+/// Render expected vs actual coil states, marking mismatches with `-`
+fn diff(header: &str, expected: &BTreeMap<String, bool>, actual: &HashMap<String, bool>) -> String {
+    let mut names: Vec<&String> = expected.keys().chain(actual.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    let show = |value: Option<&bool>| value.map_or("-".to_string(), bool::to_string);
+    let mut out = format!("{}\n", header);
+    let _ = writeln!(out, "  {:<24} {:<9} actual", "coil", "expected");
+    for name in names {
+        let want = expected.get(name);
+        let got = actual.get(name);
+        let marker = if want.is_some() && want != got { '-' } else { ' ' };
+        let _ = writeln!(out, "{} {:<24} {:<9} {}", marker, name, show(want), show(got));
+    }
+    out.pop();
+    out
+}
+
+/// One cycle of a golden trace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct GoldenCycle {
+    cycle: u64,
+    coils: BTreeMap<String, bool>,
+}
+
+/// Replay recorded inputs and compare the coil states against a golden trace
+///
+/// `inputs` is a JSON Lines file with one object of signal values per cycle;
+/// blank lines are skipped. `golden` is a JSON Lines file with one
+/// `{"cycle": n, "coils": {...}}` object per cycle. Fails with
+/// [`Error::ScenarioFailed`] describing the first diverging cycle.
+///
+/// When the `CHARTA_UPDATE_GOLDEN` environment variable is set (to anything
+/// but `0`), the golden trace is rewritten from the replay instead.
+pub async fn assert_matches_golden(
+    vm: &mut ChartaVM,
+    inputs: impl AsRef<Path>,
+    golden: impl AsRef<Path>,
+) -> Result<()> {
+    let golden = golden.as_ref();
+    let trace = replay(vm, inputs.as_ref()).await?;
+
+    if std::env::var_os(UPDATE_GOLDEN_ENV).is_some_and(|value| value != "0") {
+        return write_golden(golden, &trace).await;
+    }
+
+    let expected: Vec<GoldenCycle> = read_json_lines(golden).await?;
+    for (index, (want, got)) in expected.iter().zip(&trace).enumerate() {
+        if want.coils != got.coils {
+            let header = format!(
+                "golden trace mismatch at cycle {} (input line {}):",
+                got.cycle,
+                index + 1
+            );
+            let actual: HashMap<String, bool> = got.coils.clone().into_iter().collect();
+            return Err(Error::ScenarioFailed(diff(&header, &want.coils, &actual)));
+        }
+    }
+    if expected.len() != trace.len() {
+        return Err(Error::ScenarioFailed(format!(
+            "golden trace has {} cycles but the replay produced {}",
+            expected.len(),
+            trace.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Execute one cycle per input line, recording the coil states
+async fn replay(vm: &mut ChartaVM, inputs: &Path) -> Result<Vec<GoldenCycle>> {
+    let inputs: Vec<HashMap<String, bool>> = read_json_lines(inputs).await?;
+    let mut trace = Vec::with_capacity(inputs.len());
+    for signals in inputs {
+        let outputs = vm.execute_cycle_with_inputs(signals).await?;
+        trace.push(GoldenCycle {
+            cycle: outputs.cycle(),
+            coils: outputs.iter().map(|(name, value)| (name.clone(), *value)).collect(),
+        });
+    }
+    Ok(trace)
+}
+
+async fn read_json_lines<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+    let contents = tokio::fs::read_to_string(path).await?;
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+async fn write_golden(path: &Path, trace: &[GoldenCycle]) -> Result<()> {
+    let mut contents = String::new();
+    for cycle in trace {
+        contents.push_str(&serde_json::to_string(cycle)?);
+        contents.push('\n');
+    }
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(path, contents).await?;
+    Ok(())
+}
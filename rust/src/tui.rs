@@ -0,0 +1,216 @@
+//! Terminal dashboard for Charta VM
+//!
+//! Available with the `tui` feature. [`Dashboard`] shows live signals, coils,
+//! and per-coil statistics for a VM and lets an operator toggle signals and
+//! trigger cycles from the keyboard:
+//!
+//! | Key             | Action                              |
+//! |-----------------|-------------------------------------|
+//! | `↑` / `↓`       | Select a signal                     |
+//! | `space`         | Toggle the selected signal          |
+//! | `c`             | Execute one cycle                   |
+//! | `a`             | Start/stop cycling automatically    |
+//! | `q` / `esc`     | Quit                                |
+//!
+//! ```no_run
+//! use charta::tui::Dashboard;
+//! use charta::ChartaVM;
+//! use std::time::Duration;
+//!
+//! # async fn run(mut vm: ChartaVM) -> charta::Result<()> {
+//! Dashboard::new().scan_interval(Duration::from_millis(100)).run(&mut vm).await
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::stats::CoilStats;
+use crate::vm::ChartaVM;
+use crossterm::event::{Event, EventStream, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio_stream::StreamExt;
+
+/// Interactive terminal dashboard
+pub struct Dashboard {
+    scan_interval: Duration,
+}
+
+/// Snapshot of the VM rendered in one frame
+struct View {
+    program_id: Option<String>,
+    cycle_count: u64,
+    signals: BTreeMap<String, bool>,
+    coils: BTreeMap<String, bool>,
+    stats: HashMap<String, CoilStats>,
+    auto: bool,
+    status: String,
+}
+
+impl Dashboard {
+    /// Create a dashboard cycling every 500 ms in automatic mode
+    pub fn new() -> Self {
+        Self {
+            scan_interval: Duration::from_millis(500),
+        }
+    }
+
+    /// Set the cycle interval used in automatic mode
+    pub fn scan_interval(mut self, interval: Duration) -> Self {
+        self.scan_interval = interval;
+        self
+    }
+
+    /// Take over the terminal until the operator quits or the VM's shutdown
+    /// is requested
+    pub async fn run(&self, vm: &mut ChartaVM) -> Result<()> {
+        let mut terminal = ratatui::init();
+        let result = self.event_loop(&mut terminal, vm).await;
+        ratatui::restore();
+        result
+    }
+
+    async fn event_loop(
+        &self,
+        terminal: &mut ratatui::DefaultTerminal,
+        vm: &mut ChartaVM,
+    ) -> Result<()> {
+        let mut keys = EventStream::new();
+        let mut ticker = tokio::time::interval(self.scan_interval);
+        let mut selected = ListState::default().with_selected(Some(0));
+        let mut auto = false;
+        let mut status = String::new();
+        let shutdown = vm.shutdown_handle();
+
+        loop {
+            let view = View {
+                program_id: vm.program_id(),
+                cycle_count: vm.cycle_count(),
+                signals: vm.get_all_signals().await?.into_iter().collect(),
+                coils: vm.get_all_coils().await?.into_iter().collect(),
+                stats: vm.all_coil_stats(),
+                auto,
+                status: status.clone(),
+            };
+            terminal.draw(|frame| draw(frame, &view, &mut selected))?;
+
+            tokio::select! {
+                _ = ticker.tick(), if auto => {
+                    status = cycle(vm).await;
+                }
+                _ = shutdown.requested() => return Ok(()),
+                event = keys.next() => {
+                    let Some(event) = event else { return Ok(()) };
+                    let Event::Key(key) = event? else { continue };
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                        KeyCode::Up => selected.select_previous(),
+                        KeyCode::Down => selected.select_next(),
+                        KeyCode::Char(' ') => {
+                            let signal = selected
+                                .selected()
+                                .and_then(|index| view.signals.iter().nth(index));
+                            if let Some((name, value)) = signal {
+                                vm.set_signal(name, !value).await?;
+                                status = format!("{} set to {}", name, !value);
+                            }
+                        }
+                        KeyCode::Char('c') => status = cycle(vm).await,
+                        KeyCode::Char('a') => auto = !auto,
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for Dashboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Execute a cycle, describing the outcome for the status line
+async fn cycle(vm: &mut ChartaVM) -> String {
+    match vm.execute_cycle().await {
+        Ok(outputs) => format!("cycle {} executed", outputs.cycle()),
+        Err(e) => format!("cycle failed: {}", e),
+    }
+}
+
+fn draw(frame: &mut Frame, view: &View, selected: &mut ListState) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [signals_area, coils_area, stats_area] = Layout::horizontal([
+        Constraint::Percentage(35),
+        Constraint::Percentage(30),
+        Constraint::Percentage(35),
+    ])
+    .areas(body);
+
+    let mode = if view.auto { "auto" } else { "manual" };
+    frame.render_widget(
+        Paragraph::new(format!(
+            "program {}  cycle {}  mode {}",
+            view.program_id.as_deref().map(|id| &id[..id.len().min(12)]).unwrap_or("-"),
+            view.cycle_count,
+            mode
+        ))
+        .block(Block::bordered().title("Charta")),
+        header,
+    );
+
+    let signals: Vec<ListItem> = view.signals.iter().map(|(n, v)| state_item(n, *v)).collect();
+    frame.render_stateful_widget(
+        List::new(signals)
+            .block(Block::bordered().title("Signals"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+        signals_area,
+        selected,
+    );
+
+    let coils: Vec<ListItem> = view.coils.iter().map(|(n, v)| state_item(n, *v)).collect();
+    frame.render_widget(List::new(coils).block(Block::bordered().title("Coils")), coils_area);
+
+    let stats: Vec<ListItem> = view
+        .coils
+        .keys()
+        .filter_map(|name| {
+            let stats = view.stats.get(name)?;
+            Some(ListItem::new(format!(
+                "{:<16} {:>5} on  {:>5.1}%",
+                name,
+                stats.energisations,
+                stats.duty_cycle() * 100.0
+            )))
+        })
+        .collect();
+    frame.render_widget(List::new(stats).block(Block::bordered().title("Stats")), stats_area);
+
+    frame.render_widget(
+        Paragraph::new(Line::from(format!(
+            "↑↓ select  space toggle  c cycle  a auto  q quit   {}",
+            view.status
+        ))),
+        footer,
+    );
+}
+
+fn state_item(name: &str, value: bool) -> ListItem<'static> {
+    let color = if value { Color::Green } else { Color::DarkGray };
+    ListItem::new(format!("{} {}", if value { "●" } else { "○" }, name))
+        .style(Style::default().fg(color))
+}
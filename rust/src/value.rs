@@ -0,0 +1,537 @@
+//! Typed signal values
+//!
+//! Signals are boolean unless declared with a `type`; typed signals hold a
+//! [`Value`] set with [`ChartaVM::set_value`](crate::ChartaVM::set_value)
+//! and are read by `compare` and `within_range` guard nodes:
+//!
+//! ```json
+//! {"signals": [{"name": "amount", "type": "int"}],
+//!  "rungs": [{"name": "block_large", "guard": {"type": "compare", "name": "amount", "op": ">", "value": 10000},
+//!             "actions": [{"type": "energise", "coil": "blocked"}]}]}
+//! ```
+//!
+//! Either node may test an [`Expr`] in place of a signal name, adding and
+//! subtracting typed signals and constants:
+//!
+//! ```json
+//! {"type": "within_range",
+//!  "expr": {"type": "sub", "left": {"type": "signal", "name": "limit"},
+//!                          "right": {"type": "signal", "name": "exposure"}},
+//!  "min": 0, "max": 500}
+//! ```
+//!
+//! The VM core only scans boolean contacts, so the SDK lowers each
+//! comparison to a contact on a derived signal (see [`derived_signal`]) and
+//! sets the derived signals from the current typed values at the start of
+//! every cycle. Derived signals are not listed among the VM's signals.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the derived signals comparisons are lowered to
+pub const DERIVED_SIGNAL_PREFIX: &str = "__compare:";
+
+/// A signal value
+///
+/// Serialized as the plain JSON value: `true`, `42`, `2.5`, `"gold"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    /// Boolean
+    Bool(bool),
+    /// Signed integer
+    Int(i64),
+    /// Floating-point number
+    Float(f64),
+    /// String
+    Str(String),
+}
+
+/// Declared type of a signal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    /// Boolean, scanned by the VM core
+    #[default]
+    Bool,
+    /// Signed integer
+    Int,
+    /// Floating-point number
+    Float,
+    /// String
+    #[serde(rename = "string")]
+    Str,
+}
+
+/// Comparison operator of a `compare` guard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CompareOp {
+    /// Greater than
+    #[serde(rename = ">")]
+    Gt,
+    /// Greater than or equal
+    #[serde(rename = ">=")]
+    Ge,
+    /// Less than
+    #[serde(rename = "<")]
+    Lt,
+    /// Less than or equal
+    #[serde(rename = "<=")]
+    Le,
+    /// Equal
+    #[serde(rename = "==")]
+    Eq,
+    /// Not equal
+    #[serde(rename = "!=")]
+    Ne,
+}
+
+/// Arithmetic over typed signals and constants
+///
+/// Integer arithmetic stays integral; mixing in a float widens the result.
+/// Non-numeric operands and integer overflow leave the result unknown, and
+/// comparisons of an unknown result never hold.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Expr {
+    /// Current value of a typed signal or register
+    Signal {
+        /// Typed signal or register name
+        name: String,
+    },
+    /// Constant
+    Const {
+        /// Constant value
+        value: Value,
+    },
+    /// Sum of the operands
+    Add {
+        /// Summands
+        operands: Vec<Expr>,
+    },
+    /// Difference `left - right`
+    Sub {
+        /// Minuend
+        left: Box<Expr>,
+        /// Subtrahend
+        right: Box<Expr>,
+    },
+}
+
+impl Value {
+    /// Get the type of this value
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Value::Bool(_) => ValueType::Bool,
+            Value::Int(_) => ValueType::Int,
+            Value::Float(_) => ValueType::Float,
+            Value::Str(_) => ValueType::Str,
+        }
+    }
+
+    /// Get the value as a boolean, if it is one
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the value as an integer, if it is one
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a float, widening integers
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(value) => Some(*value as f64),
+            Value::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a string slice, if it is one
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Convert to `ty`, widening integers to floats
+    ///
+    /// Returns `None` for any other conversion.
+    pub fn coerce(self, ty: ValueType) -> Option<Value> {
+        match (self, ty) {
+            (Value::Int(value), ValueType::Float) => Some(Value::Float(value as f64)),
+            (value, ty) if value.value_type() == ty => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Order two values
+    ///
+    /// Numbers compare numerically across `Int` and `Float`; strings and
+    /// booleans compare with their own type. Other pairs, and NaN, are
+    /// unordered.
+    pub fn partial_cmp_value(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+            (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+            (Value::Str(a), Value::Str(b)) => Some(a.cmp(b)),
+            (a, b) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        }
+    }
+
+    /// Apply a comparison; unordered values never compare true
+    pub fn compare(&self, op: CompareOp, other: &Value) -> bool {
+        let Some(ordering) = self.partial_cmp_value(other) else {
+            return false;
+        };
+        match op {
+            CompareOp::Gt => ordering == Ordering::Greater,
+            CompareOp::Ge => ordering != Ordering::Less,
+            CompareOp::Lt => ordering == Ordering::Less,
+            CompareOp::Le => ordering != Ordering::Greater,
+            CompareOp::Eq => ordering == Ordering::Equal,
+            CompareOp::Ne => ordering != Ordering::Equal,
+        }
+    }
+}
+
+impl Expr {
+    /// Evaluate, resolving signal values with `lookup`
+    ///
+    /// Returns `None` if the result is unknown.
+    pub fn evaluate<F>(&self, lookup: &F) -> Option<Value>
+    where
+        F: Fn(&str) -> Option<Value>,
+    {
+        match self {
+            Expr::Signal { name } => lookup(name),
+            Expr::Const { value } => Some(value.clone()),
+            Expr::Add { operands } => {
+                let mut sum = Value::Int(0);
+                for operand in operands {
+                    let operand = operand.evaluate(lookup)?;
+                    sum = arithmetic(&sum, &operand, i64::checked_add, |a, b| a + b)?;
+                }
+                Some(sum)
+            }
+            Expr::Sub { left, right } => arithmetic(
+                &left.evaluate(lookup)?,
+                &right.evaluate(lookup)?,
+                i64::checked_sub,
+                |a, b| a - b,
+            ),
+        }
+    }
+
+    /// Get the signals this expression reads
+    pub fn signals(&self) -> Vec<&str> {
+        let mut out = Vec::new();
+        self.collect_signals(&mut out);
+        out
+    }
+
+    fn collect_signals<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            Expr::Signal { name } => out.push(name),
+            Expr::Const { .. } => {}
+            Expr::Add { operands } => {
+                for operand in operands {
+                    operand.collect_signals(out);
+                }
+            }
+            Expr::Sub { left, right } => {
+                left.collect_signals(out);
+                right.collect_signals(out);
+            }
+        }
+    }
+
+    /// Rewrite every signal name with `rename`
+    pub fn try_map_signals<F, E>(&self, rename: &mut F) -> Result<Expr, E>
+    where
+        F: FnMut(&str) -> Result<String, E>,
+    {
+        Ok(match self {
+            Expr::Signal { name } => Expr::Signal {
+                name: rename(name)?,
+            },
+            Expr::Const { value } => Expr::Const {
+                value: value.clone(),
+            },
+            Expr::Add { operands } => Expr::Add {
+                operands: operands
+                    .iter()
+                    .map(|operand| operand.try_map_signals(rename))
+                    .collect::<Result<_, E>>()?,
+            },
+            Expr::Sub { left, right } => Expr::Sub {
+                left: Box::new(left.try_map_signals(rename)?),
+                right: Box::new(right.try_map_signals(rename)?),
+            },
+        })
+    }
+}
+
+/// Apply a binary numeric operation, staying integral when both sides are
+fn arithmetic(
+    a: &Value,
+    b: &Value,
+    int: fn(i64, i64) -> Option<i64>,
+    float: fn(f64, f64) -> f64,
+) -> Option<Value> {
+    match (a, b) {
+        (Value::Int(a), Value::Int(b)) => int(*a, *b).map(Value::Int),
+        (a, b) => Some(Value::Float(float(a.as_f64()?, b.as_f64()?))),
+    }
+}
+
+impl ValueType {
+    /// Check whether this is the boolean type
+    pub fn is_bool(&self) -> bool {
+        *self == ValueType::Bool
+    }
+
+    /// Check whether this is `Int` or `Float`
+    pub fn is_numeric(self) -> bool {
+        matches!(self, ValueType::Int | ValueType::Float)
+    }
+
+    /// Check whether values of the two types can be ordered
+    ///
+    /// Integers and floats compare with each other; other types only with
+    /// themselves.
+    pub fn is_comparable_with(self, other: ValueType) -> bool {
+        self == other || (self.is_numeric() && other.is_numeric())
+    }
+
+    /// Value a signal of this type holds before it is first set
+    pub fn default_value(self) -> Value {
+        match self {
+            ValueType::Bool => Value::Bool(false),
+            ValueType::Int => Value::Int(0),
+            ValueType::Float => Value::Float(0.0),
+            ValueType::Str => Value::Str(String::new()),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Float(value) => write!(f, "{:?}", value),
+            Value::Str(value) => write!(f, "{:?}", value),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Signal { name } => f.write_str(name),
+            Expr::Const { value } => write!(f, "{}", value),
+            Expr::Add { operands } => {
+                f.write_str("(")?;
+                for (i, operand) in operands.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" + ")?;
+                    }
+                    write!(f, "{}", operand)?;
+                }
+                f.write_str(")")
+            }
+            Expr::Sub { left, right } => write!(f, "({} - {})", left, right),
+        }
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ValueType::Bool => "bool",
+            ValueType::Int => "int",
+            ValueType::Float => "float",
+            ValueType::Str => "string",
+        })
+    }
+}
+
+impl fmt::Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+        })
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Int(value.into())
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Str(value)
+    }
+}
+
+/// Name of the derived signal a condition is lowered to
+///
+/// e.g. `__compare:amount > 10000` for the condition `amount > 10000`
+pub fn derived_signal(condition: &str) -> String {
+    format!("{}{}", DERIVED_SIGNAL_PREFIX, condition)
+}
+
+/// Check whether a signal name is a derived comparison, quality, bypass, or
+/// scan group signal
+pub fn is_derived_signal(name: &str) -> bool {
+    name.starts_with(DERIVED_SIGNAL_PREFIX)
+        || name.starts_with(crate::quality::QUALITY_SIGNAL_PREFIX)
+        || name.starts_with(crate::bypass::BYPASS_SIGNAL_PREFIX)
+        || name.starts_with(crate::scan_group::GROUP_SIGNAL_PREFIX)
+}
+
+/// Refuse writes to a derived signal
+///
+/// The SDK sets derived signals every cycle or from bypasses; a write would
+/// override a comparison, quality gate, bypass, or scan group. Fails with
+/// [`Error::AccessDenied`](crate::Error::AccessDenied).
+#[cfg(feature = "std")]
+pub(crate) fn check_writable(name: &str) -> crate::error::Result<()> {
+    if is_derived_signal(name) {
+        return Err(crate::error::Error::AccessDenied(format!(
+            "signal '{}' is derived and cannot be written",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Where a value written to a signal goes
+#[cfg(feature = "std")]
+pub(crate) enum Assignment {
+    /// Into the typed value store
+    Typed(Value),
+    /// Into the VM core, as a boolean signal
+    Bool(bool),
+}
+
+/// Check a write of `value` to signal `name` against its declared type
+#[cfg(feature = "std")]
+pub(crate) fn assign(
+    program: Option<&crate::ir::Program>,
+    name: &str,
+    value: Value,
+) -> crate::error::Result<Assignment> {
+    use crate::error::Error;
+
+    check_writable(name)?;
+    let declared = program.and_then(|program| {
+        program
+            .module
+            .signals
+            .iter()
+            .find(|signal| signal.name == name)
+            .map(|signal| signal.value_type)
+    });
+    match (declared, value) {
+        (Some(ty), value) if !ty.is_bool() => {
+            let actual = value.value_type();
+            value.coerce(ty).map(Assignment::Typed).ok_or_else(|| {
+                Error::TypeMismatch(format!("signal '{}' is {}, got {}", name, ty, actual))
+            })
+        }
+        (_, Value::Bool(value)) => Ok(Assignment::Bool(value)),
+        (_, value) => Err(Error::TypeMismatch(format!(
+            "signal '{}' is bool, got {}",
+            name,
+            value.value_type()
+        ))),
+    }
+}
+
+/// Keep the values still declared with the same type in `program`, and
+/// default the rest
+#[cfg(feature = "std")]
+pub(crate) fn declare(
+    values: &mut std::collections::HashMap<String, Value>,
+    program: Option<&crate::ir::Program>,
+) {
+    let declared = program
+        .iter()
+        .flat_map(|program| &program.module.signals)
+        .filter(|signal| !signal.value_type.is_bool())
+        .map(|signal| (signal.name.as_str(), signal.value_type));
+    retain_declared(values, declared.collect());
+}
+
+/// Keep the registers still declared with the same type in `program`, and
+/// default the rest
+#[cfg(feature = "std")]
+pub(crate) fn declare_registers(
+    registers: &mut std::collections::HashMap<String, Value>,
+    program: Option<&crate::ir::Program>,
+) {
+    let declared = program
+        .iter()
+        .flat_map(|program| &program.module.registers)
+        .map(|register| (register.name.as_str(), register.value_type));
+    retain_declared(registers, declared.collect());
+}
+
+#[cfg(feature = "std")]
+fn retain_declared(
+    values: &mut std::collections::HashMap<String, Value>,
+    declared: std::collections::HashMap<&str, ValueType>,
+) {
+    values.retain(|name, value| declared.get(name.as_str()) == Some(&value.value_type()));
+    for (name, ty) in declared {
+        values
+            .entry(name.to_string())
+            .or_insert_with(|| ty.default_value());
+    }
+}
@@ -1,10 +1,55 @@
 //! Charta VM wrapper for Rust SDK
 
+use crate::access::SignalWriter;
+use crate::builder::{ChartaVMBuilder, VmConfig};
+use crate::bypass::{self, RungBypass};
 use crate::error::{Error, Result};
-use crate::callbacks::CallbackManager;
+use crate::callbacks::{
+    CallbackError, CallbackManager, CycleContext, ErrorContext, ErrorPhase, PanicPolicy,
+};
+use crate::collections::Map;
+use crate::coverage::CoverageReport;
+use crate::debug::{Breakpoint, Debugger, RungStep, WatchState, Watches};
+use crate::decision::{DecisionExporter, DecisionRecord};
+use crate::dispatch::{Dispatch, DispatchMode, Dispatcher};
+use crate::engine::Engine;
+use crate::execution::CycleOptions;
+use crate::events::{CoilEventReceiver, EventReceiver, SubscriberOptions, Subscription, VmEvent};
+use crate::filter::InputFilters;
+use crate::history::History;
+use crate::io::{CoilChanges, InputSource, OutputSink};
+use crate::ir::{Guard, Metadata, Program, RungInfo};
+use crate::load::{self, LoadOptions, LoadReport};
+use crate::mode::VmMode;
+use crate::observer::{ChartaObserver, LoadedProgram};
+use crate::order::{self, OrderIssue};
+use crate::outputs::{CycleDetails, CycleOutputs};
+use crate::persistence::Checkpoint;
+use crate::quality::Quality;
+use crate::recorder::{RecordedCycle, Recorder};
+use crate::registry::program_hash;
+use crate::reload::ProgramDiff;
+use crate::safety::{self, SafetyOverrides};
+use crate::scan;
+use crate::scan_group;
+use crate::shadow::{Shadow, ShadowDivergence};
+use crate::shutdown::{ShutdownHandle, ShutdownOptions, ShutdownState, SHUTDOWN_SIGNAL};
+use crate::snapshot::StateSnapshot;
+use crate::stats::CoilStats;
+use crate::value::{self, Assignment, Value};
+#[cfg(feature = "prometheus")]
+use crate::metrics::VmMetrics;
+#[cfg(feature = "otel")]
+use crate::otel::OtelExporter;
+#[cfg(feature = "chaos")]
+use crate::chaos::FaultInjector;
+#[cfg(feature = "notify")]
+use crate::watch::ProgramWatcher;
 use charta_vm::{VM, ir::load_ir};
 use std::collections::HashMap;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::RwLock;
 
 /// Charta VM instance for embedding in Rust applications
@@ -13,83 +58,511 @@ use tokio::sync::RwLock;
 /// an async-friendly API for loading programs, setting signals, executing
 /// cycles, and reading coil states.
 pub struct ChartaVM {
-    /// Internal VM instance (wrapped in Arc for async sharing)
-    vm: Arc<RwLock<VM>>,
+    /// Internal VM instance, event stream, and observable state, shared
+    /// with read-only observers
+    observer: ChartaObserver,
     /// Callback manager for event handling
     callbacks: Arc<RwLock<CallbackManager>>,
+    /// Queue feeding callbacks to their own task, in queued dispatch mode
+    dispatcher: Option<Dispatcher>,
+    /// Candidate program executed alongside the active one
+    shadow: Option<Shadow>,
+    /// Drivers polled for signals at the start of every cycle
+    input_sources: Vec<Box<dyn InputSource>>,
+    /// Drivers receiving coil changes after every cycle
+    output_sinks: Vec<Box<dyn OutputSink>>,
+    /// Exporters receiving a decision record after every cycle
+    decision_exporters: Vec<Box<dyn DecisionExporter>>,
+    /// Settings chosen at construction
+    config: VmConfig,
+    /// Outputs of the last cycle
+    outputs: Arc<CycleOutputs>,
+    /// Conditioning of the loaded program's inputs
+    filters: InputFilters,
+    /// Pause state, step position, and breakpoints
+    debugger: Debugger,
+    /// Guard expressions re-evaluated after every cycle
+    watches: Watches,
+    /// Recent cycles kept for rewinding
+    recorder: Option<Recorder>,
+    /// Confirmation tokens for safety coil overrides
+    safety_overrides: SafetyOverrides,
+    /// When the last checkpoint was saved (or the VM created)
+    last_checkpoint: Instant,
+    /// Lifecycle shared with scan loops
+    shutdown: ShutdownHandle,
+    /// Prometheus metrics sink
+    #[cfg(feature = "prometheus")]
+    metrics: Option<Arc<VmMetrics>>,
+    /// OpenTelemetry cycle span exporter
+    #[cfg(feature = "otel")]
+    otel: Option<Arc<OtelExporter>>,
+    /// Faults injected by tests
+    #[cfg(feature = "chaos")]
+    faults: Option<FaultInjector>,
+    /// Program file reloaded when it changes
+    #[cfg(feature = "notify")]
+    watcher: Option<ProgramWatcher>,
 }
 
 impl ChartaVM {
     /// Create a new Charta VM instance
     pub fn new() -> Self {
+        Self::with_config(VmConfig::default())
+    }
+
+    /// Create a builder for a VM with non-default settings
+    pub fn builder() -> ChartaVMBuilder {
+        ChartaVMBuilder::new()
+    }
+
+    pub(crate) fn with_config(config: VmConfig) -> Self {
+        let callbacks = Arc::new(RwLock::new(CallbackManager::new()));
+        let dispatcher = match config.dispatch {
+            DispatchMode::Inline => None,
+            DispatchMode::Queued { capacity, overflow } => {
+                Some(Dispatcher::new(capacity, overflow, Arc::clone(&callbacks)))
+            }
+        };
         Self {
-            vm: Arc::new(RwLock::new(VM::new())),
-            callbacks: Arc::new(RwLock::new(CallbackManager::new())),
+            observer: ChartaObserver::new(VM::new(), config.event_capacity),
+            callbacks,
+            dispatcher,
+            shadow: None,
+            outputs: Arc::default(),
+            filters: InputFilters::default(),
+            debugger: Debugger::default(),
+            watches: Watches::default(),
+            recorder: None,
+            safety_overrides: SafetyOverrides::default(),
+            last_checkpoint: Instant::now(),
+            shutdown: ShutdownHandle::new(),
+            input_sources: Vec::new(),
+            output_sinks: Vec::new(),
+            decision_exporters: Vec::new(),
+            config,
+            #[cfg(feature = "prometheus")]
+            metrics: None,
+            #[cfg(feature = "otel")]
+            otel: None,
+            #[cfg(feature = "chaos")]
+            faults: None,
+            #[cfg(feature = "notify")]
+            watcher: None,
         }
     }
 
     /// Load a program from IR JSON string
-    pub async fn load_program(&mut self, ir_json: &str) -> Result<()> {
-        let ir = load_ir(ir_json)
+    ///
+    /// Returns a [`LoadReport`] of what was loaded; the same report is kept
+    /// for [`load_report`](Self::load_report).
+    pub async fn load_program(&mut self, ir_json: &str) -> Result<LoadReport> {
+        self.load_program_with_options(ir_json, &LoadOptions::default())
+            .await
+    }
+
+    /// Load a program from IR JSON string, starting signals and coils at the
+    /// values in `options`
+    ///
+    /// The initial values are applied before the loaded state is published,
+    /// so no reader sees the program with its signals and coils at false.
+    /// See [`LoadOptions`].
+    pub async fn load_program_with_options(
+        &mut self,
+        ir_json: &str,
+        options: &LoadOptions,
+    ) -> Result<LoadReport> {
+        let program_id = program_hash(ir_json);
+
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!("charta.load_program", program_id = %program_id);
+        let load = self.load_program_inner(ir_json, program_id, options);
+        #[cfg(feature = "tracing")]
+        let load = tracing::Instrument::instrument(load, span);
+
+        let result = load.await;
+        if let Err(e) = &result {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %e, "program load failed");
+            self.report_error(e, self.observer.cycle_count(), ErrorPhase::Load).await;
+        }
+        result
+    }
+
+    async fn load_program_inner(
+        &mut self,
+        ir_json: &str,
+        program_id: String,
+        options: &LoadOptions,
+    ) -> Result<LoadReport> {
+        let load::ValidatedIr {
+            ir_json,
+            program,
+            lowered,
+            ignored,
+            order_issues,
+            scanner,
+        } = load::validate(
+            ir_json,
+            self.config.unknown_nodes,
+            &self.config.limits,
+            self.config.quality,
+        )?;
+        let filters = InputFilters::new(program.as_ref(), &self.config.filters)?;
+        let initial = load::initial_state(program.as_ref(), options)?;
+        #[cfg(feature = "tracing")]
+        for node in &ignored {
+            tracing::warn!(
+                rung = %node.rung,
+                node_type = %node.node_type,
+                path = %node.path,
+                "rung disabled: unknown node"
+            );
+        }
+
+        let ir = load_ir(&ir_json)
             .map_err(|e| Error::IRLoad(e.to_string()))?;
-        
-        let mut vm = self.vm.write().await;
-        vm.load_program(ir)
-            .map_err(Error::VM)?;
-        
-        Ok(())
+
+        // The first program loaded picks up the persisted state
+        let mut checkpoint = match &self.config.persist {
+            Some(persist) if self.observer.program_id().is_none() => persist.store.load().await?,
+            _ => None,
+        };
+        if let (Some(checkpoint), Some(program)) = (&mut checkpoint, &program) {
+            checkpoint.retain_coils(program);
+        }
+
+        {
+            let mut vm = self.observer.vm.write().await;
+            vm.load_program(ir)
+                .map_err(Error::VM)?;
+            initial.apply(&mut vm);
+            if let Some(checkpoint) = &checkpoint {
+                checkpoint.apply(&mut vm);
+                self.observer.state.cycle_count.store(checkpoint.cycle, Ordering::SeqCst);
+            }
+            self.outputs = Arc::new(CycleOutputs::loaded(vm.get_all_coils()));
+            self.observer.state.publish(&vm, Arc::clone(&self.outputs));
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            rungs = program.as_ref().map(|p| p.module.rungs.len()),
+            "program loaded"
+        );
+
+        self.filters = filters;
+        self.debugger.rewind();
+        if let Some(recorder) = &mut self.recorder {
+            recorder.clear();
+        }
+        let state = &self.observer.state;
+        {
+            let mut values = state.values();
+            value::declare(&mut values, program.as_ref());
+            values.extend(initial.values);
+        }
+        value::declare_registers(&mut state.registers(), program.as_ref());
+        let report = LoadReport::new(program_id.clone(), program.as_ref(), ignored, order_issues);
+        state.set_loaded(LoadedProgram {
+            // With the `jit` feature, batch evaluations scan native code
+            engine: lowered.as_ref().map(|lowered| {
+                Arc::new(if cfg!(feature = "jit") {
+                    Engine::compiled(lowered)
+                } else {
+                    Engine::new(lowered)
+                })
+            }),
+            program: program.map(Arc::new),
+            id: Some(program_id),
+            report: report.clone(),
+            scanner: Arc::new(scanner),
+        });
+        state.stats().clear();
+        state.bypasses().clear();
+        if let Some(coverage) = state.coverage().as_mut() {
+            *coverage = CoverageReport::default();
+        }
+
+        let context = CycleContext::now(self.observer.cycle_count(), self.observer.program_id().as_deref());
+        self.observer.emit(VmEvent::ProgramLoaded { context });
+        Ok(report)
     }
 
     /// Load a program from a file
+    ///
+    /// Files the program imports are resolved relative to it and merged in;
+    /// see [`crate::imports`].
+    #[cfg(not(target_arch = "wasm32"))]
     pub async fn load_program_from_file<P: AsRef<std::path::Path>>(
         &mut self,
         path: P,
-    ) -> Result<()> {
-        let contents = tokio::fs::read_to_string(path).await?;
+    ) -> Result<LoadReport> {
+        let contents = match crate::imports::read_program_file(path.as_ref()).await {
+            Ok(contents) => contents,
+            Err(e) => {
+                self.report_error(&e, self.observer.cycle_count(), ErrorPhase::Load).await;
+                return Err(e);
+            }
+        };
         self.load_program(&contents).await
     }
 
-    /// Execute one scan cycle
+    /// Load a program whose imports `resolver` locates
     ///
-    /// Returns a map of coil names to their new states (true if energised).
-    /// Triggers callbacks for coil changes and cycle completion.
-    pub async fn execute_cycle(&mut self) -> Result<HashMap<String, bool>> {
-        // Get old coil states before execution
-        let old_coils = {
-            let vm = self.vm.read().await;
-            vm.get_all_coils()
-        };
-
-        // Execute cycle
-        let outputs = {
-            let mut vm = self.vm.write().await;
-            let inputs = HashMap::new();
-            vm.step(inputs).map_err(Error::VM)?
-        };
-
-        // Calculate changes and trigger callbacks
-        let changes: HashMap<String, (bool, bool)> = outputs
-            .iter()
-            .filter_map(|(name, &new_value)| {
-                let old_value = old_coils.get(name).copied().unwrap_or(false);
-                if old_value != new_value {
-                    Some((name.clone(), (old_value, new_value)))
-                } else {
-                    None
+    /// `root` identifies `ir_json` to the resolver, as the importer of its
+    /// direct imports.
+    pub async fn load_program_with_resolver(
+        &mut self,
+        ir_json: &str,
+        root: &str,
+        resolver: &dyn crate::imports::ImportResolver,
+    ) -> Result<LoadReport> {
+        let program = match crate::imports::resolve_imports(root, ir_json, resolver).await {
+            Ok(program) => program,
+            Err(e) => {
+                self.report_error(&e, self.observer.cycle_count(), ErrorPhase::Load).await;
+                return Err(e);
+            }
+        };
+        self.load_program(&serde_json::to_string(&program)?).await
+    }
+
+    /// Load a program published at `url`, verifying its SHA-256
+    ///
+    /// Uses a [`RemoteLoader`](crate::remote::RemoteLoader) with default
+    /// settings, caching in [`default_cache_dir`](crate::remote::default_cache_dir).
+    #[cfg(feature = "remote")]
+    pub async fn load_program_from_url(
+        &mut self,
+        url: &str,
+        expected_sha256: &str,
+    ) -> Result<LoadReport> {
+        let ir = crate::remote::RemoteLoader::new()
+            .fetch(url, expected_sha256)
+            .await?;
+        self.load_program(&ir).await
+    }
+
+    /// Link several IR modules into one program and load it
+    ///
+    /// Signals, coils, and rungs are namespaced by module name; modules
+    /// share points through `imports` and `exports` declarations. See
+    /// [`crate::link`].
+    pub async fn load_modules(&mut self, modules: &[&str]) -> Result<LoadReport> {
+        let linked = match crate::link::link_json(modules) {
+            Ok(program) => program,
+            Err(e) => {
+                self.report_error(&e, self.observer.cycle_count(), ErrorPhase::Load).await;
+                return Err(e);
+            }
+        };
+        self.load_program(&serde_json::to_string(&linked)?).await
+    }
+
+    /// Replace the running program with a new version, keeping state
+    ///
+    /// The new IR is validated first; if it is rejected the running program
+    /// is untouched. Signal states and the states of coils marked `retained`
+    /// carry over to the names the new version still declares; other coils
+    /// restart at their initial state. A
+    /// [`VmEvent::ProgramReloaded`] event reports the returned diff. Without
+    /// a running program this is [`load_program`](Self::load_program).
+    pub async fn reload_program(&mut self, ir_json: &str) -> Result<ProgramDiff> {
+        if self.observer.program_id().is_none() {
+            self.load_program(ir_json).await?;
+            return Ok(ProgramDiff::default());
+        }
+
+        let old = self.observer.program();
+        let state = self.checkpoint();
+        self.load_program(ir_json).await?;
+        self.restore_checkpoint(&state).await;
+
+        let diff = match (old, self.observer.program()) {
+            (Some(old), Some(new)) => ProgramDiff::between(&old, &new),
+            _ => ProgramDiff::default(),
+        };
+        #[cfg(feature = "tracing")]
+        tracing::info!(?diff, "program reloaded");
+        let context = CycleContext::now(self.observer.cycle_count(), self.observer.program_id().as_deref());
+        self.observer.emit(VmEvent::ProgramReloaded {
+            diff: diff.clone(),
+            context,
+        });
+        Ok(diff)
+    }
+
+    /// Unload the running program
+    ///
+    /// Signals, coils, typed values, registers, bypasses, statistics,
+    /// coverage, and recorded cycles are cleared and a
+    /// [`VmEvent::ProgramUnloaded`] event is emitted. The cycle count,
+    /// callbacks, and drivers are kept, and a watched program file is no
+    /// longer watched. The next program loaded restores the persisted
+    /// checkpoint, as the first one does. Returns whether a program was
+    /// loaded.
+    pub async fn unload_program(&mut self) -> bool {
+        let Some(program_id) = self.observer.program_id() else {
+            return false;
+        };
+        {
+            let mut vm = self.observer.vm.write().await;
+            *vm = VM::new();
+            self.outputs = Arc::new(CycleOutputs::loaded(HashMap::new()));
+            self.observer.state.publish(&vm, Arc::clone(&self.outputs));
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::info!(%program_id, "program unloaded");
+
+        self.filters = InputFilters::default();
+        self.debugger.rewind();
+        self.safety_overrides = SafetyOverrides::default();
+        if let Some(recorder) = &mut self.recorder {
+            recorder.clear();
+        }
+        #[cfg(feature = "notify")]
+        {
+            self.watcher = None;
+        }
+        let state = &self.observer.state;
+        state.values().clear();
+        state.registers().clear();
+        state.set_loaded(LoadedProgram::default());
+        state.stats().clear();
+        state.bypasses().clear();
+        if let Some(coverage) = state.coverage().as_mut() {
+            *coverage = CoverageReport::default();
+        }
+
+        let context = CycleContext::now(self.observer.cycle_count(), Some(program_id.as_str()));
+        self.observer.emit(VmEvent::ProgramUnloaded { context });
+        true
+    }
+
+    /// Return the loaded program to its state just after loading
+    ///
+    /// Signals and coils, retained ones included, go back to their initial
+    /// values, typed values and registers to their defaults, and the cycle
+    /// count to zero. Input filters and signal quality timers restart, and
+    /// statistics, history, coverage, and recorded cycles are cleared. The
+    /// program, bypasses, callbacks, and drivers are kept. Emits a
+    /// [`VmEvent::StateReset`] event. Fails if no program is loaded.
+    pub async fn reset_state(&mut self) -> Result<()> {
+        let Some(program_id) = self.observer.program_id() else {
+            return Err(Error::InvalidOperation("no program loaded".to_string()));
+        };
+        let program = self.observer.program();
+        let initial = load::initial_state(program.as_deref(), &LoadOptions::default())?;
+        let filters = InputFilters::new(program.as_deref(), &self.config.filters)?;
+        {
+            let mut vm = self.observer.vm.write().await;
+            // Derived signals are set every cycle or mirror bypasses
+            for (name, _) in vm.get_all_signals() {
+                if !value::is_derived_signal(&name) {
+                    vm.set_signal(name, false);
                 }
-            })
-            .collect();
+            }
+            for (name, _) in vm.get_all_coils() {
+                vm.set_coil(name, false);
+            }
+            initial.apply(&mut vm);
+            self.observer.state.cycle_count.store(0, Ordering::SeqCst);
+            self.outputs = Arc::new(CycleOutputs::loaded(vm.get_all_coils()));
+            self.observer.state.publish(&vm, Arc::clone(&self.outputs));
+        }
 
-        // Trigger callbacks
-        if !changes.is_empty() {
-            let callbacks = self.callbacks.read().await;
-            callbacks.trigger_coil_changes(&changes);
+        self.filters = filters;
+        self.debugger.rewind();
+        self.safety_overrides = SafetyOverrides::default();
+        if let Some(recorder) = &mut self.recorder {
+            recorder.clear();
+        }
+        let state = &self.observer.state;
+        {
+            let mut values = state.values();
+            values.clear();
+            value::declare(&mut values, program.as_deref());
+            values.extend(initial.values);
+        }
+        {
+            let mut registers = state.registers();
+            registers.clear();
+            value::declare_registers(&mut registers, program.as_deref());
+        }
+        state.quality().restart();
+        state.stats().clear();
+        if let Some(history) = state.history().as_mut() {
+            history.clear();
+        }
+        if let Some(coverage) = state.coverage().as_mut() {
+            *coverage = CoverageReport::default();
         }
 
-        let callbacks = self.callbacks.read().await;
-        callbacks.trigger_cycle_complete(&outputs);
+        #[cfg(feature = "tracing")]
+        tracing::info!(%program_id, "state reset");
+        let context = CycleContext::now(0, Some(program_id.as_str()));
+        self.observer.emit(VmEvent::StateReset { context });
+        Ok(())
+    }
+
+    /// Load a program file and reload it whenever it changes on disk
+    ///
+    /// Changes are picked up at the start of the next cycle, or by
+    /// [`poll_program_file`](Self::poll_program_file), through
+    /// [`reload_program`](Self::reload_program). A change that fails to read
+    /// or validate is reported to the [`on_error`](Self::on_error) hook and
+    /// the running program stays in place. Replaces any previous watch.
+    #[cfg(feature = "notify")]
+    pub async fn watch_program_file<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<()> {
+        let watcher = ProgramWatcher::new(path.as_ref())?;
+        let contents = tokio::fs::read_to_string(watcher.path()).await?;
+        self.reload_program(&contents).await?;
+        self.watcher = Some(watcher);
+        Ok(())
+    }
 
-        Ok(outputs)
+    /// Stop watching the program file
+    ///
+    /// Returns whether a file was watched.
+    #[cfg(feature = "notify")]
+    pub fn unwatch_program_file(&mut self) -> bool {
+        self.watcher.take().is_some()
+    }
+
+    /// Reload the watched program file if it changed
+    ///
+    /// Returns the diff if a new version was loaded. Saves that leave the
+    /// program unchanged are skipped.
+    #[cfg(feature = "notify")]
+    pub async fn poll_program_file(&mut self) -> Result<Option<ProgramDiff>> {
+        let Some(watcher) = &self.watcher else {
+            return Ok(None);
+        };
+        if !watcher.take_change() {
+            return Ok(None);
+        }
+        let contents = match crate::imports::read_program_file(watcher.path()).await {
+            Ok(contents) => contents,
+            Err(e) => {
+                self.report_error(&e, self.observer.cycle_count(), ErrorPhase::Load).await;
+                return Err(e);
+            }
+        };
+        if self.observer.program_id().as_deref() == Some(program_hash(&contents).as_str()) {
+            return Ok(None);
+        }
+        self.reload_program(&contents).await.map(Some)
+    }
+
+    /// Execute one scan cycle
+    ///
+    /// Returns the new coil states (true if energised) and the changes
+    /// since the previous cycle, shared with event subscribers. Triggers
+    /// callbacks for coil changes and cycle completion.
+    pub async fn execute_cycle(&mut self) -> Result<Arc<CycleOutputs>> {
+        self.execute_cycle_with_inputs(HashMap::new()).await
     }
 
     /// Execute one scan cycle with input signals
@@ -98,100 +571,1535 @@ impl ChartaVM {
     pub async fn execute_cycle_with_inputs(
         &mut self,
         inputs: HashMap<String, bool>,
-    ) -> Result<HashMap<String, bool>> {
-        // Get old coil states before execution
-        let old_coils = {
-            let vm = self.vm.read().await;
-            vm.get_all_coils()
-        };
-
-        // Execute cycle
-        let outputs = {
-            let mut vm = self.vm.write().await;
-            vm.step(inputs).map_err(Error::VM)?
-        };
-
-        // Calculate changes and trigger callbacks
-        let changes: HashMap<String, (bool, bool)> = outputs
-            .iter()
-            .filter_map(|(name, &new_value)| {
-                let old_value = old_coils.get(name).copied().unwrap_or(false);
-                if old_value != new_value {
-                    Some((name.clone(), (old_value, new_value)))
-                } else {
-                    None
+    ) -> Result<Arc<CycleOutputs>> {
+        let cycle = self.execute(CycleOptions::new().inputs(inputs)).await?;
+        Ok(cycle.outputs)
+    }
+
+    /// Compute the outputs of a cycle with input signals without committing
+    /// any state
+    ///
+    /// The scan sees the current state plus `inputs`. Signals, coils
+    /// (including seal-ins and coils written with `set_coil`), registers,
+    /// and the cycle count are left as they were, and no callbacks, events,
+    /// drivers, or checkpoints run, so pre-flight checks do not perturb the
+    /// running VM. The outputs are numbered as the next cycle.
+    pub async fn execute_cycle_dry_run(
+        &mut self,
+        inputs: HashMap<String, bool>,
+    ) -> Result<Arc<CycleOutputs>> {
+        let cycle = self
+            .execute(CycleOptions::new().inputs(inputs).dry_run(true))
+            .await?;
+        Ok(cycle.outputs)
+    }
+
+    /// Execute one scan cycle, returning the outputs and the coils that
+    /// changed
+    pub async fn execute_cycle_detailed(&mut self) -> Result<CycleDetails> {
+        self.execute(CycleOptions::new()).await
+    }
+
+    /// Execute one scan cycle with input signals, returning the outputs and
+    /// the coils that changed
+    pub async fn execute_cycle_detailed_with_inputs(
+        &mut self,
+        inputs: HashMap<String, bool>,
+    ) -> Result<CycleDetails> {
+        self.execute(CycleOptions::new().inputs(inputs)).await
+    }
+
+    /// Execute one cycle for each map of input signals, in order
+    ///
+    /// Returns the details of every cycle. Stops at the first cycle that
+    /// fails and returns its error; the cycles before it stay committed.
+    /// Each cycle is published as it completes, so subscribers and
+    /// observers see every cycle of the sequence. Each cycle takes the VM
+    /// lock once, only around its step; drivers and callbacks run with it
+    /// released, so writers are not held up for the whole sequence.
+    pub async fn execute_cycles(
+        &mut self,
+        inputs_per_cycle: Vec<HashMap<String, bool>>,
+    ) -> Result<Vec<CycleDetails>> {
+        let mut cycles = Vec::with_capacity(inputs_per_cycle.len());
+        for inputs in inputs_per_cycle {
+            cycles.push(self.execute(CycleOptions::new().inputs(inputs)).await?);
+        }
+        Ok(cycles)
+    }
+
+    /// Execute one scan cycle as described by `options`
+    ///
+    /// Returns the outputs, the coils that changed, and, if traced, the
+    /// evaluation of every rung. While the VM is paused, cycles other than
+    /// dry runs return the held outputs. See [`crate::execution`].
+    pub async fn execute(&mut self, options: CycleOptions) -> Result<CycleDetails> {
+        if let Some(group) = &options.group {
+            self.observer.state.loaded().scanner.check_group(group)?;
+        }
+        self.scan(options).await
+    }
+
+    /// Execute one scan cycle of the rungs in scan group `group`
+    ///
+    /// Rungs of other groups hold their coils and move nothing; ungrouped
+    /// rungs scan as usual. Fails if no rung of the loaded program is in
+    /// `group`. See [`crate::scan_group`].
+    pub async fn execute_group(&mut self, group: &str) -> Result<Arc<CycleOutputs>> {
+        let cycle = self.execute(CycleOptions::new().group(group)).await?;
+        Ok(cycle.outputs)
+    }
+
+    /// Scan each group given an interval with
+    /// [`ChartaVMBuilder::scan_group`](crate::ChartaVMBuilder::scan_group)
+    /// at that interval until shutdown is requested
+    ///
+    /// Faster groups scan first when several are due together, and a group
+    /// that falls behind skips its missed scans. Returns the error of the
+    /// first failed cycle.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn run_scan_groups(&mut self) -> Result<()> {
+        let mut schedule = scan_group::ScanSchedule::new(&self.config.scan_groups, Instant::now())?;
+        let shutdown = self.shutdown.clone();
+        while !shutdown.is_requested() {
+            let due = tokio::time::Instant::from_std(schedule.next_due());
+            tokio::select! {
+                _ = tokio::time::sleep_until(due) => {
+                    for group in schedule.take_due(Instant::now()) {
+                        self.execute_group(&group).await?;
+                    }
                 }
+                _ = shutdown.requested() => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Execute a cycle whenever a write changes a signal, until shutdown is
+    /// requested
+    ///
+    /// Changes are picked up from [`SignalWriter`]s and other handles
+    /// writing while this runs, and from writes made since the last cycle.
+    /// Signals polled from input drivers are only read during cycles, so
+    /// they do not trigger one. After a change the cycle waits for the
+    /// builder's [`coalesce_window`](crate::ChartaVMBuilder::coalesce_window),
+    /// scanning every change made meanwhile at once. Returns the error of
+    /// the first failed cycle.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn run_event_driven(&mut self) -> Result<()> {
+        let state = Arc::clone(&self.observer.state);
+        let window = self.config.coalesce_window;
+        let shutdown = self.shutdown.clone();
+        while !shutdown.is_requested() {
+            tokio::select! {
+                _ = state.signals_changed() => {
+                    if !window.is_zero() {
+                        tokio::time::sleep(window).await;
+                    }
+                    self.execute_cycle().await?;
+                }
+                _ = shutdown.requested() => {}
+            }
+        }
+        Ok(())
+    }
+
+    async fn scan(&mut self, options: CycleOptions) -> Result<CycleDetails> {
+        if self.shutdown.state() == ShutdownState::Stopped {
+            return Err(Error::InvalidOperation("VM has been shut down".to_string()));
+        }
+        if options.dry_run {
+            return self.dry_run_cycle(options).await;
+        }
+        if self.debugger.is_paused() {
+            return Ok(CycleDetails {
+                outputs: Arc::clone(&self.outputs),
+                changes: Vec::new(),
+                evaluations: Vec::new(),
+            });
+        }
+
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "charta.execute_cycle",
+            cycle = self.observer.cycle_count() + 1,
+            program_id = %self.observer.program_id().unwrap_or_default(),
+        );
+        let cycle = self.run_cycle(options);
+        #[cfg(feature = "tracing")]
+        let cycle = tracing::Instrument::instrument(cycle, span);
+
+        let result = cycle.await;
+        #[cfg(feature = "tracing")]
+        if let Err(e) = &result {
+            tracing::warn!(cycle = self.observer.cycle_count() + 1, error = %e, "cycle failed");
+        }
+        result
+    }
+
+    async fn run_cycle(&mut self, options: CycleOptions) -> Result<CycleDetails> {
+        let inputs = options.inputs;
+        let group = options.group.as_deref();
+
+        // Failed reloads are already reported and leave the program running
+        #[cfg(feature = "notify")]
+        let _ = self.poll_program_file().await;
+
+        let started = Instant::now();
+        let deadline = self.config.cycle_deadline;
+        let abort_on_deadline = self.config.abort_on_deadline;
+        let abort_dispatch = move || {
+            abort_on_deadline && deadline.is_some_and(|deadline| started.elapsed() > deadline)
+        };
+        #[cfg(feature = "otel")]
+        let started_at = std::time::SystemTime::now();
+        #[cfg(feature = "otel")]
+        let otel_inputs = self.otel.as_ref().map(|_| inputs.clone());
+
+        // Poll input drivers; explicitly passed inputs take precedence
+        let inputs = if self.input_sources.is_empty() {
+            inputs
+        } else {
+            let mut polled = HashMap::new();
+            for source in &mut self.input_sources {
+                #[cfg(feature = "chaos")]
+                if self.faults.as_ref().is_some_and(|faults| faults.take_dropped_read()) {
+                    continue;
+                }
+                polled.extend(source.read().await);
+            }
+            polled.extend(inputs);
+            polled
+        };
+        if let Err(e) = scan::check_inputs(&inputs) {
+            self.report_error(&e, self.observer.cycle_count() + 1, ErrorPhase::Cycle)
+                .await;
+            return Err(e);
+        }
+
+        // Record the state the cycle starts from, for rewinding
+        let recorded = self
+            .recorder
+            .as_ref()
+            .map(|_| self.recorded_state(self.observer.cycle_count() + 1, inputs.clone()));
+
+        let scanner = Arc::clone(&self.observer.state.loaded().scanner);
+
+        // Inputs passed in or polled are fresh; signals past their TTL
+        // revert to their fallback
+        let expired = scan::refresh_quality(&mut self.observer.state.quality(), &inputs);
+
+        // Set the derived signals and apply the quality policy
+        let mut inputs = inputs;
+        let checked = {
+            let state = &self.observer.state;
+            scanner.prepare(
+                &mut inputs,
+                group,
+                &state.values(),
+                &state.registers(),
+                &state.quality(),
+            )
+        };
+        #[cfg(feature = "chaos")]
+        let checked = checked.and_then(|()| {
+            let cycle = self.observer.cycle_count() + 1;
+            match &self.faults {
+                Some(faults) if faults.take_cycle_failure(cycle) => {
+                    Err(Error::InjectedFault(format!("cycle {} failed", cycle)))
+                }
+                _ => Ok(()),
+            }
+        });
+
+        // Snapshot the scan state for per-rung tracing, coverage, and moves
+        #[cfg(feature = "tracing")]
+        let trace_rungs = tracing::enabled!(tracing::Level::TRACE);
+        #[cfg(not(feature = "tracing"))]
+        let trace_rungs = false;
+        let program = self.observer.program();
+        let record_coverage = self.observer.state.coverage().is_some();
+        let collect_fired = self.debugger.breaks_on_rungs() || !self.decision_exporters.is_empty();
+        let replay_rungs =
+            trace_rungs || options.trace || record_coverage || collect_fired || scanner.has_moves();
+        let snapshot_scan = program.is_some() && replay_rungs;
+
+        // Hold the VM only to revert expired signals, condition the inputs,
+        // step, and publish the result; drivers and callbacks run after
+        let next_cycle = self.observer.cycle_count() + 1;
+        let mut vm = self.observer.vm.write().await;
+        for (name, fallback) in &expired {
+            vm.set_signal(name.clone(), *fallback);
+        }
+        if !expired.is_empty() {
+            self.observer.state.publish_signals(&vm);
+        }
+        let scanned = checked.and_then(|()| {
+            let signals = (!self.filters.is_empty() || snapshot_scan || self.shadow.is_some())
+                .then(|| vm.get_all_signals())
+                .unwrap_or_default();
+
+            // Condition filtered inputs; their raw values are restored after
+            // the scan
+            let held = if self.filters.is_empty() {
+                Vec::new()
+            } else {
+                self.filters.condition(&signals, &mut inputs)
+            };
+            let scan_state = snapshot_scan
+                .then(|| scan::scan_state(signals.clone(), self.outputs.coils(), &inputs));
+            let shadow_inputs = self.shadow.as_ref().map(|_| (signals, inputs.clone()));
+
+            let stepped = vm.step(inputs);
+            for (name, raw) in held {
+                vm.set_signal(name, raw);
+            }
+            let outputs = stepped.map_err(Error::VM)?;
+            let cycle = self.observer.state.cycle_count.fetch_add(1, Ordering::SeqCst) + 1;
+            self.outputs = Arc::new(CycleOutputs::next(&self.outputs, cycle, outputs));
+            self.observer.state.publish(&vm, Arc::clone(&self.outputs));
+            self.observer.state.mark_scanned();
+            Ok((cycle, scan_state, shadow_inputs))
+        });
+        drop(vm);
+
+        if !expired.is_empty() {
+            let context = CycleContext::now(next_cycle, self.observer.program_id().as_deref());
+            for (name, fallback) in expired {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(signal = %name, fallback, "signal expired");
+                self.observer.emit(VmEvent::SignalExpired {
+                    name,
+                    fallback,
+                    context: context.clone(),
+                });
+            }
+        }
+        let (cycle, scan_state, shadow_inputs) = match scanned {
+            Ok(scanned) => scanned,
+            Err(e) => {
+                self.report_error(&e, next_cycle, ErrorPhase::Cycle).await;
+                return Err(e);
+            }
+        };
+        let outputs = Arc::clone(&self.outputs);
+        if let (Some(recorder), Some(recorded)) = (&mut self.recorder, recorded) {
+            recorder.record(recorded);
+        }
+
+        let mut fired = Vec::new();
+        let mut traced = Vec::new();
+        if let (Some(program), Some(state)) = (&program, &scan_state) {
+            let bypasses = self.observer.state.bypasses().clone();
+            let evaluations = scanner.evaluate(program, state, &bypasses, group);
+            if collect_fired {
+                fired.extend(
+                    evaluations
+                        .iter()
+                        .filter(|evaluation| evaluation.energised)
+                        .map(|evaluation| evaluation.rung.clone()),
+                );
+            }
+            if scanner.has_moves() {
+                let values = self.observer.state.values();
+                let mut registers = self.observer.state.registers();
+                scanner.apply_moves(&evaluations, &values, &mut registers);
+            }
+            #[cfg(feature = "tracing")]
+            if trace_rungs {
+                for evaluation in &evaluations {
+                    tracing::trace!(
+                        rung = %evaluation.rung,
+                        source = evaluation.source.as_ref().map(tracing::field::display),
+                        energised = evaluation.energised,
+                        bypassed = bypasses.contains(&evaluation.rung),
+                        "rung evaluated"
+                    );
+                }
+            }
+            if let Some(coverage) = self.observer.state.coverage().as_mut() {
+                coverage.record(program, state, &evaluations, group);
+            }
+            if options.trace {
+                traced = evaluations;
+            }
+        }
+
+        let watched = if self.watches.is_empty() {
+            Vec::new()
+        } else {
+            let snapshot = self.observer.state.snapshot();
+            let values = self.observer.state.values();
+            let registers = self.observer.state.registers();
+            self.watches.update(&WatchState {
+                signals: snapshot.signals(),
+                coils: snapshot.coils(),
+                values: &values,
+                registers: &registers,
             })
+        };
+
+        let divergence = match (&mut self.shadow, shadow_inputs) {
+            (Some(shadow), Some((signals, inputs))) => {
+                shadow.compare(cycle, signals, inputs, outputs.coils())
+            }
+            _ => None,
+        };
+
+        // Collect changes and trigger callbacks
+        let changes: Map<String, (bool, bool)> = outputs
+            .changes()
+            .map(|(name, old, new)| (name.to_string(), (old, new)))
             .collect();
+        let context = CycleContext::now(cycle, self.observer.program_id().as_deref());
+        let hits = self.debugger.hits(&changes, &fired);
+        if !hits.is_empty() {
+            self.debugger.pause();
+        }
+
+        self.observer.state.stats().record(cycle, outputs.coils(), &changes);
+        if let Some(history) = self.observer.state.history().as_mut() {
+            history.record(cycle, context.at, outputs.coils(), &changes);
+        }
+
+        #[cfg(feature = "chaos")]
+        if let Some(delay) = self.faults.as_ref().and_then(|faults| faults.callback_delay()) {
+            tokio::time::sleep(delay).await;
+        }
 
-        // Trigger callbacks
-        if !changes.is_empty() {
+        #[cfg(feature = "tracing")]
+        for (name, (old, new)) in &changes {
+            tracing::debug!(coil = %name, old = *old, new = *new, "coil changed");
+        }
+
+        // Trigger callbacks, inline or through the dispatch queue
+        let mut callback_errors = Vec::new();
+        let callback_result = if options.suppress_callbacks {
+            Ok(())
+        } else if let Some(dispatcher) = &mut self.dispatcher {
+            dispatcher
+                .push(Dispatch {
+                    changes: changes.clone(),
+                    outputs: Arc::clone(&outputs),
+                    divergence: divergence.clone(),
+                    context: context.clone(),
+                })
+                .await
+        } else {
             let callbacks = self.callbacks.read().await;
-            callbacks.trigger_coil_changes(&changes);
+            {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::debug_span!("charta.callbacks", changes = changes.len()).entered();
+
+                // Each dispatch phase checks the deadline before starting
+                if !changes.is_empty() && !abort_dispatch() {
+                    callback_errors.extend(callbacks.trigger_coil_changes(&changes, &context));
+                }
+                if !abort_dispatch() {
+                    callback_errors.extend(callbacks.trigger_cycle_complete(outputs.coils(), &context));
+                }
+                if let Some(divergence) = &divergence {
+                    if !abort_dispatch() {
+                        callback_errors.extend(callbacks.trigger_shadow_divergence(divergence, &context));
+                    }
+                }
+            }
+            let result = callbacks.check(&callback_errors);
+            let context = ErrorContext {
+                cycle,
+                phase: ErrorPhase::Callback,
+            };
+            for error in &callback_errors {
+                let error = Error::CallbackPanicked(format!("{}: {}", error.callback, error.message));
+                callbacks.trigger_error(&error, &context);
+            }
+            result
+        };
+
+        #[cfg(feature = "prometheus")]
+        if let Some(metrics) = &self.metrics {
+            metrics.observe_cycle(started.elapsed(), &changes);
+            for _ in &callback_errors {
+                metrics.record_callback_error();
+            }
+        }
+
+        #[cfg(feature = "otel")]
+        if let (Some(otel), Some(inputs)) = (&self.otel, &otel_inputs) {
+            otel.record_cycle(
+                cycle,
+                self.observer.program_id().as_deref(),
+                inputs,
+                &changes,
+                started_at,
+            );
+        }
+
+        // Publish to event subscribers
+        let events = &self.observer;
+        for (name, (old, new)) in &changes {
+            events.emit(VmEvent::CoilChanged {
+                name: name.clone(),
+                old: *old,
+                new: *new,
+                context: context.clone(),
+            });
+        }
+        events.emit(VmEvent::CycleCompleted {
+            outputs: Arc::clone(&outputs),
+            context: context.clone(),
+        });
+        if let Some(divergence) = divergence {
+            events.emit(VmEvent::ShadowDiverged {
+                divergence,
+                context: context.clone(),
+            });
+        }
+        if let Some(deadline) = deadline {
+            let elapsed = started.elapsed();
+            if elapsed > deadline {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(cycle, ?elapsed, ?deadline, "cycle deadline exceeded");
+                events.emit(VmEvent::DeadlineExceeded {
+                    context: context.clone(),
+                    elapsed,
+                    deadline,
+                });
+            }
+        }
+        if !hits.is_empty() {
+            let signals = self.observer.state.snapshot().signals().clone();
+            for breakpoint in hits {
+                #[cfg(feature = "tracing")]
+                tracing::info!(cycle, ?breakpoint, "breakpoint hit");
+                events.emit(VmEvent::BreakpointHit {
+                    breakpoint,
+                    signals: signals.clone(),
+                    outputs: Arc::clone(&outputs),
+                    context: context.clone(),
+                });
+            }
         }
+        for (name, old, new) in watched {
+            events.emit(VmEvent::WatchChanged {
+                name,
+                old,
+                new,
+                context: context.clone(),
+            });
+        }
+
+        // Export the cycle's decision record; failures go to the fault hook
+        if !self.decision_exporters.is_empty() {
+            let record = DecisionRecord {
+                cycle,
+                program_id: context.program_id.as_deref().map(String::from),
+                at: context.at,
+                inputs: self.observer.state.snapshot().signals().clone(),
+                outputs: outputs.coils().clone(),
+                changes: changes
+                    .iter()
+                    .map(|(name, change)| (name.clone(), *change))
+                    .collect(),
+                fired_rungs: fired,
+            };
+            let mut failures = Vec::new();
+            for exporter in &mut self.decision_exporters {
+                if let Err(e) = exporter.export(&record).await {
+                    failures.push(e);
+                }
+            }
+            for e in &failures {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(cycle, error = %e, "decision export failed");
+                self.report_error(e, cycle, ErrorPhase::Driver).await;
+            }
+        }
+
+        // Push changes to output drivers; their failures go to the fault hook
+        if !self.output_sinks.is_empty() {
+            let changes = CoilChanges {
+                cycle,
+                program_id: context.program_id.as_deref().map(String::from),
+                at: context.at,
+                changes: changes.into_iter().collect(),
+            };
+            let mut failures = Vec::new();
+            for sink in &mut self.output_sinks {
+                if let Err(e) = sink.write(&changes).await {
+                    failures.push(e);
+                }
+            }
+            for e in &failures {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(cycle, error = %e, "output sink failed");
+                self.report_error(e, cycle, ErrorPhase::Driver).await;
+            }
+        }
+
+        // Checkpoint once the interval has elapsed; failures go to the fault hook
+        let checkpoint_due = self
+            .config
+            .persist
+            .as_ref()
+            .is_some_and(|persist| self.last_checkpoint.elapsed() >= persist.interval);
+        if checkpoint_due {
+            if let Err(e) = self.save_checkpoint().await {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(cycle, error = %e, "checkpoint failed");
+                self.report_error(&e, cycle, ErrorPhase::Persistence).await;
+            }
+        }
+
+        callback_result?;
+        Ok(CycleDetails {
+            changes: outputs.coil_changes(),
+            outputs,
+            evaluations: traced,
+        })
+    }
+
+    /// Scan against the current state and `options.inputs`, then put the
+    /// state back before releasing the VM
+    async fn dry_run_cycle(&self, options: CycleOptions) -> Result<CycleDetails> {
+        let group = options.group.as_deref();
+        let mut inputs = options.inputs;
+        scan::check_inputs(&inputs)?;
+        let scanner = Arc::clone(&self.observer.state.loaded().scanner);
+        {
+            let state = &self.observer.state;
+            scanner.prepare(
+                &mut inputs,
+                group,
+                &state.values(),
+                &state.registers(),
+                &state.quality(),
+            )?;
+        }
+
+        let program = self.observer.program().filter(|_| options.trace);
+        let (outputs, scan_state) = {
+            let mut vm = self.observer.vm.write().await;
+            scan::dry_step(&mut vm, &self.filters, inputs, program.is_some())?
+        };
+
+        let mut evaluations = Vec::new();
+        if let (Some(program), Some(state)) = (&program, &scan_state) {
+            let bypasses = self.observer.state.bypasses().clone();
+            evaluations = scanner.evaluate(program, state, &bypasses, group);
+        }
+        let outputs = Arc::new(CycleOutputs::next(
+            &self.outputs,
+            self.observer.cycle_count() + 1,
+            outputs,
+        ));
+        Ok(CycleDetails {
+            changes: outputs.coil_changes(),
+            outputs,
+            evaluations,
+        })
+    }
+
+    /// Capture the current signal states and retained coil states
+    ///
+    /// Coils the program does not mark `retained` are left out; see
+    /// [`crate::persistence`].
+    pub fn checkpoint(&self) -> Checkpoint {
+        let snapshot = self.observer.snapshot();
+        let mut checkpoint = Checkpoint {
+            program_id: self.observer.program_id(),
+            cycle: snapshot.cycle(),
+            saved_at: SystemTime::now(),
+            coils: snapshot.coils().clone(),
+            signals: snapshot.signals().clone(),
+        };
+        if let Some(program) = self.observer.program() {
+            checkpoint.retain_coils(&program);
+        }
+        checkpoint
+    }
 
+    /// Save a checkpoint to the store set with
+    /// [`persist_to`](ChartaVMBuilder::persist_to) now
+    ///
+    /// Restarts the checkpoint interval.
+    pub async fn save_checkpoint(&mut self) -> Result<()> {
+        let Some(persist) = &self.config.persist else {
+            return Err(Error::InvalidOperation("no state store configured".to_string()));
+        };
+        let store = Arc::clone(&persist.store);
+        self.last_checkpoint = Instant::now();
+        store.save(&self.checkpoint()).await
+    }
+
+    /// Restore coil and signal states and the cycle count from a checkpoint
+    ///
+    /// States of names the loaded program does not declare are skipped.
+    /// Returns the number of states restored. No events are emitted.
+    pub async fn restore_checkpoint(&mut self, checkpoint: &Checkpoint) -> usize {
+        let mut vm = self.observer.vm.write().await;
+        let restored = checkpoint.apply(&mut vm);
+        self.observer.state.cycle_count.store(checkpoint.cycle, Ordering::SeqCst);
+        self.outputs = Arc::new(CycleOutputs::loaded(vm.get_all_coils()));
+        self.observer.state.publish(&vm, Arc::clone(&self.outputs));
+        restored
+    }
+
+    /// Record the state each of the last `capacity` cycles started from,
+    /// with its inputs, for [`rewind`](Self::rewind)
+    ///
+    /// Replaces any recording. See [`crate::recorder`].
+    pub fn enable_recording(&mut self, capacity: usize) {
+        self.recorder = Some(Recorder::new(capacity));
+    }
+
+    /// Stop recording cycles and forget those recorded
+    pub fn disable_recording(&mut self) {
+        self.recorder = None;
+    }
+
+    /// Get the recorded cycles, oldest first
+    pub fn recorded_cycles(&self) -> Vec<RecordedCycle> {
+        self.recorder
+            .as_ref()
+            .map(Recorder::cycles)
+            .unwrap_or_default()
+    }
+
+    /// Check whether a rewind has restored an earlier state
+    pub fn is_rewound(&self) -> bool {
+        self.recorder.as_ref().is_some_and(Recorder::is_rewound)
+    }
+
+    /// Restore the state from `cycles` recorded cycles before the current
+    /// one
+    ///
+    /// Restores signals, coils, typed values, registers, and the cycle
+    /// count; no cycle runs and no events are emitted. Returns the restored
+    /// cycle count. Fails if recording is off or fewer cycles are recorded.
+    pub async fn rewind(&mut self, cycles: usize) -> Result<u64> {
+        self.config.mode.permit("rewind")?;
+        if cycles == 0 && self.recorder.is_some() {
+            return Ok(self.observer.cycle_count());
+        }
+        let present = (!self.is_rewound())
+            .then(|| self.recorded_state(self.observer.cycle_count() + 1, HashMap::new()));
+        let Some(recorder) = &mut self.recorder else {
+            return Err(Error::InvalidOperation(
+                "recording is not enabled".to_string(),
+            ));
+        };
+        let state = recorder.rewind(cycles, present)?.clone();
+        self.restore_recorded(&state).await;
+        Ok(self.observer.cycle_count())
+    }
+
+    /// Move a rewound VM to the state after the next recorded cycle
+    ///
+    /// Returns that cycle with the inputs it ran with. Replaying past the
+    /// last recorded cycle restores the state rewinding left. Fails if the
+    /// VM is not rewound.
+    pub async fn replay_forward(&mut self) -> Result<RecordedCycle> {
+        self.config.mode.permit("replay_forward")?;
+        let Some(recorder) = &mut self.recorder else {
+            return Err(Error::InvalidOperation(
+                "recording is not enabled".to_string(),
+            ));
+        };
+        let (replayed, after) = recorder.forward()?;
+        self.restore_recorded(&after).await;
+        Ok(replayed)
+    }
+
+    /// Capture the current state as the start of cycle `cycle`
+    fn recorded_state(&self, cycle: u64, inputs: HashMap<String, bool>) -> RecordedCycle {
+        let snapshot = self.observer.snapshot();
+        RecordedCycle {
+            cycle,
+            inputs,
+            signals: snapshot.signals().clone(),
+            coils: snapshot.coils().clone(),
+            values: self.observer.state.values().clone(),
+            registers: self.observer.state.registers().clone(),
+        }
+    }
+
+    async fn restore_recorded(&mut self, state: &RecordedCycle) {
+        *self.observer.state.values() = state.values.clone();
+        *self.observer.state.registers() = state.registers.clone();
+        let checkpoint = Checkpoint {
+            program_id: self.observer.program_id(),
+            cycle: state.cycle.saturating_sub(1),
+            saved_at: SystemTime::now(),
+            coils: state.coils.clone(),
+            signals: state.signals.clone(),
+        };
+        self.restore_checkpoint(&checkpoint).await;
+    }
+
+    /// Handle for requesting and awaiting shutdown from other tasks
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// Shut the VM down
+    ///
+    /// Runs the optional final cycle, waits for queued callbacks, and saves
+    /// a last checkpoint, in that order, then marks the VM stopped and
+    /// resolves [`ShutdownHandle::completed`]. Every step runs even if an
+    /// earlier one fails; the first failure is returned. Cycles fail once
+    /// the VM is stopped; shutting down again does nothing.
+    pub async fn shutdown(&mut self, options: ShutdownOptions) -> Result<()> {
+        if self.shutdown.state() == ShutdownState::Stopped {
+            return Ok(());
+        }
+        self.shutdown.request();
+        #[cfg(feature = "tracing")]
+        tracing::info!(cycle = self.observer.cycle_count(), "shutting down");
+
+        let mut result = Ok(());
+        if options.final_cycle && self.observer.program_id().is_some() {
+            let mut inputs = HashMap::new();
+            if self.observer.snapshot().signal_names().iter().any(|name| name == SHUTDOWN_SIGNAL) {
+                inputs.insert(SHUTDOWN_SIGNAL.to_string(), true);
+            }
+            result = self.execute_cycle_with_inputs(inputs).await.map(drop);
+        }
+
+        self.flush_callbacks().await;
+
+        if options.checkpoint && self.config.persist.is_some() {
+            let saved = self.save_checkpoint().await;
+            if let Err(e) = &saved {
+                self.report_error(e, self.observer.cycle_count(), ErrorPhase::Persistence).await;
+            }
+            result = result.and(saved);
+        }
+
+        self.shutdown.complete();
+        result
+    }
+
+    /// Pass a fault to the error hook
+    pub(crate) async fn report_error(&self, error: &Error, cycle: u64, phase: ErrorPhase) {
         let callbacks = self.callbacks.read().await;
-        callbacks.trigger_cycle_complete(&outputs);
+        callbacks.trigger_error(error, &ErrorContext { cycle, phase });
+    }
+
+    /// Register a driver polled for signals at the start of every cycle
+    ///
+    /// Sources are read in registration order, later sources overriding
+    /// earlier ones; inputs passed to
+    /// [`execute_cycle_with_inputs`](Self::execute_cycle_with_inputs)
+    /// override all of them.
+    pub fn add_input_source<S: InputSource + 'static>(&mut self, source: S) {
+        self.input_sources.push(Box::new(source));
+    }
+
+    /// Remove all input drivers
+    pub fn clear_input_sources(&mut self) {
+        self.input_sources.clear();
+    }
+
+    /// Register a driver receiving coil changes after every cycle
+    ///
+    /// Sinks are written in registration order. A failing sink is reported
+    /// to the [`on_error`](Self::on_error) hook and does not stop the others.
+    pub fn add_output_sink<S: OutputSink + 'static>(&mut self, sink: S) {
+        self.output_sinks.push(Box::new(sink));
+    }
+
+    /// Remove all output drivers
+    pub fn clear_output_sinks(&mut self) {
+        self.output_sinks.clear();
+    }
+
+    /// Register an exporter receiving a [`DecisionRecord`] after every cycle
+    ///
+    /// Exporters are written in registration order. A failing exporter is
+    /// reported to the [`on_error`](Self::on_error) hook and does not stop
+    /// the others. See [`crate::decision`].
+    pub fn add_decision_exporter<E: DecisionExporter + 'static>(&mut self, exporter: E) {
+        self.decision_exporters.push(Box::new(exporter));
+    }
+
+    /// Remove all decision exporters
+    pub fn clear_decision_exporters(&mut self) {
+        self.decision_exporters.clear();
+    }
+
+    /// Get a handle that may only set the given signals
+    ///
+    /// Entries may be exact names or patterns like `"user.*"`. Writes to any
+    /// other signal fail with [`Error::AccessDenied`], so subsystems holding
+    /// different writers cannot spoof each other's inputs.
+    pub fn writer_for(&self, signals: &[&str]) -> SignalWriter {
+        SignalWriter::new(self.observer.vm.clone(), self.observer.state.clone(), signals)
+    }
+
+    /// Get a read-only handle to this VM
+    ///
+    /// The observer shares state with the VM and exposes only getters, event
+    /// streams, statistics, and history.
+    pub fn observer(&self) -> ChartaObserver {
+        self.observer.clone()
+    }
+
+    /// Get the latest published signal and coil states without locking
+    pub fn snapshot(&self) -> Arc<StateSnapshot> {
+        self.observer.snapshot()
+    }
+
+    /// Get the hash identifying the loaded program
+    pub fn program_id(&self) -> Option<String> {
+        self.observer.program_id()
+    }
+
+    /// Get the report of the last successful program load
+    pub fn load_report(&self) -> Option<LoadReport> {
+        self.observer.load_report()
+    }
+
+    /// Get the loaded rungs' names in scan order
+    pub fn rung_order(&self) -> Vec<String> {
+        self.observer.rung_order()
+    }
+
+    /// Get the structure of the loaded rungs in scan order: names, target
+    /// coils, and guards
+    ///
+    /// Lets editors, visualizers, and documentation generators inspect the
+    /// loaded program without re-parsing its IR.
+    pub fn rungs(&self) -> Vec<RungInfo> {
+        self.observer.rungs()
+    }
+
+    /// Scan the loaded rungs in `order`, keeping state
+    ///
+    /// `order` must name every rung exactly once. The program is reloaded
+    /// with its rungs rearranged and their priorities cleared, as by
+    /// [`reload_program`](Self::reload_program), so its id changes; active
+    /// bypasses are kept. Returns the order dependencies of the new order.
+    pub async fn set_rung_order(&mut self, order: Vec<String>) -> Result<Vec<OrderIssue>> {
+        let mut program = self
+            .observer
+            .program()
+            .map(|program| (*program).clone())
+            .ok_or_else(|| Error::InvalidOperation("No program loaded".to_string()))?;
+        order::arrange(&mut program, &order)?;
+
+        let bypasses = self.disabled_rungs();
+        let ir_json = serde_json::to_string(&program)?;
+        self.reload_program(&ir_json).await?;
+        for bypass in bypasses {
+            self.set_bypass(&bypass.rung, true).await;
+            self.observer.state.bypasses().restore(bypass);
+        }
+        Ok(self
+            .load_report()
+            .map(|report| report.order_issues)
+            .unwrap_or_default())
+    }
+
+    /// Get the number of cycles executed so far
+    pub fn cycle_count(&self) -> u64 {
+        self.observer.cycle_count()
+    }
+
+    /// Get statistics for a coil
+    ///
+    /// Returns `None` if the coil has not appeared in any cycle output since
+    /// the program was loaded.
+    pub fn coil_stats(&self, name: &str) -> Option<CoilStats> {
+        self.observer.coil_stats(name)
+    }
+
+    /// Get statistics for all coils
+    pub fn all_coil_stats(&self) -> HashMap<String, CoilStats> {
+        self.observer.all_coil_stats()
+    }
+
+    /// Start recording coil changes and cycles
+    ///
+    /// Keeps up to `capacity` entries of each kind. Calling this again
+    /// replaces the existing history.
+    pub fn enable_history(&mut self, capacity: usize) {
+        *self.observer.state.history() = Some(History::new(capacity));
+    }
 
-        Ok(outputs)
+    /// Stop recording and discard the history
+    pub fn disable_history(&mut self) {
+        *self.observer.state.history() = None;
+    }
+
+    /// Get a snapshot of the recorded history, if enabled
+    pub fn history(&self) -> Option<History> {
+        self.observer.history()
+    }
+
+    /// Start recording rung and guard branch coverage
+    ///
+    /// Coverage accumulates until disabled and restarts whenever a program is
+    /// loaded. Requires IR the SDK can model; programs it cannot parse
+    /// record nothing.
+    pub fn enable_coverage(&mut self) {
+        let mut coverage = self.observer.state.coverage();
+        if coverage.is_none() {
+            *coverage = Some(CoverageReport::default());
+        }
+    }
+
+    /// Stop recording and discard the coverage
+    pub fn disable_coverage(&mut self) {
+        *self.observer.state.coverage() = None;
+    }
+
+    /// Get a snapshot of the recorded coverage, if enabled
+    pub fn coverage_report(&self) -> Option<CoverageReport> {
+        self.observer.coverage_report()
+    }
+
+    /// Attach a candidate program to run in the shadow of the active one
+    ///
+    /// Every subsequent cycle also executes the candidate with the same
+    /// signal state and inputs; whenever the outputs differ a
+    /// [`VmEvent::ShadowDiverged`] event is emitted and the shadow divergence
+    /// callback is invoked. The shadow never affects the active outputs.
+    /// Attaching replaces any previously attached shadow.
+    pub async fn attach_shadow(&mut self, candidate_ir: &str) -> Result<()> {
+        let (candidate_ir, _) =
+            load::resolve_unknown_nodes(candidate_ir, self.config.unknown_nodes)?;
+        if self.config.limits.is_enabled() {
+            self.config.limits.check(&Program::from_json(&candidate_ir)?)?;
+        }
+        self.shadow = Some(Shadow::load(&candidate_ir)?);
+        Ok(())
+    }
+
+    /// Detach the shadow program
+    ///
+    /// Returns true if a shadow was attached.
+    pub fn detach_shadow(&mut self) -> bool {
+        self.shadow.take().is_some()
+    }
+
+    /// Check whether a shadow program is attached
+    pub fn has_shadow(&self) -> bool {
+        self.shadow.is_some()
+    }
+
+    /// Attach Prometheus metrics collection
+    #[cfg(feature = "prometheus")]
+    pub fn set_metrics(&mut self, metrics: Arc<VmMetrics>) {
+        self.metrics = Some(metrics);
+    }
+
+    /// Get the attached Prometheus metrics
+    #[cfg(feature = "prometheus")]
+    pub fn metrics(&self) -> Option<&Arc<VmMetrics>> {
+        self.metrics.as_ref()
+    }
+
+    /// Attach an OpenTelemetry exporter emitting one span per cycle
+    #[cfg(feature = "otel")]
+    pub fn set_otel_exporter(&mut self, exporter: Arc<OtelExporter>) {
+        self.otel = Some(exporter);
+    }
+
+    /// Attach a fault injector for testing error handling
+    #[cfg(feature = "chaos")]
+    pub fn set_fault_injector(&mut self, faults: FaultInjector) {
+        self.faults = Some(faults);
+    }
+
+    /// Detach the fault injector
+    #[cfg(feature = "chaos")]
+    pub fn clear_fault_injector(&mut self) {
+        self.faults = None;
     }
 
     /// Get the current state of a coil
     pub async fn get_coil(&self, name: &str) -> Result<Option<bool>> {
-        let vm = self.vm.read().await;
-        Ok(vm.get_coil_state(name))
+        self.observer.get_coil(name).await
     }
 
     /// Get the current state of a signal
     pub async fn get_signal(&self, name: &str) -> Result<Option<bool>> {
-        let vm = self.vm.read().await;
-        Ok(vm.get_signal_state(name))
+        self.observer.get_signal(name).await
     }
 
     /// Get all coil states
     pub async fn get_all_coils(&self) -> Result<HashMap<String, bool>> {
-        let vm = self.vm.read().await;
-        Ok(vm.get_all_coils())
+        self.observer.get_all_coils().await
     }
 
     /// Get all signal states
     pub async fn get_all_signals(&self) -> Result<HashMap<String, bool>> {
-        let vm = self.vm.read().await;
-        Ok(vm.get_all_signals())
+        self.observer.get_all_signals().await
+    }
+
+    /// Get all signal states within a namespace
+    ///
+    /// `signals_in("governance")` returns `governance.compliance_ok`,
+    /// `governance.review.done`, and so on.
+    pub async fn signals_in(&self, namespace: &str) -> Result<HashMap<String, bool>> {
+        self.observer.signals_in(namespace).await
+    }
+
+    /// Get all coil states within a namespace
+    pub async fn coils_in(&self, namespace: &str) -> Result<HashMap<String, bool>> {
+        self.observer.coils_in(namespace).await
+    }
+
+    /// Get every namespace used by signal or coil names, sorted
+    pub async fn namespaces(&self) -> Result<Vec<String>> {
+        self.observer.namespaces().await
+    }
+
+    /// Get the metadata declared for a signal
+    pub fn signal_meta(&self, name: &str) -> Option<Metadata> {
+        self.observer.signal_meta(name)
+    }
+
+    /// Get the metadata declared for a coil
+    pub fn coil_meta(&self, name: &str) -> Option<Metadata> {
+        self.observer.coil_meta(name)
+    }
+
+    /// Get the names of all signals and coils carrying a tag
+    pub fn find_by_tag(&self, tag: &str) -> Vec<String> {
+        self.observer.find_by_tag(tag)
+    }
+
+    /// Evaluate independent input sets in parallel without committing state
+    ///
+    /// Every evaluation is one scan by the SDK [`Engine`] of the program the
+    /// VM core runs, starting from a copy of the current signal and coil
+    /// states with its inputs applied. Comparisons, quality gates, bypasses,
+    /// and scan groups apply as in [`execute_cycle`](Self::execute_cycle).
+    /// No callbacks run and the VM is unchanged. Returns the coil states of
+    /// each evaluation, in input order.
+    #[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+    pub async fn evaluate_batch_parallel(
+        &self,
+        mut inputs: Vec<HashMap<String, bool>>,
+    ) -> Result<Vec<HashMap<String, bool>>> {
+        let engine = self
+            .observer
+            .engine()
+            .ok_or_else(|| Error::InvalidOperation("No program loaded".to_string()))?;
+        let scanner = Arc::clone(&self.observer.state.loaded().scanner);
+        {
+            let state = &self.observer.state;
+            let values = state.values();
+            let registers = state.registers();
+            let quality = state.quality();
+            for inputs in &mut inputs {
+                scan::check_inputs(inputs)?;
+                scanner.prepare(inputs, None, &values, &registers, &quality)?;
+            }
+        }
+        let mut base = engine.state();
+        {
+            let vm = self.observer.vm.read().await;
+            for (name, value) in vm.get_all_signals() {
+                if let Some(id) = engine.signal_id(&name) {
+                    base.set_signal(id, value);
+                }
+            }
+            for (name, value) in vm.get_all_coils() {
+                if let Some(id) = engine.coil_id(&name) {
+                    base.set_coil(id, value);
+                }
+            }
+        }
+
+        tokio::task::spawn_blocking(move || {
+            engine
+                .evaluate_batch_parallel(&base, &inputs)
+                .iter()
+                .map(|state| {
+                    engine
+                        .coil_names()
+                        .iter()
+                        .cloned()
+                        .zip(state.coils().iter().copied())
+                        .collect()
+                })
+                .collect::<Vec<HashMap<String, bool>>>()
+        })
+        .await
+        .map_err(|e| Error::InvalidOperation(format!("Batch evaluation failed: {}", e)))
     }
 
     /// Set a signal value
+    ///
+    /// Fails with [`Error::AccessDenied`] for derived signals (see
+    /// [`is_derived_signal`](crate::value::is_derived_signal)), which the
+    /// SDK sets itself; so do cycle inputs naming one.
     pub async fn set_signal(&mut self, name: &str, value: bool) -> Result<()> {
-        let mut vm = self.vm.write().await;
+        self.set_signal_with_quality(name, value, Quality::Good)
+            .await
+    }
+
+    /// Set a signal value along with its quality
+    ///
+    /// How rungs reading a Bad or Stale signal behave depends on the
+    /// builder's [`quality_policy`](crate::ChartaVMBuilder::quality_policy).
+    pub async fn set_signal_with_quality(
+        &mut self,
+        name: &str,
+        value: bool,
+        quality: Quality,
+    ) -> Result<()> {
+        value::check_writable(name)?;
+        let mut vm = self.observer.vm.write().await;
         vm.set_signal(name.to_string(), value);
+        self.observer.state.quality().update(name, quality);
+        self.observer.state.publish_signals(&vm);
+        Ok(())
+    }
+
+    /// Mark a signal [`Quality::Stale`] when it is not written for `timeout`
+    ///
+    /// The timeout counts from now and restarts on every write.
+    pub fn set_stale_timeout(&mut self, name: &str, timeout: Duration) {
+        self.observer.state.quality().set_timeout(name, timeout);
+    }
+
+    /// Revert a signal to `fallback` when it is not written for `ttl`
+    ///
+    /// The TTL counts from now and restarts on every write. The first cycle
+    /// after it runs out sets the signal to `fallback`, marks it
+    /// [`Quality::Stale`] and emits a [`VmEvent::SignalExpired`] event.
+    pub fn set_signal_ttl(&mut self, name: &str, ttl: Duration, fallback: bool) {
+        self.observer.state.quality().set_ttl(name, ttl, fallback);
+    }
+
+    /// Take a rung out of the scan until it is enabled again
+    ///
+    /// From the next cycle the rung's guard evaluates false, so it
+    /// energises no coils and moves nothing. Emits a
+    /// [`VmEvent::RungDisabled`] event; disabling a rung that already is
+    /// does nothing. See [`crate::bypass`].
+    pub async fn disable_rung(&mut self, name: &str) -> Result<()> {
+        let program = self.observer.program();
+        let cycle = self.observer.cycle_count();
+        let disabled = {
+            let mut bypasses = self.observer.state.bypasses();
+            bypasses.disable(program.as_deref(), name, cycle)?
+        };
+        if !disabled {
+            return Ok(());
+        }
+        self.set_bypass(name, true).await;
+        #[cfg(feature = "tracing")]
+        tracing::warn!(rung = %name, "rung disabled");
+        let context = CycleContext::now(cycle, self.observer.program_id().as_deref());
+        self.observer.emit(VmEvent::RungDisabled {
+            rung: name.to_string(),
+            context,
+        });
         Ok(())
     }
 
+    /// Return a disabled rung to the scan
+    ///
+    /// Emits a [`VmEvent::RungEnabled`] event; enabling a rung that is not
+    /// disabled does nothing.
+    pub async fn enable_rung(&mut self, name: &str) -> Result<()> {
+        if !self.observer.state.bypasses().enable(name) {
+            return Ok(());
+        }
+        self.set_bypass(name, false).await;
+        #[cfg(feature = "tracing")]
+        tracing::warn!(rung = %name, "rung enabled");
+        let context = CycleContext::now(
+            self.observer.cycle_count(),
+            self.observer.program_id().as_deref(),
+        );
+        self.observer.emit(VmEvent::RungEnabled {
+            rung: name.to_string(),
+            context,
+        });
+        Ok(())
+    }
+
+    /// Get the rungs currently disabled, in the order they were disabled
+    pub fn disabled_rungs(&self) -> Vec<RungBypass> {
+        self.observer.disabled_rungs()
+    }
+
+    async fn set_bypass(&self, rung: &str, bypassed: bool) {
+        let mut vm = self.observer.vm.write().await;
+        vm.set_signal(bypass::bypass_signal(rung), bypassed);
+    }
+
+    /// Pause scanning
+    ///
+    /// Until [`resume`](Self::resume), cycles return the outputs of the last
+    /// cycle unchanged and [`step_single_rung`](Self::step_single_rung)
+    /// walks through a scan. See [`crate::debug`].
+    pub fn pause(&mut self) {
+        self.debugger.pause();
+    }
+
+    /// Resume scanning, abandoning a partly stepped scan
+    pub fn resume(&mut self) {
+        self.debugger.resume();
+    }
+
+    /// Resume scanning after a breakpoint pauses the VM
+    ///
+    /// Same as [`resume`](Self::resume); breakpoints stay set.
+    pub fn continue_(&mut self) {
+        self.debugger.resume();
+    }
+
+    /// Pause the VM at the end of every cycle that hits `breakpoint`
+    ///
+    /// Each hit emits a [`VmEvent::BreakpointHit`] event. Setting a
+    /// breakpoint twice does nothing. See [`crate::debug`].
+    pub fn set_breakpoint(&mut self, breakpoint: Breakpoint) {
+        self.debugger.set_breakpoint(breakpoint);
+    }
+
+    /// Remove a breakpoint, returning whether it was set
+    pub fn clear_breakpoint(&mut self, breakpoint: &Breakpoint) -> bool {
+        self.debugger.clear_breakpoint(breakpoint)
+    }
+
+    /// Remove all breakpoints
+    pub fn clear_breakpoints(&mut self) {
+        self.debugger.clear_breakpoints();
+    }
+
+    /// Get the breakpoints set, in the order they were set
+    pub fn breakpoints(&self) -> Vec<Breakpoint> {
+        self.debugger.breakpoints().to_vec()
+    }
+
+    /// Watch a guard expression over signals and coils
+    ///
+    /// The expression is evaluated now and after every cycle, and each
+    /// change emits a [`VmEvent::WatchChanged`] event. A watch of the same
+    /// name is replaced. Returns the current value. See [`crate::debug`].
+    pub fn add_watch(&mut self, name: &str, expr: Guard) -> bool {
+        let snapshot = self.observer.state.snapshot();
+        let values = self.observer.state.values();
+        let registers = self.observer.state.registers();
+        let state = WatchState {
+            signals: snapshot.signals(),
+            coils: snapshot.coils(),
+            values: &values,
+            registers: &registers,
+        };
+        self.watches.add(name, expr, &state)
+    }
+
+    /// Remove a watch, returning whether there was one
+    pub fn remove_watch(&mut self, name: &str) -> bool {
+        self.watches.remove(name)
+    }
+
+    /// Get the value of each watch as of the last cycle
+    pub fn watches(&self) -> HashMap<String, bool> {
+        self.watches.values()
+    }
+
+    /// Check whether scanning is paused
+    pub fn is_paused(&self) -> bool {
+        self.debugger.is_paused()
+    }
+
+    /// Evaluate the next rung in scan order while paused
+    ///
+    /// Writes the coils the rung drives and applies its moves. Disabled
+    /// rungs, and rungs gated on a degraded signal, evaluate false. Fails
+    /// if the VM is not paused or no program with rungs is loaded, and
+    /// under [`QualityPolicy::Fault`](crate::QualityPolicy::Fault) while a
+    /// rung reads a degraded signal.
+    pub async fn step_single_rung(&mut self) -> Result<RungStep> {
+        self.config.mode.permit("step_single_rung")?;
+        if !self.debugger.is_paused() {
+            return Err(Error::InvalidOperation("VM is not paused".to_string()));
+        }
+        let program = self
+            .observer
+            .program()
+            .filter(|program| !program.module.rungs.is_empty())
+            .ok_or_else(|| Error::InvalidOperation("No rungs to step".to_string()))?;
+        let scanner = Arc::clone(&self.observer.state.loaded().scanner);
+
+        let mut vm = self.observer.vm.write().await;
+        let mut state = vm.get_all_signals();
+        state.extend(vm.get_all_coils());
+        scanner.derive(
+            &mut state,
+            &self.observer.state.values(),
+            &self.observer.state.registers(),
+            &self.observer.state.quality(),
+        )?;
+        let (index, scan_complete) = self.debugger.advance(program.module.rungs.len());
+        let rung = &program.module.rungs[index];
+        let bypasses = self.observer.state.bypasses().clone();
+        let energised = scanner.rung_holds(&program, index, &state, &bypasses);
+        for coil in rung.target_coils() {
+            vm.set_coil(coil.to_string(), energised);
+            Arc::make_mut(&mut self.outputs).set(coil, energised);
+        }
+        self.observer.state.publish(&vm, Arc::clone(&self.outputs));
+        drop(vm);
+
+        if energised && scanner.has_moves() {
+            let values = self.observer.state.values();
+            let mut registers = self.observer.state.registers();
+            scanner.apply_rung_moves(index, program.module.rungs.len(), &values, &mut registers);
+        }
+        Ok(RungStep {
+            rung: rung.name.clone(),
+            index,
+            energised,
+            source: rung.source.clone(),
+            scan_complete,
+        })
+    }
+
+    /// Get the quality of a signal's value
+    pub async fn get_signal_quality(&self, name: &str) -> Result<Quality> {
+        self.observer.get_signal_quality(name).await
+    }
+
+    /// Set the value of a signal
+    ///
+    /// Typed signals take a value of their declared type (integers widen
+    /// to floats) and are read by `compare` guards from the next cycle;
+    /// boolean signals take [`Value::Bool`]. Other values fail with
+    /// [`Error::TypeMismatch`].
+    pub async fn set_value(&mut self, name: &str, value: impl Into<Value>) -> Result<()> {
+        let program = self.observer.program();
+        match value::assign(program.as_deref(), name, value.into())? {
+            Assignment::Typed(value) => {
+                self.observer.state.values().insert(name.to_string(), value);
+                self.observer.state.quality().update(name, Quality::Good);
+                Ok(())
+            }
+            Assignment::Bool(value) => self.set_signal(name, value).await,
+        }
+    }
+
+    /// Get the value of a signal, typed or boolean
+    pub async fn get_value(&self, name: &str) -> Result<Option<Value>> {
+        self.observer.get_value(name).await
+    }
+
+    /// Get the values of all typed signals
+    pub async fn get_all_values(&self) -> Result<HashMap<String, Value>> {
+        self.observer.get_all_values().await
+    }
+
+    /// Get the value of a register written by `move` actions
+    pub async fn get_register(&self, name: &str) -> Result<Option<Value>> {
+        self.observer.get_register(name).await
+    }
+
+    /// Get the values of all registers
+    pub async fn get_all_registers(&self) -> Result<HashMap<String, Value>> {
+        self.observer.get_all_registers().await
+    }
+
     /// Set a coil value (for testing/debugging)
+    ///
+    /// Fails with [`Error::AccessDenied`] for safety coils (see
+    /// [`set_safety_coil`](Self::set_safety_coil)), and with
+    /// [`Error::InvalidOperation`] in [production mode](crate::mode).
     pub async fn set_coil(&mut self, name: &str, value: bool) -> Result<()> {
-        let mut vm = self.vm.write().await;
+        self.config.mode.permit("set_coil")?;
+        if self.is_safety_coil(name) {
+            return Err(safety::refused(name));
+        }
+        self.write_coil(name, value).await;
+        Ok(())
+    }
+
+    async fn write_coil(&mut self, name: &str, value: bool) {
+        let mut vm = self.observer.vm.write().await;
         vm.set_coil(name.to_string(), value);
+        Arc::make_mut(&mut self.outputs).set(name, value);
+        self.observer.state.publish(&vm, Arc::clone(&self.outputs));
+    }
+
+    /// Get whether test-oriented mutators are permitted; see [`crate::mode`]
+    pub fn mode(&self) -> VmMode {
+        self.config.mode
+    }
+
+    /// Check whether a coil is safety-classified, in the IR or by
+    /// [`ChartaVMBuilder::safety_coil`]
+    pub fn is_safety_coil(&self, name: &str) -> bool {
+        let program = self.observer.program();
+        safety::is_safety_coil(program.as_deref(), &self.config.safety_coils, name)
+    }
+
+    /// Issue a one-time token confirming a write of `value` to a safety coil
+    ///
+    /// The token expires after [`safety::TOKEN_TTL`]. Fails if the coil is
+    /// not safety-classified. See [`crate::safety`].
+    pub fn request_safety_override(&mut self, name: &str, value: bool) -> Result<String> {
+        self.config.mode.permit("request_safety_override")?;
+        if !self.is_safety_coil(name) {
+            return Err(safety::not_safety(name));
+        }
+        #[cfg(feature = "tracing")]
+        tracing::warn!(coil = %name, value, "safety override requested");
+        Ok(self.safety_overrides.issue(name, value))
+    }
+
+    /// Set a safety coil with a token from
+    /// [`request_safety_override`](Self::request_safety_override)
+    ///
+    /// Consumes the token and emits a [`VmEvent::SafetyOverride`]. Fails
+    /// with [`Error::AccessDenied`] if the token is unknown, expired, or was
+    /// issued for another coil or value.
+    pub async fn set_safety_coil(&mut self, name: &str, value: bool, token: &str) -> Result<()> {
+        self.config.mode.permit("set_safety_coil")?;
+        if !self.is_safety_coil(name) {
+            return Err(safety::not_safety(name));
+        }
+        self.safety_overrides.redeem(name, value, token)?;
+        self.write_coil(name, value).await;
+        #[cfg(feature = "tracing")]
+        tracing::error!(coil = %name, value, "safety coil overridden");
+        let context = CycleContext::now(
+            self.observer.cycle_count(),
+            self.observer.program_id().as_deref(),
+        );
+        self.observer.emit(VmEvent::SafetyOverride {
+            coil: name.to_string(),
+            value,
+            context,
+        });
         Ok(())
     }
 
     /// Get signal names
     pub async fn signal_names(&self) -> Result<Vec<String>> {
-        let vm = self.vm.read().await;
-        Ok(vm.signal_names().to_vec())
+        self.observer.signal_names().await
     }
 
     /// Get coil names
     pub async fn coil_names(&self) -> Result<Vec<String>> {
-        let vm = self.vm.read().await;
-        Ok(vm.coil_names().to_vec())
+        self.observer.coil_names().await
     }
 
     /// Register a callback for when a specific coil changes state
     ///
-    /// The callback receives: (coil_name, old_value, new_value)
+    /// `coil_name` may also be a pattern like `"allow_*"` or `"safety/**"`.
+    ///
+    /// The callback receives: (coil_name, old_value, new_value, context)
     pub async fn on_coil_change<F>(&self, coil_name: &str, callback: F)
     where
-        F: Fn(&str, bool, bool) + Send + Sync + 'static,
+        F: Fn(&str, bool, bool, &CycleContext) + Send + Sync + 'static,
     {
         let mut callbacks = self.callbacks.write().await;
         callbacks.on_coil_change(coil_name, callback);
@@ -199,10 +2107,10 @@ impl ChartaVM {
 
     /// Register a callback for when any coil changes state
     ///
-    /// The callback receives: (coil_name, old_value, new_value)
+    /// The callback receives: (coil_name, old_value, new_value, context)
     pub async fn on_any_coil_change<F>(&self, callback: F)
     where
-        F: Fn(&str, bool, bool) + Send + Sync + 'static,
+        F: Fn(&str, bool, bool, &CycleContext) + Send + Sync + 'static,
     {
         let mut callbacks = self.callbacks.write().await;
         callbacks.on_any_coil_change(callback);
@@ -213,12 +2121,100 @@ impl ChartaVM {
     /// The callback receives the outputs map (coil_name -> new_state)
     pub async fn on_cycle_complete<F>(&self, callback: F)
     where
-        F: Fn(&HashMap<String, bool>) + Send + Sync + 'static,
+        F: Fn(&HashMap<String, bool>, &CycleContext) + Send + Sync + 'static,
     {
         let mut callbacks = self.callbacks.write().await;
         callbacks.on_cycle_complete(callback);
     }
 
+    /// Register a callback for shadow program divergences
+    pub async fn on_shadow_divergence<F>(&self, callback: F)
+    where
+        F: Fn(&ShadowDivergence, &CycleContext) + Send + Sync + 'static,
+    {
+        let mut callbacks = self.callbacks.write().await;
+        callbacks.on_shadow_divergence(callback);
+    }
+
+    /// Subscribe to the VM event stream
+    ///
+    /// Each receiver sees every event emitted after it subscribed. Slow
+    /// receivers that fall more than the channel capacity behind observe
+    /// `RecvError::Lagged` and skip the missed events.
+    pub fn subscribe(&self) -> EventReceiver {
+        self.observer.subscribe()
+    }
+
+    /// Subscribe to changes of coils matching a name or pattern
+    ///
+    /// Accepts exact names or patterns like `"allow_*"` and `"safety/**"`.
+    pub fn subscribe_coils(&self, pattern: &str) -> CoilEventReceiver {
+        self.observer.subscribe_coils(pattern)
+    }
+
+    /// Subscribe to the event stream with a dedicated bounded buffer
+    ///
+    /// Unlike [`subscribe`](Self::subscribe), a slow subscriber here only
+    /// affects its own buffer, handled by its [`Backpressure`](crate::events::Backpressure)
+    /// policy.
+    pub fn subscribe_with(&self, options: SubscriberOptions) -> Subscription {
+        self.observer.subscribe_with(options)
+    }
+
+    /// Register a callback for panicking callbacks
+    ///
+    /// Invoked with the failing callback and its panic message whenever a
+    /// user callback panics during dispatch.
+    pub async fn on_callback_error<F>(&self, callback: F)
+    where
+        F: Fn(&CallbackError) + Send + Sync + 'static,
+    {
+        let mut callbacks = self.callbacks.write().await;
+        callbacks.on_callback_error(callback);
+    }
+
+    /// Register a callback for VM faults
+    ///
+    /// Invoked when a cycle fails, a program load fails, or a callback or
+    /// driver errors, with the error and the cycle and phase it occurred in.
+    /// Long-running scan loops can alert from here instead of threading
+    /// errors through their own plumbing.
+    pub async fn on_error<F>(&self, callback: F)
+    where
+        F: Fn(&Error, &ErrorContext) + Send + Sync + 'static,
+    {
+        let mut callbacks = self.callbacks.write().await;
+        callbacks.on_error(callback);
+    }
+
+    /// Set how panicking callbacks are handled
+    ///
+    /// Defaults to [`PanicPolicy::LogAndContinue`].
+    pub async fn set_panic_policy(&self, policy: PanicPolicy) {
+        let mut callbacks = self.callbacks.write().await;
+        callbacks.set_panic_policy(policy);
+    }
+
+    /// Wait until every queued callback has run
+    ///
+    /// Returns immediately with inline dispatch.
+    pub async fn flush_callbacks(&self) {
+        if let Some(dispatcher) = &self.dispatcher {
+            dispatcher.flush().await;
+        }
+    }
+
+    /// Number of cycles whose callbacks are queued or running
+    pub fn pending_callbacks(&self) -> usize {
+        self.dispatcher.as_ref().map_or(0, Dispatcher::pending)
+    }
+
+    /// Number of cycles whose callbacks were discarded because the queue was
+    /// full
+    pub fn dropped_callbacks(&self) -> u64 {
+        self.dispatcher.as_ref().map_or(0, Dispatcher::dropped)
+    }
+
     /// Clear all callbacks
     pub async fn clear_callbacks(&self) {
         let mut callbacks = self.callbacks.write().await;
@@ -0,0 +1,145 @@
+//! JavaScript facade for `wasm32-unknown-unknown`
+//!
+//! Exposes a `ChartaVM` class backed by [`blocking::ChartaVM`], so browser
+//! tools such as the policy editor can preview the same governance programs
+//! the services run:
+//!
+//! ```js
+//! import { ChartaVM } from "charta";
+//!
+//! const vm = new ChartaVM();
+//! vm.loadProgram(irJson);
+//! vm.onCoilChange("allow_*", (name, oldValue, newValue) => render(name, newValue));
+//! vm.setSignal("user_submitted", true);
+//! const coils = vm.execute(); // { allow_review: true, ... }
+//! ```
+//!
+//! Callbacks run synchronously inside `execute`, after the scan; nothing is
+//! spawned.
+//!
+//! [`blocking::ChartaVM`]: crate::blocking::ChartaVM
+
+use crate::blocking;
+use crate::pattern::Pattern;
+use js_sys::{Function, Object, Reflect};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+/// Charta VM exported to JavaScript as `ChartaVM`
+#[wasm_bindgen(js_name = ChartaVM)]
+pub struct WasmVM {
+    inner: blocking::ChartaVM,
+    coil_callbacks: Vec<(Pattern, Function)>,
+}
+
+#[wasm_bindgen(js_class = ChartaVM)]
+impl WasmVM {
+    /// Create a VM with no program loaded
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmVM {
+        WasmVM {
+            inner: blocking::ChartaVM::new(),
+            coil_callbacks: Vec::new(),
+        }
+    }
+
+    /// Load a program from IR JSON
+    #[wasm_bindgen(js_name = loadProgram)]
+    pub fn load_program(&mut self, ir_json: &str) -> Result<(), JsError> {
+        self.inner.load_program(ir_json)?;
+        Ok(())
+    }
+
+    /// Set a signal value
+    #[wasm_bindgen(js_name = setSignal)]
+    pub fn set_signal(&mut self, name: &str, value: bool) -> Result<(), JsError> {
+        Ok(self.inner.set_signal(name, value)?)
+    }
+
+    /// Get a signal value, or `undefined` if unknown
+    #[wasm_bindgen(js_name = getSignal)]
+    pub fn get_signal(&self, name: &str) -> Result<Option<bool>, JsError> {
+        Ok(self.inner.get_signal(name)?)
+    }
+
+    /// Get a coil value, or `undefined` if unknown
+    #[wasm_bindgen(js_name = getCoil)]
+    pub fn get_coil(&self, name: &str) -> Result<Option<bool>, JsError> {
+        Ok(self.inner.get_coil(name)?)
+    }
+
+    /// Get all coil states as an object
+    #[wasm_bindgen(js_name = getCoils)]
+    pub fn get_coils(&self) -> Result<Object, JsError> {
+        to_object(&self.inner.get_all_coils()?)
+    }
+
+    /// Get all signal states as an object
+    #[wasm_bindgen(js_name = getSignals)]
+    pub fn get_signals(&self) -> Result<Object, JsError> {
+        to_object(&self.inner.get_all_signals()?)
+    }
+
+    /// Execute one scan cycle and return the coil states
+    ///
+    /// Registered coil callbacks are invoked for each changed coil before
+    /// this returns.
+    pub fn execute(&mut self) -> Result<Object, JsError> {
+        let old = self.inner.get_all_coils()?;
+        let outputs = self.inner.execute_cycle()?;
+
+        for (name, &new) in &outputs {
+            let old = old.get(name).copied().unwrap_or(false);
+            if old == new {
+                continue;
+            }
+            for (pattern, callback) in &self.coil_callbacks {
+                if pattern.matches(name) {
+                    callback
+                        .call3(
+                            &JsValue::NULL,
+                            &JsValue::from_str(name),
+                            &JsValue::from_bool(old),
+                            &JsValue::from_bool(new),
+                        )
+                        .map_err(|e| JsError::new(&format!("coil callback failed: {:?}", e)))?;
+                }
+            }
+        }
+        to_object(&outputs)
+    }
+
+    /// Register `callback(name, oldValue, newValue)` for coils matching a
+    /// name or pattern like `"allow_*"`
+    #[wasm_bindgen(js_name = onCoilChange)]
+    pub fn on_coil_change(&mut self, pattern: &str, callback: Function) {
+        self.coil_callbacks.push((Pattern::new(pattern), callback));
+    }
+
+    /// Remove all coil callbacks
+    #[wasm_bindgen(js_name = clearCallbacks)]
+    pub fn clear_callbacks(&mut self) {
+        self.coil_callbacks.clear();
+    }
+
+    /// Number of cycles executed so far
+    #[wasm_bindgen(getter, js_name = cycleCount)]
+    pub fn cycle_count(&self) -> f64 {
+        self.inner.cycle_count() as f64
+    }
+}
+
+impl Default for WasmVM {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn to_object(states: &HashMap<String, bool>) -> Result<Object, JsError> {
+    let object = Object::new();
+    for (name, value) in states {
+        Reflect::set(&object, &JsValue::from_str(name), &JsValue::from_bool(*value))
+            .map_err(|e| JsError::new(&format!("{:?}", e)))?;
+    }
+    Ok(object)
+}
@@ -0,0 +1,65 @@
+//! Change notifications for a watched program file
+
+use crate::error::{Error, Result};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Flags changes to one program file
+///
+/// Watches the file's directory rather than the file, so editors that save
+/// by writing a new file and renaming it over the old one are followed.
+pub(crate) struct ProgramWatcher {
+    path: PathBuf,
+    changed: Arc<AtomicBool>,
+    _watcher: RecommendedWatcher,
+}
+
+impl ProgramWatcher {
+    pub(crate) fn new(path: &Path) -> Result<Self> {
+        let path = std::path::absolute(path)?;
+        let dir = path
+            .parent()
+            .ok_or_else(|| Error::InvalidOperation(format!("'{}' has no directory", path.display())))?
+            .to_path_buf();
+        let name = path.file_name().map(|name| name.to_os_string());
+
+        let changed = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&changed);
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else { return };
+            let relevant = !event.kind.is_access()
+                && event
+                    .paths
+                    .iter()
+                    .any(|changed| changed.file_name().map(|n| n.to_os_string()) == name);
+            if relevant {
+                flag.store(true, Ordering::SeqCst);
+            }
+        })
+        .map_err(|e| watch_error(&path, e))?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| watch_error(&path, e))?;
+
+        Ok(Self {
+            path,
+            changed,
+            _watcher: watcher,
+        })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check for and clear a pending change
+    pub(crate) fn take_change(&self) -> bool {
+        self.changed.swap(false, Ordering::SeqCst)
+    }
+}
+
+fn watch_error(path: &Path, error: notify::Error) -> Error {
+    Error::Driver(format!("watching '{}': {}", path.display(), error))
+}
@@ -0,0 +1,42 @@
+/// Integration tests for capability-scoped signal writers
+
+use charta::{ChartaVM, Error};
+use std::collections::HashMap;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "access_program",
+        "signals": [
+            {"name": "user_submitted"},
+            {"name": "operation_requested"},
+            {"name": "system_ok"}
+        ],
+        "coils": [],
+        "rungs": []
+    }
+}"#;
+
+#[tokio::test]
+async fn test_writer_is_scoped() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let writer = vm.writer_for(&["user_submitted", "operation_requested"]);
+    writer.set_signal("user_submitted", true).await?;
+    assert_eq!(vm.get_signal("user_submitted").await?, Some(true));
+
+    let denied = writer.set_signal("system_ok", true).await;
+    assert!(matches!(denied, Err(Error::AccessDenied(_))));
+    assert_eq!(vm.get_signal("system_ok").await?, Some(false));
+
+    // Batch writes are all-or-nothing
+    let mut batch = HashMap::new();
+    batch.insert("operation_requested".to_string(), true);
+    batch.insert("system_ok".to_string(), true);
+    assert!(writer.set_signals(&batch).await.is_err());
+    assert_eq!(vm.get_signal("operation_requested").await?, Some(false));
+
+    Ok(())
+}
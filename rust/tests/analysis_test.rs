@@ -0,0 +1,151 @@
+/// Tests for interlock analysis
+
+use charta::analysis::{self, Condition, InterlockKind, Requirement};
+use charta::ir::Program;
+use charta::Error;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "interlock_program",
+        "signals": [
+            {"name": "kyc_passed"},
+            {"name": "sanctions_hit"},
+            {"name": "manual_override"},
+            {"name": "amount", "type": "int"}
+        ],
+        "coils": [
+            {"name": "screened"},
+            {"name": "allow_operation"},
+            {"name": "held"}
+        ],
+        "rungs": [
+            {
+                "name": "screen",
+                "guard": {
+                    "type": "and",
+                    "operands": [
+                        {"type": "contact", "name": "kyc_passed"},
+                        {"type": "contact", "name": "sanctions_hit", "contact_type": "NC"}
+                    ]
+                },
+                "actions": [{"type": "energise", "coil": "screened"}]
+            },
+            {
+                "name": "operation_gate",
+                "guard": {
+                    "type": "or",
+                    "operands": [
+                        {
+                            "type": "and",
+                            "operands": [
+                                {"type": "contact", "name": "screened"},
+                                {"type": "compare", "name": "amount", "op": "<", "value": 10000}
+                            ]
+                        },
+                        {"type": "contact", "name": "manual_override"}
+                    ]
+                },
+                "actions": [{"type": "energise", "coil": "allow_operation"}]
+            },
+            {
+                "name": "hold",
+                "guard": {
+                    "type": "or",
+                    "operands": [
+                        {"type": "contact", "name": "held"},
+                        {"type": "not", "operand": {"type": "contact", "name": "screened"}}
+                    ]
+                },
+                "actions": [{"type": "energise", "coil": "held"}]
+            }
+        ]
+    }
+}"#;
+
+fn program() -> Program {
+    Program::from_json(IR_JSON).unwrap()
+}
+
+fn required(report: &analysis::Interlocks, name: &str) -> Option<(InterlockKind, Requirement)> {
+    report
+        .inventory
+        .iter()
+        .find(|interlock| interlock.name == name)
+        .map(|interlock| (interlock.kind, interlock.required))
+}
+
+#[test]
+fn test_inventory_resolves_intermediate_coils() -> Result<(), Error> {
+    let report = analysis::interlocks(&program(), "allow_operation")?;
+    assert_eq!(report.rung.as_deref(), Some("operation_gate"));
+    assert_eq!(
+        required(&report, "screened"),
+        Some((InterlockKind::Coil, Requirement::True))
+    );
+    assert_eq!(
+        required(&report, "kyc_passed"),
+        Some((InterlockKind::Signal, Requirement::True))
+    );
+    assert_eq!(
+        required(&report, "sanctions_hit"),
+        Some((InterlockKind::Signal, Requirement::False))
+    );
+    assert_eq!(
+        required(&report, "amount < 10000"),
+        Some((InterlockKind::Comparison, Requirement::True))
+    );
+    assert_eq!(report.inventory.len(), 5);
+    assert!(matches!(report.condition, Some(Condition::Any { .. })));
+    Ok(())
+}
+
+#[test]
+fn test_negations_reach_the_leaves() -> Result<(), Error> {
+    let report = analysis::interlocks(&program(), "held")?;
+    assert_eq!(
+        required(&report, "screened"),
+        Some((InterlockKind::Coil, Requirement::False))
+    );
+    // screened is false when either of its contacts fails
+    assert_eq!(
+        required(&report, "kyc_passed"),
+        Some((InterlockKind::Signal, Requirement::False))
+    );
+    assert_eq!(
+        required(&report, "sanctions_hit"),
+        Some((InterlockKind::Signal, Requirement::True))
+    );
+
+    // The seal-in contact is a leaf
+    let Some(Condition::Any { conditions }) = &report.condition else {
+        panic!("unexpected condition {:?}", report.condition);
+    };
+    assert!(matches!(
+        &conditions[0],
+        Condition::Coil { name, condition: None, .. } if name == "held"
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_report_renders_and_serializes() -> Result<(), Error> {
+    let report = analysis::interlocks(&program(), "allow_operation")?;
+    let text = report.to_string();
+    assert!(text.starts_with("allow_operation <- rung 'operation_gate'\n"));
+    assert!(text.contains("screened = true <- rung 'screen'"));
+    assert!(text.contains("sanctions_hit = false"));
+    assert!(text.contains("amount < 10000 holds"));
+
+    let json = serde_json::to_value(&report)?;
+    assert_eq!(json["condition"]["type"], "any");
+    assert_eq!(json["inventory"][0]["kind"], "signal");
+    Ok(())
+}
+
+#[test]
+fn test_unknown_coil_is_not_found() {
+    let result = analysis::interlocks(&program(), "missing");
+    assert!(matches!(result, Err(Error::NotFound(_))), "{:?}", result);
+}
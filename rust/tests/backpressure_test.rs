@@ -0,0 +1,114 @@
+/// Integration tests for per-subscriber event backpressure
+
+use charta::{Backpressure, ChartaVM, Error, SubscriberOptions, VmEvent};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "backpressure_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_full_buffer_drops_oldest_and_reports_lag() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let mut events = vm.subscribe_with(SubscriberOptions {
+        capacity: 2,
+        ..SubscriberOptions::default()
+    });
+
+    for _ in 0..3 {
+        vm.execute_cycle().await?;
+    }
+    assert_eq!(events.len(), 2);
+    assert_eq!(events.skipped(), 1);
+
+    assert_eq!(events.recv().await, Some(VmEvent::Lagged { skipped: 1 }));
+    for cycle in [2, 3] {
+        match events.recv().await {
+            Some(VmEvent::CycleCompleted { outputs, .. }) => assert_eq!(outputs.cycle(), cycle),
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+    assert!(events.try_recv().is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coalesce_merges_coil_change_bursts() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let mut events = vm.subscribe_with(SubscriberOptions {
+        backpressure: Backpressure::Coalesce,
+        ..SubscriberOptions::default()
+    });
+
+    for input in [true, false, true, false, true] {
+        vm.execute_cycle_with_inputs([("input".to_string(), input)].into()).await?;
+    }
+
+    let mut changes = Vec::new();
+    while let Some(event) = events.try_recv() {
+        if let VmEvent::CoilChanged { name, old, new, .. } = event {
+            changes.push((name, old, new));
+        }
+    }
+    // The burst collapses to one change from the first old to the latest new
+    assert_eq!(changes, vec![("output".to_string(), false, true)]);
+    assert_eq!(events.skipped(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_lag_notification_can_be_disabled() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let mut events = vm.subscribe_with(SubscriberOptions {
+        capacity: 1,
+        notify_lag: false,
+        ..SubscriberOptions::default()
+    });
+
+    vm.execute_cycle().await?;
+    vm.execute_cycle().await?;
+    assert!(matches!(events.recv().await, Some(VmEvent::CycleCompleted { .. })));
+    assert_eq!(events.skipped(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscription_ends_with_the_vm() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let mut events = vm.subscribe_with(SubscriberOptions::default());
+    vm.execute_cycle().await?;
+    drop(vm);
+
+    assert!(matches!(events.recv().await, Some(VmEvent::CycleCompleted { .. })));
+    assert_eq!(events.recv().await, None);
+    Ok(())
+}
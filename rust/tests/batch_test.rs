@@ -0,0 +1,92 @@
+#![cfg(feature = "rayon")]
+/// Tests for parallel batch evaluation
+
+use charta::{ChartaVM, Error};
+use std::collections::HashMap;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "batch_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_batch_results_in_input_order() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let inputs: Vec<HashMap<String, bool>> = (0..100)
+        .map(|i| HashMap::from([("input".to_string(), i % 3 == 0)]))
+        .collect();
+    let results = vm.evaluate_batch_parallel(inputs).await?;
+
+    assert_eq!(results.len(), 100);
+    for (i, coils) in results.iter().enumerate() {
+        assert_eq!(coils.get("output"), Some(&(i % 3 == 0)), "evaluation {}", i);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_batch_does_not_commit_state() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.set_signal("input", true).await?;
+
+    let results = vm
+        .evaluate_batch_parallel(vec![HashMap::new(), HashMap::from([("input".to_string(), false)])])
+        .await?;
+
+    // Evaluations start from the current signals
+    assert_eq!(results[0].get("output"), Some(&true));
+    assert_eq!(results[1].get("output"), Some(&false));
+    assert_eq!(vm.get_coil("output").await?, Some(false));
+    assert_eq!(vm.cycle_count(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_batch_requires_program() {
+    let vm = ChartaVM::new();
+    let result = vm.evaluate_batch_parallel(vec![HashMap::new()]).await;
+    assert!(matches!(result, Err(Error::InvalidOperation(_))));
+}
+
+#[tokio::test]
+async fn test_batch_matches_cycle_with_bypassed_rung() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.disable_rung("test_rung").await?;
+
+    let inputs = HashMap::from([("input".to_string(), true)]);
+    let results = vm.evaluate_batch_parallel(vec![inputs.clone()]).await?;
+    let outputs = vm.execute_cycle_with_inputs(inputs).await?;
+
+    assert_eq!(outputs.get("output"), Some(&false));
+    assert_eq!(&results[0], outputs.coils());
+    Ok(())
+}
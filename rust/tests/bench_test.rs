@@ -0,0 +1,39 @@
+#![cfg(feature = "bench")]
+/// Tests for the benchmark fixtures
+
+use charta::bench::{self, BenchSize};
+use charta::{ChartaVM, Error};
+
+#[test]
+fn test_programs_are_reproducible() {
+    let size = BenchSize::rungs(200);
+    assert_eq!(bench::program(&size), bench::program(&size));
+    assert_eq!(bench::program(&size).module.rungs.len(), 200);
+
+    let other = BenchSize { seed: 2, ..size };
+    assert_ne!(bench::program(&size), bench::program(&other));
+}
+
+#[test]
+fn test_inputs_change_gradually() {
+    let program = bench::program(&BenchSize::default());
+    let inputs = bench::inputs(&program, 50, 0.1, 3);
+    assert_eq!(inputs.len(), 50);
+    assert_eq!(inputs[0].len(), program.module.signals.len());
+    assert!(inputs[1..].iter().all(|set| set.len() < program.module.signals.len()));
+    assert_eq!(inputs, bench::inputs(&program, 50, 0.1, 3));
+}
+
+#[tokio::test]
+async fn test_time_cycles() -> Result<(), Error> {
+    let size = BenchSize::rungs(50);
+    let mut vm = ChartaVM::new();
+    vm.load_program(&bench::program_json(&size)).await?;
+
+    let inputs = bench::inputs(&bench::program(&size), 20, 0.2, 1);
+    let timings = bench::time_cycles(&mut vm, inputs).await?;
+    assert_eq!(timings.cycles, 20);
+    assert_eq!(vm.cycle_count(), 20);
+    assert!(timings.p50 <= timings.p99 && timings.p99 <= timings.max);
+    Ok(())
+}
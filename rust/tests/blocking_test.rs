@@ -0,0 +1,90 @@
+/// Tests for the blocking VM
+
+use charta::blocking::ChartaVM;
+use charta::{Error, ErrorPhase};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "blocking_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[test]
+fn test_load_and_execute() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON)?;
+    assert!(vm.program_id().is_some());
+
+    vm.set_signal("input", true)?;
+    let outputs = vm.execute_cycle()?;
+    assert_eq!(outputs.get("output"), Some(&true));
+    assert_eq!(vm.get_coil("output")?, Some(true));
+    assert_eq!(vm.cycle_count(), 1);
+
+    let outputs = vm.execute_cycle_with_inputs(HashMap::from([("input".to_string(), false)]))?;
+    assert_eq!(outputs.get("output"), Some(&false));
+    Ok(())
+}
+
+#[test]
+fn test_coil_change_callbacks() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON)?;
+
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let seen = changes.clone();
+    vm.on_coil_change("output", move |name, old, new, _| {
+        seen.lock().unwrap().push((name.to_string(), old, new));
+    });
+
+    vm.set_signal("input", true)?;
+    vm.execute_cycle()?;
+    vm.execute_cycle()?;
+
+    assert_eq!(*changes.lock().unwrap(), vec![("output".to_string(), false, true)]);
+    Ok(())
+}
+
+#[test]
+fn test_load_failure_reaches_fault_hook() {
+    let mut vm = ChartaVM::new();
+    let phases = Arc::new(Mutex::new(Vec::new()));
+    let seen = phases.clone();
+    vm.on_error(move |_, context| seen.lock().unwrap().push(context.phase));
+
+    assert!(vm.load_program("not json").is_err());
+    assert_eq!(*phases.lock().unwrap(), vec![ErrorPhase::Load]);
+}
+
+#[test]
+fn test_builder_applies_limits() {
+    let mut vm = charta::ChartaVM::builder().max_rungs(0).build_blocking();
+    let result = vm.load_program(IR_JSON);
+    assert!(matches!(result, Err(Error::LimitExceeded(_))));
+}
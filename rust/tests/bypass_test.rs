@@ -0,0 +1,176 @@
+/// Tests for runtime rung bypass
+
+use charta::{ChartaVM, Error, VmEvent};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "bypass_program",
+        "signals": [
+            {"name": "sanctions_clear"},
+            {"name": "submitted"}
+        ],
+        "coils": [
+            {"name": "permit"},
+            {"name": "received"}
+        ],
+        "rungs": [
+            {
+                "name": "sanctions_interlock",
+                "guard": {"type": "contact", "name": "sanctions_clear", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "permit"}
+                ]
+            },
+            {
+                "name": "receive",
+                "guard": {"type": "contact", "name": "submitted", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "received"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_disabled_rung_does_not_energise() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.set_signal("sanctions_clear", true).await?;
+    vm.set_signal("submitted", true).await?;
+
+    vm.disable_rung("sanctions_interlock").await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("permit"), Some(&false));
+    assert_eq!(outputs.get("received"), Some(&true));
+
+    vm.enable_rung("sanctions_interlock").await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("permit"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bypasses_are_listed_and_audited() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let observer = vm.observer();
+    let mut events = vm.subscribe();
+
+    vm.execute_cycle().await?;
+    vm.disable_rung("sanctions_interlock").await?;
+    vm.disable_rung("sanctions_interlock").await?;
+
+    let bypasses = observer.disabled_rungs();
+    assert_eq!(bypasses.len(), 1);
+    assert_eq!(bypasses[0].rung, "sanctions_interlock");
+    assert_eq!(bypasses[0].cycle, 1);
+
+    vm.enable_rung("sanctions_interlock").await?;
+    vm.enable_rung("receive").await?;
+    assert!(vm.disabled_rungs().is_empty());
+
+    let mut audit = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        match event {
+            VmEvent::RungDisabled { rung, .. } => audit.push(format!("disabled {}", rung)),
+            VmEvent::RungEnabled { rung, .. } => audit.push(format!("enabled {}", rung)),
+            _ => {}
+        }
+    }
+    assert_eq!(
+        audit,
+        [
+            "disabled sanctions_interlock",
+            "enabled sanctions_interlock"
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unknown_rung_and_reload() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let result = vm.disable_rung("missing").await;
+    assert!(matches!(result, Err(Error::NotFound(_))), "{:?}", result);
+
+    vm.disable_rung("receive").await?;
+    vm.reload_program(IR_JSON).await?;
+    assert!(vm.disabled_rungs().is_empty());
+    vm.set_signal("submitted", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("received"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bypass_signals_are_hidden() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.disable_rung("receive").await?;
+    vm.execute_cycle().await?;
+
+    let mut names = vm.signal_names().await?;
+    names.sort();
+    assert_eq!(names, ["sanctions_clear", "submitted"]);
+    Ok(())
+}
+
+#[test]
+fn test_blocking_vm_bypasses_rung() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder().build_blocking();
+    vm.load_program(IR_JSON)?;
+    vm.set_signal("sanctions_clear", true)?;
+
+    vm.disable_rung("sanctions_interlock")?;
+    let outputs = vm.execute_cycle()?;
+    assert_eq!(outputs.get("permit"), Some(&false));
+    assert_eq!(vm.disabled_rungs().len(), 1);
+
+    vm.enable_rung("sanctions_interlock")?;
+    let outputs = vm.execute_cycle()?;
+    assert_eq!(outputs.get("permit"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bypass_signals_cannot_be_written() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.set_signal("sanctions_clear", true).await?;
+    let name = "__bypass:sanctions_interlock";
+
+    let result = vm.set_signal(name, true).await;
+    assert!(matches!(result, Err(Error::AccessDenied(_))), "{:?}", result);
+    let result = vm.set_value(name, true).await;
+    assert!(matches!(result, Err(Error::AccessDenied(_))), "{:?}", result);
+    let result = vm.writer_for(&["*"]).set_signal(name, true).await;
+    assert!(matches!(result, Err(Error::AccessDenied(_))), "{:?}", result);
+
+    let inputs = [(name.to_string(), true)].into();
+    let result = vm.execute_cycle_with_inputs(inputs).await;
+    assert!(matches!(result, Err(Error::AccessDenied(_))), "{:?}", result);
+    assert_eq!(vm.cycle_count(), 0);
+
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("permit"), Some(&true));
+    Ok(())
+}
+
+#[test]
+fn test_blocking_vm_refuses_bypass_signal_writes() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder().build_blocking();
+    vm.load_program(IR_JSON)?;
+    let name = "__bypass:sanctions_interlock";
+
+    let result = vm.set_signal(name, true);
+    assert!(matches!(result, Err(Error::AccessDenied(_))), "{:?}", result);
+    let inputs = [(name.to_string(), true)].into();
+    let result = vm.execute_cycle_with_inputs(inputs);
+    assert!(matches!(result, Err(Error::AccessDenied(_))), "{:?}", result);
+    Ok(())
+}
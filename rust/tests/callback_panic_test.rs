@@ -0,0 +1,75 @@
+/// Integration tests for callback panic isolation
+
+use charta::{ChartaVM, Error, PanicPolicy};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "panic_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_panicking_callback_is_isolated() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let errors = Arc::new(AtomicU32::new(0));
+    let errors_clone = errors.clone();
+    vm.on_callback_error(move |error| {
+        assert_eq!(error.callback, "coil_change:output");
+        assert_eq!(error.message, "boom");
+        errors_clone.fetch_add(1, Ordering::Relaxed);
+    })
+    .await;
+
+    let completed = Arc::new(AtomicU32::new(0));
+    let completed_clone = completed.clone();
+    vm.on_coil_change("output", |_, _, _, _| panic!("boom")).await;
+    vm.on_cycle_complete(move |_, _| {
+        completed_clone.fetch_add(1, Ordering::Relaxed);
+    })
+    .await;
+
+    // Default policy: report and keep dispatching
+    vm.set_signal("input", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("output"), Some(&true));
+    assert_eq!(errors.load(Ordering::Relaxed), 1);
+    assert_eq!(completed.load(Ordering::Relaxed), 1);
+
+    // ReturnError: the cycle still commits but reports the panic
+    vm.set_panic_policy(PanicPolicy::ReturnError).await;
+    vm.set_signal("input", false).await?;
+    let result = vm.execute_cycle().await;
+    assert!(matches!(result, Err(Error::CallbackPanicked(_))));
+    assert_eq!(vm.get_coil("output").await?, Some(false));
+    assert_eq!(completed.load(Ordering::Relaxed), 2);
+
+    Ok(())
+}
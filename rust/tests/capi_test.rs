@@ -0,0 +1,119 @@
+#![cfg(feature = "capi")]
+/// Tests for the C API
+
+use charta::capi::*;
+use std::ffi::{c_char, c_void, CStr, CString};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "capi_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+extern "C" fn count_changes(_name: *const c_char, _old: bool, _new: bool, user_data: *mut c_void) {
+    unsafe { *(user_data as *mut u32) += 1 };
+}
+
+#[test]
+fn test_load_set_execute_get() {
+    let ir = CString::new(IR_JSON).unwrap();
+    let input = CString::new("input").unwrap();
+    let output = CString::new("output").unwrap();
+    let pattern = CString::new("*").unwrap();
+    let mut changes = 0u32;
+
+    unsafe {
+        let vm = charta_vm_new();
+        assert_eq!(charta_vm_load(vm, ir.as_ptr()), ChartaStatus::Ok);
+        assert_eq!(
+            charta_vm_on_coil_change(
+                vm,
+                pattern.as_ptr(),
+                count_changes,
+                &mut changes as *mut u32 as *mut c_void
+            ),
+            ChartaStatus::Ok
+        );
+        assert_eq!(charta_vm_set_signal(vm, input.as_ptr(), true), ChartaStatus::Ok);
+        assert_eq!(charta_vm_execute(vm), ChartaStatus::Ok);
+
+        let mut value = false;
+        assert_eq!(charta_vm_get_coil(vm, output.as_ptr(), &mut value), ChartaStatus::Ok);
+        assert!(value);
+        assert_eq!(charta_vm_cycle_count(vm), 1);
+        charta_vm_free(vm);
+    }
+    assert_eq!(changes, 1);
+}
+
+#[test]
+fn test_errors_set_last_error() {
+    let bad = CString::new("not json").unwrap();
+    unsafe {
+        let vm = charta_vm_new();
+        assert_eq!(charta_vm_load(vm, bad.as_ptr()), ChartaStatus::Error);
+        assert!(!CStr::from_ptr(charta_last_error()).to_bytes().is_empty());
+
+        assert_eq!(charta_vm_load(vm, std::ptr::null()), ChartaStatus::NullArgument);
+        assert_eq!(charta_vm_execute(std::ptr::null_mut()), ChartaStatus::NullArgument);
+        charta_vm_free(vm);
+    }
+}
+
+fn last_error() -> String {
+    unsafe { CStr::from_ptr(charta_last_error()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[test]
+fn test_every_failure_sets_last_error() {
+    let ir = CString::new(IR_JSON).unwrap();
+    let missing = CString::new("missing").unwrap();
+    let invalid = [0xffu8, 0];
+    unsafe {
+        let vm = charta_vm_new();
+        assert_eq!(charta_vm_load(vm, ir.as_ptr()), ChartaStatus::Ok);
+
+        let mut value = false;
+        assert_eq!(
+            charta_vm_get_coil(vm, missing.as_ptr(), &mut value),
+            ChartaStatus::NotFound
+        );
+        assert!(last_error().contains("missing"));
+
+        assert_eq!(
+            charta_vm_set_signal(vm, invalid.as_ptr() as *const c_char, true),
+            ChartaStatus::InvalidUtf8
+        );
+        assert!(last_error().contains("UTF-8"));
+
+        assert_eq!(charta_vm_execute(std::ptr::null_mut()), ChartaStatus::NullArgument);
+        assert!(last_error().contains("null"));
+        charta_vm_free(vm);
+    }
+}
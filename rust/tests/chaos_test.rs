@@ -0,0 +1,105 @@
+#![cfg(feature = "chaos")]
+/// Tests for fault injection
+
+use charta::chaos::FaultInjector;
+use charta::io::{async_trait, InputSource};
+use charta::{ChartaVM, Error, ErrorPhase};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "chaos_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+/// Input source always reporting `input` as true
+struct AlwaysOn;
+
+#[async_trait]
+impl InputSource for AlwaysOn {
+    async fn read(&mut self) -> HashMap<String, bool> {
+        HashMap::from([("input".to_string(), true)])
+    }
+}
+
+#[tokio::test]
+async fn test_fail_cycle_reaches_fault_hook() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let faults = FaultInjector::new();
+    vm.set_fault_injector(faults.clone());
+
+    let phases = Arc::new(Mutex::new(Vec::new()));
+    let seen = phases.clone();
+    vm.on_error(move |_, context| seen.lock().unwrap().push((context.cycle, context.phase)))
+        .await;
+
+    faults.fail_cycle(2);
+    vm.execute_cycle().await?;
+    let result = vm.execute_cycle().await;
+    assert!(matches!(result, Err(Error::InjectedFault(_))));
+    assert_eq!(vm.cycle_count(), 1);
+    assert_eq!(*phases.lock().unwrap(), vec![(2, ErrorPhase::Cycle)]);
+
+    // The fault fires once
+    vm.execute_cycle().await?;
+    assert_eq!(vm.cycle_count(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delay_callbacks() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let faults = FaultInjector::new();
+    vm.set_fault_injector(faults.clone());
+    faults.delay_callbacks(Duration::from_millis(20));
+
+    let started = Instant::now();
+    vm.execute_cycle().await?;
+    assert!(started.elapsed() >= Duration::from_millis(20));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_drop_reads() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.add_input_source(AlwaysOn);
+    let faults = FaultInjector::new();
+    vm.set_fault_injector(faults.clone());
+
+    faults.drop_reads(1);
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("output"), Some(&false));
+
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("output"), Some(&true));
+    Ok(())
+}
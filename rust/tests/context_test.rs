@@ -0,0 +1,96 @@
+/// Tests for cycle contexts on callbacks and events
+
+use charta::{ChartaVM, CycleContext, Error, VmEvent};
+use std::sync::{Arc, Mutex};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "context_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_callbacks_and_events_share_the_cycle_context() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    let mut events = vm.subscribe();
+    vm.load_program(IR_JSON).await?;
+    let program_id = vm.program_id();
+
+    let seen: Arc<Mutex<Vec<CycleContext>>> = Arc::default();
+    let seen_clone = seen.clone();
+    vm.on_coil_change("output", move |_, _, _, context| {
+        seen_clone.lock().unwrap().push(context.clone());
+    })
+    .await;
+    let seen_clone = seen.clone();
+    vm.on_cycle_complete(move |_, context| {
+        seen_clone.lock().unwrap().push(context.clone());
+    })
+    .await;
+
+    vm.set_signal("input", true).await?;
+    vm.execute_cycle().await?;
+
+    let seen = seen.lock().unwrap().clone();
+    assert_eq!(seen.len(), 2);
+    assert_eq!(seen[0], seen[1]);
+    assert_eq!(seen[0].cycle, 1);
+    assert_eq!(seen[0].program_id.as_deref(), program_id.as_deref());
+
+    match events.recv().await.unwrap() {
+        VmEvent::ProgramLoaded { context } => {
+            assert_eq!(context.cycle, 0);
+            assert_eq!(context.program_id.as_deref(), program_id.as_deref());
+        }
+        other => panic!("unexpected event: {:?}", other),
+    }
+    for _ in 0..2 {
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.context(), Some(&seen[0]));
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_context_timestamps_follow_cycle_order() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let mut events = vm.subscribe();
+
+    vm.execute_cycle().await?;
+    vm.execute_cycle().await?;
+
+    let mut contexts = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        contexts.extend(event.context().cloned());
+    }
+    assert_eq!(contexts.len(), 2);
+    assert_eq!((contexts[0].cycle, contexts[1].cycle), (1, 2));
+    assert!(contexts[0].at <= contexts[1].at);
+    assert!(contexts[0].timestamp_millis() > 0);
+    Ok(())
+}
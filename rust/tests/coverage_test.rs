@@ -0,0 +1,102 @@
+/// Tests for rung and guard branch coverage
+
+use charta::{ChartaVM, Error};
+use std::collections::HashMap;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "coverage_program",
+        "signals": [
+            {"name": "a"},
+            {"name": "b"}
+        ],
+        "coils": [
+            {"name": "both"},
+            {"name": "never"}
+        ],
+        "rungs": [
+            {
+                "name": "both_rung",
+                "guard": {
+                    "type": "and",
+                    "operands": [
+                        {"type": "contact", "name": "a", "contact_type": "NO"},
+                        {"type": "contact", "name": "b", "contact_type": "NO"}
+                    ]
+                },
+                "actions": [
+                    {"type": "energise", "coil": "both"}
+                ]
+            },
+            {
+                "name": "never_rung",
+                "guard": {
+                    "type": "and",
+                    "operands": [
+                        {"type": "contact", "name": "a", "contact_type": "NO"},
+                        {"type": "contact", "name": "a", "contact_type": "NC"}
+                    ]
+                },
+                "actions": [
+                    {"type": "energise", "coil": "never"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+fn inputs(a: bool, b: bool) -> HashMap<String, bool> {
+    HashMap::from([("a".to_string(), a), ("b".to_string(), b)])
+}
+
+#[tokio::test]
+async fn test_coverage_disabled_by_default() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.execute_cycle_with_inputs(inputs(true, true)).await?;
+
+    assert!(vm.coverage_report().is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rung_and_branch_coverage() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.enable_coverage();
+
+    vm.execute_cycle_with_inputs(inputs(true, true)).await?;
+    vm.execute_cycle_with_inputs(inputs(true, false)).await?;
+
+    let report = vm.coverage_report().unwrap();
+    assert_eq!(report.cycles, 2);
+    assert_eq!(report.unfired_rungs(), vec!["never_rung"]);
+    assert_eq!(report.rung_coverage(), 0.5);
+
+    let both = &report.rungs[0];
+    assert_eq!((both.energised, both.de_energised), (1, 1));
+    let paths: Vec<&str> = both.branches.iter().map(|b| b.path.as_str()).collect();
+    assert_eq!(paths, vec!["guard", "guard.operands[0]", "guard.operands[1]"]);
+    assert_eq!(both.branches[1].node, "contact a (NO)");
+    assert!(!both.branches[1].is_covered());
+    assert!(both.branches[2].is_covered());
+    assert!(report.branch_coverage() < 1.0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coverage_resets_on_load() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.enable_coverage();
+    vm.load_program(IR_JSON).await?;
+    vm.execute_cycle_with_inputs(inputs(true, true)).await?;
+
+    vm.load_program(IR_JSON).await?;
+    assert_eq!(vm.coverage_report().unwrap().cycles, 0);
+
+    vm.disable_coverage();
+    assert!(vm.coverage_report().is_none());
+    Ok(())
+}
@@ -0,0 +1,76 @@
+/// Integration tests for cycle deadlines
+
+use charta::{ChartaVM, Error, VmEvent};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "deadline_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_overrun_emits_event_and_aborts_dispatch() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .cycle_deadline(Duration::from_millis(5))
+        .abort_on_deadline(true)
+        .build();
+    vm.load_program(IR_JSON).await?;
+    let mut events = vm.subscribe();
+
+    let completed = Arc::new(AtomicU32::new(0));
+    let completed_clone = completed.clone();
+    vm.on_coil_change("output", |_, _, _, _| std::thread::sleep(Duration::from_millis(20)))
+        .await;
+    vm.on_cycle_complete(move |_, _| {
+        completed_clone.fetch_add(1, Ordering::Relaxed);
+    })
+    .await;
+
+    vm.set_signal("input", true).await?;
+    vm.execute_cycle().await?;
+
+    // The slow coil callback used up the budget, so cycle completion was skipped
+    assert_eq!(completed.load(Ordering::Relaxed), 0);
+
+    let mut exceeded = false;
+    while let Ok(event) = events.try_recv() {
+        if let VmEvent::DeadlineExceeded {
+            context, deadline, ..
+        } = event
+        {
+            assert_eq!(context.cycle, 1);
+            assert_eq!(deadline, Duration::from_millis(5));
+            exceeded = true;
+        }
+    }
+    assert!(exceeded);
+
+    Ok(())
+}
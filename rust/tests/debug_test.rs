@@ -0,0 +1,254 @@
+/// Tests for pausing, single-stepping, breakpoints, and watches
+
+use charta::ir::Guard;
+use charta::{Breakpoint, ChartaVM, Error, VmEvent};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "debug_program",
+        "signals": [
+            {"name": "kyc_passed"},
+            {"name": "flagged"}
+        ],
+        "coils": [
+            {"name": "checked"},
+            {"name": "approved"}
+        ],
+        "rungs": [
+            {
+                "name": "check",
+                "guard": {"type": "contact", "name": "kyc_passed", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "checked"}
+                ]
+            },
+            {
+                "name": "approve",
+                "guard": {
+                    "type": "and",
+                    "operands": [
+                        {"type": "contact", "name": "checked", "contact_type": "NO"},
+                        {"type": "contact", "name": "flagged", "contact_type": "NC"}
+                    ]
+                },
+                "actions": [
+                    {"type": "energise", "coil": "approved"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_paused_cycles_do_not_scan() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.pause();
+    assert!(vm.is_paused());
+
+    vm.set_signal("kyc_passed", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("checked"), Some(&false));
+    assert_eq!(vm.cycle_count(), 0);
+
+    vm.resume();
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("approved"), Some(&true));
+    assert_eq!(vm.cycle_count(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_step_walks_scan_order() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.set_signal("kyc_passed", true).await?;
+    vm.pause();
+
+    let step = vm.step_single_rung().await?;
+    assert_eq!((step.rung.as_str(), step.index), ("check", 0));
+    assert!(step.energised && !step.scan_complete);
+    assert_eq!(vm.get_coil("checked").await?, Some(true));
+    assert_eq!(vm.get_coil("approved").await?, Some(false));
+
+    vm.set_signal("flagged", true).await?;
+    let step = vm.step_single_rung().await?;
+    assert_eq!((step.rung.as_str(), step.index), ("approve", 1));
+    assert!(!step.energised && step.scan_complete);
+
+    vm.set_signal("flagged", false).await?;
+    assert_eq!(vm.step_single_rung().await?.rung, "check");
+    assert_eq!(vm.cycle_count(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_step_requires_pause() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.pause();
+    let result = vm.step_single_rung().await;
+    assert!(
+        matches!(result, Err(Error::InvalidOperation(_))),
+        "{:?}",
+        result
+    );
+
+    vm.load_program(IR_JSON).await?;
+    vm.resume();
+    let result = vm.step_single_rung().await;
+    assert!(
+        matches!(result, Err(Error::InvalidOperation(_))),
+        "{:?}",
+        result
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bypassed_rung_steps_de_energised() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.set_signal("kyc_passed", true).await?;
+    vm.disable_rung("check").await?;
+    vm.pause();
+
+    let step = vm.step_single_rung().await?;
+    assert!(!step.energised);
+    assert_eq!(vm.get_coil("checked").await?, Some(false));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coil_breakpoint_pauses_and_emits_event() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let mut events = vm.subscribe();
+    vm.set_breakpoint(Breakpoint::CoilChange("approved".into()));
+    vm.set_signal("kyc_passed", true).await?;
+
+    vm.execute_cycle().await?;
+    assert!(vm.is_paused());
+    assert_eq!(vm.cycle_count(), 1);
+    vm.execute_cycle().await?;
+    assert_eq!(vm.cycle_count(), 1);
+
+    let hit = std::iter::from_fn(|| events.try_recv().ok())
+        .find(|event| event.kind() == "breakpoint_hit")
+        .expect("breakpoint hit");
+    match hit {
+        VmEvent::BreakpointHit {
+            breakpoint,
+            signals,
+            outputs,
+            context,
+        } => {
+            assert_eq!(breakpoint, Breakpoint::CoilChange("approved".into()));
+            assert_eq!(signals.get("kyc_passed"), Some(&true));
+            assert_eq!(outputs.get("approved"), Some(&true));
+            assert_eq!(context.cycle, 1);
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+
+    vm.continue_();
+    vm.execute_cycle().await?;
+    assert!(!vm.is_paused());
+    assert_eq!(vm.cycle_count(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rung_breakpoint_hits_when_rung_fires() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.set_breakpoint(Breakpoint::Rung("check".into()));
+    vm.set_breakpoint(Breakpoint::Rung("check".into()));
+    assert_eq!(vm.breakpoints().len(), 1);
+
+    vm.execute_cycle().await?;
+    assert!(!vm.is_paused());
+    vm.set_signal("kyc_passed", true).await?;
+    vm.execute_cycle().await?;
+    assert!(vm.is_paused());
+
+    assert!(vm.clear_breakpoint(&Breakpoint::Rung("check".into())));
+    vm.continue_();
+    vm.execute_cycle().await?;
+    assert!(!vm.is_paused());
+    Ok(())
+}
+
+#[test]
+fn test_breakpoint_serialization() {
+    let breakpoint = Breakpoint::CoilChange("allow_operation".into());
+    let json = serde_json::to_value(&breakpoint).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"type": "coil_change", "name": "allow_operation"})
+    );
+    assert_eq!(
+        serde_json::from_value::<Breakpoint>(json).unwrap(),
+        breakpoint
+    );
+}
+
+fn guard(json: &str) -> Guard {
+    serde_json::from_str(json).unwrap()
+}
+
+#[tokio::test]
+async fn test_watch_emits_changes() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let mut events = vm.subscribe();
+
+    let held = guard(
+        r#"{"type": "and", "operands": [
+            {"type": "contact", "name": "checked"},
+            {"type": "contact", "name": "flagged", "contact_type": "NC"}
+        ]}"#,
+    );
+    assert!(!vm.add_watch("clear_and_checked", held));
+
+    vm.set_signal("kyc_passed", true).await?;
+    vm.execute_cycle().await?;
+    vm.execute_cycle().await?;
+    assert_eq!(vm.watches().get("clear_and_checked"), Some(&true));
+
+    vm.set_signal("flagged", true).await?;
+    vm.execute_cycle().await?;
+
+    let changes: Vec<(bool, bool)> = std::iter::from_fn(|| events.try_recv().ok())
+        .filter_map(|event| match event {
+            VmEvent::WatchChanged { name, old, new, .. } => {
+                assert_eq!(name, "clear_and_checked");
+                Some((old, new))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(changes, [(false, true), (true, false)]);
+
+    assert!(vm.remove_watch("clear_and_checked"));
+    assert!(vm.watches().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_watch_compares_typed_values() -> Result<(), Error> {
+    let ir = IR_JSON.replace(
+        r#"{"name": "flagged"}"#,
+        r#"{"name": "flagged"}, {"name": "amount", "type": "int"}"#,
+    );
+    let mut vm = ChartaVM::new();
+    vm.load_program(&ir).await?;
+    vm.set_value("amount", 20_000).await?;
+
+    let large = guard(r#"{"type": "compare", "name": "amount", "op": ">", "value": 10000}"#);
+    assert!(vm.add_watch("large", large));
+    let unknown = guard(r#"{"type": "contact", "name": "missing"}"#);
+    assert!(!vm.add_watch("unknown", unknown));
+    Ok(())
+}
@@ -0,0 +1,112 @@
+/// Tests for per-cycle decision records
+
+use charta::decision::{ChannelExporter, FileExporter};
+use charta::{ChartaVM, DecisionRecord, Error, ErrorPhase};
+use std::sync::{Arc, Mutex};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "decision_program",
+        "signals": [
+            {"name": "kyc_passed"},
+            {"name": "sanctions_hit"}
+        ],
+        "coils": [
+            {"name": "checked"},
+            {"name": "approved"}
+        ],
+        "rungs": [
+            {
+                "name": "check",
+                "guard": {"type": "contact", "name": "kyc_passed", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "checked"}
+                ]
+            },
+            {
+                "name": "approve",
+                "guard": {
+                    "type": "and",
+                    "operands": [
+                        {"type": "contact", "name": "checked", "contact_type": "NO"},
+                        {"type": "contact", "name": "sanctions_hit", "contact_type": "NC"}
+                    ]
+                },
+                "actions": [
+                    {"type": "energise", "coil": "approved"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_channel_exporter_receives_each_cycle() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let (exporter, mut records) = ChannelExporter::channel(8);
+    vm.add_decision_exporter(exporter);
+
+    vm.set_signal("kyc_passed", true).await?;
+    vm.execute_cycle().await?;
+    let record = records.recv().await.expect("record");
+    assert_eq!(record.cycle, 1);
+    assert_eq!(record.program_id, vm.program_id());
+    assert_eq!(record.inputs.get("kyc_passed"), Some(&true));
+    assert_eq!(record.outputs.get("approved"), Some(&true));
+    assert_eq!(record.changes.get("approved"), Some(&(false, true)));
+    assert_eq!(record.fired_rungs, ["check", "approve"]);
+
+    vm.set_signal("sanctions_hit", true).await?;
+    vm.execute_cycle().await?;
+    let record = records.recv().await.expect("record");
+    assert_eq!(record.cycle, 2);
+    assert_eq!(record.fired_rungs, ["check"]);
+    assert_eq!(record.changes.len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_exporter_appends_json_lines() -> Result<(), Error> {
+    let dir = std::env::temp_dir().join(format!("charta-decision-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("decisions.jsonl");
+    let _ = std::fs::remove_file(&path);
+
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.add_decision_exporter(FileExporter::new(&path));
+    vm.execute_cycle().await?;
+    vm.execute_cycle().await?;
+
+    let contents = std::fs::read_to_string(&path)?;
+    let records: Vec<DecisionRecord> = contents
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].cycle, 2);
+    assert!(records[0].fired_rungs.is_empty());
+    assert!(contents.contains("\"at_ms\""));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dropped_receiver_is_reported() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let (exporter, records) = ChannelExporter::channel(1);
+    drop(records);
+    vm.add_decision_exporter(exporter);
+
+    let faults = Arc::new(Mutex::new(Vec::new()));
+    let faults_clone = faults.clone();
+    vm.on_error(move |_, context| faults_clone.lock().unwrap().push(context.phase))
+        .await;
+
+    vm.execute_cycle().await?;
+    assert_eq!(*faults.lock().unwrap(), [ErrorPhase::Driver]);
+    Ok(())
+}
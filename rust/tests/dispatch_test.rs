@@ -0,0 +1,139 @@
+/// Integration tests for queued callback dispatch
+
+use charta::{ChartaVM, DispatchMode, Error, OverflowPolicy};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "dispatch_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+/// A VM whose cycle callback blocks until `open` is set
+async fn gated_vm(
+    overflow: OverflowPolicy,
+) -> Result<(ChartaVM, Arc<AtomicBool>, Arc<AtomicU32>), Error> {
+    let mut vm = ChartaVM::builder()
+        .dispatch(DispatchMode::Queued {
+            capacity: 1,
+            overflow,
+        })
+        .build();
+    vm.load_program(IR_JSON).await?;
+
+    let entered = Arc::new(AtomicBool::new(false));
+    let open = Arc::new(AtomicBool::new(false));
+    let completed = Arc::new(AtomicU32::new(0));
+    let (entered_clone, open_clone, completed_clone) =
+        (entered.clone(), open.clone(), completed.clone());
+    vm.on_cycle_complete(move |_, _| {
+        entered_clone.store(true, Ordering::SeqCst);
+        while !open_clone.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        completed_clone.fetch_add(1, Ordering::SeqCst);
+    })
+    .await;
+
+    // Occupy the dispatcher with the first cycle's callbacks
+    vm.execute_cycle().await?;
+    while !entered.load(Ordering::SeqCst) {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+    Ok((vm, open, completed))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_queued_callbacks_run_off_the_scan_path() -> Result<(), Error> {
+    let (mut vm, open, completed) = gated_vm(OverflowPolicy::DropOldest).await?;
+
+    // The blocked callback does not hold up the next cycle
+    vm.set_signal("input", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("output"), Some(&true));
+    assert_eq!(vm.pending_callbacks(), 2);
+    assert_eq!(completed.load(Ordering::SeqCst), 0);
+
+    open.store(true, Ordering::SeqCst);
+    vm.flush_callbacks().await;
+    assert_eq!(completed.load(Ordering::SeqCst), 2);
+    assert_eq!(vm.pending_callbacks(), 0);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_drop_oldest_discards_queued_work() -> Result<(), Error> {
+    let (mut vm, open, completed) = gated_vm(OverflowPolicy::DropOldest).await?;
+
+    vm.execute_cycle().await?;
+    vm.execute_cycle().await?;
+    assert_eq!(vm.dropped_callbacks(), 1);
+
+    open.store(true, Ordering::SeqCst);
+    vm.flush_callbacks().await;
+    assert_eq!(completed.load(Ordering::SeqCst), 2);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_error_policy_fails_the_cycle() -> Result<(), Error> {
+    let (mut vm, open, completed) = gated_vm(OverflowPolicy::Error).await?;
+
+    vm.set_signal("input", true).await?;
+    vm.execute_cycle().await?;
+    let result = vm.execute_cycle().await;
+    assert!(matches!(result, Err(Error::QueueFull(_))));
+    // The cycle itself was committed
+    assert_eq!(vm.cycle_count(), 3);
+    assert_eq!(vm.get_coil("output").await?, Some(true));
+
+    open.store(true, Ordering::SeqCst);
+    vm.flush_callbacks().await;
+    assert_eq!(completed.load(Ordering::SeqCst), 2);
+    assert_eq!(vm.dropped_callbacks(), 1);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_block_policy_waits_for_room() -> Result<(), Error> {
+    let (mut vm, open, completed) = gated_vm(OverflowPolicy::Block).await?;
+
+    vm.execute_cycle().await?;
+    let release = open.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        release.store(true, Ordering::SeqCst);
+    });
+    vm.execute_cycle().await?;
+
+    vm.flush_callbacks().await;
+    assert_eq!(completed.load(Ordering::SeqCst), 3);
+    assert_eq!(vm.dropped_callbacks(), 0);
+    Ok(())
+}
@@ -0,0 +1,88 @@
+/// Tests for dry-run cycles
+
+use charta::blocking;
+use charta::{ChartaVM, Error};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "dry_run_program",
+        "signals": [
+            {"name": "kyc_passed"},
+            {"name": "amount_over_limit"}
+        ],
+        "coils": [
+            {"name": "allow_operation"},
+            {"name": "manual_hold"}
+        ],
+        "rungs": [
+            {
+                "name": "operation_gate",
+                "guard": {
+                    "type": "and",
+                    "operands": [
+                        {"type": "contact", "name": "kyc_passed", "contact_type": "NO"},
+                        {"type": "contact", "name": "amount_over_limit", "contact_type": "NC"}
+                    ]
+                },
+                "actions": [
+                    {"type": "energise", "coil": "allow_operation"}
+                ]
+            },
+            {
+                "name": "hold",
+                "guard": {"type": "contact", "name": "manual_hold", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "manual_hold"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_dry_run_leaves_state_untouched() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.execute_cycle_with_inputs([("kyc_passed".to_string(), true)].into())
+        .await?;
+    vm.set_coil("manual_hold", true).await?;
+    let calls = Arc::new(AtomicU32::new(0));
+    let calls_clone = calls.clone();
+    vm.on_cycle_complete(move |_, _| {
+        calls_clone.fetch_add(1, Ordering::Relaxed);
+    })
+    .await;
+    let before = vm.snapshot();
+
+    let outputs = vm
+        .execute_cycle_dry_run([("amount_over_limit".to_string(), true)].into())
+        .await?;
+    assert_eq!(outputs.get("allow_operation"), Some(&false));
+    assert_eq!(outputs.get("manual_hold"), Some(&true));
+    assert_eq!(outputs.cycle(), 2);
+
+    let after = vm.snapshot();
+    assert_eq!(after.signals(), before.signals());
+    assert_eq!(after.coils(), before.coils());
+    assert_eq!(vm.cycle_count(), 1);
+    assert_eq!(calls.load(Ordering::Relaxed), 0);
+    Ok(())
+}
+
+#[test]
+fn test_blocking_dry_run() -> Result<(), Error> {
+    let mut vm = blocking::ChartaVM::new();
+    vm.load_program(IR_JSON)?;
+    vm.set_signal("kyc_passed", true)?;
+    vm.execute_cycle()?;
+
+    let outputs = vm.execute_cycle_dry_run([("amount_over_limit".to_string(), true)].into())?;
+    assert_eq!(outputs.get("allow_operation"), Some(&false));
+    assert_eq!(vm.get_coil("allow_operation")?, Some(true));
+    assert_eq!(vm.get_signal("amount_over_limit")?, Some(false));
+    Ok(())
+}
@@ -0,0 +1,222 @@
+/// Tests for the embedded VM
+
+use charta::embedded::{ChartaVM, Error};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "embedded_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+const CHAIN_JSON: &str = r#"
+{
+    "module": {
+        "name": "chain",
+        "signals": [{"name": "start"}],
+        "coils": [{"name": "first"}, {"name": "second"}],
+        "rungs": [
+            {
+                "name": "first_rung",
+                "guard": {"type": "contact", "name": "start", "contact_type": "NO"},
+                "actions": [{"type": "energise", "coil": "first"}]
+            },
+            {
+                "name": "second_rung",
+                "guard": {"type": "contact", "name": "first", "contact_type": "NO"},
+                "actions": [{"type": "energise", "coil": "second"}]
+            }
+        ]
+    }
+}"#;
+
+#[test]
+fn test_execute_cycle() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON)?;
+    assert_eq!(vm.get_coil("output"), Some(false));
+
+    vm.set_signal("input", true)?;
+    vm.execute_cycle()?;
+    assert_eq!(vm.get_coil("output"), Some(true));
+    assert_eq!(vm.cycle_count(), 1);
+
+    vm.set_signal("input", false)?;
+    vm.execute_cycle()?;
+    assert_eq!(vm.get_coil("output"), Some(false));
+    Ok(())
+}
+
+#[test]
+fn test_later_rungs_see_earlier_coils() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(CHAIN_JSON)?;
+    vm.set_signal("start", true)?;
+    vm.execute_cycle()?;
+    assert_eq!(vm.get_coil("second"), Some(true));
+    Ok(())
+}
+
+#[test]
+fn test_index_based_access() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON)?;
+    let input = vm.signal_id("input").unwrap();
+    let output = vm.coil_id("output").unwrap();
+
+    vm.set_signal_id(input, true)?;
+    vm.execute_cycle()?;
+    assert_eq!(vm.get_coil_id(output), Some(true));
+    assert_eq!(vm.coils().collect::<Vec<_>>(), vec![("output", true)]);
+    Ok(())
+}
+
+/// Feedback from a later rung, and two rungs driving one coil
+const FEEDBACK_JSON: &str = r#"
+{
+    "module": {
+        "name": "feedback",
+        "signals": [{"name": "a"}, {"name": "b"}, {"name": "c"}],
+        "coils": [{"name": "x"}, {"name": "y"}, {"name": "shared"}],
+        "rungs": [
+            {
+                "name": "x_rung",
+                "guard": {"type": "and", "operands": [
+                    {"type": "contact", "name": "a", "contact_type": "NO"},
+                    {"type": "contact", "name": "y", "contact_type": "NC"}
+                ]},
+                "actions": [{"type": "energise", "coil": "x"}, {"type": "energise", "coil": "shared"}]
+            },
+            {
+                "name": "y_rung",
+                "guard": {"type": "or", "operands": [
+                    {"type": "contact", "name": "x", "contact_type": "NO"},
+                    {"type": "contact", "name": "b", "contact_type": "NO"}
+                ]},
+                "actions": [{"type": "energise", "coil": "y"}]
+            },
+            {
+                "name": "shared_rung",
+                "guard": {"type": "contact", "name": "c", "contact_type": "NO"},
+                "actions": [{"type": "energise", "coil": "shared"}]
+            }
+        ]
+    }
+}"#;
+
+#[test]
+fn test_incremental_matches_full_scan() -> Result<(), Error> {
+    let mut full = ChartaVM::new();
+    let mut incremental = ChartaVM::new();
+    incremental.set_incremental(true);
+    full.load_program(FEEDBACK_JSON)?;
+    incremental.load_program(FEEDBACK_JSON)?;
+
+    let steps: &[&[(&str, bool)]] = &[
+        &[],
+        &[("a", true)],
+        &[],
+        &[("c", true)],
+        &[("a", false), ("b", true)],
+        &[("b", false)],
+        &[("c", false), ("a", true)],
+        &[],
+    ];
+    for (cycle, signals) in steps.iter().enumerate() {
+        for &(name, value) in signals.iter() {
+            full.set_signal(name, value)?;
+            incremental.set_signal(name, value)?;
+        }
+        full.execute_cycle()?;
+        incremental.execute_cycle()?;
+        assert_eq!(
+            incremental.coils().collect::<Vec<_>>(),
+            full.coils().collect::<Vec<_>>(),
+            "cycle {}",
+            cycle + 1
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_incremental_skips_unaffected_rungs() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(FEEDBACK_JSON)?;
+    let engine = vm.engine().unwrap().clone();
+    let c = engine.signal_id("c").unwrap();
+    let mut state = engine.state();
+
+    // The first scan evaluates everything, later ones only what changed
+    assert_eq!(engine.scan_incremental(&mut state), 3);
+    assert_eq!(engine.scan_incremental(&mut state), 0);
+
+    state.set_signal(c, true);
+    assert_eq!(engine.scan_incremental(&mut state), 1);
+    assert!(state.coil(engine.coil_id("shared").unwrap()));
+    Ok(())
+}
+
+#[test]
+fn test_compiled_matches_tree() -> Result<(), Error> {
+    let mut tree = ChartaVM::new();
+    let mut compiled = ChartaVM::new();
+    compiled.set_compile(true);
+    tree.load_program(FEEDBACK_JSON)?;
+    compiled.load_program(FEEDBACK_JSON)?;
+    assert!(compiled.engine().unwrap().is_compiled());
+    assert!(!tree.engine().unwrap().is_compiled());
+
+    for inputs in 0..8u8 {
+        for (bit, name) in ["a", "b", "c"].iter().enumerate() {
+            let value = inputs & (1 << bit) != 0;
+            tree.set_signal(name, value)?;
+            compiled.set_signal(name, value)?;
+        }
+        tree.execute_cycle()?;
+        compiled.execute_cycle()?;
+        assert_eq!(
+            compiled.coils().collect::<Vec<_>>(),
+            tree.coils().collect::<Vec<_>>(),
+            "inputs {:03b}",
+            inputs
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_errors() {
+    let mut vm = ChartaVM::new();
+    assert_eq!(vm.execute_cycle().unwrap_err(), Error::NoProgram);
+    assert!(matches!(vm.load_program("{"), Err(Error::IRLoad(_))));
+
+    vm.load_program(IR_JSON).unwrap();
+    assert_eq!(
+        vm.set_signal("missing", true),
+        Err(Error::UnknownSignal("missing".to_string()))
+    );
+}
@@ -0,0 +1,80 @@
+/// Tests for index-based signal and coil access
+
+use charta::embedded::{ChartaVM, Error};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "engine_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[test]
+fn test_ids_resolved_at_load() {
+    let mut vm = ChartaVM::new();
+    assert!(vm.signal_id("input").is_none());
+
+    vm.load_program(IR_JSON).unwrap();
+
+    let input = vm.signal_id("input").unwrap();
+    let output = vm.coil_id("output").unwrap();
+    assert_eq!(input.index(), 0);
+    assert_eq!(output.index(), 0);
+    assert!(vm.signal_id("missing").is_none());
+    assert!(vm.coil_id("input").is_none());
+}
+
+#[test]
+fn test_set_signal_by_id() {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).unwrap();
+
+    let input = vm.signal_id("input").unwrap();
+    let output = vm.coil_id("output").unwrap();
+
+    vm.set_signal_id(input, true).unwrap();
+    vm.execute_cycle().unwrap();
+    assert_eq!(vm.get_coil_id(output), Some(true));
+
+    vm.set_signal_id(input, false).unwrap();
+    vm.execute_cycle().unwrap();
+    assert_eq!(vm.get_coil_id(output), Some(false));
+}
+
+#[test]
+fn test_unknown_id_rejected() {
+    let mut source = ChartaVM::new();
+    source
+        .load_program(&IR_JSON.replace(r#"{"name": "input"}"#, r#"{"name": "input"}, {"name": "extra"}"#))
+        .unwrap();
+    let extra = source.signal_id("extra").unwrap();
+
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).unwrap();
+
+    let result = vm.set_signal_id(extra, true);
+    assert!(matches!(result, Err(Error::UnknownSignal(_))));
+}
@@ -0,0 +1,105 @@
+/// Tests for event-driven execution
+
+use charta::{ChartaVM, Error};
+use std::time::Duration;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "event_driven_program",
+        "signals": [
+            {"name": "submitted"},
+            {"name": "verified"}
+        ],
+        "coils": [
+            {"name": "received"},
+            {"name": "approved"}
+        ],
+        "rungs": [
+            {
+                "name": "receive",
+                "guard": {"type": "contact", "name": "submitted", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "received"}
+                ]
+            },
+            {
+                "name": "approve",
+                "guard": {"type": "contact", "name": "verified", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "approved"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_signal_change_triggers_cycle() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let writer = vm.writer_for(&["submitted"]);
+    let observer = vm.observer();
+    let shutdown = vm.shutdown_handle();
+
+    let driver = tokio::spawn(async move {
+        writer.set_signal("submitted", true).await?;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let received = observer.get_coil("received").await?;
+
+        // Rewriting the same value changes nothing
+        writer.set_signal("submitted", true).await?;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        shutdown.request();
+        Ok::<_, Error>(received)
+    });
+    vm.run_event_driven().await?;
+
+    assert_eq!(driver.await.unwrap()?, Some(true));
+    assert_eq!(vm.cycle_count(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coalescing_window_scans_changes_together() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .coalesce_window(Duration::from_millis(50))
+        .build();
+    vm.load_program(IR_JSON).await?;
+    let writer = vm.writer_for(&["*"]);
+    let shutdown = vm.shutdown_handle();
+
+    tokio::spawn(async move {
+        writer.set_signal("submitted", true).await.ok();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        writer.set_signal("verified", true).await.ok();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        shutdown.request();
+    });
+    vm.run_event_driven().await?;
+
+    assert_eq!(vm.cycle_count(), 1);
+    assert_eq!(vm.get_coil("received").await?, Some(true));
+    assert_eq!(vm.get_coil("approved").await?, Some(true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_changes_before_run_are_scanned() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.execute_cycle().await?;
+    vm.set_signal("verified", true).await?;
+
+    let shutdown = vm.shutdown_handle();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        shutdown.request();
+    });
+    vm.run_event_driven().await?;
+
+    assert_eq!(vm.cycle_count(), 2);
+    assert_eq!(vm.get_coil("approved").await?, Some(true));
+    Ok(())
+}
@@ -0,0 +1,106 @@
+#![cfg(any(feature = "kafka", feature = "nats"))]
+/// Tests for broker event sinks
+
+use charta::integrations::event_sink::{
+    Batching, EventPublisher, EventSink, Record, TopicMapping,
+};
+use charta::io::async_trait;
+use charta::{ChartaVM, CycleContext, Error, Result, VmEvent};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "sink_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+/// Publisher recording every batch
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<Vec<Record>>>>);
+
+#[async_trait]
+impl EventPublisher for Recorder {
+    async fn publish(&mut self, records: &[Record]) -> Result<()> {
+        self.0.lock().unwrap().push(records.to_vec());
+        Ok(())
+    }
+}
+
+#[test]
+fn test_topic_mapping() {
+    let mapping = TopicMapping::new("events")
+        .coil("allow_*", "decisions")
+        .event("deadline_exceeded", "alerts")
+        .exclude("lagged");
+    let coil = |name: &str| VmEvent::CoilChanged {
+        name: name.to_string(),
+        old: false,
+        new: true,
+        context: CycleContext::now(1, None),
+    };
+
+    assert_eq!(mapping.topic_for(&coil("allow_review")), Some("decisions"));
+    assert_eq!(mapping.topic_for(&coil("output")), Some("events"));
+    assert_eq!(mapping.topic_for(&VmEvent::Lagged { skipped: 1 }), None);
+
+    let record = mapping.record(&coil("allow_review")).unwrap().unwrap();
+    assert_eq!(record.key.as_deref(), Some("allow_review"));
+    let event: VmEvent = serde_json::from_slice(&record.payload).unwrap();
+    assert_eq!(event.kind(), "coil_changed");
+
+    let mapped_only = TopicMapping::mapped_only().coil("allow_*", "decisions");
+    assert_eq!(mapped_only.topic_for(&coil("output")), None);
+}
+
+#[tokio::test]
+async fn test_sink_publishes_batches() -> std::result::Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let recorder = Recorder::default();
+    let sink = EventSink::new(
+        recorder.clone(),
+        TopicMapping::mapped_only().event("coil_changed", "coils").event("cycle_completed", "cycles"),
+    )
+    .batching(Batching {
+        max_events: 4,
+        max_delay: Duration::from_millis(10),
+    })
+    .attach(&vm.observer());
+
+    for input in [true, false] {
+        vm.execute_cycle_with_inputs([("input".to_string(), input)].into()).await?;
+    }
+    drop(vm);
+    sink.await.unwrap();
+
+    let batches = recorder.0.lock().unwrap().clone();
+    let topics: Vec<&str> = batches.iter().flatten().map(|record| record.topic.as_str()).collect();
+    assert_eq!(topics, ["coils", "cycles", "coils", "cycles"]);
+    assert!(batches.iter().all(|batch| batch.len() <= 4));
+    Ok(())
+}
@@ -0,0 +1,167 @@
+/// Tests for executing cycles with CycleOptions
+
+use charta::blocking;
+use charta::{ChartaVM, CycleOptions, Error, VmEvent};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "execution_program",
+        "signals": [
+            {"name": "start"},
+            {"name": "stop"}
+        ],
+        "coils": [
+            {"name": "running"}
+        ],
+        "rungs": [
+            {
+                "name": "seal_in",
+                "guard": {
+                    "type": "and",
+                    "operands": [
+                        {
+                            "type": "or",
+                            "operands": [
+                                {"type": "contact", "name": "start", "contact_type": "NO"},
+                                {"type": "contact", "name": "running", "contact_type": "NO"}
+                            ]
+                        },
+                        {"type": "contact", "name": "stop", "contact_type": "NC"}
+                    ]
+                },
+                "actions": [
+                    {"type": "energise", "coil": "running"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_execute_with_inputs_and_trace() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let cycle = vm
+        .execute(CycleOptions::new().input("start", true).trace(true))
+        .await?;
+    assert_eq!(cycle.outputs.cycle(), 1);
+    assert_eq!(cycle.outputs.get("running"), Some(&true));
+    assert_eq!(cycle.changes.len(), 1);
+    assert_eq!(cycle.evaluations.len(), 1);
+    assert_eq!(cycle.evaluations[0].rung, "seal_in");
+    assert!(cycle.evaluations[0].energised);
+
+    // Untraced cycles return no evaluations
+    let cycle = vm.execute(CycleOptions::new()).await?;
+    assert!(cycle.evaluations.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_suppressed_callbacks_still_emit_events() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let calls = Arc::new(AtomicU32::new(0));
+    let calls_clone = calls.clone();
+    vm.on_coil_change("running", move |_, _, _, _| {
+        calls_clone.fetch_add(1, Ordering::Relaxed);
+    })
+    .await;
+    let mut events = vm.subscribe();
+
+    vm.execute(
+        CycleOptions::new()
+            .input("start", true)
+            .suppress_callbacks(true),
+    )
+    .await?;
+    assert_eq!(calls.load(Ordering::Relaxed), 0);
+    assert!(std::iter::from_fn(|| events.try_recv().ok())
+        .any(|event| matches!(event, VmEvent::CoilChanged { ref name, .. } if name == "running")));
+
+    vm.execute_cycle_with_inputs([("stop".to_string(), true)].into())
+        .await?;
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dry_run_commits_nothing() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.execute_cycle_with_inputs([("start".to_string(), true)].into())
+        .await?;
+    vm.set_signal("start", false).await?;
+    let mut events = vm.subscribe();
+
+    let cycle = vm
+        .execute(CycleOptions::new().input("stop", true).dry_run(true))
+        .await?;
+    assert_eq!(cycle.outputs.cycle(), 2);
+    assert_eq!(cycle.outputs.get("running"), Some(&false));
+    assert_eq!(cycle.changes.len(), 1);
+
+    assert_eq!(vm.cycle_count(), 1);
+    assert_eq!(vm.get_coil("running").await?, Some(true));
+    assert_eq!(vm.get_signal("stop").await?, Some(false));
+    assert!(events.try_recv().is_err());
+
+    // The latch still holds on a real cycle
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("running"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_unknown_group_fails() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let result = vm.execute(CycleOptions::new().group("missing")).await;
+    assert!(matches!(result, Err(Error::NotFound(_))), "{:?}", result);
+    assert_eq!(vm.cycle_count(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_cycles_runs_sequence() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let cycles = vm
+        .execute_cycles(vec![
+            [("start".to_string(), true)].into(),
+            [("start".to_string(), false)].into(),
+            [("stop".to_string(), true)].into(),
+        ])
+        .await?;
+    let running: Vec<Option<bool>> = cycles
+        .iter()
+        .map(|cycle| cycle.outputs.get("running").copied())
+        .collect();
+    assert_eq!(running, vec![Some(true), Some(true), Some(false)]);
+    let cycle_numbers: Vec<u64> = cycles.iter().map(|cycle| cycle.outputs.cycle()).collect();
+    assert_eq!(cycle_numbers, vec![1, 2, 3]);
+    assert!(cycles[1].changes.is_empty());
+    assert_eq!(vm.cycle_count(), 3);
+    Ok(())
+}
+
+#[test]
+fn test_blocking_execute_cycles() -> Result<(), Error> {
+    let mut vm = blocking::ChartaVM::new();
+    vm.load_program(IR_JSON)?;
+
+    let outputs = vm.execute_cycles(vec![
+        [("start".to_string(), true)].into(),
+        [("start".to_string(), false)].into(),
+    ])?;
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(outputs[1].get("running"), Some(&true));
+    assert_eq!(vm.cycle_count(), 2);
+    Ok(())
+}
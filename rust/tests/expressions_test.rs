@@ -0,0 +1,163 @@
+/// Tests for arithmetic expressions and range guards
+
+use charta::ir::{Guard, Program};
+use charta::value::Expr;
+use charta::{ChartaVM, Error, Value};
+use std::collections::HashMap;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "exposure_program",
+        "signals": [
+            {"name": "limit", "type": "int"},
+            {"name": "exposure", "type": "int"},
+            {"name": "amount", "type": "int"},
+            {"name": "temperature", "type": "float"}
+        ],
+        "coils": [
+            {"name": "approved"},
+            {"name": "in_band"}
+        ],
+        "rungs": [
+            {
+                "name": "within_limit",
+                "guard": {
+                    "type": "compare",
+                    "expr": {
+                        "type": "sub",
+                        "left": {"type": "signal", "name": "limit"},
+                        "right": {
+                            "type": "add",
+                            "operands": [
+                                {"type": "signal", "name": "exposure"},
+                                {"type": "signal", "name": "amount"}
+                            ]
+                        }
+                    },
+                    "op": ">=",
+                    "value": 0
+                },
+                "actions": [
+                    {"type": "energise", "coil": "approved"}
+                ]
+            },
+            {
+                "name": "temperature_band",
+                "guard": {"type": "within_range", "name": "temperature", "min": 18, "max": 22.5},
+                "actions": [
+                    {"type": "energise", "coil": "in_band"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_arithmetic_comparison() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    vm.set_value("limit", 1_000).await?;
+    vm.set_value("exposure", 700).await?;
+    vm.set_value("amount", 300).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("approved"), Some(&true));
+
+    vm.set_value("amount", 301).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("approved"), Some(&false));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_within_range_is_inclusive() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    for (temperature, expected) in [(17.9, false), (18.0, true), (22.5, true), (22.6, false)] {
+        vm.set_value("temperature", temperature).await?;
+        let outputs = vm.execute_cycle().await?;
+        assert_eq!(outputs.get("in_band"), Some(&expected), "{}", temperature);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_overflow_never_compares_true() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    vm.set_value("limit", i64::MIN).await?;
+    vm.set_value("amount", 1).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("approved"), Some(&false));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_expressions_fail_load() {
+    let string_arithmetic = IR_JSON.replace(
+        r#"{"name": "amount", "type": "int"}"#,
+        r#"{"name": "amount", "type": "string"}"#,
+    );
+    let empty_range = IR_JSON.replace(r#""max": 22.5"#, r#""max": 10"#);
+    let name_and_expr = IR_JSON.replace(
+        r#""type": "compare",
+                    "expr""#,
+        r#""type": "compare", "name": "limit",
+                    "expr""#,
+    );
+    let untyped_operand = IR_JSON.replace(
+        r#"{"type": "signal", "name": "exposure"}"#,
+        r#"{"type": "signal", "name": "undeclared"}"#,
+    );
+
+    for ir in [string_arithmetic, empty_range, name_and_expr, untyped_operand] {
+        let mut vm = ChartaVM::new();
+        let result = vm.load_program(&ir).await;
+        assert!(matches!(result, Err(Error::IRLoad(_))), "{:?}", result);
+    }
+}
+
+#[test]
+fn test_guard_condition_describes_node() {
+    let program = Program::from_json(IR_JSON).unwrap();
+    let rungs = &program.module.rungs;
+    assert_eq!(
+        rungs[0].guard.condition().unwrap(),
+        "(limit - (exposure + amount)) >= 0"
+    );
+    assert_eq!(rungs[1].guard.condition().unwrap(), "temperature in [18, 22.5]");
+    assert!(matches!(rungs[1].guard, Guard::WithinRange { .. }));
+
+    let json = serde_json::to_string(&program).unwrap();
+    assert_eq!(Program::from_json(&json).unwrap(), program);
+}
+
+#[test]
+fn test_expr_evaluation() {
+    let values = HashMap::from([("a", Value::Int(5)), ("b", Value::Float(0.5))]);
+    let lookup = |name: &str| values.get(name).cloned();
+    let signal = |name: &str| Expr::Signal {
+        name: name.to_string(),
+    };
+
+    let sum = Expr::Add {
+        operands: vec![signal("a"), signal("a")],
+    };
+    assert_eq!(sum.evaluate(&lookup), Some(Value::Int(10)));
+
+    let mixed = Expr::Sub {
+        left: Box::new(signal("a")),
+        right: Box::new(signal("b")),
+    };
+    assert_eq!(mixed.evaluate(&lookup), Some(Value::Float(4.5)));
+    assert_eq!(mixed.signals(), ["a", "b"]);
+
+    let missing = Expr::Add {
+        operands: vec![signal("a"), signal("c")],
+    };
+    assert_eq!(missing.evaluate(&lookup), None);
+}
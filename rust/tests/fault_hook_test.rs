@@ -0,0 +1,62 @@
+/// Integration tests for the VM fault hook
+
+use charta::{ChartaVM, Error, ErrorPhase};
+use std::sync::{Arc, Mutex};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "fault_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_on_error_reports_load_and_callback_faults() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+
+    let faults = Arc::new(Mutex::new(Vec::new()));
+    let faults_clone = faults.clone();
+    vm.on_error(move |_, context| {
+        faults_clone.lock().unwrap().push(*context);
+    })
+    .await;
+
+    assert!(vm.load_program("not valid ir").await.is_err());
+
+    vm.load_program(IR_JSON).await?;
+    vm.on_coil_change("output", |_, _, _, _| panic!("boom")).await;
+    vm.set_signal("input", true).await?;
+    vm.execute_cycle().await?;
+
+    let faults = faults.lock().unwrap();
+    assert_eq!(faults.len(), 2);
+    assert_eq!(faults[0].phase, ErrorPhase::Load);
+    assert_eq!(faults[0].cycle, 0);
+    assert_eq!(faults[1].phase, ErrorPhase::Callback);
+    assert_eq!(faults[1].cycle, 1);
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+/// Tests for input debounce and throttle filters
+
+use charta::{ChartaVM, Error};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "filter_program",
+        "signals": [
+            {"name": "door_closed", "filter": {"type": "debounce", "cycles": 3}},
+            {"name": "risk_flag"}
+        ],
+        "coils": [
+            {"name": "locked"},
+            {"name": "review"}
+        ],
+        "rungs": [
+            {
+                "name": "lock",
+                "guard": {"type": "contact", "name": "door_closed", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "locked"}
+                ]
+            },
+            {
+                "name": "flag_review",
+                "guard": {"type": "contact", "name": "risk_flag", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "review"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_debounce_from_ir() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    // Chatter never holds for three cycles
+    for value in [true, true, false, true, false] {
+        vm.set_signal("door_closed", value).await?;
+        let outputs = vm.execute_cycle().await?;
+        assert_eq!(outputs.get("locked"), Some(&false));
+    }
+
+    vm.set_signal("door_closed", true).await?;
+    let mut locked = Vec::new();
+    for _ in 0..3 {
+        let outputs = vm.execute_cycle().await?;
+        locked.push(outputs["locked"]);
+    }
+    assert_eq!(locked, [false, false, true]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_raw_value_stays_readable() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    vm.set_signal("door_closed", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("locked"), Some(&false));
+    assert_eq!(vm.get_signal("door_closed").await?, Some(true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_throttle_from_builder() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder().throttle("risk_flag", 3).build();
+    vm.load_program(IR_JSON).await?;
+
+    let mut review = Vec::new();
+    for value in [true, false, true, false, true, false] {
+        let inputs = [("risk_flag".to_string(), value)].into();
+        let outputs = vm.execute_cycle_with_inputs(inputs).await?;
+        review.push(outputs["review"]);
+    }
+    assert_eq!(review, [true, true, true, false, false, false]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_builder_overrides_ir_filter() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder().debounce("door_closed", 1).build();
+    vm.load_program(IR_JSON).await?;
+
+    vm.set_signal("door_closed", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("locked"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_filters_fail_load() {
+    let zero_cycles = IR_JSON.replace(r#""cycles": 3"#, r#""cycles": 0"#);
+    let typed = IR_JSON.replace(
+        r#"{"name": "door_closed", "filter""#,
+        r#"{"name": "door_closed", "type": "int", "filter""#,
+    );
+
+    for ir in [zero_cycles, typed] {
+        let mut vm = ChartaVM::new();
+        let result = vm.load_program(&ir).await;
+        assert!(matches!(result, Err(Error::IRLoad(_))), "{:?}", result);
+    }
+}
+
+#[test]
+fn test_blocking_vm_debounces_inputs() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder().build_blocking();
+    vm.load_program(IR_JSON)?;
+
+    vm.set_signal("door_closed", true)?;
+    let mut locked = Vec::new();
+    for _ in 0..3 {
+        let outputs = vm.execute_cycle()?;
+        locked.push(outputs["locked"]);
+    }
+    assert_eq!(locked, [false, false, true]);
+    Ok(())
+}
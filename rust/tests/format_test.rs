@@ -0,0 +1,87 @@
+/// Tests for canonical IR formatting
+
+use charta::format::is_formatted;
+use charta::registry::program_hash;
+use charta::{format_ir, format_ir_minified, ChartaVM};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "format_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+/// The same program with keys in another order and different whitespace
+const REORDERED_IR_JSON: &str = r#"{"module":{"rungs":[{"actions":[{"coil":"output","type":"energise"}],
+"guard":{"contact_type":"NO","name":"input","type":"contact"},"name":"test_rung"}],
+"coils":[{"name":"output"}],"signals":[{"name":"input"}],"name":"format_program"},"version":"0.1.0"}"#;
+
+#[test]
+fn test_key_order_and_whitespace_do_not_change_canonical_form() {
+    assert_eq!(format_ir(IR_JSON).unwrap(), format_ir(REORDERED_IR_JSON).unwrap());
+    assert_eq!(
+        program_hash(&format_ir_minified(IR_JSON).unwrap()),
+        program_hash(&format_ir_minified(REORDERED_IR_JSON).unwrap())
+    );
+}
+
+#[test]
+fn test_keys_are_sorted_and_arrays_keep_order() {
+    let minified = format_ir_minified(r#"{"b": [3, 1, {"z": 1, "a": 2}], "a": null}"#).unwrap();
+    assert_eq!(minified, r#"{"a":null,"b":[3,1,{"a":2,"z":1}]}"#);
+}
+
+#[test]
+fn test_pretty_form_is_stable() {
+    let formatted = format_ir(IR_JSON).unwrap();
+    assert!(formatted.ends_with("}\n"));
+    assert!(formatted.starts_with("{\n  \"module\": {\n"));
+    assert!(is_formatted(&formatted).unwrap());
+    assert!(!is_formatted(IR_JSON).unwrap());
+    assert_eq!(format_ir(&formatted).unwrap(), formatted);
+}
+
+#[test]
+fn test_unknown_fields_are_preserved() {
+    let ir = IR_JSON.replace("\"name\": \"test_rung\",", "\"name\": \"test_rung\", \"owner\": \"ops\",");
+    assert!(format_ir_minified(&ir).unwrap().contains(r#""owner":"ops""#));
+}
+
+#[test]
+fn test_invalid_json_is_rejected() {
+    assert!(format_ir("{not json").is_err());
+}
+
+#[tokio::test]
+async fn test_formatted_program_loads() {
+    let mut vm = ChartaVM::new();
+    vm.load_program(&format_ir(REORDERED_IR_JSON).unwrap()).await.unwrap();
+    let outputs = vm
+        .execute_cycle_with_inputs([("input".to_string(), true)].into())
+        .await
+        .unwrap();
+    assert_eq!(outputs.get("output"), Some(&true));
+}
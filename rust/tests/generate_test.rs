@@ -0,0 +1,63 @@
+#![cfg(feature = "proptest")]
+/// Tests for random program generation
+
+use charta::generate::{self, ProgramShape};
+use charta::ir::Program;
+use charta::ChartaVM;
+use proptest::prelude::*;
+
+fn declared(program: &Program, name: &str) -> bool {
+    program.module.signals.iter().any(|s| s.name == name)
+        || program.module.coils.iter().any(|c| c.name == name)
+}
+
+fn contacts(guard: &charta::ir::Guard, out: &mut Vec<String>) {
+    if let charta::ir::Guard::Contact { name, .. } = guard {
+        out.push(name.clone());
+    }
+    for operand in guard.operands() {
+        contacts(operand, out);
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_generated_programs_reference_declared_names(
+        program in generate::program_with(ProgramShape { max_rungs: 4, ..Default::default() })
+    ) {
+        prop_assert!(!program.module.rungs.is_empty());
+        prop_assert!(program.module.rungs.len() <= 4);
+        for rung in &program.module.rungs {
+            let mut names = Vec::new();
+            contacts(&rung.guard, &mut names);
+            for name in names.iter().map(String::as_str).chain(rung.target_coils()) {
+                prop_assert!(declared(&program, name), "undeclared {}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generated_runs_execute(run in generate::program_run(1..5)) {
+        let ir = serde_json::to_string(&run.program).unwrap();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let mut vm = ChartaVM::new();
+            vm.load_program(&ir).await.unwrap();
+            for inputs in &run.inputs {
+                assert_eq!(inputs.len(), run.program.module.signals.len());
+                vm.execute_cycle_with_inputs(inputs.clone()).await.unwrap();
+            }
+        });
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn test_arbitrary_program_round_trips() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    let bytes: Vec<u8> = (0..=255).collect();
+    let program = Program::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+    let ir = serde_json::to_string(&program).unwrap();
+    assert_eq!(Program::from_json(&ir).unwrap(), program);
+}
@@ -0,0 +1,92 @@
+/// Tests for golden-trace regression testing
+
+use charta::testing::assert_matches_golden;
+use charta::{ChartaVM, Error};
+use std::path::PathBuf;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "golden_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+fn fixture(name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("charta-golden-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+const INPUTS: &str = r#"{"input": true}
+{"input": false}
+"#;
+
+#[tokio::test]
+async fn test_matching_golden_passes() -> Result<(), Error> {
+    let inputs = fixture("match_inputs.jsonl", INPUTS);
+    let golden = fixture(
+        "match_golden.jsonl",
+        "{\"cycle\":1,\"coils\":{\"output\":true}}\n{\"cycle\":2,\"coils\":{\"output\":false}}\n",
+    );
+
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    assert_matches_golden(&mut vm, &inputs, &golden).await
+}
+
+#[tokio::test]
+async fn test_diverging_golden_reports_cycle() -> Result<(), Error> {
+    let inputs = fixture("diverge_inputs.jsonl", INPUTS);
+    let golden = fixture(
+        "diverge_golden.jsonl",
+        "{\"cycle\":1,\"coils\":{\"output\":true}}\n{\"cycle\":2,\"coils\":{\"output\":true}}\n",
+    );
+
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    match assert_matches_golden(&mut vm, &inputs, &golden).await {
+        Err(Error::ScenarioFailed(diff)) => {
+            assert!(diff.starts_with("golden trace mismatch at cycle 2 (input line 2):"));
+            assert!(diff.contains("- output"));
+        }
+        other => panic!("expected ScenarioFailed, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_short_golden_fails() -> Result<(), Error> {
+    let inputs = fixture("short_inputs.jsonl", INPUTS);
+    let golden = fixture("short_golden.jsonl", "{\"cycle\":1,\"coils\":{\"output\":true}}\n");
+
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let result = assert_matches_golden(&mut vm, &inputs, &golden).await;
+    assert!(matches!(result, Err(Error::ScenarioFailed(_))));
+    Ok(())
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "gpio")]
+/// Tests for the GPIO driver
+
+use charta::integrations::gpio::{GpioBridge, GpioInputs, GpioOutputs, PinMap};
+use charta::{ChartaVM, Error};
+use embedded_hal_mock::eh1::digital::{Mock as PinMock, State, Transaction};
+use std::collections::HashMap;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "gpio_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[test]
+fn test_pin_map_from_json() {
+    let map: PinMap = serde_json::from_str(
+        r#"{"inputs": [{"pin": 17, "signal": "input", "active_low": true}],
+            "outputs": [{"pin": 22, "coil": "output"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(map, PinMap::new().input_active_low(17, "input").output("output", 22));
+}
+
+#[test]
+fn test_sample_and_apply() {
+    let mut inputs = GpioInputs::new();
+    let mut input_pin = PinMock::new(&[Transaction::get(State::Low)]);
+    inputs.add("input", input_pin.clone(), true);
+    assert_eq!(inputs.sample(), HashMap::from([("input".to_string(), true)]));
+
+    let mut outputs = GpioOutputs::new();
+    let mut output_pin = PinMock::new(&[Transaction::set(State::High)]);
+    outputs.add("output", output_pin.clone(), false);
+    outputs
+        .apply(&HashMap::from([("output".to_string(), true), ("other".to_string(), true)]))
+        .unwrap();
+
+    input_pin.done();
+    output_pin.done();
+}
+
+#[tokio::test]
+async fn test_drives_vm() -> Result<(), Error> {
+    let map = PinMap::new().input(17, "input").output("output", 22);
+    let input_pin = PinMock::new(&[Transaction::get(State::High)]);
+    let output_pin = PinMock::new(&[Transaction::set(State::High)]);
+    let (inputs, outputs) = GpioBridge::open(
+        &map,
+        |_| Ok::<_, ()>(input_pin.clone()),
+        |_| Ok::<_, ()>(output_pin.clone()),
+    )?;
+
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.add_input_source(inputs);
+    vm.add_output_sink(outputs);
+
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("output"), Some(&true));
+
+    input_pin.clone().done();
+    output_pin.clone().done();
+    Ok(())
+}
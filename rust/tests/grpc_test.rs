@@ -0,0 +1,90 @@
+#![cfg(feature = "grpc")]
+
+/// Integration tests for the gRPC service
+
+use charta::grpc::proto::charta_server::Charta;
+use charta::grpc::proto::{ExecuteCycleRequest, GetStateRequest, LoadProgramRequest, SetSignalsRequest};
+use charta::grpc::ChartaGrpc;
+use charta::ChartaVM;
+use std::collections::HashMap;
+use tonic::{Code, Request};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "grpc_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_load_set_execute_and_get_state() {
+    let service = ChartaGrpc::new(ChartaVM::new());
+
+    let loaded = service
+        .load_program(Request::new(LoadProgramRequest {
+            ir_json: IR_JSON.to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!loaded.program_id.is_empty());
+
+    service
+        .set_signals(Request::new(SetSignalsRequest {
+            signals: HashMap::from([("input".to_string(), true)]),
+        }))
+        .await
+        .unwrap();
+
+    let cycle = service
+        .execute_cycle(Request::new(ExecuteCycleRequest::default()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(cycle.cycle, 1);
+    assert_eq!(cycle.outputs.get("output"), Some(&true));
+
+    let state = service
+        .get_state(Request::new(GetStateRequest {}))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(state.cycle_count, 1);
+    assert_eq!(state.coils.get("output"), Some(&true));
+    assert_eq!(state.program_id, Some(loaded.program_id));
+}
+
+#[tokio::test]
+async fn test_invalid_program_is_invalid_argument() {
+    let service = ChartaGrpc::new(ChartaVM::new());
+    let error = service
+        .load_program(Request::new(LoadProgramRequest {
+            ir_json: "not valid ir".to_string(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code(), Code::InvalidArgument);
+}
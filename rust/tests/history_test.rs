@@ -0,0 +1,63 @@
+/// Integration tests for event history
+
+use charta::{ChartaVM, Error};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "history_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_history_is_bounded_and_queryable() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    assert!(vm.history().is_none());
+
+    vm.enable_history(3);
+
+    // Toggle the coil every cycle: 5 changes over 5 cycles
+    for value in [true, false, true, false, true] {
+        vm.set_signal("input", value).await?;
+        vm.execute_cycle().await?;
+    }
+
+    let history = vm.history().expect("history enabled");
+    let cycles: Vec<u64> = history.coil_changes().map(|r| r.cycle).collect();
+    assert_eq!(cycles, vec![3, 4, 5]);
+
+    let since: Vec<u64> = history.coil_changes_since(4).iter().map(|r| r.cycle).collect();
+    assert_eq!(since, vec![5]);
+
+    let last = history.last_coil_changes(1);
+    assert_eq!(last.len(), 1);
+    assert!(last[0].new);
+
+    assert_eq!(history.cycles().count(), 3);
+
+    Ok(())
+}
@@ -0,0 +1,161 @@
+/// Tests for IR file imports
+
+use charta::imports::{resolve_imports, ImportResolver, Resolved};
+use charta::{ChartaVM, Error, Result};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// IR of a module driving `coil` from `signal`, importing `imports`
+fn ir(name: &str, imports: &[&str], signal: &str, coil: &str) -> String {
+    json!({
+        "version": "0.1.0",
+        "imports": imports,
+        "module": {
+            "name": name,
+            "signals": [{"name": signal}],
+            "coils": [{"name": coil}],
+            "rungs": [{
+                "name": format!("drive_{}", coil),
+                "guard": {"type": "contact", "name": signal, "contact_type": "NO"},
+                "actions": [{"type": "energise", "coil": coil}]
+            }]
+        }
+    })
+    .to_string()
+}
+
+fn temp_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("charta-imports-{}-{}", std::process::id(), test));
+    std::fs::create_dir_all(dir.join("common")).unwrap();
+    dir
+}
+
+/// Resolves imports from an in-memory map of files
+struct MapResolver(HashMap<&'static str, String>);
+
+#[charta::io::async_trait]
+impl ImportResolver for MapResolver {
+    async fn resolve(&self, _importer: &str, import: &str) -> Result<Resolved> {
+        let ir_json = self
+            .0
+            .get(import)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(import.to_string()))?;
+        Ok(Resolved {
+            id: import.to_string(),
+            ir_json,
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_load_program_from_file_merges_relative_imports() {
+    let dir = temp_dir("relative");
+    std::fs::write(
+        dir.join("common/interlocks.ir.json"),
+        ir("interlocks", &[], "guard_closed", "interlock_ok"),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("main.ir.json"),
+        ir("orders", &["common/interlocks.ir.json"], "input", "output"),
+    )
+    .unwrap();
+
+    let mut vm = ChartaVM::new();
+    vm.load_program_from_file(dir.join("main.ir.json")).await.unwrap();
+
+    let mut coils = vm.coil_names().await.unwrap();
+    coils.sort();
+    assert_eq!(coils, ["interlock_ok", "output"]);
+
+    vm.set_signal("guard_closed", true).await.unwrap();
+    let outputs = vm.execute_cycle().await.unwrap();
+    assert_eq!(outputs.get("interlock_ok"), Some(&true));
+    assert_eq!(outputs.get("output"), Some(&false));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_diamond_import_is_included_once() {
+    let files = MapResolver(HashMap::from([
+        ("base", ir("base", &[], "base_in", "base_out")),
+        ("left", ir("left", &["base"], "left_in", "left_out")),
+        ("right", ir("right", &["base"], "right_in", "right_out")),
+    ]));
+    let root = ir("root", &["left", "right"], "input", "output");
+
+    let program = resolve_imports("root", &root, &files).await.unwrap();
+    let coils: Vec<_> = program.module.coils.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(coils, ["base_out", "left_out", "right_out", "output"]);
+}
+
+#[tokio::test]
+async fn test_import_cycle_is_rejected_with_chain() {
+    let files = MapResolver(HashMap::from([
+        ("a", ir("a", &["b"], "a_in", "a_out")),
+        ("b", ir("b", &["a"], "b_in", "b_out")),
+    ]));
+    let root = ir("root", &["a"], "input", "output");
+
+    let err = resolve_imports("root", &root, &files).await.unwrap_err();
+    assert!(matches!(&err, Error::IRLoad(msg) if msg.contains("a -> b -> a")), "{}", err);
+}
+
+#[tokio::test]
+async fn test_duplicate_name_names_both_files() {
+    let files = MapResolver(HashMap::from([(
+        "shared",
+        ir("shared", &[], "input", "shared_out"),
+    )]));
+    let root = ir("root", &["shared"], "input", "output");
+
+    let err = resolve_imports("root", &root, &files).await.unwrap_err();
+    assert!(
+        matches!(&err, Error::IRLoad(msg) if msg.contains("'input'") && msg.contains("'shared'") && msg.contains("'root'")),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_missing_import_fails_load() {
+    let dir = temp_dir("missing");
+    std::fs::write(
+        dir.join("main.ir.json"),
+        ir("orders", &["absent.ir.json"], "input", "output"),
+    )
+    .unwrap();
+
+    let mut vm = ChartaVM::new();
+    let err = vm.load_program_from_file(dir.join("main.ir.json")).await.unwrap_err();
+    assert!(matches!(err, Error::NotFound(_)));
+    assert!(vm.program_id().is_none());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_load_program_with_resolver() {
+    let files = MapResolver(HashMap::from([(
+        "interlocks",
+        ir("interlocks", &[], "guard_closed", "interlock_ok"),
+    )]));
+    let root = ir("orders", &["interlocks"], "input", "output");
+
+    let mut vm = ChartaVM::new();
+    vm.load_program_with_resolver(&root, "orders", &files).await.unwrap();
+    assert_eq!(vm.coil_names().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_unresolved_imports_are_rejected_by_load_program() {
+    let mut vm = ChartaVM::new();
+    let err = vm
+        .load_program(&ir("orders", &["interlocks"], "input", "output"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::IRLoad(_)));
+}
@@ -0,0 +1,131 @@
+/// Tests for initial signal and coil states on load
+
+use charta::blocking;
+use charta::value::Value;
+use charta::{ChartaVM, Error, LoadOptions};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "initial_program",
+        "signals": [
+            {"name": "door_closed", "initial": true},
+            {"name": "stop_pressed"},
+            {"name": "setpoint", "type": "float", "initial": 72.5}
+        ],
+        "coils": [
+            {"name": "motor_running", "initial": true},
+            {"name": "alarm"}
+        ],
+        "rungs": [
+            {
+                "name": "motor_seal_in",
+                "guard": {
+                    "type": "and",
+                    "operands": [
+                        {"type": "contact", "name": "motor_running", "contact_type": "NO"},
+                        {"type": "contact", "name": "stop_pressed", "contact_type": "NC"}
+                    ]
+                },
+                "actions": [
+                    {"type": "energise", "coil": "motor_running"}
+                ]
+            },
+            {
+                "name": "door_alarm",
+                "guard": {"type": "contact", "name": "door_closed", "contact_type": "NC"},
+                "actions": [
+                    {"type": "energise", "coil": "alarm"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_ir_initial_values_applied_on_load() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    assert_eq!(vm.get_signal("door_closed").await?, Some(true));
+    assert_eq!(vm.get_signal("stop_pressed").await?, Some(false));
+    assert_eq!(vm.get_value("setpoint").await?, Some(Value::Float(72.5)));
+    assert_eq!(vm.get_coil("motor_running").await?, Some(true));
+    assert_eq!(vm.get_coil("alarm").await?, Some(false));
+
+    // The seal-in rung holds the initially latched coil
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("motor_running"), Some(&true));
+    assert_eq!(outputs.get("alarm"), Some(&false));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_options_override_ir() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    let options = LoadOptions::new()
+        .signal("door_closed", false)
+        .signal("setpoint", 68)
+        .coil("motor_running", false);
+    vm.load_program_with_options(IR_JSON, &options).await?;
+    assert_eq!(vm.get_signal("door_closed").await?, Some(false));
+    assert_eq!(vm.get_value("setpoint").await?, Some(Value::Float(68.0)));
+    assert_eq!(vm.get_coil("motor_running").await?, Some(false));
+
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("motor_running"), Some(&false));
+    assert_eq!(outputs.get("alarm"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_options_reject_undeclared_names() {
+    let mut vm = ChartaVM::new();
+    let result = vm
+        .load_program_with_options(IR_JSON, &LoadOptions::new().signal("missing", true))
+        .await;
+    assert!(matches!(result, Err(Error::NotFound(_))), "{:?}", result);
+
+    let result = vm
+        .load_program_with_options(IR_JSON, &LoadOptions::new().coil("missing", true))
+        .await;
+    assert!(matches!(result, Err(Error::NotFound(_))), "{:?}", result);
+    assert!(vm.program_id().is_none());
+}
+
+#[tokio::test]
+async fn test_options_reject_mismatched_types() {
+    let mut vm = ChartaVM::new();
+    let result = vm
+        .load_program_with_options(IR_JSON, &LoadOptions::new().signal("setpoint", "warm"))
+        .await;
+    assert!(
+        matches!(result, Err(Error::TypeMismatch(_))),
+        "{:?}",
+        result
+    );
+
+    let result = vm
+        .load_program_with_options(IR_JSON, &LoadOptions::new().signal("door_closed", 1))
+        .await;
+    assert!(
+        matches!(result, Err(Error::TypeMismatch(_))),
+        "{:?}",
+        result
+    );
+}
+
+#[test]
+fn test_blocking_initial_values() -> Result<(), Error> {
+    let mut vm = blocking::ChartaVM::new();
+    vm.load_program_with_options(IR_JSON, &LoadOptions::new().signal("stop_pressed", true))?;
+    assert_eq!(vm.get_signal("door_closed")?, Some(true));
+    assert_eq!(vm.get_signal("stop_pressed")?, Some(true));
+    assert_eq!(vm.get_value("setpoint")?, Some(Value::Float(72.5)));
+    assert_eq!(vm.get_coil("motor_running")?, Some(true));
+
+    // The stop button breaks the seal-in on the first scan
+    let outputs = vm.execute_cycle()?;
+    assert_eq!(outputs.get("motor_running"), Some(&false));
+    Ok(())
+}
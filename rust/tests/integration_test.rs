@@ -222,7 +222,7 @@ async fn test_coil_change_callbacks() -> Result<(), Error> {
     let callback_count_clone = callback_count.clone();
 
     // Register callback
-    vm.on_coil_change("output", move |name, old_val, new_val| {
+    vm.on_coil_change("output", move |name, old_val, new_val, _| {
         assert_eq!(name, "output");
         assert_eq!(old_val, false);
         assert_eq!(new_val, true);
@@ -297,7 +297,7 @@ async fn test_any_coil_change_callbacks() -> Result<(), Error> {
     let callback_count_clone = callback_count.clone();
 
     // Register callback for any coil
-    vm.on_any_coil_change(move |_name, _old_val, _new_val| {
+    vm.on_any_coil_change(move |_name, _old_val, _new_val, _| {
         callback_count_clone.fetch_add(1, Ordering::Relaxed);
     })
     .await;
@@ -354,7 +354,7 @@ async fn test_cycle_complete_callbacks() -> Result<(), Error> {
     let callback_count_clone = callback_count.clone();
 
     // Register callback
-    vm.on_cycle_complete(move |outputs| {
+    vm.on_cycle_complete(move |outputs, _| {
         assert!(outputs.contains_key("output"));
         callback_count_clone.fetch_add(1, Ordering::Relaxed);
     })
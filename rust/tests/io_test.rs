@@ -0,0 +1,160 @@
+/// Integration tests for I/O drivers
+
+use charta::io::{async_trait, CoilChanges, InputSource, OutputSink};
+use charta::{ChartaVM, Error, ErrorPhase, SignalWriter};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "io_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+/// Input source toggling `input` on every read
+struct Toggle {
+    value: bool,
+}
+
+#[async_trait]
+impl InputSource for Toggle {
+    async fn read(&mut self) -> HashMap<String, bool> {
+        self.value = !self.value;
+        HashMap::from([("input".to_string(), self.value)])
+    }
+}
+
+#[tokio::test]
+async fn test_input_source_polled_each_cycle() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.add_input_source(Toggle { value: false });
+
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("output"), Some(&true));
+
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("output"), Some(&false));
+
+    // Explicit inputs override polled ones
+    let outputs = vm
+        .execute_cycle_with_inputs(HashMap::from([("input".to_string(), false)]))
+        .await?;
+    assert_eq!(outputs.get("output"), Some(&false));
+
+    Ok(())
+}
+
+/// Output sink recording changes, failing when `output` is de-energised
+struct Recorder {
+    written: Arc<Mutex<Vec<CoilChanges>>>,
+}
+
+#[async_trait]
+impl OutputSink for Recorder {
+    async fn write(&mut self, changes: &CoilChanges) -> charta::Result<()> {
+        self.written.lock().unwrap().push(changes.clone());
+        if changes.get("output") == Some(false) {
+            return Err(Error::Driver("actuator offline".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_output_sink_receives_changes_and_reports_errors() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let written = Arc::new(Mutex::new(Vec::new()));
+    vm.add_output_sink(Recorder {
+        written: written.clone(),
+    });
+
+    let faults = Arc::new(Mutex::new(Vec::new()));
+    let faults_clone = faults.clone();
+    vm.on_error(move |error, context| {
+        faults_clone
+            .lock()
+            .unwrap()
+            .push((error.to_string(), context.phase, context.cycle));
+    })
+    .await;
+
+    vm.set_signal("input", true).await?;
+    vm.execute_cycle().await?;
+    vm.set_signal("input", false).await?;
+    vm.execute_cycle().await?;
+
+    let written = written.lock().unwrap();
+    assert_eq!(written.len(), 2);
+    assert_eq!(written[0].cycle, 1);
+    assert_eq!(written[0].get("output"), Some(true));
+    assert_eq!(written[1].get("output"), Some(false));
+
+    let faults = faults.lock().unwrap();
+    assert_eq!(faults.len(), 1);
+    assert!(faults[0].0.contains("actuator offline"));
+    assert_eq!(faults[0].1, ErrorPhase::Driver);
+    assert_eq!(faults[0].2, 2);
+
+    Ok(())
+}
+
+/// Output sink acknowledging each change by clearing `input`
+struct Acknowledge {
+    writer: SignalWriter,
+}
+
+#[async_trait]
+impl OutputSink for Acknowledge {
+    async fn write(&mut self, _changes: &CoilChanges) -> charta::Result<()> {
+        self.writer.set_signal("input", false).await
+    }
+}
+
+#[tokio::test]
+async fn test_output_sink_can_write_signals() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.add_output_sink(Acknowledge {
+        writer: vm.writer_for(&["input"]),
+    });
+
+    // Sinks run after the VM is released, so the write does not wait on
+    // the cycle that triggered it
+    let outputs = tokio::time::timeout(
+        Duration::from_secs(5),
+        vm.execute_cycle_with_inputs(HashMap::from([("input".to_string(), true)])),
+    )
+    .await
+    .expect("sink write blocked on the cycle")?;
+    assert_eq!(outputs.get("output"), Some(&true));
+    assert_eq!(vm.get_signal("input").await?, Some(false));
+    Ok(())
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "jit")]
+/// Tests for the Cranelift scan backend
+
+use charta::engine::Engine;
+use charta::ir::Program;
+
+const IR_JSON: &str = r#"
+{
+    "module": {
+        "name": "jit_program",
+        "signals": [{"name": "a"}, {"name": "b"}, {"name": "c"}],
+        "coils": [{"name": "x"}, {"name": "y"}, {"name": "z"}],
+        "rungs": [
+            {
+                "name": "x_rung",
+                "guard": {"type": "and", "operands": [
+                    {"type": "contact", "name": "a", "contact_type": "NO"},
+                    {"type": "or", "operands": [
+                        {"type": "contact", "name": "b", "contact_type": "NO"},
+                        {"type": "not", "operand": {"type": "contact", "name": "c", "contact_type": "NO"}}
+                    ]}
+                ]},
+                "actions": [{"type": "energise", "coil": "x"}]
+            },
+            {
+                "name": "y_rung",
+                "guard": {"type": "or", "operands": [
+                    {"type": "contact", "name": "x", "contact_type": "NC"},
+                    {"type": "contact", "name": "z", "contact_type": "NO"}
+                ]},
+                "actions": [{"type": "energise", "coil": "y"}]
+            },
+            {
+                "name": "z_rung",
+                "guard": {"type": "and", "operands": []},
+                "actions": [{"type": "energise", "coil": "z"}]
+            }
+        ]
+    }
+}"#;
+
+#[test]
+fn test_native_scan_matches_interpreter() {
+    let program: Program = serde_json::from_str(IR_JSON).unwrap();
+    let native = Engine::compiled(&program);
+    let interpreted = Engine::new(&program);
+    assert_eq!(native.is_native(), charta::jit::is_available());
+
+    let names = ["a", "b", "c"];
+    let mut native_state = native.state();
+    let mut interpreted_state = interpreted.state();
+    for inputs in 0..8u8 {
+        for (bit, name) in names.iter().enumerate() {
+            let value = inputs & (1 << bit) != 0;
+            native_state.set_signal(native.signal_id(name).unwrap(), value);
+            interpreted_state.set_signal(interpreted.signal_id(name).unwrap(), value);
+        }
+        native.scan(&mut native_state);
+        interpreted.scan(&mut interpreted_state);
+        assert_eq!(native_state.coils(), interpreted_state.coils(), "inputs {:03b}", inputs);
+    }
+}
+
+#[test]
+fn test_mismatched_state_falls_back() {
+    let program: Program = serde_json::from_str(IR_JSON).unwrap();
+    let native = Engine::compiled(&program);
+
+    // A state from a program with different shape is never passed to native code
+    let other: Program = serde_json::from_str(
+        &IR_JSON.replace(r#"{"name": "c"}]"#, r#"{"name": "c"}, {"name": "d"}]"#),
+    )
+    .unwrap();
+    let mut state = Engine::new(&other).state();
+    native.scan(&mut state);
+    assert_eq!(state.coils().len(), 3);
+}
+
+#[cfg(feature = "rayon")]
+#[tokio::test]
+async fn test_batch_evaluation_matches_cycles() -> Result<(), charta::Error> {
+    use std::collections::HashMap;
+
+    let mut batch = charta::ChartaVM::new();
+    batch.load_program(IR_JSON).await?;
+    let inputs: Vec<HashMap<String, bool>> = (0..8u8)
+        .map(|inputs| {
+            ["a", "b", "c"]
+                .iter()
+                .enumerate()
+                .map(|(bit, name)| (name.to_string(), inputs & (1 << bit) != 0))
+                .collect()
+        })
+        .collect();
+    let results = batch.evaluate_batch_parallel(inputs.clone()).await?;
+
+    for (inputs, result) in inputs.into_iter().zip(&results) {
+        let mut vm = charta::ChartaVM::new();
+        vm.load_program(IR_JSON).await?;
+        let outputs = vm.execute_cycle_with_inputs(inputs.clone()).await?;
+        assert_eq!(result, outputs.coils(), "inputs {:?}", inputs);
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+/// Integration tests for load-time resource limits
+
+use charta::{ChartaVM, Error};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "limits_program",
+        "signals": [
+            {"name": "a"},
+            {"name": "b"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "nested_rung",
+                "guard": {
+                    "type": "and",
+                    "left": {"type": "contact", "name": "a", "contact_type": "NO"},
+                    "right": {
+                        "type": "not",
+                        "operand": {"type": "contact", "name": "b", "contact_type": "NO"}
+                    }
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_program_within_limits_loads() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .max_rungs(1)
+        .max_signals(2)
+        .max_guard_depth(3)
+        .max_actions_per_rung(1)
+        .build();
+    vm.load_program(IR_JSON).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_program_exceeding_limits_is_rejected() {
+    let mut vm = ChartaVM::builder().max_signals(1).build();
+    let result = vm.load_program(IR_JSON).await;
+    assert!(matches!(result, Err(Error::LimitExceeded(_))));
+
+    let mut vm = ChartaVM::builder().max_guard_depth(2).build();
+    let result = vm.load_program(IR_JSON).await;
+    assert!(matches!(result, Err(Error::LimitExceeded(msg)) if msg.contains("nested_rung")));
+    assert!(vm.program_id().is_none());
+}
@@ -0,0 +1,127 @@
+/// Tests for linking multi-module programs
+
+use charta::link::link_json;
+use charta::{ChartaVM, Error};
+
+const SAFETY_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "safety",
+        "signals": [
+            {"name": "guard_closed"}
+        ],
+        "coils": [
+            {"name": "interlock_ok"}
+        ],
+        "exports": ["interlock_ok"],
+        "rungs": [
+            {
+                "name": "interlock",
+                "guard": {
+                    "type": "contact",
+                    "name": "guard_closed",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "interlock_ok"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+const ORDERS_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "orders",
+        "signals": [
+            {"name": "submitted"}
+        ],
+        "coils": [
+            {"name": "accept"}
+        ],
+        "imports": ["safety.interlock_ok"],
+        "rungs": [
+            {
+                "name": "accept_order",
+                "guard": {
+                    "type": "and",
+                    "operands": [
+                        {"type": "contact", "name": "submitted", "contact_type": "NO"},
+                        {"type": "contact", "name": "safety.interlock_ok", "contact_type": "NO"}
+                    ]
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "accept"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+fn orders_with(replace: &str, with: &str) -> String {
+    ORDERS_IR.replace(replace, with)
+}
+
+#[tokio::test]
+async fn test_linked_modules_share_exports() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_modules(&[SAFETY_IR, ORDERS_IR]).await?;
+
+    let mut coils = vm.coil_names().await?;
+    coils.sort();
+    assert_eq!(coils, vec!["orders.accept".to_string(), "safety.interlock_ok".to_string()]);
+    assert_eq!(vm.namespaces().await?, vec!["orders".to_string(), "safety".to_string()]);
+
+    let inputs = [
+        ("safety.guard_closed".to_string(), true),
+        ("orders.submitted".to_string(), true),
+    ];
+    vm.execute_cycle_with_inputs(inputs.into()).await?;
+    assert_eq!(vm.get_coil("safety.interlock_ok").await?, Some(true));
+    assert_eq!(vm.get_coil("orders.accept").await?, Some(true));
+    Ok(())
+}
+
+#[test]
+fn test_link_namespaces_rungs() {
+    let program = link_json(&[SAFETY_IR, ORDERS_IR]).unwrap();
+    let rungs: Vec<&str> = program.module.rungs.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(rungs, vec!["safety.interlock", "orders.accept_order"]);
+    assert_eq!(program.module.name, "safety+orders");
+}
+
+#[test]
+fn test_import_must_be_exported() {
+    let unexported = SAFETY_IR.replace(r#""exports": ["interlock_ok"],"#, "");
+    let result = link_json(&[&unexported, ORDERS_IR]);
+    assert!(matches!(result, Err(Error::Link(message)) if message.contains("does not export")));
+
+    let result = link_json(&[ORDERS_IR]);
+    assert!(matches!(result, Err(Error::Link(message)) if message.contains("unknown module")));
+}
+
+#[test]
+fn test_undeclared_reads_and_foreign_writes_fail() {
+    let undeclared = orders_with(r#""imports": ["safety.interlock_ok"],"#, "");
+    let result = link_json(&[SAFETY_IR, &undeclared]);
+    assert!(matches!(result, Err(Error::Link(message)) if message.contains("neither declared nor imported")));
+
+    let foreign = orders_with(r#""coil": "accept""#, r#""coil": "safety.interlock_ok""#);
+    let result = link_json(&[SAFETY_IR, &foreign]);
+    assert!(matches!(result, Err(Error::Link(message)) if message.contains("does not declare as a coil")));
+}
+
+#[test]
+fn test_duplicate_modules_fail() {
+    let result = link_json(&[SAFETY_IR, SAFETY_IR]);
+    assert!(matches!(result, Err(Error::Link(message)) if message.contains("duplicate module")));
+}
@@ -0,0 +1,75 @@
+/// Tests for the report returned by program loads
+
+use charta::{ChartaVM, Error, UnknownNodePolicy};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "report_program",
+        "signals": [
+            {"name": "manual"},
+            {"name": "auto"}
+        ],
+        "coils": [
+            {"name": "pump"},
+            {"name": "future_output"}
+        ],
+        "rungs": [
+            {
+                "name": "manual_run",
+                "guard": {"type": "contact", "name": "manual", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "pump"}
+                ]
+            },
+            {
+                "name": "auto_run",
+                "guard": {"type": "contact", "name": "auto", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "pump"}
+                ]
+            },
+            {
+                "name": "future_rung",
+                "guard": {"type": "timer", "preset_ms": 500},
+                "actions": [
+                    {"type": "energise", "coil": "future_output"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_load_program_returns_report() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .unknown_nodes(UnknownNodePolicy::Permissive)
+        .build();
+    let report = vm.load_program(IR_JSON).await?;
+
+    assert_eq!(Some(report.program_id.clone()), vm.program_id());
+    assert_eq!(report.signals, 2);
+    assert_eq!(report.coils, 2);
+    assert_eq!(report.rungs, 2);
+    assert_eq!(report.disabled_rungs, vec!["future_rung".to_string()]);
+    assert_eq!(report.ignored_nodes.len(), 1);
+    assert_eq!(report.warnings.len(), 2);
+    assert!(report.warnings[0].contains("future_rung"));
+    assert!(report.warnings[0].contains("timer"));
+    assert!(report.warnings[1].contains("pump"));
+    assert_eq!(vm.load_report(), Some(report));
+    Ok(())
+}
+
+#[test]
+fn test_blocking_load_report() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .unknown_nodes(UnknownNodePolicy::Permissive)
+        .build_blocking();
+    let report = vm.load_program(IR_JSON)?;
+    assert_eq!(Some(report.program_id.clone()), vm.program_id());
+    assert_eq!(report.rungs, 2);
+    assert_eq!(vm.load_report(), Some(report));
+    Ok(())
+}
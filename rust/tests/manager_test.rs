@@ -0,0 +1,100 @@
+/// Integration tests for multi-VM management
+
+use charta::{Error, EvictionPolicy, VmEvent, VmManager};
+use std::time::Duration;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "tenant_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_get_or_load_reuses_and_evicts() -> Result<(), Error> {
+    let manager = VmManager::with_eviction(EvictionPolicy::MaxInstances(2));
+
+    let a = manager.get_or_load("tenant_a", IR_JSON).await?;
+    a.lock().await.set_signal("input", true).await?;
+
+    // Same tenant returns the same instance with its state intact
+    let a_again = manager.get_or_load("tenant_a", IR_JSON).await?;
+    assert_eq!(a_again.lock().await.get_signal("input").await?, Some(true));
+
+    manager.get_or_load("tenant_b", IR_JSON).await?;
+    manager.get_or_load("tenant_a", IR_JSON).await?;
+    manager.get_or_load("tenant_c", IR_JSON).await?;
+
+    // tenant_b was least recently used
+    let mut tenants = manager.tenants().await;
+    tenants.sort();
+    assert_eq!(tenants, vec!["tenant_a".to_string(), "tenant_c".to_string()]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_aggregate_event_stream() -> Result<(), Error> {
+    let manager = VmManager::new();
+    let mut events = manager.subscribe();
+
+    let vm = manager.get_or_load("tenant_a", IR_JSON).await?;
+    {
+        let mut vm = vm.lock().await;
+        vm.set_signal("input", true).await?;
+        vm.execute_cycle().await?;
+    }
+
+    let event = events.recv().await.expect("event stream closed");
+    assert_eq!(event.tenant, "tenant_a");
+    match event.event {
+        VmEvent::CoilChanged {
+            name,
+            old,
+            new,
+            context,
+        } => {
+            assert_eq!((name.as_str(), old, new), ("output", false, true));
+            assert_eq!(context.cycle, 1);
+        }
+        other => panic!("unexpected event: {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_idle_vms_evicted_on_access() -> Result<(), Error> {
+    let manager = VmManager::with_eviction(EvictionPolicy::IdleTimeout(Duration::from_millis(20)));
+    manager.get_or_load("tenant_a", IR_JSON).await?;
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    manager.get_or_load("tenant_b", IR_JSON).await?;
+
+    assert!(manager.get("tenant_a").await.is_none());
+    assert_eq!(manager.tenants().await, vec!["tenant_b".to_string()]);
+    Ok(())
+}
@@ -0,0 +1,50 @@
+/// Integration tests for signal and coil metadata
+
+use charta::{ChartaVM, Error};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "metadata_program",
+        "signals": [
+            {
+                "name": "pressure_ok",
+                "description": "Line pressure within limits",
+                "tags": ["safety", "process"],
+                "unit": "bar"
+            },
+            {"name": "operator_request"}
+        ],
+        "coils": [
+            {
+                "name": "allow_start",
+                "description": "Permission to start the pump",
+                "tags": ["safety"]
+            }
+        ],
+        "rungs": []
+    }
+}"#;
+
+#[tokio::test]
+async fn test_metadata_is_exposed() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let meta = vm.signal_meta("pressure_ok").expect("signal declared");
+    assert_eq!(meta.description.as_deref(), Some("Line pressure within limits"));
+    assert_eq!(meta.unit.as_deref(), Some("bar"));
+
+    let plain = vm.signal_meta("operator_request").expect("signal declared");
+    assert!(plain.tags.is_empty());
+
+    assert!(vm.coil_meta("allow_start").unwrap().has_tag("safety"));
+    assert!(vm.coil_meta("missing").is_none());
+
+    let mut safety = vm.find_by_tag("safety");
+    safety.sort();
+    assert_eq!(safety, vec!["allow_start", "pressure_ok"]);
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "prometheus")]
+
+/// Integration tests for Prometheus metrics
+
+use charta::{ChartaVM, Error, VmMetrics};
+use std::sync::Arc;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "metrics_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_cycle_metrics_are_exported() -> Result<(), Error> {
+    let metrics = Arc::new(VmMetrics::new()?);
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.set_metrics(metrics.clone());
+
+    vm.set_signal("input", true).await?;
+    vm.execute_cycle().await?;
+    vm.execute_cycle().await?;
+
+    let text = metrics.encode_text()?;
+    assert!(text.contains("charta_cycles_total 2"));
+    assert!(text.contains(r#"charta_coil_transitions_total{coil="output",state="energised"} 1"#));
+    assert!(text.contains("charta_cycle_duration_seconds_count 2"));
+
+    Ok(())
+}
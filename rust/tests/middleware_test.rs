@@ -0,0 +1,105 @@
+#![cfg(feature = "tower")]
+
+/// Integration tests for the request gating middleware
+
+use charta::middleware::DecisionLayer;
+use charta::{ChartaVM, Error};
+use http::{Request, Response, StatusCode};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tower::{service_fn, Layer, ServiceExt};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "middleware_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_requests_gated_on_coil() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let vm = Arc::new(Mutex::new(vm));
+
+    let layer = DecisionLayer::new(vm, "output", |parts: &http::request::Parts| {
+        HashMap::from([("input".to_string(), parts.headers.contains_key("authorization"))])
+    });
+    let service = layer.layer(service_fn(|_: Request<String>| async {
+        Ok::<_, Infallible>(Response::new("ok".to_string()))
+    }));
+
+    let request = Request::get("/").body(String::new()).unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = Request::get("/")
+        .header("authorization", "token")
+        .body(String::new())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.into_body(), "ok");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_requests_evaluated_in_isolation() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let vm = Arc::new(Mutex::new(vm));
+
+    // Only requests carrying a token set the input
+    let layer = DecisionLayer::new(vm.clone(), "output", |parts: &http::request::Parts| {
+        let mut signals = HashMap::new();
+        if parts.headers.contains_key("authorization") {
+            signals.insert("input".to_string(), true);
+        }
+        signals
+    });
+    let service = layer.layer(service_fn(|_: Request<String>| async {
+        Ok::<_, Infallible>(Response::new("ok".to_string()))
+    }));
+
+    let request = Request::get("/")
+        .header("authorization", "token")
+        .body(String::new())
+        .unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::get("/").body(String::new()).unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let vm = vm.lock().await;
+    assert_eq!(vm.get_signal("input").await?, Some(false));
+    assert_eq!(vm.cycle_count(), 0);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "uniffi")]
+/// Tests for the uniffi mobile facade
+
+use charta::mobile::{CoilListener, MobileError, MobileVM};
+use std::sync::{Arc, Mutex};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "mobile_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+struct Recorder(Arc<Mutex<Vec<(String, bool)>>>);
+
+impl CoilListener for Recorder {
+    fn on_coil_change(&self, name: String, _old: bool, new: bool) {
+        self.0.lock().unwrap().push((name, new));
+    }
+}
+
+#[test]
+fn test_mobile_vm_round_trip() -> Result<(), MobileError> {
+    let vm = MobileVM::new();
+    vm.load_program(IR_JSON.to_string())?;
+
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    vm.on_coil_change("output".to_string(), Box::new(Recorder(changes.clone())));
+
+    vm.set_signal("input".to_string(), true)?;
+    let coils = vm.execute_cycle()?;
+    assert_eq!(coils.get("output"), Some(&true));
+    assert_eq!(vm.get_coil("output".to_string())?, Some(true));
+    assert_eq!(vm.cycle_count(), 1);
+    assert_eq!(*changes.lock().unwrap(), vec![("output".to_string(), true)]);
+    Ok(())
+}
+
+#[test]
+fn test_load_error() {
+    let vm = MobileVM::new();
+    let result = vm.load_program("not json".to_string());
+    assert!(matches!(result, Err(MobileError::Load { .. })));
+}
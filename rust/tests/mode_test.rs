@@ -0,0 +1,80 @@
+/// Tests for simulation and production modes
+
+use charta::{ChartaVM, Error, VmMode};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "mode_program",
+        "signals": [
+            {"name": "kyc_passed"}
+        ],
+        "coils": [
+            {"name": "allow_operation"},
+            {"name": "trip", "safety": true}
+        ],
+        "rungs": [
+            {
+                "name": "operation_gate",
+                "guard": {"type": "contact", "name": "kyc_passed", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "allow_operation"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+fn assert_rejected<T: std::fmt::Debug>(result: Result<T, Error>) {
+    assert!(
+        matches!(&result, Err(Error::InvalidOperation(message)) if message.contains("production")),
+        "{:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn test_production_rejects_test_mutators() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder().mode(VmMode::Production).build();
+    vm.load_program(IR_JSON).await?;
+    assert_eq!(vm.mode(), VmMode::Production);
+
+    assert_rejected(vm.set_coil("allow_operation", true).await);
+    assert_rejected(vm.request_safety_override("trip", true));
+    assert_rejected(vm.set_safety_coil("trip", true, "00000000").await);
+    vm.enable_recording(4);
+    vm.execute_cycle().await?;
+    assert_rejected(vm.rewind(1).await);
+    assert_rejected(vm.replay_forward().await);
+    vm.pause();
+    assert_rejected(vm.step_single_rung().await);
+    vm.resume();
+
+    // Normal operation is unaffected
+    vm.set_signal("kyc_passed", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("allow_operation"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_simulation_is_the_default() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    assert_eq!(vm.mode(), VmMode::Simulation);
+    vm.set_coil("allow_operation", true).await?;
+    assert_eq!(vm.get_coil("allow_operation").await?, Some(true));
+    Ok(())
+}
+
+#[test]
+fn test_blocking_vm_honours_production_mode() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .mode(VmMode::Production)
+        .build_blocking();
+    vm.load_program(IR_JSON)?;
+    assert_rejected(vm.set_coil("allow_operation", true));
+    assert_rejected(vm.request_safety_override("trip", true));
+    Ok(())
+}
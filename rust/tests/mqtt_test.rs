@@ -0,0 +1,17 @@
+#![cfg(feature = "mqtt")]
+
+/// Tests for the MQTT bridge
+
+use charta::integrations::mqtt::parse_payload;
+
+#[test]
+fn test_parse_payload() {
+    assert_eq!(parse_payload(b"true"), Some(true));
+    assert_eq!(parse_payload(b" ON\n"), Some(true));
+    assert_eq!(parse_payload(b"1"), Some(true));
+    assert_eq!(parse_payload(b"False"), Some(false));
+    assert_eq!(parse_payload(b"off"), Some(false));
+    assert_eq!(parse_payload(b"0"), Some(false));
+    assert_eq!(parse_payload(b"maybe"), None);
+    assert_eq!(parse_payload(&[0xff, 0xfe]), None);
+}
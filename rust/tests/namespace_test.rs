@@ -0,0 +1,70 @@
+/// Integration tests for namespaced signal and coil names
+
+use charta::namespace;
+use charta::{ChartaVM, Error};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "namespaced",
+        "signals": [
+            {"name": "governance.compliance_ok"},
+            {"name": "governance.review.done"},
+            {"name": "motor.start"}
+        ],
+        "coils": [
+            {"name": "motor.run"}
+        ],
+        "rungs": []
+    }
+}"#;
+
+#[test]
+fn test_name_helpers() {
+    assert_eq!(namespace::namespace_of("motor.start"), Some("motor"));
+    assert_eq!(namespace::local_name("plant.line1.motor"), "motor");
+    assert!(namespace::in_namespace("plant.line1.motor", "plant"));
+    assert!(!namespace::in_namespace("plantation.x", "plant"));
+    assert!(namespace::validate_name("motor..start").is_err());
+}
+
+#[tokio::test]
+async fn test_namespace_queries() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    vm.set_signal("governance.compliance_ok", true).await?;
+
+    let governance = vm.signals_in("governance").await?;
+    assert_eq!(governance.len(), 2);
+    assert_eq!(governance.get("governance.compliance_ok"), Some(&true));
+
+    assert_eq!(vm.coils_in("motor").await?.len(), 1);
+    assert_eq!(
+        vm.namespaces().await?,
+        vec!["governance", "governance.review", "motor"]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_name_used_as_namespace_is_rejected() {
+    let ir_json = r#"
+    {
+        "version": "0.1.0",
+        "module": {
+            "name": "conflict",
+            "signals": [
+                {"name": "motor"},
+                {"name": "motor.start"}
+            ],
+            "coils": [],
+            "rungs": []
+        }
+    }"#;
+
+    let mut vm = ChartaVM::new();
+    assert!(vm.load_program(ir_json).await.is_err());
+}
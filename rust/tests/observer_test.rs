@@ -0,0 +1,61 @@
+/// Integration tests for read-only observers
+
+use charta::{ChartaVM, Error, VmEvent};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "observed_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_observer_sees_vm_state() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.enable_history(10);
+
+    let observer = vm.observer();
+    let mut events = observer.subscribe_coils("output");
+
+    vm.set_signal("input", true).await?;
+    vm.execute_cycle().await?;
+
+    // A clone made before the cycle observes the same state
+    let observer_clone = observer.clone();
+    assert_eq!(observer_clone.get_coil("output").await?, Some(true));
+    assert_eq!(observer.get_signal("input").await?, Some(true));
+    assert_eq!(observer.cycle_count(), 1);
+    assert_eq!(observer.program_id(), vm.program_id());
+    assert_eq!(observer.coil_stats("output").map(|s| s.energisations), Some(1));
+    assert_eq!(observer.history().map(|h| h.coil_changes().count()), Some(1));
+    assert!(matches!(
+        events.try_recv(),
+        Ok(VmEvent::CoilChanged { new: true, .. })
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+/// Tests for rung scan order and priorities
+
+use charta::ir::Program;
+use charta::order::{self, OrderIssue};
+use charta::{ChartaVM, Error};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "order_program",
+        "signals": [
+            {"name": "kyc_passed"},
+            {"name": "flagged"}
+        ],
+        "coils": [
+            {"name": "approved"},
+            {"name": "checked"},
+            {"name": "alarm"}
+        ],
+        "rungs": [
+            {
+                "name": "approve",
+                "guard": {"type": "contact", "name": "checked", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "approved"}
+                ]
+            },
+            {
+                "name": "check",
+                "guard": {"type": "contact", "name": "kyc_passed", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "checked"},
+                    {"type": "energise", "coil": "alarm"}
+                ]
+            },
+            {
+                "name": "flag",
+                "guard": {"type": "contact", "name": "flagged", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "alarm"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[test]
+fn test_analyze_reports_dependencies() {
+    let program = Program::from_json(IR_JSON).unwrap();
+    assert_eq!(
+        order::analyze(&program),
+        [
+            OrderIssue::ReadBeforeWrite {
+                reader: "approve".to_string(),
+                writer: "check".to_string(),
+                coil: "checked".to_string(),
+            },
+            OrderIssue::SharedCoil {
+                coil: "alarm".to_string(),
+                rungs: vec!["check".to_string(), "flag".to_string()],
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_document_order_reads_previous_scan() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    assert_eq!(vm.rung_order(), ["approve", "check", "flag"]);
+    assert_eq!(vm.load_report().unwrap().order_issues.len(), 2);
+
+    vm.set_signal("kyc_passed", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("approved"), Some(&false));
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("approved"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_priority_scans_first() -> Result<(), Error> {
+    let ir = IR_JSON.replace(r#""name": "check","#, r#""name": "check", "priority": 5,"#);
+    let mut vm = ChartaVM::new();
+    vm.load_program(&ir).await?;
+    assert_eq!(vm.rung_order(), ["check", "approve", "flag"]);
+
+    vm.set_signal("kyc_passed", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("approved"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_rung_order_keeps_state() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.set_signal("kyc_passed", true).await?;
+    vm.execute_cycle().await?;
+    vm.disable_rung("flag").await?;
+
+    let order = ["check", "approve", "flag"].map(String::from).to_vec();
+    let issues = vm.set_rung_order(order).await?;
+    assert_eq!(vm.rung_order(), ["check", "approve", "flag"]);
+    assert!(!issues
+        .iter()
+        .any(|issue| matches!(issue, OrderIssue::ReadBeforeWrite { .. })));
+    assert_eq!(vm.get_coil("checked").await?, Some(true));
+    assert_eq!(vm.disabled_rungs().len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_rung_order_is_rejected() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let missing = ["check", "approve"].map(String::from).to_vec();
+    let repeated = ["check", "check", "approve", "flag"]
+        .map(String::from)
+        .to_vec();
+    for order in [missing, repeated] {
+        let result = vm.set_rung_order(order).await;
+        assert!(matches!(result, Err(Error::InvalidOperation(_))), "{:?}", result);
+    }
+    assert_eq!(vm.rung_order(), ["approve", "check", "flag"]);
+    Ok(())
+}
@@ -0,0 +1,138 @@
+/// Tests for the per-cycle outputs view
+
+use charta::{ChartaVM, CoilChange, Error, VmEvent};
+use std::sync::Arc;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "outputs_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_outputs_report_changes() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    vm.set_signal("input", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.cycle(), 1);
+    assert_eq!(outputs.get("output"), Some(&true));
+    assert_eq!(outputs.previous("output"), Some(false));
+    assert!(outputs.changed("output"));
+    assert_eq!(outputs.changes().collect::<Vec<_>>(), vec![("output", false, true)]);
+
+    // Unchanged coils report no changes
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.cycle(), 2);
+    assert!(!outputs.changed("output"));
+    assert_eq!(outputs.changes().count(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_coil_seen_as_previous_state() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    vm.set_coil("output", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.previous("output"), Some(true));
+    assert_eq!(outputs.changes().collect::<Vec<_>>(), vec![("output", true, false)]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reload_takes_coils_from_new_program() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.set_signal("input", true).await?;
+    vm.execute_cycle().await?;
+
+    vm.load_program(IR_JSON).await?;
+    let loaded = vm.get_coil("output").await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.previous("output"), loaded);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_outputs_shared_with_events() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let mut events = vm.subscribe();
+
+    vm.set_signal("input", true).await?;
+    let outputs = vm.execute_cycle().await?;
+
+    let mut shared = None;
+    while let Ok(event) = events.try_recv() {
+        if let VmEvent::CycleCompleted { outputs, .. } = event {
+            shared = Some(outputs);
+        }
+    }
+    let shared = shared.expect("cycle completed event");
+    assert!(Arc::ptr_eq(&outputs, &shared));
+
+    // Held outputs stay valid after later cycles
+    vm.set_signal("input", false).await?;
+    vm.execute_cycle().await?;
+    assert_eq!(outputs.get("output"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_cycle_detailed_lists_changes() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let cycle = vm
+        .execute_cycle_detailed_with_inputs([("input".to_string(), true)].into())
+        .await?;
+    assert_eq!(cycle.outputs.cycle(), 1);
+    assert_eq!(cycle.outputs.get("output"), Some(&true));
+    assert_eq!(
+        cycle.changes,
+        vec![CoilChange {
+            name: "output".to_string(),
+            old: false,
+            new: true,
+        }]
+    );
+    assert_eq!(
+        serde_json::to_value(&cycle.changes)?,
+        serde_json::json!([{"name": "output", "old": false, "new": true}])
+    );
+
+    let cycle = vm.execute_cycle_detailed().await?;
+    assert_eq!(cycle.outputs.cycle(), 2);
+    assert!(cycle.changes.is_empty());
+    Ok(())
+}
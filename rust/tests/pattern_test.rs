@@ -0,0 +1,76 @@
+/// Integration tests for pattern subscriptions
+
+use charta::{ChartaVM, Error, Pattern, VmEvent};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "pattern_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "allow_read"},
+            {"name": "allow_write"},
+            {"name": "audit"}
+        ],
+        "rungs": [
+            {
+                "name": "read_rung",
+                "guard": {"type": "contact", "name": "input", "contact_type": "NO"},
+                "actions": [{"type": "energise", "coil": "allow_read"}]
+            },
+            {
+                "name": "write_rung",
+                "guard": {"type": "contact", "name": "input", "contact_type": "NO"},
+                "actions": [{"type": "energise", "coil": "allow_write"}]
+            },
+            {
+                "name": "audit_rung",
+                "guard": {"type": "contact", "name": "input", "contact_type": "NO"},
+                "actions": [{"type": "energise", "coil": "audit"}]
+            }
+        ]
+    }
+}"#;
+
+#[test]
+fn test_pattern_matching() {
+    assert!(Pattern::new("allow_*").matches("allow_read"));
+    assert!(!Pattern::new("allow_*").matches("allow/read"));
+    assert!(Pattern::new("safety/**").matches("safety/zone1/estop"));
+    assert!(!Pattern::new("safety/**").matches("governance/ok"));
+    assert!(Pattern::new("zone?").matches("zone1"));
+    assert!(Pattern::new("*").matches("any/namespaced/name"));
+}
+
+#[tokio::test]
+async fn test_pattern_callbacks_and_streams() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let count = Arc::new(AtomicU32::new(0));
+    let count_clone = count.clone();
+    vm.on_coil_change("allow_*", move |name, _old, _new, _| {
+        assert!(name.starts_with("allow_"));
+        count_clone.fetch_add(1, Ordering::Relaxed);
+    })
+    .await;
+
+    let mut events = vm.subscribe_coils("allow_w*");
+
+    vm.set_signal("input", true).await?;
+    vm.execute_cycle().await?;
+
+    assert_eq!(count.load(Ordering::Relaxed), 2);
+    match events.try_recv() {
+        Ok(VmEvent::CoilChanged { name, .. }) => assert_eq!(name, "allow_write"),
+        other => panic!("unexpected event: {:?}", other),
+    }
+    assert!(events.try_recv().is_err());
+
+    Ok(())
+}
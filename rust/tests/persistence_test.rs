@@ -0,0 +1,158 @@
+/// Tests for persisted state checkpoints
+
+use charta::persistence::FileStore;
+use charta::{ChartaVM, Checkpoint, Error, StateStore};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "persistence_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output", "retained": true}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+
+fn state_path(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("charta-persistence-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.json", test));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[tokio::test]
+async fn test_file_store_round_trip() -> Result<(), Error> {
+    let store = FileStore::new(state_path("round_trip"));
+    assert_eq!(store.load().await?, None);
+
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.execute_cycle_with_inputs([("input".to_string(), true)].into()).await?;
+
+    let checkpoint = vm.checkpoint();
+    assert_eq!(checkpoint.cycle, 1);
+    assert_eq!(checkpoint.coils.get("output"), Some(&true));
+    assert_eq!(checkpoint.signals.get("input"), Some(&true));
+    assert_eq!(checkpoint.program_id, vm.program_id());
+
+    store.save(&checkpoint).await?;
+    let loaded = store.load().await?.expect("checkpoint saved");
+    assert_eq!(loaded.coils, checkpoint.coils);
+    assert_eq!(loaded.cycle, 1);
+
+    // The save replaced the file without leaving the temporary behind
+    let temp = store.path().with_file_name("round_trip.json.tmp");
+    assert!(!temp.exists());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_first_load_restores_checkpoint() -> Result<(), Error> {
+    let path = state_path("restart");
+
+    {
+        let mut vm = ChartaVM::builder()
+            .persist_to(FileStore::new(&path), Duration::from_secs(3600))
+            .build();
+        vm.load_program(IR_JSON).await?;
+        vm.execute_cycle_with_inputs([("input".to_string(), true)].into()).await?;
+        vm.save_checkpoint().await?;
+    }
+
+    // A new VM on the same store starts where the previous one stopped
+    let mut vm = ChartaVM::builder()
+        .persist_to(FileStore::new(&path), Duration::from_secs(3600))
+        .build();
+    vm.load_program(IR_JSON).await?;
+    assert_eq!(vm.get_coil("output").await?, Some(true));
+    assert_eq!(vm.get_signal("input").await?, Some(true));
+    assert_eq!(vm.cycle_count(), 1);
+
+    vm.execute_cycle().await?;
+    assert_eq!(vm.cycle_count(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_checkpoint_saved_after_interval() -> Result<(), Error> {
+    let path = state_path("interval");
+    let mut vm = ChartaVM::builder()
+        .persist_to(FileStore::new(&path), Duration::ZERO)
+        .build();
+    vm.load_program(IR_JSON).await?;
+    assert!(!path.exists());
+
+    vm.execute_cycle_with_inputs([("input".to_string(), true)].into()).await?;
+    let saved = FileStore::new(&path).load().await?.expect("checkpoint saved");
+    assert_eq!(saved.cycle, 1);
+    assert_eq!(saved.coils.get("output"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_restore_skips_unknown_names() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let mut checkpoint = vm.checkpoint();
+    checkpoint.cycle = 7;
+    checkpoint.coils.insert("output".to_string(), true);
+    checkpoint.coils.insert("removed".to_string(), true);
+
+    // One coil and the signal
+    assert_eq!(vm.restore_checkpoint(&checkpoint).await, 2);
+    assert_eq!(vm.get_coil("output").await?, Some(true));
+    assert_eq!(vm.get_coil("removed").await?, None);
+    assert_eq!(vm.cycle_count(), 7);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_save_without_store_fails() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    assert!(matches!(
+        vm.save_checkpoint().await,
+        Err(Error::InvalidOperation(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_checkpoint_json_shape() {
+    let json = r#"{
+        "program_id": null,
+        "cycle": 3,
+        "saved_at_ms": 1700000000000,
+        "coils": {"output": true},
+        "signals": {}
+    }"#;
+    let checkpoint: Checkpoint = serde_json::from_str(json).unwrap();
+    assert_eq!(checkpoint.cycle, 3);
+    let value = serde_json::to_value(&checkpoint).unwrap();
+    assert_eq!(value["saved_at_ms"], 1700000000000u64);
+}
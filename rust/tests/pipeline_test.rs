@@ -0,0 +1,87 @@
+/// Integration tests for multi-stage pipelines
+
+use charta::{ChartaVM, Error, Pipeline};
+use std::collections::HashMap;
+
+const UPSTREAM_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "upstream",
+        "signals": [
+            {"name": "request"}
+        ],
+        "coils": [
+            {"name": "allow_x"}
+        ],
+        "rungs": [
+            {
+                "name": "allow_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "request",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "allow_x"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+const DOWNSTREAM_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "downstream",
+        "signals": [
+            {"name": "x_approved"}
+        ],
+        "coils": [
+            {"name": "execute_x"}
+        ],
+        "rungs": [
+            {
+                "name": "execute_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "x_approved",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "execute_x"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_pipeline_feeds_coils_downstream() -> Result<(), Error> {
+    let mut upstream = ChartaVM::new();
+    upstream.load_program(UPSTREAM_IR).await?;
+    let mut downstream = ChartaVM::new();
+    downstream.load_program(DOWNSTREAM_IR).await?;
+
+    let mut pipeline = Pipeline::new()
+        .stage(upstream)
+        .stage(downstream)
+        .map("allow_x", "x_approved");
+
+    let mut inputs = HashMap::new();
+    inputs.insert("request".to_string(), true);
+
+    let outputs = pipeline.execute_cycle(inputs).await?;
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(outputs[0].get("allow_x"), Some(&true));
+    assert_eq!(outputs[1].get("execute_x"), Some(&true));
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+/// Integration tests for VM pooling
+
+use charta::{Error, VmPool};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "pooled_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_pool_checkout_and_metrics() -> Result<(), Error> {
+    let pool = VmPool::new(IR_JSON, 2).await?;
+    assert_eq!(pool.metrics().in_use, 0);
+
+    let mut first = pool.checkout().await?;
+    let second = pool.checkout().await?;
+    assert_eq!(pool.metrics().in_use, 2);
+    assert!(pool.try_checkout().await?.is_none());
+
+    first.set_signal("input", true).await?;
+    let outputs = first.execute_cycle().await?;
+    assert_eq!(outputs.get("output"), Some(&true));
+
+    drop(first);
+    drop(second);
+
+    let metrics = pool.metrics();
+    assert_eq!(metrics.in_use, 0);
+    assert_eq!(metrics.checkouts, 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pool_resets_state_between_checkouts() -> Result<(), Error> {
+    let pool = VmPool::new(IR_JSON, 1).await?;
+
+    let mut first = pool.checkout().await?;
+    first.set_signal("input", true).await?;
+    first.execute_cycle().await?;
+    drop(first);
+
+    let mut second = pool.checkout().await?;
+    assert_eq!(second.get_signal("input").await?, Some(false));
+    let outputs = second.execute_cycle().await?;
+    assert_eq!(outputs.get("output"), Some(&false));
+    assert_eq!(second.cycle_count(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pool_does_not_leak_bypasses_or_programs() -> Result<(), Error> {
+    let pool = VmPool::new(IR_JSON, 1).await?;
+
+    let mut first = pool.checkout().await?;
+    first.disable_rung("test_rung").await?;
+    drop(first);
+
+    let mut second = pool.checkout().await?;
+    second.set_signal("input", true).await?;
+    let outputs = second.execute_cycle().await?;
+    assert_eq!(outputs.get("output"), Some(&true));
+    second
+        .load_program(&IR_JSON.replace("output", "other"))
+        .await?;
+    drop(second);
+
+    let third = pool.checkout().await?;
+    assert_eq!(third.get_coil("output").await?, Some(false));
+    assert_eq!(third.get_coil("other").await?, None);
+
+    Ok(())
+}
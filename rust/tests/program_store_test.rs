@@ -0,0 +1,145 @@
+/// Tests for program stores and pinned registry versions
+
+use charta::io::async_trait;
+use charta::program_store::{program_key, DirectoryStore};
+use charta::{ChartaVM, Error, ProgramOrigin, ProgramRegistry, ProgramStore, Result};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const V1_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "policy",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "v1_output"}
+        ],
+        "rungs": []
+    }
+}"#;
+
+const V2_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "policy",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "v2_output"}
+        ],
+        "rungs": []
+    }
+}"#;
+
+/// Store serving fixed versions and counting fetches
+struct Fixed {
+    programs: HashMap<(String, String), String>,
+    fetches: AtomicUsize,
+}
+
+impl Fixed {
+    fn new() -> Self {
+        let programs = HashMap::from([
+            (("orders".to_string(), "v1".to_string()), V1_IR.to_string()),
+            (("orders".to_string(), "v2".to_string()), V2_IR.to_string()),
+        ]);
+        Self {
+            programs,
+            fetches: AtomicUsize::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl ProgramStore for Fixed {
+    async fn fetch(&self, id: &str, version: &str) -> Result<String> {
+        self.fetches.fetch_add(1, Ordering::SeqCst);
+        self.programs
+            .get(&(id.to_string(), version.to_string()))
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("{} {}", id, version)))
+    }
+}
+
+#[tokio::test]
+async fn test_pin_pulls_and_activates() -> Result<()> {
+    let store = Fixed::new();
+    let mut registry = ProgramRegistry::new();
+    let mut vm = ChartaVM::new();
+
+    let v1 = registry.pin(&mut vm, &store, "orders", "v1").await?;
+    let v2 = registry.pin(&mut vm, &store, "orders", "v2").await?;
+    assert_eq!(vm.coil_names().await?, vec!["v2_output".to_string()]);
+    assert_eq!(
+        registry.get(v2).and_then(|v| v.origin.clone()),
+        Some(ProgramOrigin {
+            id: "orders".to_string(),
+            version: "v2".to_string(),
+        })
+    );
+
+    // Pinned versions are fetched once
+    assert_eq!(registry.pin(&mut vm, &store, "orders", "v1").await?, v1);
+    assert_eq!(store.fetches.load(Ordering::SeqCst), 2);
+    assert_eq!(registry.find("orders", "v1").map(|v| v.version), Some(v1));
+    assert_eq!(vm.coil_names().await?, vec!["v1_output".to_string()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_missing_version_is_not_found() {
+    let mut registry = ProgramRegistry::new();
+    let result = registry.pull(&Fixed::new(), "orders", "v9").await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+    assert!(registry.versions().is_empty());
+}
+
+#[tokio::test]
+async fn test_directory_store() -> Result<()> {
+    let root = std::env::temp_dir().join(format!("charta-programs-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("orders"))?;
+    std::fs::write(root.join("orders/v1.ir.json"), V1_IR)?;
+
+    let store = DirectoryStore::new(&root);
+    assert_eq!(store.fetch("orders", "v1").await?, V1_IR);
+    assert!(matches!(
+        store.fetch("orders", "v2").await,
+        Err(Error::NotFound(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_program_key_rejects_paths() {
+    assert_eq!(program_key("orders", "v1").unwrap(), "orders/v1.ir.json");
+    assert!(program_key("../etc", "v1").is_err());
+    assert!(program_key("orders", "..").is_err());
+    assert!(program_key("", "v1").is_err());
+}
+
+#[cfg(any(feature = "s3", feature = "gcs"))]
+#[tokio::test]
+async fn test_bucket_store() -> Result<()> {
+    use charta::program_store::BucketStore;
+    use object_store::{memory::InMemory, path::Path, ObjectStore};
+    use std::sync::Arc;
+
+    let objects = Arc::new(InMemory::new());
+    objects
+        .put(&Path::from("policies/orders/v1.ir.json"), V1_IR.into())
+        .await
+        .unwrap();
+
+    let store = BucketStore::new(objects).prefix("/policies/");
+    assert_eq!(store.fetch("orders", "v1").await?, V1_IR);
+    assert!(matches!(
+        store.fetch("orders", "v2").await,
+        Err(Error::NotFound(_))
+    ));
+    Ok(())
+}
@@ -0,0 +1,207 @@
+/// Tests for signal quality propagation
+
+use charta::{ChartaVM, CycleOptions, Error, Quality, QualityPolicy, Value};
+use std::time::Duration;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "quality_program",
+        "signals": [
+            {"name": "kyc_verified"},
+            {"name": "system_ok"}
+        ],
+        "coils": [
+            {"name": "approved"},
+            {"name": "online"}
+        ],
+        "rungs": [
+            {
+                "name": "approve",
+                "guard": {
+                    "type": "and",
+                    "left": {"type": "contact", "name": "kyc_verified", "contact_type": "NO"},
+                    "right": {"type": "contact", "name": "system_ok", "contact_type": "NO"}
+                },
+                "actions": [
+                    {"type": "energise", "coil": "approved"}
+                ]
+            },
+            {
+                "name": "report_online",
+                "guard": {"type": "contact", "name": "system_ok", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "online"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+const MOVE_IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "gated_move_program",
+        "signals": [
+            {"name": "payment_seen"}
+        ],
+        "coils": [],
+        "registers": [
+            {"name": "payments", "type": "int"}
+        ],
+        "rungs": [
+            {
+                "name": "count_payment",
+                "guard": {"type": "contact", "name": "payment_seen", "contact_type": "NO"},
+                "actions": [
+                    {
+                        "type": "move",
+                        "dest": "payments",
+                        "expr": {
+                            "type": "add",
+                            "operands": [
+                                {"type": "signal", "name": "payments"},
+                                {"type": "const", "value": 1}
+                            ]
+                        }
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_bad_signal_evaluates_false() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .quality_policy(QualityPolicy::EvaluateFalse)
+        .build();
+    vm.load_program(IR_JSON).await?;
+    vm.set_signal("system_ok", true).await?;
+
+    vm.set_signal_with_quality("kyc_verified", true, Quality::Bad)
+        .await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("approved"), Some(&false));
+    assert_eq!(outputs.get("online"), Some(&true));
+    assert_eq!(vm.get_signal_quality("kyc_verified").await?, Quality::Bad);
+
+    vm.set_signal("kyc_verified", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("approved"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fault_policy_fails_cycle() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .quality_policy(QualityPolicy::Fault)
+        .build();
+    vm.load_program(IR_JSON).await?;
+
+    vm.set_signal_with_quality("system_ok", true, Quality::Bad)
+        .await?;
+    let err = vm.execute_cycle().await.unwrap_err();
+    assert!(
+        matches!(&err, Error::SignalQuality(msg) if msg.contains("'approve'") && msg.contains("'system_ok'")),
+        "{}",
+        err
+    );
+
+    // Inputs passed to the cycle are fresh
+    let inputs = [("system_ok".to_string(), true)].into();
+    vm.execute_cycle_with_inputs(inputs).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stale_timeout() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .quality_policy(QualityPolicy::EvaluateFalse)
+        .build();
+    vm.load_program(IR_JSON).await?;
+    vm.set_stale_timeout("system_ok", Duration::from_millis(20));
+    vm.set_signal("system_ok", true).await?;
+
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("online"), Some(&true));
+
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    assert_eq!(vm.get_signal_quality("system_ok").await?, Quality::Stale);
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("online"), Some(&false));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ignore_policy_records_quality_only() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    vm.set_signal_with_quality("system_ok", true, Quality::Bad)
+        .await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("online"), Some(&true));
+    assert_eq!(vm.get_signal_quality("system_ok").await?, Quality::Bad);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_quality_signals_are_hidden() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .quality_policy(QualityPolicy::EvaluateFalse)
+        .build();
+    vm.load_program(IR_JSON).await?;
+    vm.execute_cycle().await?;
+
+    let mut names = vm.signal_names().await?;
+    names.sort();
+    assert_eq!(names, ["kyc_verified", "system_ok"]);
+    Ok(())
+}
+
+#[test]
+fn test_blocking_vm_applies_quality_policy() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .quality_policy(QualityPolicy::EvaluateFalse)
+        .build_blocking();
+    vm.load_program(IR_JSON)?;
+    vm.set_signal_with_quality("system_ok", true, Quality::Stale)?;
+    let outputs = vm.execute_cycle()?;
+    assert_eq!(outputs.get("online"), Some(&false));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bad_signal_gates_moves_and_traces() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .quality_policy(QualityPolicy::EvaluateFalse)
+        .build();
+    vm.load_program(MOVE_IR_JSON).await?;
+
+    vm.set_signal_with_quality("payment_seen", true, Quality::Bad)
+        .await?;
+    let cycle = vm.execute(CycleOptions::new().trace(true)).await?;
+    assert!(!cycle.evaluations[0].energised);
+    assert_eq!(vm.get_register("payments").await?, Some(Value::Int(0)));
+
+    vm.set_signal("payment_seen", true).await?;
+    let cycle = vm.execute(CycleOptions::new().trace(true)).await?;
+    assert!(cycle.evaluations[0].energised);
+    assert_eq!(vm.get_register("payments").await?, Some(Value::Int(1)));
+    Ok(())
+}
+
+#[test]
+fn test_blocking_bad_signal_gates_moves() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .quality_policy(QualityPolicy::EvaluateFalse)
+        .build_blocking();
+    vm.load_program(MOVE_IR_JSON)?;
+    vm.set_signal_with_quality("payment_seen", true, Quality::Bad)?;
+    vm.execute_cycle()?;
+    assert_eq!(vm.get_register("payments")?, Some(Value::Int(0)));
+    Ok(())
+}
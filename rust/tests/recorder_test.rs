@@ -0,0 +1,120 @@
+/// Tests for time-travel recording
+
+use charta::{ChartaVM, Error, Value};
+use std::collections::HashMap;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "recorder_program",
+        "signals": [
+            {"name": "kyc_passed"},
+            {"name": "amount", "type": "int"}
+        ],
+        "coils": [
+            {"name": "approved"}
+        ],
+        "rungs": [
+            {
+                "name": "approve",
+                "guard": {"type": "contact", "name": "kyc_passed", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "approved"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+fn inputs(kyc_passed: bool) -> HashMap<String, bool> {
+    HashMap::from([("kyc_passed".to_string(), kyc_passed)])
+}
+
+#[tokio::test]
+async fn test_rewind_and_replay_forward() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.enable_recording(10);
+
+    vm.set_value("amount", 100).await?;
+    vm.execute_cycle_with_inputs(inputs(true)).await?;
+    vm.set_value("amount", 200).await?;
+    vm.execute_cycle_with_inputs(inputs(false)).await?;
+    vm.execute_cycle_with_inputs(inputs(true)).await?;
+    assert_eq!(vm.recorded_cycles().len(), 3);
+
+    assert_eq!(vm.rewind(2).await?, 1);
+    assert!(vm.is_rewound());
+    assert_eq!(vm.cycle_count(), 1);
+    assert_eq!(vm.get_coil("approved").await?, Some(true));
+    assert_eq!(vm.get_value("amount").await?, Some(Value::Int(100)));
+
+    let replayed = vm.replay_forward().await?;
+    assert_eq!(replayed.cycle, 2);
+    assert_eq!(replayed.inputs, inputs(false));
+    assert_eq!(vm.get_coil("approved").await?, Some(false));
+    assert_eq!(vm.get_value("amount").await?, Some(Value::Int(200)));
+
+    assert_eq!(vm.replay_forward().await?.cycle, 3);
+    assert!(!vm.is_rewound());
+    assert_eq!(vm.cycle_count(), 3);
+    assert_eq!(vm.get_coil("approved").await?, Some(true));
+
+    let result = vm.replay_forward().await;
+    assert!(
+        matches!(result, Err(Error::InvalidOperation(_))),
+        "{:?}",
+        result
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recording_keeps_last_cycles() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let result = vm.rewind(1).await;
+    assert!(
+        matches!(result, Err(Error::InvalidOperation(_))),
+        "{:?}",
+        result
+    );
+
+    vm.enable_recording(2);
+    for _ in 0..5 {
+        vm.execute_cycle().await?;
+    }
+    let cycles: Vec<u64> = vm.recorded_cycles().iter().map(|c| c.cycle).collect();
+    assert_eq!(cycles, [4, 5]);
+
+    let result = vm.rewind(3).await;
+    assert!(
+        matches!(result, Err(Error::InvalidOperation(_))),
+        "{:?}",
+        result
+    );
+    assert!(!vm.is_rewound());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cycle_while_rewound_discards_later_cycles() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.enable_recording(10);
+    for _ in 0..3 {
+        vm.execute_cycle_with_inputs(inputs(true)).await?;
+    }
+
+    vm.rewind(3).await?;
+    assert_eq!(vm.get_coil("approved").await?, Some(false));
+    vm.execute_cycle_with_inputs(inputs(false)).await?;
+    assert!(!vm.is_rewound());
+    assert_eq!(vm.cycle_count(), 1);
+
+    let recorded = vm.recorded_cycles();
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(recorded[0].inputs, inputs(false));
+    Ok(())
+}
@@ -0,0 +1,22 @@
+#![cfg(feature = "redis")]
+/// Tests for the Redis state store and signal mirror
+
+use charta::persistence::redis::{parse_value, RedisStore};
+use charta::Error;
+
+#[test]
+fn test_parse_value() {
+    assert_eq!(parse_value(b"1"), Some(true));
+    assert_eq!(parse_value(b"0"), Some(false));
+    assert_eq!(parse_value(b" TRUE\n"), Some(true));
+    assert_eq!(parse_value(b"false"), Some(false));
+    assert_eq!(parse_value(b"on"), None);
+    assert_eq!(parse_value(&[0xff]), None);
+}
+
+#[tokio::test]
+async fn test_connect_failure_is_store_error() {
+    // Nothing listens on port 1
+    let result = RedisStore::connect("redis://127.0.0.1:1", "charta:test").await;
+    assert!(matches!(result, Err(Error::Persistence(_))));
+}
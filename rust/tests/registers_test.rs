@@ -0,0 +1,131 @@
+/// Tests for registers written by move actions
+
+use charta::{ChartaVM, Error, Value};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "scoring_program",
+        "signals": [
+            {"name": "amount", "type": "int"},
+            {"name": "new_payee"}
+        ],
+        "coils": [
+            {"name": "review"},
+            {"name": "risky"}
+        ],
+        "registers": [
+            {"name": "risk_score", "type": "int"},
+            {"name": "last_amount", "type": "float"}
+        ],
+        "rungs": [
+            {
+                "name": "score_new_payee",
+                "guard": {"type": "contact", "name": "new_payee", "contact_type": "NO"},
+                "actions": [
+                    {
+                        "type": "move",
+                        "dest": "risk_score",
+                        "expr": {
+                            "type": "add",
+                            "operands": [
+                                {"type": "signal", "name": "risk_score"},
+                                {"type": "const", "value": 30}
+                            ]
+                        }
+                    }
+                ]
+            },
+            {
+                "name": "record_amount",
+                "guard": {"type": "compare", "name": "amount", "op": ">", "value": 0},
+                "actions": [
+                    {"type": "move", "dest": "last_amount", "expr": {"type": "signal", "name": "amount"}},
+                    {"type": "energise", "coil": "review"}
+                ]
+            },
+            {
+                "name": "flag_risky",
+                "guard": {"type": "compare", "name": "risk_score", "op": ">=", "value": 50},
+                "actions": [
+                    {"type": "energise", "coil": "risky"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_move_accumulates_across_cycles() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    assert_eq!(vm.get_register("risk_score").await?, Some(Value::Int(0)));
+
+    vm.set_signal("new_payee", true).await?;
+    vm.execute_cycle().await?;
+    assert_eq!(vm.get_register("risk_score").await?, Some(Value::Int(30)));
+
+    // Guards see the register as of the start of the cycle
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(vm.get_register("risk_score").await?, Some(Value::Int(60)));
+    assert_eq!(outputs.get("risky"), Some(&false));
+
+    vm.set_signal("new_payee", false).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(vm.get_register("risk_score").await?, Some(Value::Int(60)));
+    assert_eq!(outputs.get("risky"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_move_widens_and_keeps_coil_actions() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    vm.set_value("amount", 250).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("review"), Some(&true));
+    assert_eq!(
+        vm.get_register("last_amount").await?,
+        Some(Value::Float(250.0))
+    );
+    assert_eq!(vm.get_all_registers().await?.len(), 2);
+    assert!(vm.get_register("amount").await?.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_moves_fail_load() {
+    let undeclared = IR_JSON.replace(r#""dest": "risk_score""#, r#""dest": "unknown""#);
+    let narrowing = IR_JSON
+        .replace(
+            r#"{"name": "amount", "type": "int"}"#,
+            r#"{"name": "amount", "type": "float"}"#,
+        )
+        .replace(
+            r#""name": "last_amount", "type": "float""#,
+            r#""name": "last_amount", "type": "int""#,
+        );
+    let clash = IR_JSON.replace(
+        r#"{"name": "last_amount", "type": "float"}"#,
+        r#"{"name": "amount", "type": "float"}"#,
+    );
+
+    for ir in [undeclared, narrowing, clash] {
+        let mut vm = ChartaVM::new();
+        let result = vm.load_program(&ir).await;
+        assert!(matches!(result, Err(Error::IRLoad(_))), "{:?}", result);
+    }
+}
+
+#[test]
+fn test_blocking_vm_applies_moves() -> Result<(), Error> {
+    let mut vm = charta::blocking::ChartaVM::new();
+    vm.load_program(IR_JSON)?;
+    vm.set_signal("new_payee", true)?;
+    vm.execute_cycle()?;
+    vm.execute_cycle()?;
+    assert_eq!(vm.get_register("risk_score")?, Some(Value::Int(60)));
+    Ok(())
+}
@@ -0,0 +1,77 @@
+/// Integration tests for the program registry
+
+use charta::{ChartaVM, Error, ProgramRegistry, RegistryEvent};
+use std::sync::{Arc, Mutex};
+
+const V1_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "policy",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "v1_output"}
+        ],
+        "rungs": []
+    }
+}"#;
+
+const V2_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "policy",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "v2_output"}
+        ],
+        "rungs": []
+    }
+}"#;
+
+#[tokio::test]
+async fn test_activate_and_rollback() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    let mut registry = ProgramRegistry::new();
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_clone = events.clone();
+    registry.on_event(move |event| events_clone.lock().unwrap().push(event.clone()));
+
+    let v1 = registry.register(V1_IR)?;
+    let v2 = registry.register(V2_IR)?;
+    assert_eq!((v1, v2), (1, 2));
+
+    // Re-registering identical source is deduplicated
+    assert_eq!(registry.register(V1_IR)?, v1);
+
+    registry.activate(&mut vm, v1).await?;
+    registry.activate(&mut vm, v2).await?;
+    assert_eq!(vm.coil_names().await?, vec!["v2_output".to_string()]);
+
+    assert_eq!(registry.rollback(&mut vm).await?, v1);
+    assert_eq!(registry.active().map(|v| v.version), Some(v1));
+    assert_eq!(vm.coil_names().await?, vec!["v1_output".to_string()]);
+
+    // Nothing left to roll back to
+    assert!(registry.rollback(&mut vm).await.is_err());
+
+    let events = events.lock().unwrap();
+    assert!(matches!(
+        events.last(),
+        Some(RegistryEvent::RolledBack { from: 2, to: 1, .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_register_rejects_invalid_ir() {
+    let mut registry = ProgramRegistry::new();
+    assert!(registry.register("invalid json").is_err());
+    assert!(registry.versions().is_empty());
+}
@@ -0,0 +1,138 @@
+/// Tests for reloading a running program
+
+use charta::{ChartaVM, Error, ProgramDiff, VmEvent};
+
+const V1_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "policy",
+        "signals": [
+            {"name": "input"},
+            {"name": "legacy"}
+        ],
+        "coils": [
+            {"name": "output", "retained": true}
+        ],
+        "rungs": [
+            {
+                "name": "drive_output",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+const V2_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "policy",
+        "signals": [
+            {"name": "input"},
+            {"name": "override"}
+        ],
+        "coils": [
+            {"name": "output", "retained": true},
+            {"name": "audit"}
+        ],
+        "rungs": [
+            {
+                "name": "drive_output",
+                "guard": {
+                    "type": "contact",
+                    "name": "override",
+                    "contact_type": "NC"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            },
+            {
+                "name": "drive_audit",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "audit"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_reload_keeps_state_and_reports_diff() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(V1_IR).await?;
+    vm.execute_cycle_with_inputs([("input".to_string(), true)].into()).await?;
+    let mut events = vm.subscribe();
+
+    let diff = vm.reload_program(V2_IR).await?;
+    assert_eq!(
+        diff,
+        ProgramDiff {
+            added_signals: vec!["override".to_string()],
+            removed_signals: vec!["legacy".to_string()],
+            added_coils: vec!["audit".to_string()],
+            removed_coils: vec![],
+            added_rungs: vec!["drive_audit".to_string()],
+            removed_rungs: vec![],
+            changed_rungs: vec!["drive_output".to_string()],
+        }
+    );
+
+    // States of names both versions declare carry over
+    assert_eq!(vm.get_coil("output").await?, Some(true));
+    assert_eq!(vm.get_signal("input").await?, Some(true));
+    assert_eq!(vm.get_coil("audit").await?, Some(false));
+    assert_eq!(vm.cycle_count(), 1);
+
+    assert!(matches!(events.recv().await, Ok(VmEvent::ProgramLoaded { .. })));
+    match events.recv().await {
+        Ok(VmEvent::ProgramReloaded { diff: reported, context }) => {
+            assert_eq!(reported, diff);
+            assert_eq!(context.cycle, 1);
+        }
+        other => panic!("unexpected event: {:?}", other),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_reload_keeps_running_program() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(V1_IR).await?;
+    let program_id = vm.program_id();
+
+    assert!(vm.reload_program("{ not json").await.is_err());
+    assert_eq!(vm.program_id(), program_id);
+    assert_eq!(vm.coil_names().await?, vec!["output".to_string()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reload_without_program_loads() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    assert!(vm.reload_program(V1_IR).await?.is_empty());
+    assert!(vm.program_id().is_some());
+    Ok(())
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "remote")]
+/// Tests for loading programs from URLs
+
+use charta::registry::program_hash;
+use charta::remote::RemoteLoader;
+use charta::{ChartaVM, Error};
+use std::path::PathBuf;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "remote_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+/// Nothing listens on port 1
+const UNREACHABLE: &str = "https://127.0.0.1:1/program.ir.json";
+
+fn cache_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("charta-remote-{}-{}", std::process::id(), test));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[tokio::test]
+async fn test_cached_program_loads_offline() -> Result<(), Error> {
+    let hash = program_hash(IR_JSON);
+    let loader = RemoteLoader::new().cache_dir(cache_dir("cached"));
+    let path = loader.cache_path(&hash).unwrap();
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(&path, IR_JSON)?;
+
+    let ir = loader.fetch(UNREACHABLE, &hash.to_uppercase()).await?;
+    let mut vm = ChartaVM::new();
+    vm.load_program(&ir).await?;
+    assert_eq!(vm.program_id(), Some(hash));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_corrupt_cache_is_fetched_again() {
+    let hash = program_hash(IR_JSON);
+    let loader = RemoteLoader::new().cache_dir(cache_dir("corrupt"));
+    let path = loader.cache_path(&hash).unwrap();
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "{}").unwrap();
+
+    let result = loader.fetch(UNREACHABLE, &hash).await;
+    assert!(matches!(result, Err(Error::Fetch(_))));
+}
+
+#[tokio::test]
+async fn test_plain_http_is_refused() {
+    let hash = program_hash(IR_JSON);
+    let loader = RemoteLoader::new().no_cache();
+    let result = loader.fetch("http://127.0.0.1:1/program.ir.json", &hash).await;
+    match result {
+        Err(Error::Fetch(message)) => assert!(message.contains("refusing")),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[tokio::test]
+async fn test_malformed_hash_is_rejected() {
+    let mut vm = ChartaVM::new();
+    let result = vm.load_program_from_url(UNREACHABLE, "not-a-hash").await;
+    assert!(matches!(result, Err(Error::InvalidOperation(_))));
+}
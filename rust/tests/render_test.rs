@@ -0,0 +1,100 @@
+/// Tests for program rendering
+
+use charta::ir::Program;
+use charta::render::{ladder_text, ladder_text_with, to_mermaid, to_svg, LadderStyle};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "render_program",
+        "signals": [
+            {"name": "user_submitted"},
+            {"name": "system_ok"},
+            {"name": "override"}
+        ],
+        "coils": [
+            {"name": "allow_review"},
+            {"name": "notify"}
+        ],
+        "rungs": [
+            {
+                "name": "allow_review",
+                "guard": {
+                    "type": "and",
+                    "left": {"type": "contact", "name": "user_submitted", "contact_type": "NO"},
+                    "right": {
+                        "type": "or",
+                        "operands": [
+                            {"type": "contact", "name": "system_ok", "contact_type": "NO"},
+                            {"type": "contact", "name": "override", "contact_type": "NC"}
+                        ]
+                    }
+                },
+                "actions": [
+                    {"type": "energise", "coil": "allow_review"}
+                ]
+            },
+            {
+                "name": "notify",
+                "guard": {
+                    "type": "not",
+                    "operand": {"type": "contact", "name": "system_ok", "contact_type": "NO"}
+                },
+                "actions": [
+                    {"type": "energise", "coil": "notify"},
+                    {"type": "energise", "coil": "allow_review"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[test]
+fn test_ladder_text() {
+    let program = Program::from_json(IR_JSON).unwrap();
+    let expected = "\
+allow_review
+|--[ user_submitted ]--+-[ system_ok ]-+--( allow_review )
+                       +-[/override ]--+
+notify
+|--[/system_ok ]--+-( notify )-------+
+                  +-( allow_review )-+
+";
+    assert_eq!(ladder_text(&program), expected);
+}
+
+#[test]
+fn test_ladder_text_unicode() {
+    let program = Program::from_json(IR_JSON).unwrap();
+    let text = ladder_text_with(&program, LadderStyle::Unicode);
+    assert!(text.contains("┃──[ user_submitted ]──┬─[ system_ok ]─┬──( allow_review )"));
+    assert!(text.contains("└─[/override ]──┘"));
+}
+
+#[test]
+fn test_to_mermaid() {
+    let program = Program::from_json(IR_JSON).unwrap();
+    let mermaid = to_mermaid(&program);
+    assert!(mermaid.starts_with("flowchart LR\n"));
+    // Declared signals and coils come first, then rungs
+    assert!(mermaid.contains("    n0([\"user_submitted\"])"));
+    assert!(mermaid.contains("    n3((\"allow_review\"))"));
+    assert!(mermaid.contains("    n5[\"allow_review\"]"));
+    assert!(mermaid.contains("    n0 --> n5"));
+    assert!(mermaid.contains("    n2 -- NC --> n5"));
+    assert!(mermaid.contains("    n5 --> n3"));
+    // NOT over a normally open contact is an inverted dependency
+    assert!(mermaid.contains("    n1 -- NC --> n6"));
+}
+
+#[test]
+fn test_to_svg() {
+    let program = Program::from_json(IR_JSON).unwrap();
+    let svg = to_svg(&program);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert_eq!(svg.matches("<rect").count(), 7);
+    assert_eq!(svg.matches("stroke-dasharray").count(), 2);
+    assert!(svg.contains(">user_submitted</text>"));
+}
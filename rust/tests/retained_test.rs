@@ -0,0 +1,135 @@
+/// Tests for retained coils across reloads and restarts
+
+use charta::persistence::FileStore;
+use charta::{ChartaVM, Error, StateStore};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "retained_program",
+        "signals": [
+            {"name": "fraud_flag"}
+        ],
+        "coils": [
+            {"name": "account_frozen", "retained": true},
+            {"name": "review_pending"}
+        ],
+        "rungs": [
+            {
+                "name": "freeze",
+                "guard": {
+                    "type": "or",
+                    "operands": [
+                        {"type": "contact", "name": "fraud_flag", "contact_type": "NO"},
+                        {"type": "contact", "name": "account_frozen", "contact_type": "NO"}
+                    ]
+                },
+                "actions": [
+                    {"type": "energise", "coil": "account_frozen"}
+                ]
+            },
+            {
+                "name": "review",
+                "guard": {
+                    "type": "or",
+                    "operands": [
+                        {"type": "contact", "name": "fraud_flag", "contact_type": "NO"},
+                        {"type": "contact", "name": "review_pending", "contact_type": "NO"}
+                    ]
+                },
+                "actions": [
+                    {"type": "energise", "coil": "review_pending"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+fn state_path(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("charta-retained-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.json", test));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Latch both coils, then drop the signal latching them
+async fn load_and_latch(vm: &mut ChartaVM) -> Result<(), Error> {
+    vm.load_program(IR_JSON).await?;
+    vm.execute_cycle_with_inputs([("fraud_flag".to_string(), true)].into())
+        .await?;
+    vm.execute_cycle_with_inputs([("fraud_flag".to_string(), false)].into())
+        .await?;
+    assert_eq!(vm.get_coil("account_frozen").await?, Some(true));
+    assert_eq!(vm.get_coil("review_pending").await?, Some(true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_checkpoint_holds_retained_coils() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    load_and_latch(&mut vm).await?;
+
+    let checkpoint = vm.checkpoint();
+    assert_eq!(checkpoint.coils.get("account_frozen"), Some(&true));
+    assert_eq!(checkpoint.coils.get("review_pending"), None);
+    assert_eq!(checkpoint.signals.get("fraud_flag"), Some(&false));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reload_resets_transient_coils() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    load_and_latch(&mut vm).await?;
+
+    vm.reload_program(IR_JSON).await?;
+    assert_eq!(vm.get_coil("account_frozen").await?, Some(true));
+    assert_eq!(vm.get_coil("review_pending").await?, Some(false));
+
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("account_frozen"), Some(&true));
+    assert_eq!(outputs.get("review_pending"), Some(&false));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_restart_restores_only_retained_coils() -> Result<(), Error> {
+    let path = state_path("restart");
+    {
+        let mut vm = ChartaVM::builder()
+            .persist_to(FileStore::new(&path), Duration::from_secs(3600))
+            .build();
+        load_and_latch(&mut vm).await?;
+        vm.save_checkpoint().await?;
+    }
+
+    let mut vm = ChartaVM::builder()
+        .persist_to(FileStore::new(&path), Duration::from_secs(3600))
+        .build();
+    vm.load_program(IR_JSON).await?;
+    assert_eq!(vm.get_coil("account_frozen").await?, Some(true));
+    assert_eq!(vm.get_coil("review_pending").await?, Some(false));
+    assert_eq!(vm.cycle_count(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stored_transient_coils_not_restored() -> Result<(), Error> {
+    let path = state_path("transient");
+    let mut vm = ChartaVM::new();
+    load_and_latch(&mut vm).await?;
+    let mut checkpoint = vm.checkpoint();
+    checkpoint.coils.insert("review_pending".to_string(), true);
+    FileStore::new(&path).save(&checkpoint).await?;
+
+    let mut vm = ChartaVM::builder()
+        .persist_to(FileStore::new(&path), Duration::from_secs(3600))
+        .build();
+    vm.load_program(IR_JSON).await?;
+    assert_eq!(vm.get_coil("account_frozen").await?, Some(true));
+    assert_eq!(vm.get_coil("review_pending").await?, Some(false));
+    Ok(())
+}
@@ -0,0 +1,82 @@
+/// Tests for introspecting loaded rungs
+
+use charta::blocking;
+use charta::ir::Guard;
+use charta::{ChartaVM, Error};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "rungs_program",
+        "signals": [
+            {"name": "kyc_passed"},
+            {"name": "sanctions_hit"}
+        ],
+        "coils": [
+            {"name": "allow_operation"},
+            {"name": "audit_flag"},
+            {"name": "alert"}
+        ],
+        "rungs": [
+            {
+                "name": "operation_gate",
+                "guard": {
+                    "type": "and",
+                    "operands": [
+                        {"type": "contact", "name": "kyc_passed", "contact_type": "NO"},
+                        {"type": "contact", "name": "sanctions_hit", "contact_type": "NC"}
+                    ]
+                },
+                "actions": [
+                    {"type": "energise", "coil": "allow_operation"},
+                    {"type": "energise", "coil": "audit_flag"}
+                ]
+            },
+            {
+                "name": "sanctions_alert",
+                "priority": 10,
+                "guard": {"type": "contact", "name": "sanctions_hit", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "alert"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_rungs_describe_loaded_program() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    assert!(vm.rungs().is_empty());
+    vm.load_program(IR_JSON).await?;
+
+    // In scan order: the higher priority rung first
+    let rungs = vm.rungs();
+    let names: Vec<&str> = rungs.iter().map(|rung| rung.name.as_str()).collect();
+    assert_eq!(names, vec!["sanctions_alert", "operation_gate"]);
+    assert_eq!(rungs[0].priority, 10);
+    assert_eq!(rungs[0].target_coils, vec!["alert".to_string()]);
+    assert_eq!(
+        rungs[1].target_coils,
+        vec!["allow_operation".to_string(), "audit_flag".to_string()]
+    );
+    assert!(matches!(&rungs[1].guard, Guard::And { .. }));
+    assert_eq!(rungs[1].guard.operands().len(), 2);
+
+    // The guard serializes in the IR's guard syntax
+    let json = serde_json::to_value(&rungs[0])?;
+    assert_eq!(json["name"], "sanctions_alert");
+    assert_eq!(json["guard"]["type"], "contact");
+    assert_eq!(json["guard"]["name"], "sanctions_hit");
+    Ok(())
+}
+
+#[test]
+fn test_blocking_rungs() -> Result<(), Error> {
+    let mut vm = blocking::ChartaVM::new();
+    vm.load_program(IR_JSON)?;
+    let names: Vec<String> = vm.rungs().into_iter().map(|rung| rung.name).collect();
+    assert_eq!(names, vm.rung_order());
+    Ok(())
+}
@@ -0,0 +1,134 @@
+/// Tests for safety-classified coils
+
+use charta::{ChartaVM, Error, VmEvent};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "safety_program",
+        "signals": [
+            {"name": "kyc_passed"}
+        ],
+        "coils": [
+            {"name": "allow_operation", "safety": true},
+            {"name": "audit_flag"}
+        ],
+        "rungs": [
+            {
+                "name": "operation_gate",
+                "guard": {"type": "contact", "name": "kyc_passed", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "allow_operation"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_safety_coil_needs_confirmation() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let mut events = vm.subscribe();
+    assert!(vm.is_safety_coil("allow_operation"));
+    assert_eq!(
+        vm.coil_meta("allow_operation").map(|meta| meta.safety),
+        Some(true)
+    );
+
+    let result = vm.set_coil("allow_operation", true).await;
+    assert!(
+        matches!(result, Err(Error::AccessDenied(_))),
+        "{:?}",
+        result
+    );
+    assert_eq!(vm.get_coil("allow_operation").await?, Some(false));
+
+    let token = vm.request_safety_override("allow_operation", true)?;
+    vm.set_safety_coil("allow_operation", true, &token).await?;
+    assert_eq!(vm.get_coil("allow_operation").await?, Some(true));
+
+    let event = std::iter::from_fn(|| events.try_recv().ok())
+        .find(|event| event.kind() == "safety_override")
+        .expect("safety override event");
+    assert!(matches!(
+        event,
+        VmEvent::SafetyOverride { coil, value: true, .. } if coil == "allow_operation"
+    ));
+
+    // Tokens are single-use
+    let result = vm.set_safety_coil("allow_operation", true, &token).await;
+    assert!(
+        matches!(result, Err(Error::AccessDenied(_))),
+        "{:?}",
+        result
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_token_is_bound_to_coil_and_value() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder().safety_coil("audit_flag").build();
+    vm.load_program(IR_JSON).await?;
+
+    let token = vm.request_safety_override("allow_operation", true)?;
+    let result = vm.set_safety_coil("allow_operation", false, &token).await;
+    assert!(
+        matches!(result, Err(Error::AccessDenied(_))),
+        "{:?}",
+        result
+    );
+    let result = vm.set_safety_coil("audit_flag", true, &token).await;
+    assert!(
+        matches!(result, Err(Error::AccessDenied(_))),
+        "{:?}",
+        result
+    );
+
+    let result = vm.set_coil("audit_flag", true).await;
+    assert!(
+        matches!(result, Err(Error::AccessDenied(_))),
+        "{:?}",
+        result
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ordinary_coils_are_unaffected() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.set_coil("audit_flag", true).await?;
+    assert_eq!(vm.get_coil("audit_flag").await?, Some(true));
+
+    let result = vm.request_safety_override("audit_flag", true);
+    assert!(
+        matches!(result, Err(Error::InvalidOperation(_))),
+        "{:?}",
+        result
+    );
+
+    // Scans still drive safety coils
+    vm.set_signal("kyc_passed", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("allow_operation"), Some(&true));
+    Ok(())
+}
+
+#[test]
+fn test_blocking_vm_protects_safety_coils() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder().build_blocking();
+    vm.load_program(IR_JSON)?;
+    let result = vm.set_coil("allow_operation", true);
+    assert!(
+        matches!(result, Err(Error::AccessDenied(_))),
+        "{:?}",
+        result
+    );
+
+    let token = vm.request_safety_override("allow_operation", true)?;
+    vm.set_safety_coil("allow_operation", true, &token)?;
+    assert_eq!(vm.get_coil("allow_operation")?, Some(true));
+    Ok(())
+}
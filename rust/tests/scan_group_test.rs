@@ -0,0 +1,155 @@
+/// Tests for scan groups
+
+use charta::{ChartaVM, Error};
+use std::time::Duration;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "scan_group_program",
+        "signals": [
+            {"name": "overspeed"},
+            {"name": "audit_due"},
+            {"name": "online"}
+        ],
+        "coils": [
+            {"name": "trip"},
+            {"name": "trip_latched"},
+            {"name": "audit"},
+            {"name": "heartbeat"}
+        ],
+        "rungs": [
+            {
+                "name": "overspeed_trip",
+                "group": "fast",
+                "guard": {"type": "contact", "name": "overspeed", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "trip"},
+                    {"type": "energise", "coil": "trip_latched"}
+                ]
+            },
+            {
+                "name": "compliance_audit",
+                "group": "slow",
+                "guard": {"type": "contact", "name": "audit_due", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "audit"}
+                ]
+            },
+            {
+                "name": "heartbeat",
+                "guard": {"type": "contact", "name": "online", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "heartbeat"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_group_scan_holds_other_groups() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.set_signal("overspeed", true).await?;
+    vm.set_signal("audit_due", true).await?;
+    vm.set_signal("online", true).await?;
+
+    let outputs = vm.execute_group("fast").await?;
+    assert_eq!(outputs.get("trip"), Some(&true));
+    assert_eq!(outputs.get("trip_latched"), Some(&true));
+    assert_eq!(outputs.get("audit"), Some(&false));
+    assert_eq!(outputs.get("heartbeat"), Some(&true));
+
+    vm.set_signal("overspeed", false).await?;
+    let outputs = vm.execute_group("slow").await?;
+    assert_eq!(outputs.get("trip"), Some(&true));
+    assert_eq!(outputs.get("trip_latched"), Some(&true));
+    assert_eq!(outputs.get("audit"), Some(&true));
+
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("trip"), Some(&false));
+    assert_eq!(outputs.get("trip_latched"), Some(&false));
+    assert_eq!(outputs.get("audit"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unknown_group_and_hidden_signals() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let result = vm.execute_group("on_demand").await;
+    assert!(matches!(result, Err(Error::NotFound(_))), "{:?}", result);
+
+    vm.execute_group("slow").await?;
+    let mut names = vm.signal_names().await?;
+    names.sort();
+    assert_eq!(names, ["audit_due", "online", "overspeed"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_scan_groups_until_shutdown() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .scan_group("fast", Duration::from_millis(10))
+        .scan_group("slow", Duration::from_secs(5))
+        .build();
+    vm.load_program(IR_JSON).await?;
+    vm.set_signal("overspeed", true).await?;
+    vm.set_signal("audit_due", true).await?;
+
+    let shutdown = vm.shutdown_handle();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        shutdown.request();
+    });
+    vm.run_scan_groups().await?;
+
+    // Both groups are due at the start; only the fast one again since
+    assert!(vm.cycle_count() > 2, "{}", vm.cycle_count());
+    assert_eq!(vm.get_coil("trip").await?, Some(true));
+    assert_eq!(vm.get_coil("audit").await?, Some(true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_scan_groups_needs_a_schedule() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let result = vm.run_scan_groups().await;
+    assert!(
+        matches!(result, Err(Error::InvalidOperation(_))),
+        "{:?}",
+        result
+    );
+
+    let mut vm = ChartaVM::builder()
+        .scan_group("fast", Duration::ZERO)
+        .build();
+    vm.load_program(IR_JSON).await?;
+    let result = vm.run_scan_groups().await;
+    assert!(
+        matches!(result, Err(Error::InvalidOperation(_))),
+        "{:?}",
+        result
+    );
+    Ok(())
+}
+
+#[test]
+fn test_blocking_vm_scans_group() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder().build_blocking();
+    vm.load_program(IR_JSON)?;
+    vm.set_signal("audit_due", true)?;
+
+    let outputs = vm.execute_group("fast")?;
+    assert_eq!(outputs.get("audit"), Some(&false));
+    let outputs = vm.execute_group("slow")?;
+    assert_eq!(outputs.get("audit"), Some(&true));
+
+    let result = vm.execute_group("missing");
+    assert!(matches!(result, Err(Error::NotFound(_))), "{:?}", result);
+    Ok(())
+}
@@ -0,0 +1,65 @@
+/// Tests for the scenario harness
+
+use charta::testing::Scenario;
+use charta::Error;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "scenario_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[test]
+fn test_passing_scenario() -> Result<(), Error> {
+    Scenario::new(IR_JSON)
+        .given_signal("input", true)
+        .when_cycles(1)
+        .expect_coil("output", true)
+        .when_signal("input", false)
+        .when_cycles(1)
+        .expect_coil("output", false)
+        .run()
+}
+
+#[tokio::test]
+async fn test_failing_scenario_reports_diff() {
+    let result = Scenario::new(IR_JSON)
+        .given_signal("input", false)
+        .when_cycles(2)
+        .expect_coil("output", true)
+        .run_async()
+        .await;
+
+    match result {
+        Err(Error::ScenarioFailed(diff)) => {
+            assert!(diff.starts_with("scenario failed after cycle 2:"));
+            assert!(diff.contains("- output"));
+            assert!(diff.contains("true      false"));
+        }
+        other => panic!("expected ScenarioFailed, got {:?}", other),
+    }
+}
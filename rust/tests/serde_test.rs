@@ -0,0 +1,110 @@
+/// Tests for serialized events and records
+
+use charta::{ChartaVM, CoilChanges, CycleRecord, Error, VmEvent};
+use serde_json::json;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "serde_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+/// Serialize, deserialize, and serialize again
+fn round_trip<T>(value: &T) -> serde_json::Value
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let json = serde_json::to_value(value).unwrap();
+    let parsed: T = serde_json::from_value(json.clone()).unwrap();
+    let again = serde_json::to_value(&parsed).unwrap();
+    assert_eq!(json, again);
+    json
+}
+
+#[tokio::test]
+async fn test_events_serialize_tagged_by_type() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let mut events = vm.subscribe();
+
+    vm.set_signal("input", true).await?;
+    vm.execute_cycle().await?;
+
+    let mut kinds = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        let json = round_trip(&event);
+        assert_eq!(json["type"], event.kind());
+        assert_eq!(json["context"]["cycle"], 1);
+        assert!(json["context"]["at_ms"].as_u64().unwrap() > 0);
+        if let VmEvent::CoilChanged { .. } = event {
+            assert_eq!(json["name"], "output");
+            assert_eq!((&json["old"], &json["new"]), (&json!(false), &json!(true)));
+        }
+        if let VmEvent::CycleCompleted { .. } = event {
+            assert_eq!(json["outputs"]["coils"], json!({ "output": true }));
+            assert_eq!(json["outputs"]["previous"], json!({ "output": false }));
+        }
+        kinds.push(event.kind());
+    }
+    assert_eq!(kinds, ["coil_changed", "cycle_completed"]);
+    Ok(())
+}
+
+#[test]
+fn test_lagged_event_deserializes() {
+    let event: VmEvent = serde_json::from_value(json!({ "type": "lagged", "skipped": 3 })).unwrap();
+    assert_eq!(event, VmEvent::Lagged { skipped: 3 });
+    assert!(event.context().is_none());
+}
+
+#[tokio::test]
+async fn test_records_serialize() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.enable_history(8);
+    vm.execute_cycle_with_inputs([("input".to_string(), true)].into()).await?;
+
+    let history = vm.history().expect("history enabled");
+    let cycle: &CycleRecord = history.cycles().last().unwrap();
+    let json = round_trip(cycle);
+    assert_eq!(json["cycle"], 1);
+    assert_eq!(json["outputs"], json!({ "output": true }));
+
+    let change = history.coil_changes().last().unwrap();
+    let json = round_trip(change);
+    assert_eq!((&json["name"], &json["new"]), (&json!("output"), &json!(true)));
+
+    let changes = CoilChanges {
+        cycle: 1,
+        program_id: vm.program_id(),
+        at: cycle.at,
+        changes: [("output".to_string(), (false, true))].into(),
+    };
+    let json = round_trip(&changes);
+    assert_eq!(json["changes"]["output"], json!([false, true]));
+    Ok(())
+}
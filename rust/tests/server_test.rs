@@ -0,0 +1,87 @@
+#![cfg(feature = "server")]
+
+/// Integration tests for the HTTP control server
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use charta::server::ChartaServer;
+use charta::{ChartaVM, Error};
+use serde_json::Value;
+use tower::ServiceExt;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "server_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+async fn body_json(response: axum::response::Response) -> Value {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn test_set_signal_and_execute_cycle() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let app = ChartaServer::new(vm).router();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::put("/signals/input")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"value": true}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .clone()
+        .oneshot(Request::post("/cycle").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await["output"], Value::Bool(true));
+
+    let response = app
+        .clone()
+        .oneshot(Request::get("/program").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(body_json(response).await["cycle_count"], 1);
+
+    let response = app
+        .oneshot(Request::get("/coils/missing").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
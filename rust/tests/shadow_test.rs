@@ -0,0 +1,120 @@
+/// Integration tests for shadow execution
+
+use charta::{ChartaVM, Error, VmEvent};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+const PRIMARY_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "primary",
+        "signals": [
+            {"name": "input_a"},
+            {"name": "input_b"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "gate",
+                "guard": {
+                    "type": "contact",
+                    "name": "input_a",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+const CANDIDATE_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "candidate",
+        "signals": [
+            {"name": "input_a"},
+            {"name": "input_b"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "gate",
+                "guard": {
+                    "type": "and",
+                    "left": {
+                        "type": "contact",
+                        "name": "input_a",
+                        "contact_type": "NO"
+                    },
+                    "right": {
+                        "type": "contact",
+                        "name": "input_b",
+                        "contact_type": "NO"
+                    }
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_shadow_reports_divergence() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(PRIMARY_IR).await?;
+    vm.attach_shadow(CANDIDATE_IR).await?;
+    assert!(vm.has_shadow());
+
+    let divergences = Arc::new(AtomicU32::new(0));
+    let divergences_clone = divergences.clone();
+    vm.on_shadow_divergence(move |divergence, _| {
+        assert_eq!(divergence.coils.len(), 1);
+        assert_eq!(divergence.coils[0].name, "output");
+        assert_eq!(divergence.coils[0].primary, Some(true));
+        assert_eq!(divergence.coils[0].shadow, Some(false));
+        divergences_clone.fetch_add(1, Ordering::Relaxed);
+    })
+    .await;
+
+    let mut events = vm.subscribe();
+
+    // Programs disagree: candidate also requires input_b
+    vm.set_signal("input_a", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("output"), Some(&true));
+    assert_eq!(divergences.load(Ordering::Relaxed), 1);
+
+    // Programs agree once input_b is set too
+    vm.set_signal("input_b", true).await?;
+    vm.execute_cycle().await?;
+    assert_eq!(divergences.load(Ordering::Relaxed), 1);
+
+    let mut diverged_cycles = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let VmEvent::ShadowDiverged { divergence, .. } = event {
+            diverged_cycles.push(divergence.cycle);
+        }
+    }
+    assert_eq!(diverged_cycles, vec![1]);
+
+    assert!(vm.detach_shadow());
+    assert!(!vm.has_shadow());
+
+    Ok(())
+}
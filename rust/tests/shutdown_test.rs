@@ -0,0 +1,166 @@
+/// Tests for graceful shutdown
+
+use charta::persistence::FileStore;
+use charta::shutdown::{ShutdownState, SHUTDOWN_SIGNAL};
+use charta::{ChartaVM, Error, ShutdownOptions, StateStore};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "shutdown_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+/// Latches `released` from the shutdown signal
+const SHUTDOWN_IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "shutdown_signal_program",
+        "signals": [
+            {"name": "__shutdown"}
+        ],
+        "coils": [
+            {"name": "released"}
+        ],
+        "rungs": [
+            {
+                "name": "release_on_shutdown",
+                "guard": {
+                    "type": "contact",
+                    "name": "__shutdown",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "released"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_shutdown_stops_cycles() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let handle = vm.shutdown_handle();
+    assert_eq!(handle.state(), ShutdownState::Running);
+
+    vm.shutdown(ShutdownOptions::default()).await?;
+    assert_eq!(handle.state(), ShutdownState::Stopped);
+    assert!(matches!(
+        vm.execute_cycle().await,
+        Err(Error::InvalidOperation(_))
+    ));
+
+    // Shutting down again is a no-op
+    vm.shutdown(ShutdownOptions::default()).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_final_cycle_sets_shutdown_signal() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(SHUTDOWN_IR_JSON).await?;
+    vm.execute_cycle().await?;
+    assert_eq!(vm.get_coil("released").await?, Some(false));
+
+    vm.shutdown(ShutdownOptions::default().final_cycle(true)).await?;
+    assert_eq!(vm.cycle_count(), 2);
+    assert_eq!(vm.get_coil("released").await?, Some(true));
+    assert_eq!(vm.get_signal(SHUTDOWN_SIGNAL).await?, Some(true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_final_cycle_without_shutdown_signal() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.shutdown(ShutdownOptions::default().final_cycle(true)).await?;
+    assert_eq!(vm.cycle_count(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_shutdown_saves_checkpoint() -> Result<(), Error> {
+    let dir = std::env::temp_dir().join(format!("charta-shutdown-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("state.json");
+    let _ = std::fs::remove_file(&path);
+
+    let mut vm = ChartaVM::builder()
+        .persist_to(FileStore::new(&path), Duration::from_secs(3600))
+        .build();
+    vm.load_program(IR_JSON).await?;
+    vm.execute_cycle_with_inputs([("input".to_string(), true)].into()).await?;
+    vm.shutdown(ShutdownOptions::default()).await?;
+
+    let saved = FileStore::new(&path).load().await?.expect("checkpoint saved");
+    assert_eq!(saved.cycle, 1);
+    assert_eq!(saved.coils.get("output"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_handle_requests_and_awaits_shutdown() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let handle = vm.shutdown_handle();
+    let cycles = Arc::new(AtomicU64::new(0));
+
+    // A scan loop owning the VM until shutdown is requested
+    let scan = tokio::spawn({
+        let handle = handle.clone();
+        let cycles = Arc::clone(&cycles);
+        async move {
+            let mut ticker = tokio::time::interval(Duration::from_millis(1));
+            while !handle.is_requested() {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        vm.execute_cycle().await?;
+                        cycles.fetch_add(1, Ordering::SeqCst);
+                    }
+                    _ = handle.requested() => {}
+                }
+            }
+            vm.shutdown(ShutdownOptions::default()).await
+        }
+    });
+
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    handle.request();
+    handle.completed().await;
+    assert_eq!(handle.state(), ShutdownState::Stopped);
+    scan.await.unwrap()?;
+    assert!(cycles.load(Ordering::SeqCst) > 0);
+    Ok(())
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "sled")]
+/// Tests for the sled checkpoint store
+
+use charta::persistence::sled::SledStore;
+use charta::persistence::Retention;
+use charta::{ChartaVM, Checkpoint, Error, StateStore};
+use std::time::{Duration, SystemTime};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "sled_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+fn open(retention: Retention) -> SledStore {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    SledStore::new(&db).unwrap().retention(retention)
+}
+
+fn checkpoint(cycle: u64, saved_at: SystemTime) -> Checkpoint {
+    Checkpoint {
+        program_id: None,
+        cycle,
+        saved_at,
+        coils: [("output".to_string(), cycle % 2 == 1)].into(),
+        signals: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_load_returns_newest() -> Result<(), Error> {
+    let store = open(Retention::keep_all());
+    assert_eq!(store.load().await?, None);
+
+    let now = SystemTime::now();
+    for cycle in 1..=3 {
+        store.save(&checkpoint(cycle, now)).await?;
+    }
+    assert_eq!(store.load().await?.map(|c| c.cycle), Some(3));
+    let cycles: Vec<u64> = store.history()?.iter().map(|c| c.cycle).collect();
+    assert_eq!(cycles, vec![1, 2, 3]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retention_keeps_last() -> Result<(), Error> {
+    let store = open(Retention::keep_last(2));
+    let now = SystemTime::now();
+    for cycle in 1..=5 {
+        store.save(&checkpoint(cycle, now)).await?;
+    }
+    let cycles: Vec<u64> = store.history()?.iter().map(|c| c.cycle).collect();
+    assert_eq!(cycles, vec![4, 5]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retention_drops_old_but_keeps_newest() -> Result<(), Error> {
+    let store = open(Retention::max_age(Duration::from_secs(60)));
+    let old = SystemTime::now() - Duration::from_secs(3600);
+    store.save(&checkpoint(1, old)).await?;
+    store.save(&checkpoint(2, old)).await?;
+    // Both are too old, but the newest survives
+    assert_eq!(store.history()?.len(), 1);
+
+    store.save(&checkpoint(3, SystemTime::now())).await?;
+    let cycles: Vec<u64> = store.history()?.iter().map(|c| c.cycle).collect();
+    assert_eq!(cycles, vec![3]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_vm_checkpoints_to_store() -> Result<(), Error> {
+    let store = open(Retention::default());
+    let mut vm = ChartaVM::builder()
+        .persist_to(store.clone(), Duration::ZERO)
+        .build();
+    vm.load_program(IR_JSON).await?;
+    vm.execute_cycle_with_inputs([("input".to_string(), true)].into()).await?;
+    vm.execute_cycle().await?;
+
+    let history = store.history()?;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].coils.get("output"), Some(&true));
+    assert_eq!(history[1].cycle, 2);
+    Ok(())
+}
@@ -0,0 +1,75 @@
+/// Tests for published state snapshots
+
+use charta::{ChartaVM, Error};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "snapshot_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_snapshot_follows_writes() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    let observer = vm.observer();
+    vm.load_program(IR_JSON).await?;
+
+    let loaded = observer.snapshot();
+    assert_eq!(loaded.cycle(), 0);
+    assert_eq!(loaded.get_coil("output"), Some(false));
+    assert_eq!(loaded.coil_names(), ["output".to_string()]);
+
+    vm.set_signal("input", true).await?;
+    assert_eq!(observer.snapshot().get_signal("input"), Some(true));
+    assert_eq!(observer.snapshot().get_coil("output"), Some(false));
+
+    vm.execute_cycle().await?;
+    let cycled = observer.snapshot();
+    assert_eq!(cycled.cycle(), 1);
+    assert_eq!(cycled.get_coil("output"), Some(true));
+    assert!(cycled.outputs().changed("output"));
+
+    vm.set_coil("output", false).await?;
+    assert_eq!(observer.get_coil("output").await?, Some(false));
+
+    // Earlier snapshots are unaffected by later writes
+    assert_eq!(loaded.get_signal("input"), Some(false));
+    assert_eq!(cycled.get_coil("output"), Some(true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_writer_publishes_signals() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let writer = vm.writer_for(&["input"]);
+
+    writer.set_signal("input", true).await?;
+    assert_eq!(vm.observer().snapshot().get_signal("input"), Some(true));
+    assert_eq!(vm.get_signal("input").await?, Some(true));
+    Ok(())
+}
@@ -0,0 +1,114 @@
+/// Tests for source locations carried by IR rungs
+
+use charta::ir::Program;
+use charta::{ChartaVM, Error, SourceLocation, UnknownNodePolicy};
+use std::collections::HashMap;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "mapped_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"},
+            {"name": "future_output"}
+        ],
+        "rungs": [
+            {
+                "name": "operation_gate",
+                "guard": {
+                    "type": "not",
+                    "operand": {"type": "contact", "name": "input", "contact_type": "NC"}
+                },
+                "actions": [
+                    {"type": "energise", "coil": "output"}
+                ],
+                "source": {"file": "program.charta", "line": 42}
+            },
+            {
+                "name": "future_rung",
+                "guard": {"type": "timer", "preset_ms": 500},
+                "actions": [
+                    {"type": "energise", "coil": "future_output"}
+                ],
+                "source": {"file": "program.charta", "line": 57, "column": 5}
+            }
+        ]
+    }
+}"#;
+
+/// The program with the timer guard replaced by a contact
+fn known_ir() -> String {
+    IR_JSON.replace(
+        r#"{"type": "timer", "preset_ms": 500}"#,
+        r#"{"type": "contact", "name": "input"}"#,
+    )
+}
+
+#[test]
+fn test_source_location_round_trips() {
+    let program = Program::from_json(&known_ir()).unwrap();
+    let source = program.module.rungs[1].source.as_ref().unwrap();
+    assert_eq!(
+        *source,
+        SourceLocation {
+            file: "program.charta".to_string(),
+            line: 57,
+            column: Some(5),
+        }
+    );
+    assert_eq!(source.to_string(), "program.charta:57:5");
+    assert_eq!(
+        program.module.rungs[0].describe(),
+        "'operation_gate' (program.charta:42)"
+    );
+
+    let json = serde_json::to_string(&program).unwrap();
+    assert_eq!(Program::from_json(&json).unwrap(), program);
+}
+
+#[tokio::test]
+async fn test_unknown_node_error_reports_source() {
+    let mut vm = ChartaVM::new();
+    let err = vm.load_program(IR_JSON).await.unwrap_err();
+    assert!(
+        matches!(&err, Error::IRLoad(msg) if msg.contains("'future_rung' (program.charta:57:5)")),
+        "{}",
+        err
+    );
+
+    let mut vm = ChartaVM::builder()
+        .unknown_nodes(UnknownNodePolicy::Permissive)
+        .build();
+    vm.load_program(IR_JSON).await.unwrap();
+    let report = vm.load_report().unwrap();
+    assert_eq!(report.ignored_nodes[0].source.as_ref().unwrap().line, 57);
+}
+
+#[tokio::test]
+async fn test_limit_error_reports_source() {
+    let mut vm = ChartaVM::builder().max_guard_depth(1).build();
+    let err = vm.load_program(&known_ir()).await.unwrap_err();
+    assert!(
+        matches!(&err, Error::LimitExceeded(msg) if msg.contains("'operation_gate' (program.charta:42)")),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_rung_evaluation_carries_source() {
+    let program = Program::from_json(&known_ir()).unwrap();
+    let evaluations = program.evaluate_rungs(&HashMap::new());
+    assert_eq!(evaluations[0].source.as_ref().unwrap().line, 42);
+}
+
+#[test]
+fn test_moved_rung_is_not_a_change() {
+    let old = Program::from_json(&known_ir()).unwrap();
+    let new = Program::from_json(&known_ir().replace("\"line\": 42", "\"line\": 44")).unwrap();
+    assert!(charta::ProgramDiff::between(&old, &new).is_empty());
+}
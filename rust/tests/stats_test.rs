@@ -0,0 +1,55 @@
+/// Integration tests for per-coil statistics
+
+use charta::{ChartaVM, Error};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "stats_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_coil_stats_track_energisations() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    assert!(vm.coil_stats("output").is_none());
+
+    // on, on, off, on
+    for value in [true, true, false, true] {
+        vm.set_signal("input", value).await?;
+        vm.execute_cycle().await?;
+    }
+
+    let stats = vm.coil_stats("output").expect("coil observed");
+    assert_eq!(stats.energisations, 2);
+    assert_eq!(stats.cycles_energised, 3);
+    assert_eq!(stats.cycles_observed, 4);
+    assert_eq!(stats.last_change_cycle, Some(4));
+    assert_eq!(stats.duty_cycle(), 75.0);
+
+    Ok(())
+}
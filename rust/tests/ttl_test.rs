@@ -0,0 +1,91 @@
+/// Tests for signal TTLs with fallback values
+
+use charta::{ChartaVM, Error, Quality, VmEvent};
+use std::time::Duration;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "ttl_program",
+        "signals": [
+            {"name": "sanctions_clear"}
+        ],
+        "coils": [
+            {"name": "approved"}
+        ],
+        "rungs": [
+            {
+                "name": "approve",
+                "guard": {"type": "contact", "name": "sanctions_clear", "contact_type": "NO"},
+                "actions": [
+                    {"type": "energise", "coil": "approved"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_expired_signal_reverts_to_fallback() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    let mut events = vm.subscribe();
+    vm.set_signal_ttl("sanctions_clear", Duration::from_millis(20), false);
+    vm.set_signal("sanctions_clear", true).await?;
+
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("approved"), Some(&true));
+
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("approved"), Some(&false));
+    assert_eq!(vm.get_signal("sanctions_clear").await?, Some(false));
+    assert_eq!(vm.get_signal_quality("sanctions_clear").await?, Quality::Stale);
+
+    // The expiry is reported once
+    vm.execute_cycle().await?;
+    let mut expired = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let VmEvent::SignalExpired {
+            name,
+            fallback,
+            context,
+        } = event
+        {
+            expired.push((name, fallback, context.cycle));
+        }
+    }
+    assert_eq!(expired, [("sanctions_clear".to_string(), false, 2)]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_write_restarts_ttl() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.set_signal_ttl("sanctions_clear", Duration::from_millis(40), false);
+
+    for _ in 0..3 {
+        tokio::time::sleep(Duration::from_millis(15)).await;
+        let inputs = [("sanctions_clear".to_string(), true)].into();
+        let outputs = vm.execute_cycle_with_inputs(inputs).await?;
+        assert_eq!(outputs.get("approved"), Some(&true));
+    }
+    assert_eq!(vm.get_signal_quality("sanctions_clear").await?, Quality::Good);
+    Ok(())
+}
+
+#[test]
+fn test_blocking_vm_reverts_expired_signal() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder().build_blocking();
+    vm.load_program(IR_JSON)?;
+    vm.set_signal_ttl("sanctions_clear", Duration::from_millis(20), false);
+    vm.set_signal("sanctions_clear", true)?;
+
+    std::thread::sleep(Duration::from_millis(40));
+    let outputs = vm.execute_cycle()?;
+    assert_eq!(outputs.get("approved"), Some(&false));
+    assert_eq!(vm.get_signal_quality("sanctions_clear")?, Quality::Stale);
+    Ok(())
+}
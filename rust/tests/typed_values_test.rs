@@ -0,0 +1,172 @@
+/// Tests for typed signal values and comparison contacts
+
+use charta::value::{CompareOp, ValueType};
+use charta::{ChartaVM, Error, Value};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "threshold_program",
+        "signals": [
+            {"name": "amount", "type": "int"},
+            {"name": "score", "type": "float"},
+            {"name": "tier", "type": "string"},
+            {"name": "override"}
+        ],
+        "coils": [
+            {"name": "blocked"},
+            {"name": "gold"}
+        ],
+        "rungs": [
+            {
+                "name": "block_large",
+                "guard": {
+                    "type": "and",
+                    "left": {"type": "compare", "name": "amount", "op": ">", "value": 10000},
+                    "right": {"type": "contact", "name": "override", "contact_type": "NC"}
+                },
+                "actions": [
+                    {"type": "energise", "coil": "blocked"}
+                ]
+            },
+            {
+                "name": "gold_tier",
+                "guard": {
+                    "type": "or",
+                    "operands": [
+                        {"type": "compare", "name": "tier", "op": "==", "value": "gold"},
+                        {"type": "compare", "name": "score", "op": ">=", "value": 0.9}
+                    ]
+                },
+                "actions": [
+                    {"type": "energise", "coil": "gold"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_comparison_reads_typed_value() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    vm.set_value("amount", 20_000).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("blocked"), Some(&true));
+
+    vm.set_value("amount", 5_000).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("blocked"), Some(&false));
+
+    vm.set_value("amount", 20_000).await?;
+    vm.set_value("override", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("blocked"), Some(&false));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_string_and_float_comparisons() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("gold"), Some(&false));
+
+    vm.set_value("tier", "gold").await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("gold"), Some(&true));
+
+    vm.set_value("tier", "silver").await?;
+    vm.set_value("score", 0.95).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("gold"), Some(&true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_value_and_defaults() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    assert_eq!(vm.get_value("amount").await?, Some(Value::Int(0)));
+    assert_eq!(vm.get_value("tier").await?, Some(Value::Str(String::new())));
+    assert_eq!(vm.get_value("override").await?, Some(Value::Bool(false)));
+
+    // Integers widen to floats
+    vm.set_value("score", 1).await?;
+    assert_eq!(vm.get_value("score").await?, Some(Value::Float(1.0)));
+    assert_eq!(vm.get_all_values().await?.len(), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_derived_signals_are_hidden() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.execute_cycle().await?;
+
+    assert_eq!(vm.signal_names().await?, vec!["override".to_string()]);
+    assert!(vm.get_all_signals().await?.keys().all(|name| name == "override"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mismatched_value_is_rejected() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+
+    assert!(matches!(vm.set_value("amount", "lots").await, Err(Error::TypeMismatch(_))));
+    assert!(matches!(vm.set_value("amount", 1.5).await, Err(Error::TypeMismatch(_))));
+    assert!(matches!(vm.set_value("override", 1).await, Err(Error::TypeMismatch(_))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_typed_usage_fails_load() {
+    let contact_on_typed = IR_JSON.replace(
+        r#"{"type": "contact", "name": "override", "contact_type": "NC"}"#,
+        r#"{"type": "contact", "name": "amount"}"#,
+    );
+    let compare_untyped = IR_JSON.replace(r#""name": "amount", "op""#, r#""name": "override", "op""#);
+    let wrong_constant = IR_JSON.replace(r#""value": 10000"#, r#""value": "large""#);
+
+    for ir in [contact_on_typed, compare_untyped, wrong_constant] {
+        let mut vm = ChartaVM::new();
+        let result = vm.load_program(&ir).await;
+        assert!(matches!(result, Err(Error::IRLoad(_))), "{:?}", result);
+    }
+}
+
+#[test]
+fn test_value_serializes_as_plain_json() {
+    let values: Vec<Value> = serde_json::from_str(r#"[true, 42, 2.5, "gold"]"#).unwrap();
+    assert_eq!(
+        values,
+        vec![Value::Bool(true), Value::Int(42), Value::Float(2.5), Value::from("gold")]
+    );
+    assert_eq!(serde_json::to_string(&values).unwrap(), r#"[true,42,2.5,"gold"]"#);
+}
+
+#[test]
+fn test_value_comparison() {
+    assert!(Value::Int(3).compare(CompareOp::Lt, &Value::Float(3.5)));
+    assert!(Value::from("b").compare(CompareOp::Gt, &Value::from("a")));
+    assert!(!Value::Int(1).compare(CompareOp::Ne, &Value::from("1")));
+    assert!(!Value::Float(f64::NAN).compare(CompareOp::Eq, &Value::Float(f64::NAN)));
+    assert!(ValueType::Int.is_comparable_with(ValueType::Float));
+    assert!(!ValueType::Str.is_comparable_with(ValueType::Int));
+}
+
+#[test]
+fn test_blocking_vm_supports_typed_values() -> Result<(), Error> {
+    let mut vm = charta::blocking::ChartaVM::new();
+    vm.load_program(IR_JSON)?;
+    vm.set_value("amount", 10_001)?;
+    let outputs = vm.execute_cycle()?;
+    assert_eq!(outputs.get("blocked"), Some(&true));
+    assert_eq!(vm.get_value("amount")?, Some(Value::Int(10_001)));
+    Ok(())
+}
@@ -0,0 +1,77 @@
+/// Integration tests for unknown IR node handling
+
+use charta::{ChartaVM, Error, NodeKind, UnknownNodePolicy};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "unknown_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"},
+            {"name": "future_output"}
+        ],
+        "rungs": [
+            {
+                "name": "known_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {"type": "energise", "coil": "output"}
+                ]
+            },
+            {
+                "name": "future_rung",
+                "guard": {
+                    "type": "and",
+                    "left": {"type": "contact", "name": "input", "contact_type": "NO"},
+                    "right": {"type": "timer", "preset_ms": 500}
+                },
+                "actions": [
+                    {"type": "energise", "coil": "future_output"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_strict_mode_names_offending_node() {
+    let mut vm = ChartaVM::new();
+    let result = vm.load_program(IR_JSON).await;
+    match result {
+        Err(Error::IRLoad(msg)) => {
+            assert!(msg.contains("future_rung"));
+            assert!(msg.contains("timer"));
+            assert!(msg.contains("module.rungs[1].guard.right"));
+        }
+        other => panic!("expected IRLoad error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_permissive_mode_disables_rung() -> Result<(), Error> {
+    let mut vm = ChartaVM::builder()
+        .unknown_nodes(UnknownNodePolicy::Permissive)
+        .build();
+    vm.load_program(IR_JSON).await?;
+
+    let report = vm.load_report().unwrap();
+    assert_eq!(report.disabled_rungs, vec!["future_rung".to_string()]);
+    assert_eq!(report.ignored_nodes.len(), 1);
+    assert_eq!(report.ignored_nodes[0].kind, NodeKind::Guard);
+    assert_eq!(report.ignored_nodes[0].node_type, "timer");
+
+    vm.set_signal("input", true).await?;
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.get("output"), Some(&true));
+    assert_ne!(outputs.get("future_output"), Some(&true));
+
+    Ok(())
+}
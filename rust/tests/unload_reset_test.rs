@@ -0,0 +1,122 @@
+/// Tests for unloading programs and resetting state
+
+use charta::blocking;
+use charta::{ChartaVM, Error, Value, VmEvent};
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "reset_program",
+        "signals": [
+            {"name": "start"},
+            {"name": "permissive", "initial": true},
+            {"name": "setpoint", "type": "float", "initial": 72.5}
+        ],
+        "coils": [
+            {"name": "running"}
+        ],
+        "rungs": [
+            {
+                "name": "seal_in",
+                "guard": {
+                    "type": "and",
+                    "operands": [
+                        {
+                            "type": "or",
+                            "operands": [
+                                {"type": "contact", "name": "start", "contact_type": "NO"},
+                                {"type": "contact", "name": "running", "contact_type": "NO"}
+                            ]
+                        },
+                        {"type": "contact", "name": "permissive", "contact_type": "NO"}
+                    ]
+                },
+                "actions": [
+                    {"type": "energise", "coil": "running"}
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_unload_program_clears_everything() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    assert!(!vm.unload_program().await);
+    vm.load_program(IR_JSON).await?;
+    vm.execute_cycle_with_inputs([("start".to_string(), true)].into())
+        .await?;
+    let mut events = vm.subscribe();
+
+    assert!(vm.unload_program().await);
+    assert_eq!(vm.program_id(), None);
+    assert!(vm.rungs().is_empty());
+    assert!(vm.get_all_coils().await?.is_empty());
+    assert_eq!(vm.get_value("setpoint").await?, None);
+    assert!(std::iter::from_fn(|| events.try_recv().ok())
+        .any(|event| matches!(event, VmEvent::ProgramUnloaded { .. })));
+    assert!(!vm.unload_program().await);
+
+    // A program can be loaded again afterwards
+    vm.load_program(IR_JSON).await?;
+    assert_eq!(vm.get_coil("running").await?, Some(false));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reset_state_restores_initial_values() -> Result<(), Error> {
+    let mut vm = ChartaVM::new();
+    let result = vm.reset_state().await;
+    assert!(
+        matches!(result, Err(Error::InvalidOperation(_))),
+        "{:?}",
+        result
+    );
+
+    vm.load_program(IR_JSON).await?;
+    vm.execute_cycle_with_inputs([("start".to_string(), true)].into())
+        .await?;
+    vm.set_signal("permissive", false).await?;
+    vm.set_value("setpoint", 80.0).await?;
+    assert_eq!(vm.get_coil("running").await?, Some(true));
+    let program_id = vm.program_id();
+    let mut events = vm.subscribe();
+
+    vm.reset_state().await?;
+    assert_eq!(vm.program_id(), program_id);
+    assert_eq!(vm.cycle_count(), 0);
+    assert_eq!(vm.get_coil("running").await?, Some(false));
+    assert_eq!(vm.get_signal("start").await?, Some(false));
+    assert_eq!(vm.get_signal("permissive").await?, Some(true));
+    assert_eq!(vm.get_value("setpoint").await?, Some(Value::Float(72.5)));
+    assert!(std::iter::from_fn(|| events.try_recv().ok())
+        .any(|event| matches!(event, VmEvent::StateReset { .. })));
+
+    // The latch has to be started again
+    let outputs = vm.execute_cycle().await?;
+    assert_eq!(outputs.cycle(), 1);
+    assert_eq!(outputs.get("running"), Some(&false));
+    Ok(())
+}
+
+#[test]
+fn test_blocking_unload_and_reset() -> Result<(), Error> {
+    let mut vm = blocking::ChartaVM::new();
+    vm.load_program(IR_JSON)?;
+    vm.set_signal("start", true)?;
+    vm.execute_cycle()?;
+
+    vm.reset_state()?;
+    assert_eq!(vm.cycle_count(), 0);
+    assert_eq!(vm.get_coil("running")?, Some(false));
+    assert_eq!(vm.get_signal("start")?, Some(false));
+    assert_eq!(vm.get_signal("permissive")?, Some(true));
+
+    assert!(vm.unload_program());
+    assert_eq!(vm.program_id(), None);
+    assert!(vm.get_all_coils()?.is_empty());
+    assert!(matches!(vm.reset_state(), Err(Error::InvalidOperation(_))));
+    assert!(!vm.unload_program());
+    Ok(())
+}
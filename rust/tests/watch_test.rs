@@ -0,0 +1,141 @@
+#![cfg(feature = "notify")]
+/// Tests for watched program files
+
+use charta::{ChartaVM, Error};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const V1_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "policy",
+        "signals": [
+            {"name": "input"},
+            {"name": "legacy"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "drive_output",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+const V2_IR: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "policy",
+        "signals": [
+            {"name": "input"},
+            {"name": "override"}
+        ],
+        "coils": [
+            {"name": "output"},
+            {"name": "audit"}
+        ],
+        "rungs": [
+            {
+                "name": "drive_output",
+                "guard": {
+                    "type": "contact",
+                    "name": "override",
+                    "contact_type": "NC"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            },
+            {
+                "name": "drive_audit",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "audit"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+fn program_path(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("charta-watch-{}-{}", std::process::id(), test));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join("policy.ir.json")
+}
+
+/// Poll until the watcher has seen a change, or give up
+async fn wait_for_reload(vm: &mut ChartaVM) -> Result<bool, Error> {
+    for _ in 0..100 {
+        if vm.poll_program_file().await?.is_some() {
+            return Ok(true);
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    Ok(false)
+}
+
+#[tokio::test]
+async fn test_changed_file_is_reloaded() -> Result<(), Error> {
+    let path = program_path("changed");
+    std::fs::write(&path, V1_IR)?;
+
+    let mut vm = ChartaVM::new();
+    vm.watch_program_file(&path).await?;
+    vm.execute_cycle_with_inputs([("input".to_string(), true)].into()).await?;
+    assert_eq!(vm.coil_names().await?, vec!["output".to_string()]);
+
+    std::fs::write(&path, V2_IR)?;
+    assert!(wait_for_reload(&mut vm).await?);
+    let mut coils = vm.coil_names().await?;
+    coils.sort();
+    assert_eq!(coils, vec!["audit".to_string(), "output".to_string()]);
+    assert_eq!(vm.get_coil("output").await?, Some(true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_edit_keeps_program() -> Result<(), Error> {
+    let path = program_path("invalid");
+    std::fs::write(&path, V1_IR)?;
+
+    let mut vm = ChartaVM::new();
+    vm.watch_program_file(&path).await?;
+    let program_id = vm.program_id();
+
+    std::fs::write(&path, "{ half written")?;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(vm.execute_cycle().await.is_ok());
+    assert_eq!(vm.program_id(), program_id);
+
+    assert!(vm.unwatch_program_file());
+    std::fs::write(&path, V2_IR)?;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(vm.poll_program_file().await?, None);
+    assert_eq!(vm.program_id(), program_id);
+    Ok(())
+}
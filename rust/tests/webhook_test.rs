@@ -0,0 +1,73 @@
+#![cfg(feature = "webhook")]
+
+/// Integration tests for the webhook notifier
+
+use charta::integrations::webhook::WebhookNotifier;
+use charta::{ChartaVM, Error};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+const IR_JSON: &str = r#"
+{
+    "version": "0.1.0",
+    "module": {
+        "name": "webhook_program",
+        "signals": [
+            {"name": "input"}
+        ],
+        "coils": [
+            {"name": "output"}
+        ],
+        "rungs": [
+            {
+                "name": "test_rung",
+                "guard": {
+                    "type": "contact",
+                    "name": "input",
+                    "contact_type": "NO"
+                },
+                "actions": [
+                    {
+                        "type": "energise",
+                        "coil": "output"
+                    }
+                ]
+            }
+        ]
+    }
+}"#;
+
+#[tokio::test]
+async fn test_undeliverable_notification_is_dead_lettered() -> Result<(), Error> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let notifier = WebhookNotifier::builder()
+        // Nothing listens on the discard port
+        .url("http://127.0.0.1:9/hook")
+        .coil("output")
+        .max_retries(1)
+        .backoff(Duration::from_millis(1), Duration::from_millis(1))
+        .on_dead_letter(move |letter| {
+            let _ = tx.send(letter.clone());
+        })
+        .build();
+
+    let mut vm = ChartaVM::new();
+    vm.load_program(IR_JSON).await?;
+    vm.add_output_sink(notifier);
+
+    vm.set_signal("input", true).await?;
+    vm.execute_cycle().await?;
+
+    let letter = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+        .await
+        .expect("dead letter not delivered")
+        .unwrap();
+    assert_eq!(letter.attempts, 2);
+    assert_eq!(letter.payload.coil, "output");
+    assert!(!letter.payload.old);
+    assert!(letter.payload.new);
+    assert_eq!(letter.payload.cycle, 1);
+    assert_eq!(letter.payload.program_id, vm.program_id());
+
+    Ok(())
+}